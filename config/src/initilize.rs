@@ -7,10 +7,15 @@ use std::sync::Mutex;
 pub const TB_FAVOURATES_MUSIC: &str = "favourates_music";
 pub const TB_FAVOURATES_PLAYLIST: &str = "favourates_playlist";
 pub const TB_FAVOURATES_ARTIST: &str = "favourates_artist";
+pub const TB_PODCAST_POSITION: &str = "podcast_position";
+pub const TB_RESPONSE_CACHE: &str = "response_cache";
+pub const TB_SUBSCRIPTIONS: &str = "subscriptions";
+pub const TB_LAST_SEEN: &str = "last_seen";
+pub const TB_HISTORY: &str = "history";
 
 compute_static! {
     pub static ref CONFIG: Config = {
-        match ConfigContainer::give_me_config() {
+        let mut config = match ConfigContainer::give_me_config() {
             Some(config_container) => config_container.config,
 
             None => {
@@ -27,7 +32,9 @@ compute_static! {
                     }
                 }
             }
-        }
+        };
+        config.theme.adapt_to_background();
+        config
     };
 
     pub static ref STORAGE: Mutex<Connection> = {
@@ -50,6 +50,7 @@ where
 type Color = (u8, u8, u8);
 
 #[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[serde(default)]
 pub struct ShortcutsKeys {
     pub toggle_play: char,
     pub next: char,
@@ -66,6 +67,41 @@ pub struct ShortcutsKeys {
     pub favourates_remove: char,
     pub vol_increase: char,
     pub vol_decrease: char,
+    pub mute: char,
+    pub related: char,
+    pub lyrics: char,
+    pub podcast: char,
+    pub load_all: char,
+    pub next_chapter: char,
+    pub prev_chapter: char,
+    pub chapters: char,
+    pub mix: char,
+    pub comments: char,
+    pub subscribe: char,
+    pub help: char,
+    pub queue: char,
+    pub filter: char,
+    pub mark: char,
+    pub enqueue: char,
+    pub add_to_playlist: char,
+    pub actions: char,
+    pub shrink_sidebar: char,
+    pub grow_sidebar: char,
+    pub grow_music: char,
+    pub shrink_music: char,
+    pub maximize: char,
+    pub layout: char,
+    pub clear_history: char,
+    pub yank_url: char,
+    pub open_in_browser: char,
+    pub sort_music: char,
+    pub command: char,
+    pub hints: char,
+    pub details: char,
+    pub search_scope: char,
+    pub retry: char,
+    pub contrast: char,
+    pub favourite: char,
 }
 
 impl Default for ShortcutsKeys {
@@ -134,11 +170,171 @@ impl Default for ShortcutsKeys {
 
             // Same as vol_increase but decrease the volume
             vol_decrease: '-',
+
+            // Mute the playback if it is not muted and unmute otherwise. Volume level is kept as is
+            mute: 'M',
+
+            // Fill the music section with music related to the currently focused music
+            related: 'm',
+
+            // Show/hide the lyrics of currently playing music in place of playlist and artist section.
+            // Not on j/k/h/l which are arrow keys with vim_keys
+            lyrics: 'Y',
+
+            // Open the focused artist as podcast. Episodes are listed newest first and remember
+            // where they were left off
+            podcast: 'e',
+
+            // Load every music of focused playlist (or playlist shown in music section) instead of
+            // loading it chunk by chunk. Playing from such playlist queues the whole playlist
+            load_all: 'a',
+
+            // Move to next/previous chapter of music being played. Going to previous chapter
+            // after few seconds of chapter start moves to start of current chapter instead
+            next_chapter: ']',
+            prev_chapter: '[',
+
+            // Show list of chapters of music being played from which selected chapter is jumped to.
+            // Not on j/k/h/l for the same reason as lyrics
+            chapters: 'N',
+
+            // Fill the music section with the Mix (auto-generated station of similar music) of
+            // currently focused music
+            mix: 'x',
+
+            // Show/hide the comments of currently playing music in place of playlist and artist
+            // section. Comments are scrolled with PageUp/PageDown
+            comments: 'o',
+
+            // Subscribe to the focused artist or unsubscribe if already subscribed. Latest uploads
+            // of subscribed artists are shown in Subscriptions feed
+            subscribe: 'b',
+
+            // Show/hide the list of every shortcut key grouped by where they apply
+            help: '?',
+
+            // Show/hide the play queue. Inside the queue J/K moves the selected music down/up, x
+            // removes it and Enter plays it
+            queue: 'q',
+
+            // Narrow the focused music/playlist/artist list to entries fuzzy matching the typed
+            // text. Enter keeps the narrowed list and Esc brings back the whole list
+            filter: 'i',
+
+            // Mark/unmark the focused music/playlist/artist. Adding to favourites, removing from
+            // favourites, downloading, queueing and adding to local playlist are then applied to
+            // every marked row at once. Esc unmarks everything
+            mark: 't',
+
+            // Add the focused (or marked) music at the end of play queue
+            enqueue: 'Q',
+
+            // Add the focused (or marked) music to local playlist file (ytui-music.m3u) inside
+            // the download directory
+            add_to_playlist: 'P',
+
+            // Show menu of everything that can be done with focused music/playlist/artist along
+            // with the key that does the same
+            actions: '.',
+
+            // Make the sidebar narrower/wider and the music section shorter/taller. New sizes are
+            // saved in config file as `sidebar_width` and `music_height` of Constants
+            shrink_sidebar: 'H',
+            grow_sidebar: 'L',
+            grow_music: 'J',
+            shrink_music: 'K',
+
+            // Let the focused section take the whole space between top and bottom bar. Moving to
+            // other section while maximized maximizes that one. Press again to bring back others
+            maximize: 'z',
+
+            // Switch between layout presets: every section, search results only and mini player
+            // that only shows the queue and music being played
+            layout: 'w',
+
+            // Remove everything from history. Only works while history is shown so that it is not
+            // cleared by mistake
+            clear_history: 'X',
+
+            // Copy the url of focused music/playlist/artist (or of music being played when nothing
+            // is focused) to clipboard
+            yank_url: 'y',
+
+            // Open the focused music/playlist/artist (or music being played when nothing is
+            // focused) in web browser
+            open_in_browser: 'O',
+
+            // Sort the music already shown by title, duration or channel. Pressing again after
+            // channel brings back the original order
+            sort_music: 'S',
+
+            // Open the command line to run commands like `volume 80` or `seek 1:23`
+            command: ':',
+
+            // Show/hide the footer with most used keys of focused section
+            hints: 'F',
+
+            // Show full title, channel, duration, views, upload date and description of focused music
+            details: 'D',
+
+            // Switch music search between songs only and every video. Shown music is searched again
+            search_scope: 'T',
+
+            // Fetch again the list that failed to fetch. Focused list is preferred
+            retry: 'R',
+
+            // Switch high contrast mode on/off. See high_contrast in Constants
+            contrast: 'C',
+
+            // Add focused (or marked) item to favourites or remove it if it already is one. Ids of
+            // favourites are kept in memory so existence need not be checked in database
+            favourite: '*',
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq)]
+// Two key sequences (chords). Second key is to be pressed shortly after the first one. Keys that
+// start any chord are not used as single key shortcut anymore. Empty string disables the chord
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[serde(default)]
+pub struct ChordKeys {
+    // Go to respective section of sidebar and open it as if selected from there
+    pub goto_trending: String,
+    pub goto_charts: String,
+    pub goto_moods: String,
+    pub goto_liked: String,
+    pub goto_saved: String,
+    pub goto_following: String,
+    pub goto_subscriptions: String,
+    pub goto_new_releases: String,
+    pub goto_local: String,
+    pub goto_history: String,
+
+    // Copy the url of focused music/playlist/artist to clipboard. Same as yank_url of
+    // ShortcutsKeys. Set to "yy" for vim habit but then `y` alone no longer copies
+    pub yank_url: String,
+}
+
+impl Default for ChordKeys {
+    fn default() -> Self {
+        ChordKeys {
+            goto_trending: String::from("gt"),
+            goto_charts: String::from("gc"),
+            goto_moods: String::from("gm"),
+            goto_liked: String::from("gl"),
+            goto_saved: String::from("gs"),
+            goto_following: String::from("gf"),
+            goto_subscriptions: String::from("gb"),
+            goto_new_releases: String::from("gn"),
+            goto_local: String::from("gd"),
+            goto_history: String::from("gh"),
+            yank_url: String::from("gy"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(default)]
 pub struct Theme {
     pub border_idle: Color,
     pub border_highlight: Color,
@@ -150,6 +346,19 @@ pub struct Theme {
     pub color_primary: Color,
     pub color_secondary: Color,
     pub status_text: Color,
+    pub status_warning: Color,
+    pub status_error: Color,
+
+    // Border of active section. One of "rounded", "plain", "double" or "thick"
+    pub border_highlight_type: String,
+    // Row under cursor is drawn in list_hilight colour with these attributes and background
+    pub list_hilight_bold: bool,
+    pub list_hilight_reversed: bool,
+    pub list_hilight_background: Option<Color>,
+
+    // Background of terminal. One of "auto", "dark" or "light". Colours that are left as default
+    // are picked to be readable over this background
+    pub background: String,
 }
 
 impl Default for Theme {
@@ -168,8 +377,11 @@ impl Default for Theme {
             // Apply to the list item that is currently under cursor
             list_hilight: (255, 255, 255),
 
-            // Applies to the text in top status bar
+            // Applies to the text in top status bar. Warnings and errors are shown in their own
+            // colour so that they are not missed
             status_text: (175, 125, 115),
+            status_warning: (250, 170, 0),
+            status_error: (240, 60, 60),
 
             // Applies to the progress bar of bottom bar
             gauge_fill: (85, 85, 85),
@@ -184,11 +396,153 @@ impl Default for Theme {
             // Instead of relying on terminal color, using this will bring more consistency in the ui
             color_primary: (100, 250, 20),
             color_secondary: (250, 230, 70),
+
+            // How the active section and row under cursor stands out other than by colour. Thick
+            // border and reversed row are easier to see in some terminals
+            border_highlight_type: String::from("rounded"),
+            list_hilight_bold: false,
+            list_hilight_reversed: false,
+            list_hilight_background: None,
+
+            background: String::from("auto"),
+        }
+    }
+}
+
+impl Theme {
+    // Default colours for light background. Colours above are bright enough to be read over dark
+    // background but are hardly visible (or not at all in case of white) over light one
+    pub fn light() -> Self {
+        Theme {
+            border_idle: (90, 90, 90),
+            border_highlight: (0, 110, 110),
+            list_idle: (140, 90, 0),
+            list_hilight: (0, 0, 0),
+            status_text: (120, 70, 60),
+            status_warning: (180, 100, 0),
+            status_error: (190, 20, 20),
+            gauge_fill: (200, 200, 200),
+            sidebar_list: (30, 120, 0),
+            block_title: (120, 70, 60),
+            color_primary: (30, 120, 0),
+            color_secondary: (150, 110, 0),
+            background: String::from("light"),
+            ..Theme::default()
+        }
+    }
+
+    // Colours of high contrast mode. Only black/white and few strong colours that are readable
+    // in terminals with few colours and for low vision. Picked for background of this theme
+    pub fn high_contrast(&self) -> Self {
+        if self.is_light_background() {
+            Theme {
+                border_idle: (0, 0, 0),
+                border_highlight: (0, 0, 255),
+                list_idle: (0, 0, 0),
+                list_hilight: (0, 0, 0),
+                status_text: (0, 0, 0),
+                status_warning: (128, 0, 128),
+                status_error: (200, 0, 0),
+                gauge_fill: (160, 160, 255),
+                sidebar_list: (0, 0, 0),
+                block_title: (0, 0, 0),
+                color_primary: (0, 0, 0),
+                color_secondary: (0, 0, 255),
+                background: String::from("light"),
+                ..Theme::default()
+            }
+        } else {
+            Theme {
+                border_idle: (255, 255, 255),
+                border_highlight: (255, 255, 0),
+                list_idle: (255, 255, 255),
+                list_hilight: (255, 255, 255),
+                status_text: (255, 255, 255),
+                status_warning: (255, 255, 0),
+                status_error: (255, 80, 80),
+                gauge_fill: (0, 0, 255),
+                sidebar_list: (255, 255, 255),
+                block_title: (255, 255, 255),
+                color_primary: (255, 255, 255),
+                color_secondary: (0, 255, 255),
+                background: String::from("dark"),
+                ..Theme::default()
+            }
+        }
+    }
+
+    // Terminal can be asked for it's background colour but reply is read from stdin which is also
+    // where key events are read from. So it is guessed from $COLORFGBG (set by rxvt, konsole and
+    // few others) as "foreground;background" in terminal colour numbers. Dark is assumed when unknown
+    fn is_light_background(&self) -> bool {
+        match self.background.as_str() {
+            "light" => true,
+            "dark" => false,
+            _ => std::env::var("COLORFGBG")
+                .ok()
+                .and_then(|value| value.rsplit(';').next()?.parse::<u8>().ok())
+                .is_some_and(|background| background == 7 || (9..=15).contains(&background)),
+        }
+    }
+
+    // Replace colours left as default with the ones for light background if background is light.
+    // Colours set in config file are kept as they are
+    pub fn adapt_to_background(&mut self) {
+        if !self.is_light_background() {
+            return;
+        }
+        let (dark, light) = (Theme::default(), Theme::light());
+        for (color, dark, light) in [
+            (&mut self.border_idle, dark.border_idle, light.border_idle),
+            (
+                &mut self.border_highlight,
+                dark.border_highlight,
+                light.border_highlight,
+            ),
+            (&mut self.list_idle, dark.list_idle, light.list_idle),
+            (
+                &mut self.list_hilight,
+                dark.list_hilight,
+                light.list_hilight,
+            ),
+            (
+                &mut self.sidebar_list,
+                dark.sidebar_list,
+                light.sidebar_list,
+            ),
+            (&mut self.block_title, dark.block_title, light.block_title),
+            (&mut self.gauge_fill, dark.gauge_fill, light.gauge_fill),
+            (
+                &mut self.color_primary,
+                dark.color_primary,
+                light.color_primary,
+            ),
+            (
+                &mut self.color_secondary,
+                dark.color_secondary,
+                light.color_secondary,
+            ),
+            (&mut self.status_text, dark.status_text, light.status_text),
+            (
+                &mut self.status_warning,
+                dark.status_warning,
+                light.status_warning,
+            ),
+            (
+                &mut self.status_error,
+                dark.status_error,
+                light.status_error,
+            ),
+        ] {
+            if *color == dark {
+                *color = light;
+            }
         }
     }
 }
 
 #[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[serde(default)]
 pub struct Constants {
     pub item_per_list: usize,
     pub server_time_out: u32,
@@ -206,6 +560,159 @@ pub struct Constants {
     // If it is intended to not use this feature then just set these string to some random characters
     // that you would probably never type in search query.
     pub search_by_type: [String; 3],
+
+    // Backend to send the search query to. One of "invidious", "ytmusic", "local", "soundcloud" or
+    // "bandcamp". Any query can override this by prefixing it with backend name.
+    // eg: `ytmusic:some song`
+    pub search_backend: String,
+
+    // client_id used to access soundcloud api. When left empty it is automatically extracted from
+    // soundcloud web page. Set this only if automatic extraction stops working
+    pub soundcloud_client_id: String,
+
+    // Proxy through which every request and playback is routed. eg: "http://127.0.0.1:8080" or
+    // "socks5h://127.0.0.1:9050" for tor. Empty string means no proxy
+    pub proxy: String,
+
+    // Responses from invidious servers (search, playlist, channel pages...) are cached on disk for
+    // this many seconds so that revisiting same page is instant. 0 disables the cache
+    pub cache_ttl_secs: u64,
+
+    // Favourite and subscribed artists are checked for new uploads every this many minutes.
+    // 0 disables the check. New uploads are always listed in New releases section of sidebar
+    pub new_release_check_mins: u64,
+
+    // Also show desktop notification when new releases are found on check
+    pub notify_new_releases: bool,
+
+    // Thumbnail of music being played is shown in corner of sidebar. One of "auto", "kitty",
+    // "sixel", "iterm", "halfblock" or "off". "auto" picks the graphics protocol that the terminal
+    // seems to support and draws with unicode half blocks when none is supported
+    pub album_art: String,
+
+    // Use j/k/h/l as arrow keys, gg/G to jump to first/last row and CTRL+d/CTRL+u to move by half
+    // page. j/k/h/l take over the shortcut keys that are set to same key. CTRL+d/CTRL+u do not and
+    // are left to download, next, prev and quit when set to d or u
+    pub vim_keys: bool,
+
+    // Width of sidebar and height of music section in percentage of the space they share with
+    // other sections. These are changed from inside the app with the pane resize shortcuts
+    pub sidebar_width: u16,
+    pub music_height: u16,
+
+    // Layout to start with. One of "full", "search" or "mini". Layout can be switched from inside
+    // the app with layout shortcut key
+    pub layout: String,
+
+    // Draw borders, spinner and scrollbar with plain ascii characters for terminals and fonts that
+    // do not show box drawing characters well. Album art is only shown with graphics protocol
+    pub ascii_only: bool,
+
+    // Columns of music section from left to right. See MusicColumn
+    pub music_columns: Vec<MusicColumn>,
+
+    // Ask to press quit shortcut again before quitting while music is playing or downloads are
+    // in progress
+    pub confirm_quit: bool,
+
+    // Show a footer with most used keys of focused section at the bottom of screen. It can also be
+    // toggled from inside the app with hints shortcut key
+    pub key_hints: bool,
+
+    // Search the query typed in searchbar once typing stops for this many milliseconds without
+    // pressing Enter. 0 to only search on Enter
+    pub live_search_ms: u64,
+
+    // Only search songs in music search instead of every video. Youtube queries are then sent to
+    // YouTube Music when search_backend is invidious. Toggled with search_scope shortcut key
+    pub songs_only: bool,
+
+    // Draw with bold, high contrast colours and show focus, selection and playback state with
+    // text and attributes instead of only colour. Theme colours are not used then. Toggled with
+    // contrast shortcut key
+    pub high_contrast: bool,
+
+    // Show music section on the left and playlist and artist section on the right when terminal
+    // is at least this many columns wide. 0 to always show music section above them
+    pub wide_columns: u16,
+
+    // Shown before music, playlist and artist that are in favourites. "*" is used instead of
+    // non-ascii icon with ascii_only. Empty to not show anything
+    pub favourite_icon: String,
+
+    // Let desktop media controls, widgets and playerctl see and control the player over D-Bus
+    // (MPRIS). Media keys are grabbed from desktops that need it. Only works where session bus is
+    // available (usually linux and bsd desktops)
+    pub mpris: bool,
+
+    // User token of ListenBrainz to which every music played for half of it's duration (or 4
+    // minutes) is submitted as listen. Empty to not scrobble
+    pub listenbrainz_token: String,
+
+    // Port of http server through which player can be controlled remotely (also from remote page
+    // served at /). 0 to not run the server. Every request needs remote_token so server is not run without it
+    pub remote_port: u16,
+    pub remote_token: String,
+    // Address the server listens on. Default only accepts connection from this computer. Phone can
+    // reach it (and the remote page) only when this is LAN address of computer or 0.0.0.0
+    pub remote_host: String,
+
+    // Let `ytui_music ctl` control the app through unix socket (or named pipe on windows). See
+    // ui/ctl.rs in front-end
+    pub control_socket: bool,
+
+    // Send desktop notification with title, artist and thumbnail whenever new music starts
+    // playing. Can also be switched while app is running with `notify` command
+    pub track_notifications: bool,
+
+    // File (or named pipe) to which music being played is written for status bars. Empty to not
+    // write it. See ui/status_file.rs in front-end for placeholders of status_format
+    pub status_file: String,
+    pub status_format: String,
+    // File to which music being played is written for overlay of OBS (or other streaming tool).
+    // Empty to not write it. Same placeholders as of status_format can be used
+    pub now_playing_file: String,
+    pub now_playing_format: String,
+
+    // Urls to which playback events (track-start, track-end, pause, resume and queue-empty) are
+    // POSTed as json. See ui/webhook.rs in front-end for the body
+    pub webhooks: Vec<String>,
+
+    // MQTT broker (host or host:port) to which playback state is published. Empty to not publish.
+    // See ui/mqtt.rs in front-end for topics under mqtt_topic_prefix
+    pub mqtt_broker: String,
+    pub mqtt_topic_prefix: String,
+    // Left empty when broker allows anonymous clients
+    pub mqtt_username: String,
+    pub mqtt_password: String,
+
+    // Name of Chromecast or DLNA device (as shown in `cast` command) to cast to as soon as app
+    // starts. Empty to play on this computer
+    pub cast_device: String,
+    // Name of audio device (as known by mpv) to play to. Chosen with `output` command. Empty (or
+    // device that is not connected) to play to default device of system
+    pub audio_device: String,
+}
+
+// Column of music section. Name is one of "title", "artist", "duration", "views" or "uploaded".
+// Width is relative to the width of other shown columns. When the section is too narrow to show
+// every column, columns with lower priority are hidden first
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+pub struct MusicColumn {
+    pub name: String,
+    pub width: u16,
+    #[serde(default)]
+    pub priority: u8,
+}
+
+impl MusicColumn {
+    fn new(name: &str, width: u16, priority: u8) -> Self {
+        MusicColumn {
+            name: name.to_string(),
+            width,
+            priority,
+        }
+    }
 }
 
 impl Default for Constants {
@@ -223,6 +730,48 @@ impl Default for Constants {
                 String::from("playlist:"),
                 String::from("artist:"),
             ],
+            search_backend: String::from("invidious"),
+            soundcloud_client_id: String::new(),
+            proxy: String::new(),
+            cache_ttl_secs: 3600,
+            new_release_check_mins: 30,
+            notify_new_releases: false,
+            album_art: String::from("auto"),
+            vim_keys: false,
+            sidebar_width: 20,
+            music_height: 50,
+            layout: String::from("full"),
+            ascii_only: false,
+            music_columns: vec![
+                MusicColumn::new("title", 55, 2),
+                MusicColumn::new("artist", 30, 0),
+                MusicColumn::new("duration", 15, 1),
+            ],
+            confirm_quit: false,
+            key_hints: true,
+            live_search_ms: 0,
+            songs_only: false,
+            high_contrast: false,
+            wide_columns: 0,
+            favourite_icon: String::from("★"),
+            mpris: true,
+            listenbrainz_token: String::new(),
+            remote_port: 0,
+            remote_token: String::new(),
+            remote_host: String::from("127.0.0.1"),
+            control_socket: true,
+            track_notifications: false,
+            status_file: String::new(),
+            status_format: String::from("{artist} - {title} {elapsed}/{duration}"),
+            now_playing_file: String::new(),
+            now_playing_format: String::from("{title}\n{artist}"),
+            webhooks: Vec::new(),
+            mqtt_broker: String::new(),
+            mqtt_topic_prefix: String::from("ytui_music"),
+            mqtt_username: String::new(),
+            mqtt_password: String::new(),
+            cast_device: String::new(),
+            audio_device: String::new(),
         }
     }
 }
@@ -288,6 +837,26 @@ impl Default for Downloads {
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct Local {
+    pub path: String,
+}
+
+impl Default for Local {
+    fn default() -> Self {
+        // Same as of Downloads::path but not having audio directory is not an error here.
+        // Local music is simply empty in that case
+        let music_folder = match std::env::var(AUDIO_DIR_VAR_KEY) {
+            Ok(audio_dir) => audio_dir,
+            Err(_) => dirs::audio_dir()
+                .map(|audio_dir| audio_dir.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        };
+
+        Local { path: music_folder }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct MpvOptions {
     config_path: String,
@@ -309,6 +878,8 @@ impl Default for MpvOptions {
 pub struct Config {
     #[serde(default, rename = "ShortcutKeys")]
     pub shortcut_keys: ShortcutsKeys,
+    #[serde(default, rename = "ChordKeys")]
+    pub chord_keys: ChordKeys,
     #[serde(default, rename = "Colors")]
     pub theme: Theme,
     #[serde(default, rename = "Servers")]
@@ -319,6 +890,8 @@ pub struct Config {
     pub mpv: MpvOptions,
     #[serde(default, rename = "Downloads")]
     pub download: Downloads,
+    #[serde(default, rename = "LocalMusic")]
+    pub local: Local,
 }
 
 impl Config {
@@ -469,6 +1042,34 @@ impl ConfigContainer {
         Some(())
     }
 
+    // Change a single value of Constants in config file. File is edited as plain json so that
+    // everything else (including the paths that are resolved while reading) is left as written.
+    // Order of keys is kept too (serde_json is built with preserve_order). Error is returned rather
+    // than printed as this is called while the ui is drawn
+    pub fn save_constant(key: &str, value: impl Serialize) -> Result<(), String> {
+        let file_path =
+            Self::get_config_path().ok_or_else(|| "Cannot find config file".to_string())?;
+        let content = std::fs::read_to_string(&file_path)
+            .map_err(|err| format!("Unable to read config file. Error: {}", err))?;
+        let mut config = serde_json::from_str::<serde_json::Value>(&content)
+            .map_err(|err| format!("Invalid format of config file. Error: {}", err))?;
+
+        let constants = config
+            .as_object_mut()
+            .map(|root| {
+                root.entry("Constants")
+                    .or_insert_with(|| serde_json::json!({}))
+            })
+            .and_then(serde_json::Value::as_object_mut)
+            .ok_or_else(|| "Invalid format of config file".to_string())?;
+        let value = serde_json::to_value(value).map_err(|err| err.to_string())?;
+        constants.insert(key.to_string(), value);
+
+        let content = serde_json::ser::to_string_pretty(&config).map_err(|err| err.to_string())?;
+        std::fs::write(&file_path, content)
+            .map_err(|err| format!("Unable to write config to file. Error: {}", err))
+    }
+
     pub fn get_config_dir() -> Option<path::PathBuf> {
         // If $YTUI_MUSIC_CONFIG_DIR env is set. Use it
         if let Ok(val) = std::env::var("YTUI_MUSIC_CONFIG_DIR") {
@@ -545,10 +1146,45 @@ impl ConfigContainer {
                     name    TEXT    NOT NULL,
                     count   TEXT    NOT NULL
                 );
+
+                CREATE TABLE IF NOT EXISTS {tb_podcast} (
+                    id          TEXT    NOT NULL    PRIMARY KEY,
+                    position    TEXT    NOT NULL
+                );
+
+                CREATE TABLE IF NOT EXISTS {tb_subscriptions} (
+                    id      TEXT    NOT NULL    PRIMARY KEY,
+                    name    TEXT    NOT NULL,
+                    count   TEXT    NOT NULL
+                );
+
+                CREATE TABLE IF NOT EXISTS {tb_last_seen} (
+                    id          TEXT    NOT NULL    PRIMARY KEY,
+                    published   INTEGER NOT NULL
+                );
+
+                CREATE TABLE IF NOT EXISTS {tb_history} (
+                    id          TEXT    NOT NULL    PRIMARY KEY,
+                    title       TEXT    NOT NULL,
+                    author      TEXT    NOT NULL,
+                    duration    TEXT    NOT NULL,
+                    played_at   INTEGER NOT NULL
+                );
+
+                CREATE TABLE IF NOT EXISTS {tb_cache} (
+                    path        TEXT    NOT NULL    PRIMARY KEY,
+                    fetched_at  INTEGER NOT NULL,
+                    body        TEXT    NOT NULL
+                );
            ",
             tb_music = initilize::TB_FAVOURATES_MUSIC,
             tb_playlist = initilize::TB_FAVOURATES_PLAYLIST,
-            tb_artist = initilize::TB_FAVOURATES_ARTIST
+            tb_artist = initilize::TB_FAVOURATES_ARTIST,
+            tb_podcast = initilize::TB_PODCAST_POSITION,
+            tb_cache = initilize::TB_RESPONSE_CACHE,
+            tb_subscriptions = initilize::TB_SUBSCRIPTIONS,
+            tb_last_seen = initilize::TB_LAST_SEEN,
+            tb_history = initilize::TB_HISTORY
         );
 
         let res = connection.execute_batch(&create_favourates_table);
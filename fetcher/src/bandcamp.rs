@@ -0,0 +1,451 @@
+use crate::{ExtendDuration, FetchError, Fetcher, ReturnAction};
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+
+// Bandcamp do not have any public api. Search is done with the same endpoint used by search box
+// of bandcamp.com and album content is read from the json embedded in album page itself.
+// Playing does not need any special handling as the player (via youtube-dl) understands bandcamp
+// track and album urls directly.
+const SEARCH_URL: &str = "https://bandcamp.com/api/bcsearch_public_api/1/autocomplete_elastic";
+// Type of result as used by search api. Index is same as of FILTER_TYPE in utils.rs.
+// i.e track, album and band(artist) respectively
+const SEARCH_FILTER: [&str; 3] = ["t", "a", "b"];
+// Album page keeps all the info about album and it's tracks in this html attribute
+const TRALBUM_ATTRIBUTE: &str = "data-tralbum=\"";
+
+#[derive(Deserialize)]
+struct BcSearchResponse {
+    auto: BcSearchResults,
+}
+
+#[derive(Deserialize)]
+struct BcSearchResults {
+    #[serde(default)]
+    results: Vec<BcSearchItem>,
+}
+
+#[derive(Deserialize)]
+struct BcSearchItem {
+    #[serde(rename = "type")]
+    kind: String,
+    name: String,
+    #[serde(default)]
+    band_name: Option<String>,
+    #[serde(default)]
+    item_url_path: Option<String>,
+    #[serde(default)]
+    item_url_root: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BcTralbum {
+    #[serde(default)]
+    artist: String,
+    #[serde(default)]
+    current: BcTralbumCurrent,
+    // In the form of "01 Jan 2020 00:00:00 GMT"
+    #[serde(default)]
+    album_release_date: Option<String>,
+    #[serde(default)]
+    trackinfo: Vec<BcTrackInfo>,
+}
+
+#[derive(Deserialize, Default)]
+struct BcTralbumCurrent {
+    #[serde(default)]
+    title: String,
+}
+
+#[derive(Deserialize)]
+struct BcTrackInfo {
+    #[serde(default)]
+    title: String,
+    // null for tracks that can not be streamed
+    #[serde(default)]
+    duration: Option<f64>,
+    #[serde(default)]
+    title_link: Option<String>,
+}
+
+// Ids of bandcamp units are their full url. Artist may use their own domain instead of
+// <name>.bandcamp.com in which case only album/track url can be recognized by the /album/ or
+// /track/ path
+pub fn is_bandcamp_id(id: &str) -> bool {
+    id.contains("://")
+        && (id.contains(".bandcamp.com") || id.contains("/album/") || id.contains("/track/"))
+}
+
+// Origin of url. i.e https://artist.bandcamp.com from https://artist.bandcamp.com/album/name
+fn url_root(url: &str) -> &str {
+    let after_scheme = url.find("://").map(|pos| pos + 3).unwrap_or_default();
+    match url[after_scheme..].find('/') {
+        Some(pos) => &url[..after_scheme + pos],
+        None => url,
+    }
+}
+
+fn unescape_html(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+impl BcSearchResponse {
+    // Search api also returns other kind of results along with the requested one
+    fn results_of(self, filter_index: usize) -> Vec<BcSearchItem> {
+        self.auto
+            .results
+            .into_iter()
+            .filter(|item| item.kind == SEARCH_FILTER[filter_index])
+            .collect()
+    }
+}
+
+impl BcSearchItem {
+    fn into_music(self) -> Option<super::MusicUnit> {
+        Some(super::MusicUnit {
+            artist: self.band_name.unwrap_or_default(),
+            name: self.name,
+            // Search result do not include duration
+            duration: Duration::default().to_string(),
+            id: self.item_url_path?,
+            live: false,
+            views: String::new(),
+            published: String::new(),
+        })
+    }
+
+    fn into_playlist(self) -> Option<super::PlaylistUnit> {
+        Some(super::PlaylistUnit {
+            name: self.name,
+            id: self.item_url_path?,
+            author: self.band_name.unwrap_or_default(),
+            video_count: "NaN".to_string(),
+        })
+    }
+
+    fn into_artist(self) -> Option<super::ArtistUnit> {
+        Some(super::ArtistUnit {
+            name: self.name,
+            id: self.item_url_root.or(self.item_url_path)?,
+            video_count: "NaN".to_string(),
+        })
+    }
+}
+
+// Read the album (or the single track) from the json in data-tralbum attribute of the page
+fn parse_album(page: &str, album_url: &str) -> Option<super::Album> {
+    let start = page.find(TRALBUM_ATTRIBUTE)? + TRALBUM_ATTRIBUTE.len();
+    let end = start + page[start..].find('"')?;
+    let tralbum = serde_json::from_str::<BcTralbum>(&unescape_html(&page[start..end])).ok()?;
+
+    let root = url_root(album_url);
+    let tracks = tralbum
+        .trackinfo
+        .into_iter()
+        .map(|track| super::MusicUnit {
+            artist: tralbum.artist.clone(),
+            name: track.title,
+            duration: track
+                .duration
+                .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+                .unwrap_or_default()
+                .to_string(),
+            id: match track.title_link {
+                Some(link) => format!("{}{}", root, link),
+                None => album_url.to_string(),
+            },
+            live: false,
+            views: String::new(),
+            published: String::new(),
+        })
+        .collect();
+    let year = tralbum
+        .album_release_date
+        .as_deref()
+        .and_then(|date| date.split_whitespace().nth(2))
+        .map(str::to_string);
+
+    Some(super::Album {
+        id: album_url.to_string(),
+        title: tralbum.current.title,
+        artist: tralbum.artist,
+        year,
+        tracks,
+    })
+}
+
+// Releases listed in the /music page of band. `root` is the origin of band page
+fn parse_band_albums(page: &str, root: &str) -> Vec<super::PlaylistUnit> {
+    let band_name = page
+        .split("<meta property=\"og:site_name\" content=\"")
+        .nth(1)
+        .and_then(|rest| rest.split('"').next())
+        .map(unescape_html)
+        .unwrap_or_default();
+
+    // Each release is in the form of:
+    // <li data-item-id="album-123" ...> <a href="/album/name"> ... <p class="title">Name</p>
+    page.split("<li data-item-id=\"")
+        .skip(1)
+        .filter_map(|item| {
+            let href = item.split("href=\"").nth(1)?.split('"').next()?;
+            let title = item
+                .split("<p class=\"title\">")
+                .nth(1)?
+                .split('<')
+                .next()?
+                .trim();
+            let id = if href.starts_with('/') {
+                format!("{}{}", root, href)
+            } else {
+                href.to_string()
+            };
+            Some(super::PlaylistUnit {
+                name: unescape_html(title),
+                id,
+                author: band_name.clone(),
+                video_count: "NaN".to_string(),
+            })
+        })
+        .collect()
+}
+
+impl Fetcher {
+    async fn bandcamp_search(
+        &mut self,
+        query: &str,
+        page: usize,
+        filter_index: usize,
+    ) -> Result<Vec<BcSearchItem>, ReturnAction> {
+        // Search api returns all the result at once. So there is nothing on next pages
+        if page > 0 {
+            return Ok(Vec::new());
+        }
+
+        let body = json!({
+            "search_text": query,
+            "search_filter": SEARCH_FILTER[filter_index],
+            "full_page": false,
+            "fan_id": null,
+        });
+        let request = self.client.post(SEARCH_URL).json(&body);
+        let res = self.execute(request).await;
+        match res {
+            Ok(response) => match response.json::<BcSearchResponse>().await {
+                Ok(obj) => Ok(obj.results_of(filter_index)),
+                Err(_) => Err(ReturnAction::Failed(FetchError::Parse)),
+            },
+            Err(action) => Err(action),
+        }
+    }
+
+    pub async fn bandcamp_search_music(
+        &mut self,
+        query: &str,
+        page: usize,
+    ) -> Result<Vec<super::MusicUnit>, ReturnAction> {
+        let items = self.bandcamp_search(query, page, 0).await?;
+        Ok(items
+            .into_iter()
+            .filter_map(BcSearchItem::into_music)
+            .collect())
+    }
+
+    pub async fn bandcamp_search_playlist(
+        &mut self,
+        query: &str,
+        page: usize,
+    ) -> Result<Vec<super::PlaylistUnit>, ReturnAction> {
+        let items = self.bandcamp_search(query, page, 1).await?;
+        Ok(items
+            .into_iter()
+            .filter_map(BcSearchItem::into_playlist)
+            .collect())
+    }
+
+    pub async fn bandcamp_search_artist(
+        &mut self,
+        query: &str,
+        page: usize,
+    ) -> Result<Vec<super::ArtistUnit>, ReturnAction> {
+        let items = self.bandcamp_search(query, page, 2).await?;
+        Ok(items
+            .into_iter()
+            .filter_map(BcSearchItem::into_artist)
+            .collect())
+    }
+
+    async fn bandcamp_page(&mut self, url: &str) -> Result<String, ReturnAction> {
+        let request = self.client.get(url);
+        let response = self.execute(request).await?;
+        response
+            .text()
+            .await
+            .map_err(|_| ReturnAction::Failed(FetchError::Network))
+    }
+
+    // Tracks of album (or the single track when url is of track) as read from the page
+    pub async fn bandcamp_album_tracks(
+        &mut self,
+        album_url: &str,
+    ) -> Result<Vec<super::MusicUnit>, ReturnAction> {
+        self.bandcamp_album(album_url)
+            .await
+            .map(|album| album.tracks)
+    }
+
+    pub async fn bandcamp_album(&mut self, album_url: &str) -> Result<super::Album, ReturnAction> {
+        let page = self.bandcamp_page(album_url).await?;
+        parse_album(&page, album_url).ok_or(ReturnAction::Failed(FetchError::Parse))
+    }
+
+    // Albums and tracks released by the band as listed in the /music page of band
+    pub async fn bandcamp_band_albums(
+        &mut self,
+        band_url: &str,
+    ) -> Result<Vec<super::PlaylistUnit>, ReturnAction> {
+        let root = url_root(band_url);
+        let page = self.bandcamp_page(&format!("{}/music", root)).await?;
+
+        Ok(parse_band_albums(&page, root))
+    }
+
+    // Bandcamp do not have any list of tracks for band. So tracks of every release are collected
+    // instead. Release that fails to load is skipped rather than failing the whole list
+    pub async fn bandcamp_band_tracks(
+        &mut self,
+        band_url: &str,
+    ) -> Result<Vec<super::MusicUnit>, ReturnAction> {
+        let albums = self.bandcamp_band_albums(band_url).await?;
+        let mut tracks = Vec::new();
+        for album in albums {
+            if let Ok(album_tracks) = self.bandcamp_album_tracks(&album.id).await {
+                tracks.extend(album_tracks);
+            }
+        }
+        Ok(tracks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Trimmed down response of search api. Track search also returns albums and bands
+    const SEARCH_RESPONSE: &str = r#"{"auto": {"results": [
+        {"type": "t", "id": 1, "name": "Some track", "band_name": "Some band",
+            "item_url_root": "https://someband.bandcamp.com",
+            "item_url_path": "https://someband.bandcamp.com/track/some-track"},
+        {"type": "a", "id": 2, "name": "Some album", "band_name": "Some band",
+            "item_url_root": "https://someband.bandcamp.com",
+            "item_url_path": "https://someband.bandcamp.com/album/some-album"},
+        {"type": "b", "id": 3, "name": "Some band",
+            "item_url_root": "https://someband.bandcamp.com"},
+        {"type": "t", "id": 4, "name": "No url"}
+    ]}}"#;
+
+    const ALBUM_PAGE: &str = r#"<html><head></head><body>
+        <script data-tralbum="{&quot;artist&quot;:&quot;Some band&quot;,&quot;current&quot;:{&quot;title&quot;:&quot;Rock &amp; Roll&quot;},&quot;album_release_date&quot;:&quot;01 Jan 2020 00:00:00 GMT&quot;,&quot;trackinfo&quot;:[{&quot;title&quot;:&quot;It&#39;s first&quot;,&quot;duration&quot;:215.4,&quot;title_link&quot;:&quot;/track/first&quot;},{&quot;title&quot;:&quot;Unreleased&quot;,&quot;duration&quot;:null,&quot;title_link&quot;:null}]}" src="x.js"></script>
+        </body></html>"#;
+
+    #[test]
+    fn search_results() {
+        let response = || serde_json::from_str::<BcSearchResponse>(SEARCH_RESPONSE).unwrap();
+
+        let music = response()
+            .results_of(0)
+            .into_iter()
+            .filter_map(BcSearchItem::into_music)
+            .collect::<Vec<_>>();
+        assert_eq!(music.len(), 1);
+        assert_eq!(music[0].name, "Some track");
+        assert_eq!(music[0].artist, "Some band");
+        assert_eq!(
+            music[0].id,
+            "https://someband.bandcamp.com/track/some-track"
+        );
+        assert_eq!(music[0].duration, "00:00");
+
+        let albums = response()
+            .results_of(1)
+            .into_iter()
+            .filter_map(BcSearchItem::into_playlist)
+            .collect::<Vec<_>>();
+        assert_eq!(albums.len(), 1);
+        assert_eq!(
+            albums[0].id,
+            "https://someband.bandcamp.com/album/some-album"
+        );
+        assert_eq!(albums[0].author, "Some band");
+
+        let bands = response()
+            .results_of(2)
+            .into_iter()
+            .filter_map(BcSearchItem::into_artist)
+            .collect::<Vec<_>>();
+        assert_eq!(bands.len(), 1);
+        assert_eq!(bands[0].id, "https://someband.bandcamp.com");
+    }
+
+    #[test]
+    fn album_from_tralbum() {
+        let url = "https://someband.bandcamp.com/album/rock-roll";
+        let album = parse_album(ALBUM_PAGE, url).unwrap();
+        assert_eq!(album.title, "Rock & Roll");
+        assert_eq!(album.artist, "Some band");
+        assert_eq!(album.year.as_deref(), Some("2020"));
+        assert_eq!(album.tracks.len(), 2);
+        assert_eq!(album.tracks[0].name, "It's first");
+        assert_eq!(album.tracks[0].duration, "03:35");
+        assert_eq!(
+            album.tracks[0].id,
+            "https://someband.bandcamp.com/track/first"
+        );
+        // Track without its own page is played from album
+        assert_eq!(album.tracks[1].duration, "00:00");
+        assert_eq!(album.tracks[1].id, url);
+    }
+
+    #[test]
+    fn album_without_tralbum() {
+        assert!(parse_album("<html></html>", "https://x.bandcamp.com/album/y").is_none());
+        assert!(parse_album(r#"<div data-tralbum="{broken"#, "https://x.bandcamp.com").is_none());
+    }
+
+    #[test]
+    fn band_albums() {
+        let page = r#"<meta property="og:site_name" content="Band &amp; Co">
+            <ol><li data-item-id="album-1" class="music-grid-item"><a href="/album/first">
+                <p class="title">
+                    First &amp; Best
+                </p></a></li>
+            <li data-item-id="track-2"><a href="https://other.example/track/single">
+                <p class="title">Single</p></a></li>
+            <li data-item-id="album-3">no link here</li></ol>"#;
+        let albums = parse_band_albums(page, "https://band.bandcamp.com");
+        assert_eq!(albums.len(), 2);
+        assert_eq!(albums[0].name, "First & Best");
+        assert_eq!(albums[0].id, "https://band.bandcamp.com/album/first");
+        assert_eq!(albums[0].author, "Band & Co");
+        assert_eq!(albums[1].id, "https://other.example/track/single");
+    }
+
+    #[test]
+    fn urls() {
+        assert_eq!(
+            url_root("https://band.bandcamp.com/album/name"),
+            "https://band.bandcamp.com"
+        );
+        assert_eq!(
+            url_root("https://band.bandcamp.com"),
+            "https://band.bandcamp.com"
+        );
+        assert!(is_bandcamp_id("https://band.bandcamp.com"));
+        assert!(is_bandcamp_id("https://ownsite.example/album/x"));
+        assert!(!is_bandcamp_id("bandcamp.com"));
+    }
+}
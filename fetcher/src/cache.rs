@@ -0,0 +1,63 @@
+use config::initilize::{CONFIG, STORAGE, TB_RESPONSE_CACHE};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Responses from invidious servers are saved in storage db keyed by request path (without the
+// server part as every instance gives the same response). Response younger than
+// `cache_ttl_secs` is served from here instead of sending the request again so that revisiting
+// same artist, playlist or search page is instant. Only successfully parsed responses are saved.
+
+fn now_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+fn ttl() -> i64 {
+    CONFIG.constants.cache_ttl_secs.min(i64::MAX as u64) as i64
+}
+
+// Body of cached response to this path if it is still fresh
+pub(crate) fn lookup(path: &str) -> Option<String> {
+    if ttl() == 0 {
+        return None;
+    }
+
+    let conn = STORAGE.lock().unwrap();
+    let query = format!(
+        "SELECT body FROM {tb_name} WHERE path = ? AND fetched_at > ?",
+        tb_name = TB_RESPONSE_CACHE
+    );
+    conn.query_row(
+        &query,
+        [path.to_string(), (now_secs() - ttl()).to_string()],
+        |row| row.get::<_, String>(0),
+    )
+    .ok()
+}
+
+pub(crate) fn store(path: &str, body: &str) {
+    if ttl() == 0 {
+        return;
+    }
+
+    let conn = STORAGE.lock().unwrap();
+    let query = format!(
+        "INSERT OR REPLACE INTO {tb_name} (path, fetched_at, body) VALUES (?, ?, ?)",
+        tb_name = TB_RESPONSE_CACHE
+    );
+    if let Err(err) = conn.execute(&query, [path, &now_secs().to_string(), body]) {
+        eprintln!("Cannot cache response. Error: {err}", err = err);
+    }
+}
+
+// Remove every expired response so that db does not keep growing. This is done once on startup
+pub(crate) fn purge_expired() {
+    let conn = STORAGE.lock().unwrap();
+    let query = format!(
+        "DELETE FROM {tb_name} WHERE fetched_at <= ?",
+        tb_name = TB_RESPONSE_CACHE
+    );
+    conn.execute(&query, [(now_secs() - ttl()).to_string()])
+        .ok();
+}
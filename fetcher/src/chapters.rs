@@ -0,0 +1,160 @@
+use serde::Deserialize;
+use std::time::Duration;
+
+// Long mixes and podcasts usually list their chapters in description as lines like:
+//  00:00 Intro
+//  [03:12] - Second song
+//  1:02:45 | Outro
+// This is also what youtube itself use to show chapters. Same rule as youtube is followed here
+// i.e first chapter should start at 00:00 and there should be at least 2 chapters in ascending
+// order. Otherwise it is likely just some timestamp mentioned in description
+
+#[derive(Deserialize)]
+pub(crate) struct FetchDescriptionRes {
+    #[serde(default)]
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Chapter {
+    pub start: Duration,
+    pub title: String,
+}
+
+// Parse time in form of h:mm:ss or mm:ss. Brackets around time are ignored
+fn parse_timestamp(text: &str) -> Option<Duration> {
+    let text = text.trim_matches(|ch| matches!(ch, '[' | ']' | '(' | ')'));
+    let components = text.split(':').collect::<Vec<&str>>();
+    if components.len() < 2 || components.len() > 3 {
+        return None;
+    }
+    let mut secs: u64 = 0;
+    for component in components {
+        if component.is_empty() || !component.chars().all(|ch| ch.is_ascii_digit()) {
+            return None;
+        }
+        // Description is user written so absurdly long numbers are rejected instead of overflowing
+        secs = secs
+            .checked_mul(60)?
+            .checked_add(component.parse::<u64>().ok()?)?;
+    }
+    Some(Duration::from_secs(secs))
+}
+
+pub fn parse_chapters(description: &str) -> Vec<Chapter> {
+    let mut chapters: Vec<Chapter> = Vec::new();
+    for line in description.lines() {
+        let words = line.split_whitespace().collect::<Vec<&str>>();
+        let time_at = match words
+            .iter()
+            .position(|word| parse_timestamp(word).is_some())
+        {
+            Some(index) => index,
+            None => continue,
+        };
+        let start = parse_timestamp(words[time_at]).unwrap_or_default();
+
+        // Remaining words (before or after the time) is the title. Separator between time and
+        // title is not part of title
+        let title = words
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != time_at)
+            .map(|(_, word)| *word)
+            .collect::<Vec<&str>>()
+            .join(" ");
+        let title = title
+            .trim_matches(|ch: char| {
+                matches!(ch, '-' | '–' | '|' | ':' | '.') || ch.is_whitespace()
+            })
+            .to_string();
+
+        // Timestamp out of order is some other mention of time (eg: in comments below
+        // tracklist) rather than chapter
+        if matches!(chapters.last(), Some(last) if last.start >= start) {
+            continue;
+        }
+        chapters.push(Chapter { start, title });
+    }
+
+    match chapters.first() {
+        Some(first) if first.start.as_secs() == 0 && chapters.len() >= 2 => chapters,
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn starts(chapters: &[Chapter]) -> Vec<u64> {
+        chapters
+            .iter()
+            .map(|chapter| chapter.start.as_secs())
+            .collect()
+    }
+
+    #[test]
+    fn timestamp_forms() {
+        assert_eq!(parse_timestamp("03:12"), Some(Duration::from_secs(192)));
+        assert_eq!(parse_timestamp("[03:12]"), Some(Duration::from_secs(192)));
+        assert_eq!(
+            parse_timestamp("(1:02:45)"),
+            Some(Duration::from_secs(3765))
+        );
+        assert_eq!(parse_timestamp("12"), None);
+        assert_eq!(parse_timestamp("1:2:3:4"), None);
+        assert_eq!(parse_timestamp("1:"), None);
+        assert_eq!(parse_timestamp("1:-2"), None);
+        assert_eq!(parse_timestamp("a:12"), None);
+    }
+
+    #[test]
+    fn timestamp_overflow_is_rejected() {
+        assert_eq!(parse_timestamp("99999999999999999999:00"), None);
+        assert_eq!(parse_timestamp("18446744073709551615:00"), None);
+        assert_eq!(parse_timestamp("307445734561825860:59:59"), None);
+    }
+
+    #[test]
+    fn tracklist_is_parsed() {
+        let description = "Tracklist:\n\
+            00:00 Intro\n\
+            [03:12] - Second song\n\
+            1:02:45 | Outro\n\
+            Thanks for listening";
+        let chapters = parse_chapters(description);
+        assert_eq!(starts(&chapters), vec![0, 192, 3765]);
+        let titles = chapters
+            .iter()
+            .map(|chapter| chapter.title.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(titles, vec!["Intro", "Second song", "Outro"]);
+    }
+
+    #[test]
+    fn title_before_time() {
+        let chapters = parse_chapters("Intro - 0:00\nMain part - 2:30");
+        assert_eq!(starts(&chapters), vec![0, 150]);
+        assert_eq!(chapters[1].title, "Main part");
+    }
+
+    #[test]
+    fn out_of_order_lines_are_skipped() {
+        let description = "00:00 Intro\n05:00 Middle\n02:00 mentioned in a comment\n07:00 End";
+        let chapters = parse_chapters(description);
+        assert_eq!(starts(&chapters), vec![0, 300, 420]);
+    }
+
+    #[test]
+    fn first_chapter_must_start_at_zero() {
+        assert!(parse_chapters("00:10 Intro\n03:00 Song").is_empty());
+    }
+
+    #[test]
+    fn single_chapter_is_not_chapters() {
+        assert!(parse_chapters("00:00 Full album").is_empty());
+        assert!(parse_chapters("No timestamps here\nat all").is_empty());
+        assert!(parse_chapters("").is_empty());
+    }
+}
@@ -0,0 +1,25 @@
+use serde::Deserialize;
+
+// Comments of youtube music as given by invidious. Comments are served in chunks. Next chunk is
+// requested with the continuation token of previous chunk. Chunk without continuation is the last
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct Comment {
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub content: String,
+    #[serde(default, rename = "likeCount")]
+    pub likes: u64,
+    #[serde(default, rename = "publishedText")]
+    pub published: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct CommentsChunk {
+    #[serde(default)]
+    pub comments: Vec<Comment>,
+    // None when there is no more comments
+    #[serde(default)]
+    pub continuation: Option<String>,
+}
@@ -0,0 +1,230 @@
+// Filters that can be written anywhere inside the search query in the form of `<key>:<value>`.
+// eg: `Coding music duration:>20m date:month sort:views`
+// Supported filters are:
+//  - type:     music | playlist | artist. Same as prefixing the query with search_by_type term
+//  - duration: short (< 4 min) | medium (4 to 20 min) | long (> 20 min) or range like
+//              `>20m`, `<4m`, `5m-10m`. Number without unit is taken as minutes.
+//  - date:     hour | today | week | month | year. Only music uploaded within this time
+//  - sort:     relevance | rating | date | views
+// Any word that is not a known filter is left in the query as is. Not every backend understands
+// every filter. Duration is applied to result of all backends while date and sort is only
+// understood by invidious.
+
+const SHORT_DURATION_SECS: u64 = 4 * 60;
+const LONG_DURATION_SECS: u64 = 20 * 60;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SearchFilter {
+    // Index of result type as in FILTER_TYPE in utils.rs. i.e music, playlist, artist
+    pub kind: Option<usize>,
+    // Minimum and maximum duration in seconds (both inclusive)
+    pub duration: (Option<u64>, Option<u64>),
+    pub date: Option<&'static str>,
+    pub sort: Option<&'static str>,
+}
+
+// Parse the duration like 20m, 1h, 90s or 20 into seconds
+fn parse_duration_secs(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let (number, multiplier) = match text.chars().last()? {
+        's' | 'S' => (&text[..text.len() - 1], 1),
+        'm' | 'M' => (&text[..text.len() - 1], 60),
+        'h' | 'H' => (&text[..text.len() - 1], 60 * 60),
+        _ => (text, 60),
+    };
+    number
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|num| num.checked_mul(multiplier))
+}
+
+fn parse_duration_range(value: &str) -> Option<(Option<u64>, Option<u64>)> {
+    match value.to_lowercase().as_str() {
+        "short" => return Some((None, Some(SHORT_DURATION_SECS))),
+        "medium" => return Some((Some(SHORT_DURATION_SECS), Some(LONG_DURATION_SECS))),
+        "long" => return Some((Some(LONG_DURATION_SECS), None)),
+        _ => {}
+    }
+
+    if let Some(min) = value.strip_prefix('>') {
+        Some((
+            Some(parse_duration_secs(min.trim_start_matches('='))?),
+            None,
+        ))
+    } else if let Some(max) = value.strip_prefix('<') {
+        Some((
+            None,
+            Some(parse_duration_secs(max.trim_start_matches('='))?),
+        ))
+    } else if let Some((min, max)) = value.split_once('-') {
+        Some((
+            Some(parse_duration_secs(min)?),
+            Some(parse_duration_secs(max)?),
+        ))
+    } else {
+        None
+    }
+}
+
+impl SearchFilter {
+    // Split the filters from query. Returned string is the query without any filter in it
+    pub fn from_query(query: &str) -> (SearchFilter, String) {
+        let mut filter = SearchFilter::default();
+        let mut remaining = Vec::new();
+
+        for word in query.split_whitespace() {
+            let parsed = match word.split_once(':') {
+                Some((key, value)) => filter.apply(&key.to_lowercase(), value),
+                None => false,
+            };
+            if !parsed {
+                remaining.push(word);
+            }
+        }
+
+        (filter, remaining.join(" "))
+    }
+
+    // Returns false if key is not a known filter or value is not valid for that key
+    fn apply(&mut self, key: &str, value: &str) -> bool {
+        let value_lower = value.to_lowercase();
+        match key {
+            "type" => {
+                self.kind = match value_lower.as_str() {
+                    "music" | "song" | "video" => Some(0),
+                    "playlist" | "album" => Some(1),
+                    "artist" | "channel" => Some(2),
+                    _ => return false,
+                }
+            }
+            "duration" => match parse_duration_range(value) {
+                Some(range) => self.duration = range,
+                None => return false,
+            },
+            "date" => {
+                self.date = Some(match value_lower.as_str() {
+                    "hour" => "hour",
+                    "today" | "day" => "today",
+                    "week" => "week",
+                    "month" => "month",
+                    "year" => "year",
+                    _ => return false,
+                })
+            }
+            "sort" => {
+                self.sort = Some(match value_lower.as_str() {
+                    "relevance" => "relevance",
+                    "rating" => "rating",
+                    "date" | "upload_date" | "new" => "upload_date",
+                    "views" | "view_count" | "popular" => "view_count",
+                    _ => return false,
+                })
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    // Extra query parameters to append to invidious search request
+    pub fn invidious_params(&self) -> String {
+        let mut params = String::new();
+        // Invidious only have coarse duration filter. Exact range is still applied afterwards
+        let duration = match self.duration {
+            (_, Some(max)) if max <= SHORT_DURATION_SECS => Some("short"),
+            (Some(min), _) if min >= LONG_DURATION_SECS => Some("long"),
+            _ => None,
+        };
+        if let Some(duration) = duration {
+            params.push_str(&format!("&duration={}", duration));
+        }
+        if let Some(date) = self.date {
+            params.push_str(&format!("&date={}", date));
+        }
+        if let Some(sort) = self.sort {
+            params.push_str(&format!("&sort_by={}", sort));
+        }
+        params
+    }
+
+    fn accept_duration(&self, duration: &str) -> bool {
+        use crate::ExtendDuration;
+        let secs = std::time::Duration::from_string(duration).as_secs();
+        // Some backends do not know the duration (shown as 00:00). Do not hide those
+        if secs == 0 {
+            return true;
+        }
+        let too_short = matches!(self.duration.0, Some(min) if secs < min);
+        let too_long = matches!(self.duration.1, Some(max) if secs > max);
+        !too_short && !too_long
+    }
+}
+
+// Check if the unit should be kept in search result after applying the filter
+pub trait FilterUnit {
+    fn passes(&self, filter: &SearchFilter) -> bool;
+}
+
+impl FilterUnit for super::MusicUnit {
+    fn passes(&self, filter: &SearchFilter) -> bool {
+        filter.accept_duration(&self.duration)
+    }
+}
+
+impl FilterUnit for super::PlaylistUnit {
+    fn passes(&self, _filter: &SearchFilter) -> bool {
+        true
+    }
+}
+
+impl FilterUnit for super::ArtistUnit {
+    fn passes(&self, _filter: &SearchFilter) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duration_words() {
+        let (filter, query) = SearchFilter::from_query("lofi duration:short");
+        assert_eq!(filter.duration, (None, Some(SHORT_DURATION_SECS)));
+        assert_eq!(query, "lofi");
+    }
+
+    #[test]
+    fn duration_ranges() {
+        let (filter, _) = SearchFilter::from_query("duration:>20m");
+        assert_eq!(filter.duration, (Some(20 * 60), None));
+        let (filter, _) = SearchFilter::from_query("duration:4m-10m");
+        assert_eq!(filter.duration, (Some(4 * 60), Some(10 * 60)));
+        let (filter, _) = SearchFilter::from_query("duration:<=90s");
+        assert_eq!(filter.duration, (None, Some(90)));
+    }
+
+    #[test]
+    fn unknown_filter_stays_in_query() {
+        let (filter, query) =
+            SearchFilter::from_query("live at:wembley duration:forever sort:views");
+        assert_eq!(filter.duration, (None, None));
+        assert_eq!(filter.sort, Some("view_count"));
+        assert_eq!(query, "live at:wembley duration:forever");
+    }
+
+    #[test]
+    fn overflowing_duration_stays_in_query() {
+        let (filter, query) = SearchFilter::from_query("lofi duration:>9999999999999999999h");
+        assert_eq!(filter.duration, (None, None));
+        assert_eq!(query, "lofi duration:>9999999999999999999h");
+    }
+
+    #[test]
+    fn unknown_duration_is_accepted() {
+        let (filter, _) = SearchFilter::from_query("duration:long");
+        assert!(filter.accept_duration("00:00"));
+        assert!(filter.accept_duration("1:02:00"));
+        assert!(!filter.accept_duration("03:30"));
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{FetchError, Fetcher, ReturnAction};
+use config::initilize::{STORAGE, TB_HISTORY};
+
+// Every music that starts playing is kept in history, newest first. Music played again is moved to
+// the top instead of being listed twice. History is kept across sessions until it is cleared
+
+// Add the music to the top of history
+pub fn record(music: &super::MusicUnit) {
+    let conn = STORAGE.lock().unwrap();
+    let query = format!(
+        "INSERT OR REPLACE INTO {tb_name} (id, title, author, duration, played_at)
+        VALUES (?, ?, ?, ?, ?)",
+        tb_name = TB_HISTORY
+    );
+    let played_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        .to_string();
+    let params = [
+        &music.id,
+        &music.name,
+        &music.artist,
+        &music.duration,
+        &played_at,
+    ];
+    if let Err(err) = conn.execute(&query, params) {
+        eprintln!("Cannot add music to history. Error: {err}", err = err);
+    }
+}
+
+// Remove everything from history. Returns number of removed music
+pub fn clear() -> usize {
+    let conn = STORAGE.lock().unwrap();
+    let query = format!("DELETE FROM {tb_name}", tb_name = TB_HISTORY);
+    conn.execute(&query, []).unwrap_or_else(|err| {
+        eprintln!("Cannot clear history. Error: {err}", err = err);
+        0
+    })
+}
+
+impl Fetcher {
+    pub async fn get_history(
+        &mut self,
+        page: usize,
+    ) -> Result<Vec<super::MusicUnit>, ReturnAction> {
+        let conn = STORAGE.lock().unwrap();
+        let query = format!(
+            "SELECT id, title, author, duration FROM {tb_name}
+            ORDER BY played_at DESC, rowid DESC LIMIT {from}, {count}",
+            tb_name = TB_HISTORY,
+            from = page * self.item_per_page,
+            count = self.item_per_page
+        );
+
+        let mut stmt = match conn.prepare(&query) {
+            Ok(stmt) => stmt,
+            Err(err) => {
+                eprintln!(
+                    "Error preparing select statement for history. Error: {err}",
+                    err = err
+                );
+                return Err(ReturnAction::Failed(FetchError::Storage));
+            }
+        };
+        let results = stmt.query_map([], |row| {
+            Ok(super::MusicUnit {
+                id: row.get(0).unwrap_or_default(),
+                name: row.get(1).unwrap_or("SQL_ERROR".into()),
+                artist: row.get(2).unwrap_or("SQL_ERROR".into()),
+                duration: row.get(3).unwrap_or("3:0".into()),
+                live: false,
+                views: String::new(),
+                published: String::new(),
+            })
+        });
+
+        let res = match results {
+            Ok(results) => results
+                .filter_map(Result::ok)
+                .collect::<Vec<super::MusicUnit>>(),
+            Err(err) => {
+                eprintln!("Cannot get history. Error: {err}", err = err);
+                return Err(ReturnAction::Failed(FetchError::Storage));
+            }
+        };
+        if res.is_empty() {
+            Err(ReturnAction::EOR)
+        } else {
+            Ok(res)
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use config::initilize::{STORAGE, TB_FAVOURATES_ARTIST, TB_SUBSCRIPTIONS};
+use serde::Deserialize;
+
+// Subscriptions exported from other youtube clients can be imported as subscribed or favourite
+// artists. Format of file is detected from content itself:
+// - NewPipe: json with `subscriptions` array of {service_id, url, name}
+// - FreeTube: profiles.db where every line is a json profile with `subscriptions` array of
+//   {id, name}. Channel subscribed in multiple profiles is imported once
+// - Google Takeout: subscriptions.csv with header `Channel Id,Channel Url,Channel Title`
+
+#[derive(Deserialize)]
+struct NewPipeExport {
+    subscriptions: Vec<NewPipeChannel>,
+}
+
+#[derive(Deserialize)]
+struct NewPipeChannel {
+    // 0 is youtube and 1 is soundcloud. Channels of other services cannot be played from here
+    service_id: u32,
+    url: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct FreeTubeProfile {
+    #[serde(default)]
+    subscriptions: Vec<FreeTubeChannel>,
+}
+
+#[derive(Deserialize)]
+struct FreeTubeChannel {
+    id: String,
+    name: String,
+}
+
+fn artist(id: String, name: String) -> super::ArtistUnit {
+    super::ArtistUnit {
+        name,
+        id,
+        video_count: "NaN".to_string(),
+    }
+}
+
+// NewPipe gives the url of channel instead of id. Soundcloud url is itself the id
+fn from_newpipe(export: NewPipeExport) -> Vec<super::ArtistUnit> {
+    export
+        .subscriptions
+        .into_iter()
+        .filter_map(|channel| {
+            let id = match channel.service_id {
+                0 => match super::open_target(&channel.url, false)? {
+                    super::OpenTarget::Artist(id) => id,
+                    _ => return None,
+                },
+                1 => channel.url,
+                _ => return None,
+            };
+            Some(artist(id, channel.name))
+        })
+        .collect()
+}
+
+fn from_freetube(content: &str) -> Option<Vec<super::ArtistUnit>> {
+    let profiles = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(serde_json::from_str::<FreeTubeProfile>)
+        .collect::<Result<Vec<FreeTubeProfile>, _>>()
+        .ok()?;
+    Some(
+        profiles
+            .into_iter()
+            .flat_map(|profile| profile.subscriptions)
+            .map(|channel| artist(channel.id, channel.name))
+            .collect(),
+    )
+}
+
+// Title is the last column and may itself contain comma in which case it is quoted
+fn from_takeout(content: &str) -> Option<Vec<super::ArtistUnit>> {
+    let mut lines = content.lines();
+    if !lines
+        .next()?
+        .trim_start_matches('\u{feff}')
+        .starts_with("Channel Id")
+    {
+        return None;
+    }
+    Some(
+        lines
+            .filter_map(|line| {
+                let mut columns = line.splitn(3, ',');
+                let id = columns.next()?.trim();
+                let _url = columns.next()?;
+                let title = columns.next()?.trim();
+                let title = match title.strip_prefix('"').and_then(|t| t.strip_suffix('"')) {
+                    Some(quoted) => quoted.replace("\"\"", "\""),
+                    None => title.to_string(),
+                };
+                if id.is_empty() {
+                    None
+                } else {
+                    Some(artist(id.to_string(), title))
+                }
+            })
+            .collect(),
+    )
+}
+
+// Artists from the exported file. Same artist is listed only once
+pub fn parse_subscriptions(content: &str) -> Result<Vec<super::ArtistUnit>, &'static str> {
+    let mut artists = if let Ok(export) = serde_json::from_str::<NewPipeExport>(content) {
+        from_newpipe(export)
+    } else if let Some(artists) = from_takeout(content) {
+        artists
+    } else if let Some(artists) = from_freetube(content) {
+        artists
+    } else {
+        return Err("Unknown format. Expected NewPipe json, FreeTube profiles.db or Takeout csv");
+    };
+
+    let mut seen = std::collections::HashSet::new();
+    artists.retain(|artist| seen.insert(artist.id.clone()));
+    Ok(artists)
+}
+
+// Save the artists as subscribed (or favourite when `as_favourite` is true). Artists that are
+// already saved are left as they are. Returns the number of newly saved artists
+pub fn save_artists(artists: &[super::ArtistUnit], as_favourite: bool) -> usize {
+    let conn = STORAGE.lock().unwrap();
+    let query = format!(
+        "INSERT OR IGNORE INTO {tb_name} (id, name, count) VALUES (?, ?, ?)",
+        tb_name = if as_favourite {
+            TB_FAVOURATES_ARTIST
+        } else {
+            TB_SUBSCRIPTIONS
+        }
+    );
+    artists
+        .iter()
+        .map(|artist| {
+            conn.execute(&query, [&artist.id, &artist.name, &artist.video_count])
+                .unwrap_or_else(|err| {
+                    eprintln!(
+                        "Cannot import artist {name}. Error: {err}",
+                        name = artist.name,
+                        err = err
+                    );
+                    0
+                })
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids_and_names(artists: Vec<crate::ArtistUnit>) -> Vec<(String, String)> {
+        artists
+            .into_iter()
+            .map(|artist| (artist.id, artist.name))
+            .collect()
+    }
+
+    fn pair(id: &str, name: &str) -> (String, String) {
+        (id.to_string(), name.to_string())
+    }
+
+    #[test]
+    fn newpipe() {
+        let content = r#"{"app_version": "0.24.0", "subscriptions": [
+            {"service_id": 0, "url": "https://www.youtube.com/channel/UCabc", "name": "Tube"},
+            {"service_id": 1, "url": "https://soundcloud.com/someone", "name": "Cloud"},
+            {"service_id": 3, "url": "https://peertube.example/c/x", "name": "Peer"}
+        ]}"#;
+        assert_eq!(
+            ids_and_names(parse_subscriptions(content).unwrap()),
+            [
+                pair("UCabc", "Tube"),
+                pair("https://soundcloud.com/someone", "Cloud")
+            ]
+        );
+    }
+
+    #[test]
+    fn takeout() {
+        let content = concat!(
+            "\u{feff}Channel Id,Channel Url,Channel Title\n",
+            "UCone,http://www.youtube.com/channel/UCone,Plain\n",
+            "UCtwo,http://www.youtube.com/channel/UCtwo,\"Comma, and \"\"quote\"\"\"\n",
+            "\n"
+        );
+        assert_eq!(
+            ids_and_names(parse_subscriptions(content).unwrap()),
+            [
+                pair("UCone", "Plain"),
+                pair("UCtwo", "Comma, and \"quote\"")
+            ]
+        );
+    }
+
+    #[test]
+    fn freetube() {
+        let content = concat!(
+            r#"{"name": "All Channels", "subscriptions": [{"id": "UCone", "name": "One"}]}"#,
+            "\n",
+            r#"{"name": "Empty", "_id": "x"}"#,
+            "\n"
+        );
+        assert_eq!(
+            ids_and_names(parse_subscriptions(content).unwrap()),
+            [pair("UCone", "One")]
+        );
+    }
+
+    #[test]
+    fn same_artist_once() {
+        let content = concat!(
+            r#"{"subscriptions": [{"id": "UCone", "name": "One"}, {"id": "UCtwo", "name": "Two"}]}"#,
+            "\n",
+            r#"{"subscriptions": [{"id": "UCone", "name": "One again"}]}"#
+        );
+        assert_eq!(
+            ids_and_names(parse_subscriptions(content).unwrap()),
+            [pair("UCone", "One"), pair("UCtwo", "Two")]
+        );
+    }
+
+    #[test]
+    fn unknown_format() {
+        assert!(parse_subscriptions("not an export").is_err());
+    }
+}
@@ -0,0 +1,764 @@
+use crate::{FetchError, Fetcher, ReturnAction};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+// InnerTube is the internal api used by the YouTube Music web client itself. Unlike invidious this
+// api returns the result in the form of renderer objects (the way the web page draws them) so
+// the response is deeply nested and the fields of interest are extracted with json pointers below.
+// The upside is that the result have proper music structure. i.e songs and regular videos are
+// different things, artist is the real artist (not the uploader channel) and so on.
+const INNERTUBE_BASE_URL: &str = "https://music.youtube.com/youtubei/v1";
+const CLIENT_NAME: &str = "WEB_REMIX";
+const CLIENT_VERSION: &str = "1.20240101.01.00";
+
+// Search filter params as sent by the web client when user selects the filter chip in search page.
+// Index is same as of FILTER_TYPE in utils.rs. i.e music (songs only), playlist (community
+// playlist) and artist respectively
+const SEARCH_PARAMS: [&str; 3] = [
+    "EgWKAQIIAWoMEA4QChADEAQQCRAF",
+    "EgeKAQQoAEABagwQDhAKEAMQBBAJEAU=",
+    "EgWKAQIgAWoMEA4QChADEAQQCRAF",
+];
+
+// Country code which YouTube Music uses for global charts
+pub const GLOBAL_CHARTS: &str = "ZZ";
+
+// Category in moods and genres page of YouTube Music. eg: Chill, Focus, Workout, Pop
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoodCategory {
+    // Group this category is listed under. eg: "Moods & moments" or "Genres"
+    pub group: String,
+    pub title: String,
+    // Sent along with browse request to get playlists of this category. Acts as id of category
+    pub params: String,
+}
+
+// Separator used by YouTube Music between different information in single text column.
+// eg: `Artist • Album • 3:45`
+const RUN_SEPARATOR: &str = " • ";
+
+// Collect the text runs of the given flex column of musicResponsiveListItemRenderer
+fn column_runs(item: &Value, column: usize) -> Vec<&Value> {
+    item.pointer(&format!(
+        "/flexColumns/{}/musicResponsiveListItemFlexColumnRenderer/text/runs",
+        column
+    ))
+    .and_then(Value::as_array)
+    .map(|runs| runs.iter().collect())
+    .unwrap_or_default()
+}
+
+fn run_text(run: &Value) -> &str {
+    run.get("text").and_then(Value::as_str).unwrap_or_default()
+}
+
+// Split the runs of the column into groups separated by RUN_SEPARATOR and join the text of each
+// group. Multiple artist for example are in separate runs but in the same group.
+fn column_groups(item: &Value, column: usize) -> Vec<String> {
+    let mut groups = vec![String::new()];
+    for run in column_runs(item, column) {
+        let text = run_text(run);
+        if text == RUN_SEPARATOR {
+            groups.push(String::new());
+        } else {
+            groups.last_mut().unwrap().push_str(text);
+        }
+    }
+    groups.retain(|group| !group.trim().is_empty());
+    groups
+}
+
+// YouTube Music returns duration as "3:45" or "1:02:03". Reformat it in the same way as invidious
+// results are formatted so that all list looks consistent
+fn normalize_duration(text: &str) -> Option<String> {
+    use crate::ExtendDuration;
+    let is_duration = !text.is_empty()
+        && text.contains(':')
+        && text.chars().all(|ch| ch.is_ascii_digit() || ch == ':')
+        && text.split(':').count() <= 3
+        // Longer parts are not duration and would overflow in from_string
+        && text.split(':').all(|part| !part.is_empty() && part.len() <= 4);
+    if is_duration {
+        Some(Duration::from_string(text).to_string())
+    } else {
+        None
+    }
+}
+
+// Extract the number from string like "123 songs"
+fn leading_count(text: &str) -> Option<String> {
+    let count: String = text
+        .trim()
+        .chars()
+        .take_while(|ch| ch.is_ascii_digit() || *ch == ',')
+        .filter(char::is_ascii_digit)
+        .collect();
+    if count.is_empty() {
+        None
+    } else {
+        Some(count)
+    }
+}
+
+fn parse_song(item: &Value) -> Option<super::MusicUnit> {
+    let id = item
+        .pointer("/playlistItemData/videoId")
+        .or_else(|| {
+            column_runs(item, 0)
+                .first()?
+                .pointer("/navigationEndpoint/watchEndpoint/videoId")
+        })?
+        .as_str()?
+        .to_string();
+    let name = column_runs(item, 0)
+        .into_iter()
+        .map(run_text)
+        .collect::<String>();
+
+    // In filtered song search second column is: <artists> • <album> • <duration>
+    let details = column_groups(item, 1);
+    let artist = details.first().cloned().unwrap_or_default();
+    let duration = details
+        .iter()
+        .rev()
+        .find_map(|group| normalize_duration(group))
+        .unwrap_or_else(|| "00:00".to_string());
+    // Video search also has: <views> views (or plays) before duration
+    let views = details
+        .iter()
+        .find_map(|group| {
+            group
+                .strip_suffix(" views")
+                .or_else(|| group.strip_suffix(" plays"))
+        })
+        .unwrap_or_default()
+        .to_string();
+
+    Some(super::MusicUnit {
+        artist,
+        name,
+        duration,
+        id,
+        live: false,
+        views,
+        published: String::new(),
+    })
+}
+
+// Label of type that leads the details of playlist in unfiltered results
+const PLAYLIST_TYPE_LABELS: [&str; 4] = ["Playlist", "Album", "EP", "Single"];
+
+fn parse_playlist(item: &Value) -> Option<super::PlaylistUnit> {
+    let id = item
+        .pointer("/navigationEndpoint/browseEndpoint/browseId")
+        .and_then(Value::as_str)
+        .map(|browse_id| browse_id.trim_start_matches("VL").to_string())
+        .or_else(|| {
+            item.pointer("/overlay/musicItemThumbnailOverlayRenderer/content/musicPlayButtonRenderer/playNavigationEndpoint/watchPlaylistEndpoint/playlistId")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        })?;
+    let name = column_runs(item, 0)
+        .into_iter()
+        .map(run_text)
+        .collect::<String>();
+
+    // Second column is: <author> • <count> songs or <author> • <views> views. Outside of filtered
+    // playlist search it starts with the type (Playlist, Album, EP or Single) which is skipped
+    let details = column_groups(item, 1);
+    let labeled = details
+        .first()
+        .is_some_and(|group| PLAYLIST_TYPE_LABELS.contains(&group.as_str()));
+    let author = details
+        .get(usize::from(labeled))
+        .cloned()
+        .unwrap_or_default();
+    let video_count = details
+        .iter()
+        .filter(|group| group.contains("song") || group.contains("track"))
+        .find_map(|group| leading_count(group))
+        .unwrap_or_else(|| "NaN".to_string());
+
+    Some(super::PlaylistUnit {
+        name,
+        id,
+        author,
+        video_count,
+    })
+}
+
+fn parse_artist(item: &Value) -> Option<super::ArtistUnit> {
+    let id = item
+        .pointer("/navigationEndpoint/browseEndpoint/browseId")?
+        .as_str()?
+        .to_string();
+    let name = column_runs(item, 0)
+        .into_iter()
+        .map(run_text)
+        .collect::<String>();
+
+    Some(super::ArtistUnit {
+        name,
+        id,
+        video_count: "NaN".to_string(),
+    })
+}
+
+// Playlist shown as a card (musicTwoRowItemRenderer) as in charts and home page
+fn parse_playlist_card(item: &Value) -> Option<super::PlaylistUnit> {
+    let id = item
+        .pointer("/navigationEndpoint/browseEndpoint/browseId")?
+        .as_str()?
+        .strip_prefix("VL")?
+        .to_string();
+    let run_texts = |pointer: &str| {
+        item.pointer(pointer)
+            .and_then(Value::as_array)
+            .map(|runs| runs.iter().map(run_text).collect::<String>())
+            .unwrap_or_default()
+    };
+
+    Some(super::PlaylistUnit {
+        name: run_texts("/title/runs"),
+        id,
+        author: run_texts("/subtitle/runs"),
+        video_count: "NaN".to_string(),
+    })
+}
+
+// Charts page is made of carousels (Top songs, Trending, Video charts, Top artists..). Items of
+// every carousel are sorted into music, playlist and artist by what they navigate to
+fn extract_charts(response: &Value, country: &str) -> super::ChartsRes {
+    let mut charts = super::ChartsRes {
+        country: country.to_string(),
+        ..Default::default()
+    };
+    let carousels = section_list(response)
+        .into_iter()
+        .filter_map(|section| section.pointer("/musicCarouselShelfRenderer/contents"))
+        .filter_map(Value::as_array)
+        .flatten();
+
+    for item in carousels {
+        if let Some(item) = item.get("musicResponsiveListItemRenderer") {
+            let is_artist = item
+                .pointer("/navigationEndpoint/browseEndpoint/browseId")
+                .and_then(Value::as_str)
+                .is_some_and(|browse_id| browse_id.starts_with("UC"));
+            if is_artist {
+                charts.artist.extend(parse_artist(item));
+            } else {
+                charts.music.extend(parse_song(item));
+            }
+        } else if let Some(item) = item.get("musicTwoRowItemRenderer") {
+            charts.playlist.extend(parse_playlist_card(item));
+        }
+    }
+    charts
+}
+
+fn section_list(response: &Value) -> Vec<&Value> {
+    response
+        .pointer("/contents/singleColumnBrowseResultsRenderer/tabs/0/tabRenderer/content/sectionListRenderer/contents")
+        .and_then(Value::as_array)
+        .map(|sections| sections.iter().collect())
+        .unwrap_or_default()
+}
+
+// Moods and genres page is made of grids of buttons each of which opens the category
+fn extract_mood_categories(response: &Value) -> Vec<MoodCategory> {
+    let mut categories = Vec::new();
+    for grid in section_list(response)
+        .into_iter()
+        .filter_map(|section| section.get("gridRenderer"))
+    {
+        let group = grid
+            .pointer("/header/gridHeaderRenderer/title/runs/0/text")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let buttons = grid
+            .get("items")
+            .and_then(Value::as_array)
+            .map(|items| items.as_slice())
+            .unwrap_or_default();
+        categories.extend(
+            buttons
+                .iter()
+                .filter_map(|item| item.get("musicNavigationButtonRenderer"))
+                .filter_map(|button| {
+                    Some(MoodCategory {
+                        group: group.to_string(),
+                        title: button
+                            .pointer("/buttonText/runs/0/text")?
+                            .as_str()?
+                            .to_string(),
+                        params: button
+                            .pointer("/clickCommand/browseEndpoint/params")?
+                            .as_str()?
+                            .to_string(),
+                    })
+                }),
+        );
+    }
+    categories
+}
+
+// Page of a category have carousels and grids of playlists (Featured playlists, Community
+// playlists, ..). Playlists of all of them are collected in order
+fn extract_mood_playlists(response: &Value) -> Vec<super::PlaylistUnit> {
+    section_list(response)
+        .into_iter()
+        .filter_map(|section| {
+            section
+                .pointer("/musicCarouselShelfRenderer/contents")
+                .or_else(|| section.pointer("/gridRenderer/items"))
+        })
+        .filter_map(Value::as_array)
+        .flatten()
+        .filter_map(|item| item.get("musicTwoRowItemRenderer"))
+        .filter_map(parse_playlist_card)
+        .collect()
+}
+
+// Release year shown in header of album page. Subtitle of header is like: Album • 2019 (or
+// EP • Artist • 2019 in newer layout)
+fn extract_album_year(response: &Value) -> Option<String> {
+    let header = response.pointer("/header/musicDetailHeaderRenderer").or_else(|| {
+        response.pointer("/contents/twoColumnBrowseResultsRenderer/tabs/0/tabRenderer/content/sectionListRenderer/contents/0/musicResponsiveHeaderRenderer")
+    })?;
+    header
+        .pointer("/subtitle/runs")?
+        .as_array()?
+        .iter()
+        .map(run_text)
+        .find(|text| text.len() == 4 && text.chars().all(|ch| ch.is_ascii_digit()))
+        .map(str::to_string)
+}
+
+// Chunk of the list for given page
+fn page_of<T: Clone>(
+    list: &[T],
+    page: usize,
+    item_per_page: usize,
+) -> Result<Vec<T>, ReturnAction> {
+    let lower_limit = page * item_per_page;
+    let upper_limit = std::cmp::min(list.len(), lower_limit + item_per_page);
+    if lower_limit >= upper_limit {
+        Err(ReturnAction::EOR)
+    } else {
+        Ok(list[lower_limit..upper_limit].to_vec())
+    }
+}
+
+// Get the items and continuation token from the musicShelfRenderer (on first request) or from
+// musicShelfContinuation (on continuation request)
+fn extract_shelf(response: &Value) -> (Vec<&Value>, Option<String>) {
+    let shelf = response
+        .pointer("/contents/tabbedSearchResultsRenderer/tabs/0/tabRenderer/content/sectionListRenderer/contents")
+        .and_then(Value::as_array)
+        .and_then(|sections| {
+            sections
+                .iter()
+                .find_map(|section| section.get("musicShelfRenderer"))
+        })
+        .or_else(|| response.pointer("/continuationContents/musicShelfContinuation"));
+
+    let shelf = match shelf {
+        Some(val) => val,
+        None => return (Vec::new(), None),
+    };
+
+    let items = shelf
+        .get("contents")
+        .and_then(Value::as_array)
+        .map(|contents| {
+            contents
+                .iter()
+                .filter_map(|content| content.get("musicResponsiveListItemRenderer"))
+                .collect()
+        })
+        .unwrap_or_default();
+    let continuation = shelf
+        .pointer("/continuations/0/nextContinuationData/continuation")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+
+    (items, continuation)
+}
+
+impl Fetcher {
+    fn innertube_context(&self) -> Value {
+        json!({
+            "client": {
+                "clientName": CLIENT_NAME,
+                "clientVersion": CLIENT_VERSION,
+                "hl": "en",
+                "gl": self.region,
+            }
+        })
+    }
+
+    // All the request to InnerTube api should be send from this function
+    async fn send_innertube_request(
+        &mut self,
+        path: &str,
+        body: Value,
+    ) -> Result<Value, ReturnAction> {
+        let url = format!("{}{}", INNERTUBE_BASE_URL, path);
+        let request = self
+            .client
+            .post(url)
+            .header("Origin", "https://music.youtube.com")
+            .json(&body);
+        let res = self.execute(request).await;
+
+        match res {
+            Ok(response) => match response.json::<Value>().await {
+                Ok(obj) => Ok(obj),
+                Err(_) => Err(ReturnAction::Failed(FetchError::Parse)),
+            },
+            Err(action) => Err(action),
+        }
+    }
+
+    // Fetch the next chunk of search result of given type. When page is 0 or query is different
+    // than the one of stored continuation, search is started from beginning. Otherwise the stored
+    // continuation is used to get next chunk. Empty result is returned when there is nothing more.
+    async fn ytmusic_search<T>(
+        &mut self,
+        query: &str,
+        page: usize,
+        filter_index: usize,
+        parse: fn(&Value) -> Option<T>,
+    ) -> Result<Vec<T>, ReturnAction> {
+        let is_new_query = self.ytmusic_continuation[filter_index].0 != query;
+        let response = if page == 0 || is_new_query {
+            let body = json!({
+                "context": self.innertube_context(),
+                "query": query,
+                "params": SEARCH_PARAMS[filter_index],
+            });
+            self.send_innertube_request("/search?prettyPrint=false", body)
+                .await?
+        } else {
+            let continuation = match &self.ytmusic_continuation[filter_index].1 {
+                Some(token) => token.clone(),
+                None => return Ok(Vec::new()),
+            };
+            let path = format!(
+                "/search?ctoken={token}&continuation={token}&type=next&prettyPrint=false",
+                token = continuation
+            );
+            let body = json!({ "context": self.innertube_context() });
+            self.send_innertube_request(&path, body).await?
+        };
+
+        let (items, continuation) = extract_shelf(&response);
+        self.ytmusic_continuation[filter_index] = (query.to_string(), continuation);
+
+        let mut res: Vec<T> = items.into_iter().filter_map(parse).collect();
+        res.shrink_to_fit();
+        Ok(res)
+    }
+
+    // Fetch the charts of given country (GLOBAL_CHARTS for global) unless it is already fetched
+    async fn load_charts(&mut self, country: &str) -> Result<&super::ChartsRes, ReturnAction> {
+        let is_new_charts = !matches!(&self.charts, Some(charts) if charts.country == country);
+        if is_new_charts {
+            let body = json!({
+                "context": self.innertube_context(),
+                "browseId": "FEmusic_charts",
+                "formData": { "selectedValues": [country] },
+            });
+            let response = self
+                .send_innertube_request("/browse?prettyPrint=false", body)
+                .await?;
+            let charts = extract_charts(&response, country);
+            if charts.music.is_empty() && charts.playlist.is_empty() && charts.artist.is_empty() {
+                // Charts is not available for every country
+                return Err(ReturnAction::Failed(FetchError::NotFound));
+            }
+            self.charts = Some(charts);
+        }
+        Ok(self.charts.as_ref().unwrap())
+    }
+
+    pub async fn get_chart_music(
+        &mut self,
+        country: &str,
+        page: usize,
+    ) -> Result<Vec<super::MusicUnit>, ReturnAction> {
+        let item_per_page = self.item_per_page;
+        let charts = self.load_charts(country).await?;
+        page_of(&charts.music, page, item_per_page)
+    }
+
+    pub async fn get_chart_playlists(
+        &mut self,
+        country: &str,
+        page: usize,
+    ) -> Result<Vec<super::PlaylistUnit>, ReturnAction> {
+        let item_per_page = self.item_per_page;
+        let charts = self.load_charts(country).await?;
+        page_of(&charts.playlist, page, item_per_page)
+    }
+
+    pub async fn get_chart_artists(
+        &mut self,
+        country: &str,
+        page: usize,
+    ) -> Result<Vec<super::ArtistUnit>, ReturnAction> {
+        let item_per_page = self.item_per_page;
+        let charts = self.load_charts(country).await?;
+        page_of(&charts.artist, page, item_per_page)
+    }
+
+    pub async fn get_mood_categories(&mut self) -> Result<Vec<MoodCategory>, ReturnAction> {
+        if self.mood_categories.is_empty() {
+            let body = json!({
+                "context": self.innertube_context(),
+                "browseId": "FEmusic_moods_and_genres",
+            });
+            let response = self
+                .send_innertube_request("/browse?prettyPrint=false", body)
+                .await?;
+            self.mood_categories = extract_mood_categories(&response);
+        }
+        if self.mood_categories.is_empty() {
+            Err(ReturnAction::Failed(FetchError::Parse))
+        } else {
+            Ok(self.mood_categories.clone())
+        }
+    }
+
+    // params is the `params` of MoodCategory
+    pub async fn get_mood_playlists(
+        &mut self,
+        params: &str,
+        page: usize,
+    ) -> Result<Vec<super::PlaylistUnit>, ReturnAction> {
+        if self.mood_playlists.0 != params {
+            let body = json!({
+                "context": self.innertube_context(),
+                "browseId": "FEmusic_moods_and_genres_category",
+                "params": params,
+            });
+            let response = self
+                .send_innertube_request("/browse?prettyPrint=false", body)
+                .await?;
+            let mut playlists = extract_mood_playlists(&response);
+            playlists.shrink_to_fit();
+            self.mood_playlists = (params.to_string(), playlists);
+        }
+        page_of(&self.mood_playlists.1, page, self.item_per_page)
+    }
+
+    // Release year of youtube album. Album playlist as given by invidious do not have it so it is
+    // read from the page of album in YouTube Music. None when that fails as year is only shown
+    pub async fn ytmusic_album_year(&mut self, album_id: &str) -> Option<String> {
+        let body = json!({
+            "context": self.innertube_context(),
+            "browseId": format!("VL{}", album_id),
+        });
+        let response = self
+            .send_innertube_request("/browse?prettyPrint=false", body)
+            .await
+            .ok()?;
+        extract_album_year(&response)
+    }
+
+    pub async fn ytmusic_search_music(
+        &mut self,
+        query: &str,
+        page: usize,
+    ) -> Result<Vec<super::MusicUnit>, ReturnAction> {
+        self.ytmusic_search(query, page, 0, parse_song).await
+    }
+
+    pub async fn ytmusic_search_playlist(
+        &mut self,
+        query: &str,
+        page: usize,
+    ) -> Result<Vec<super::PlaylistUnit>, ReturnAction> {
+        self.ytmusic_search(query, page, 1, parse_playlist).await
+    }
+
+    pub async fn ytmusic_search_artist(
+        &mut self,
+        query: &str,
+        page: usize,
+    ) -> Result<Vec<super::ArtistUnit>, ReturnAction> {
+        self.ytmusic_search(query, page, 2, parse_artist).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runs(texts: &[&str]) -> Value {
+        let runs = texts
+            .iter()
+            .map(|text| json!({ "text": text }))
+            .collect::<Vec<Value>>();
+        json!({ "musicResponsiveListItemFlexColumnRenderer": { "text": { "runs": runs } } })
+    }
+
+    #[test]
+    fn groups_are_split_by_separator() {
+        let item = json!({
+            "flexColumns": [
+                runs(&["Song"]),
+                runs(&["Artist A", " & ", "Artist B", RUN_SEPARATOR, "Album", RUN_SEPARATOR, "3:45"]),
+            ]
+        });
+        assert_eq!(
+            column_groups(&item, 1),
+            vec!["Artist A & Artist B", "Album", "3:45"]
+        );
+        assert!(column_groups(&item, 2).is_empty());
+        assert!(column_groups(&json!({}), 0).is_empty());
+    }
+
+    #[test]
+    fn duration_is_normalized() {
+        assert_eq!(normalize_duration("3:45").as_deref(), Some("03:45"));
+        assert_eq!(normalize_duration("1:02:03").as_deref(), Some("1:02:03"));
+        assert_eq!(normalize_duration("0:07").as_deref(), Some("00:07"));
+        assert_eq!(normalize_duration("Album"), None);
+        assert_eq!(normalize_duration("1.2M views"), None);
+        assert_eq!(normalize_duration(""), None);
+        assert_eq!(normalize_duration("3:"), None);
+        assert_eq!(normalize_duration("1:2:3:4"), None);
+        assert_eq!(normalize_duration("99999999999999999999:00"), None);
+    }
+
+    #[test]
+    fn count_is_extracted() {
+        assert_eq!(leading_count("123 songs").as_deref(), Some("123"));
+        assert_eq!(leading_count(" 1,234 songs").as_deref(), Some("1234"));
+        assert_eq!(leading_count("songs"), None);
+        assert_eq!(leading_count(""), None);
+    }
+
+    #[test]
+    fn song_from_song_search() {
+        let item = json!({
+            "flexColumns": [
+                runs(&["Never Gonna Give You Up"]),
+                runs(&["Rick Astley", RUN_SEPARATOR, "Whenever You Need Somebody", RUN_SEPARATOR, "3:33"]),
+            ],
+            "playlistItemData": { "videoId": "lYBUbBu4W08" }
+        });
+        let song = parse_song(&item).unwrap();
+        assert_eq!(song.id, "lYBUbBu4W08");
+        assert_eq!(song.name, "Never Gonna Give You Up");
+        assert_eq!(song.artist, "Rick Astley");
+        assert_eq!(song.duration, "03:33");
+        assert_eq!(song.views, "");
+    }
+
+    #[test]
+    fn song_from_video_search() {
+        // No playlistItemData, id is in title run instead. Views is before duration
+        let mut title = runs(&["Some video"]);
+        title["musicResponsiveListItemFlexColumnRenderer"]["text"]["runs"][0]
+            ["navigationEndpoint"] = json!({ "watchEndpoint": { "videoId": "dQw4w9WgXcQ" } });
+        let item = json!({
+            "flexColumns": [
+                title,
+                runs(&["Uploader", RUN_SEPARATOR, "1.2B views", RUN_SEPARATOR, "1:02:03"]),
+            ]
+        });
+        let song = parse_song(&item).unwrap();
+        assert_eq!(song.id, "dQw4w9WgXcQ");
+        assert_eq!(song.artist, "Uploader");
+        assert_eq!(song.views, "1.2B");
+        assert_eq!(song.duration, "1:02:03");
+    }
+
+    #[test]
+    fn song_without_id_or_duration() {
+        let item = json!({ "flexColumns": [runs(&["No id"])] });
+        assert!(parse_song(&item).is_none());
+
+        let item = json!({
+            "flexColumns": [runs(&["Song"]), runs(&["Artist"])],
+            "playlistItemData": { "videoId": "lYBUbBu4W08" }
+        });
+        assert_eq!(parse_song(&item).unwrap().duration, "00:00");
+    }
+
+    #[test]
+    fn playlist_from_search() {
+        let item = json!({
+            "flexColumns": [
+                runs(&["Chill mix"]),
+                runs(&["Playlist", RUN_SEPARATOR, "Someone", RUN_SEPARATOR, "1,024 songs"]),
+            ],
+            "navigationEndpoint": { "browseEndpoint": { "browseId": "VLPLabc" } }
+        });
+        let playlist = parse_playlist(&item).unwrap();
+        assert_eq!(playlist.id, "PLabc");
+        assert_eq!(playlist.name, "Chill mix");
+        assert_eq!(playlist.author, "Someone");
+        assert_eq!(playlist.video_count, "1024");
+
+        // As in filtered playlist search
+        let item = json!({
+            "flexColumns": [
+                runs(&["Chill mix"]),
+                runs(&["Someone", RUN_SEPARATOR, "12 songs"]),
+            ],
+            "navigationEndpoint": { "browseEndpoint": { "browseId": "VLPLabc" } }
+        });
+        let playlist = parse_playlist(&item).unwrap();
+        assert_eq!(playlist.author, "Someone");
+        assert_eq!(playlist.video_count, "12");
+    }
+
+    #[test]
+    fn year_of_album() {
+        let subtitle = |texts: &[&str]| {
+            let runs = texts
+                .iter()
+                .map(|text| json!({ "text": text }))
+                .collect::<Vec<Value>>();
+            json!({ "subtitle": { "runs": runs } })
+        };
+        let response = json!({
+            "header": { "musicDetailHeaderRenderer": subtitle(&["Album", RUN_SEPARATOR, "2019"]) }
+        });
+        assert_eq!(extract_album_year(&response).as_deref(), Some("2019"));
+
+        let response = json!({ "contents": { "twoColumnBrowseResultsRenderer": { "tabs": [{
+            "tabRenderer": { "content": { "sectionListRenderer": { "contents": [{
+                "musicResponsiveHeaderRenderer":
+                    subtitle(&["EP", RUN_SEPARATOR, "Artist 1999", RUN_SEPARATOR, "2021"])
+            }] } } }
+        }] } } });
+        assert_eq!(extract_album_year(&response).as_deref(), Some("2021"));
+
+        let response = json!({
+            "header": { "musicDetailHeaderRenderer": subtitle(&["Playlist", RUN_SEPARATOR, "42 songs"]) }
+        });
+        assert_eq!(extract_album_year(&response), None);
+        assert_eq!(extract_album_year(&json!({})), None);
+    }
+
+    #[test]
+    fn playlist_from_play_button() {
+        let item = json!({
+            "flexColumns": [runs(&["Radio"]), runs(&["YouTube Music", RUN_SEPARATOR, "2.1M views"])],
+            "overlay": { "musicItemThumbnailOverlayRenderer": { "content": { "musicPlayButtonRenderer": {
+                "playNavigationEndpoint": { "watchPlaylistEndpoint": { "playlistId": "RDCLAKabc" } }
+            } } } }
+        });
+        let playlist = parse_playlist(&item).unwrap();
+        assert_eq!(playlist.id, "RDCLAKabc");
+        assert_eq!(playlist.author, "YouTube Music");
+        assert_eq!(playlist.video_count, "NaN");
+
+        assert!(parse_playlist(&json!({ "flexColumns": [runs(&["No id"])] })).is_none());
+    }
+}
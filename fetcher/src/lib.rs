@@ -1,4 +1,19 @@
 use serde::{self, Deserialize, Serialize};
+pub mod bandcamp;
+pub mod cache;
+pub mod chapters;
+pub mod comments;
+pub mod filter;
+pub mod history;
+pub mod import;
+pub mod innertube;
+pub mod listenbrainz;
+pub mod local;
+pub mod lyrics;
+pub mod ratelimit;
+pub mod soundcloud;
+pub mod subscriptions;
+pub mod thumbnail;
 pub mod utils;
 use std::time::Duration;
 
@@ -17,14 +32,31 @@ where
     Ok(res)
 }
 
+fn views_to_str<'de, D>(input: D) -> Result<String, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let views: u64 = Deserialize::deserialize(input)?;
+    Ok(compact_count(views))
+}
+
+// Count shown in short form like 1.2M or 34K so that it fits in narrow column
+fn compact_count(count: u64) -> String {
+    match count {
+        0..=999 => count.to_string(),
+        1_000..=999_999 => format!("{:.1}K", count as f64 / 1_000.0),
+        1_000_000..=999_999_999 => format!("{:.1}M", count as f64 / 1_000_000.0),
+        _ => format!("{:.1}B", count as f64 / 1_000_000_000.0),
+    }
+    .replace(".0", "")
+}
+
 fn seconds_to_str<'de, D>(input: D) -> Result<String, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
-    // Note: If duration is set to 0:0 from the json response
-    // the the video may be live ({islive: true, ..} in response)
-    // to keep things simple ignore all those details and this will simply return "0:0"
-    // this should be documented to inform the user
+    // Note: Live stream do not have duration and server sends 0 for those. This will simply
+    // return "00:00" and whether it is live or not is read from `liveNow` field. See MusicUnit
     let sec: u64 = Deserialize::deserialize(input)?;
     let dur: Duration = Duration::from_secs(sec);
     Ok(dur.to_string())
@@ -35,9 +67,33 @@ where
 // this structure is only used to convert such response to Vec<MusicUnit>
 #[derive(Deserialize, Clone, PartialEq)]
 struct FetchPlaylistContentRes {
+    // Mixes do not have author nor count
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    author: String,
+    #[serde(alias = "videoCount", default)]
+    video_count: usize,
+    videos: Vec<MusicUnit>,
+}
+
+// Same as FetchPlaylistContentRes but also have the information needed to build `Album`
+#[derive(Deserialize, Clone, PartialEq)]
+struct FetchAlbumRes {
+    title: String,
+    #[serde(default)]
+    author: String,
     videos: Vec<MusicUnit>,
 }
 
+// While fetching single video from endpoint /videos/:id
+// related videos are returned as "recommendedVideos": [ { <Fields of MusicUnit> } ]
+#[derive(Deserialize, Clone, PartialEq)]
+struct FetchRelatedRes {
+    #[serde(rename = "recommendedVideos")]
+    recommended_videos: Vec<MusicUnit>,
+}
+
 // Serve same purpose as described in struct FetchPlaylistContentRes but
 // to convert to Vec<PlaylistUnit>
 #[derive(Deserialize, Clone, PartialEq)]
@@ -64,6 +120,17 @@ pub struct MusicUnit {
     pub duration: String,
     #[serde(alias = "videoId")]
     pub id: String,
+    // true if this is an ongoing live stream. This includes the radio-style 24/7 music streams.
+    // Live stream have no duration and cannot be seeked.
+    // Only invidious tells about it. Music from other backends are never live
+    #[serde(alias = "liveNow", default)]
+    pub live: bool,
+    // Number of views in short form (eg: 1.2M) and when it was uploaded as told by server
+    // (eg: 3 years ago). These are empty when not known. Local files and favourites never have them
+    #[serde(alias = "viewCount", deserialize_with = "views_to_str", default)]
+    pub views: String,
+    #[serde(alias = "publishedText", default)]
+    pub published: String,
 }
 
 #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
@@ -76,6 +143,34 @@ pub struct ArtistUnit {
     #[serde(deserialize_with = "num_to_str")]
     pub video_count: String,
 }
+// What is shown about a channel above its music. Only youtube channels have this
+#[derive(Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ChannelInfo {
+    // Not sent by server. This is set to the id with which info was requested
+    #[serde(skip)]
+    pub id: String,
+    #[serde(alias = "author", default)]
+    pub name: String,
+    // Number of subscribers in short form (eg: 1.2M). Empty if channel hides it
+    #[serde(alias = "subCount", deserialize_with = "views_to_str", default)]
+    pub subscribers: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+// Album is the playlist which is a release of an artist. See `is_album_id`.
+// Unlike regular playlist tracks of album are always in the order of release and they have
+// information of album as a whole like artist and release year.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct Album {
+    pub id: String,
+    pub title: String,
+    pub artist: String,
+    // Not every backend tells the release year
+    pub year: Option<String>,
+    pub tracks: Vec<MusicUnit>,
+}
+
 #[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
 pub struct PlaylistUnit {
     #[serde(alias = "title")]
@@ -88,6 +183,179 @@ pub struct PlaylistUnit {
     pub video_count: String,
 }
 
+// Url of music that can be passed to the player or shown to the user.
+// Music from local directory already have the path of file as id and music from other backends
+// than youtube have full url as id so it is returned as is.
+pub fn music_url(music_id: &str) -> String {
+    if Fetcher::is_local_id(music_id) || music_id.contains("://") {
+        music_id.to_string()
+    } else {
+        format!("https://www.youtube.com/watch?v={}", music_id)
+    }
+}
+
+// Other urls from which youtube music can be played when it cannot be played from music_url. eg:
+// When music is blocked in user's region. Every invidious instance can proxy the audio stream
+// (itag 140 is m4a audio) through it's own location which is usually in different region.
+// Empty for music from other source
+pub fn fallback_music_urls(music_id: &str) -> Vec<String> {
+    if Fetcher::is_local_id(music_id) || music_id.contains("://") {
+        return Vec::new();
+    }
+    config::initilize::CONFIG
+        .servers
+        .list
+        .iter()
+        .map(|server| {
+            format!(
+                "{}/latest_version?id={}&itag=140&local=true",
+                server.trim_end_matches('/').trim_end_matches("/api/v1"),
+                music_id
+            )
+        })
+        .collect()
+}
+
+// Reverse of music_url. Get the id of youtube music from url. None if url is not of youtube music
+// eg: url reported by player for playing music
+pub fn music_id_from_url(url: &str) -> Option<String> {
+    let id = url
+        .split("watch?v=")
+        .nth(1)
+        .or_else(|| url.split("youtu.be/").nth(1))?;
+    let id = id.split(['&', '?']).next()?;
+    if id.is_empty() {
+        None
+    } else {
+        Some(id.to_string())
+    }
+}
+
+// What is being pointed by url (or id) that user wants to open directly. See `open_target`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OpenTarget {
+    Music(String),
+    Playlist(String),
+    Artist(String),
+}
+
+// Find what the given url points to. Urls of youtube (also YouTube Music and youtu.be) video,
+// playlist and channel are understood, video being preferred when url has both along with urls of soundcloud and bandcamp which are already
+// the id of those. When `bare_id` is true, plain youtube id is also accepted. eg: dQw4w9WgXcQ for
+// music, PL.. for playlist and UC.. for channel. None if nothing could be recognized
+pub fn open_target(input: &str, bare_id: bool) -> Option<OpenTarget> {
+    let input = input.trim();
+    let is_id_char = |ch: char| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_';
+
+    if soundcloud::is_soundcloud_id(input) || bandcamp::is_bandcamp_id(input) {
+        // Tracking params (?si=..) are not part of the id
+        let url = input.split(['?', '#']).next()?.trim_end_matches('/');
+        let path_segments = url.split("://").nth(1)?.split('/').count() - 1;
+        return Some(if url.contains("/sets/") || url.contains("/album/") {
+            OpenTarget::Playlist(url.to_string())
+        } else if url.contains("/track/") || path_segments >= 2 {
+            OpenTarget::Music(url.to_string())
+        } else {
+            OpenTarget::Artist(url.to_string())
+        });
+    }
+
+    if input.contains("youtube.com") || input.contains("youtu.be") {
+        let param = |name: &str| {
+            input
+                .split(['?', '&'])
+                .find_map(|pair| pair.strip_prefix(name)?.strip_prefix('='))
+                .map(|value| value.split('#').next().unwrap_or_default().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let segment_after = |prefix: &str| {
+            input
+                .split(prefix)
+                .nth(1)
+                .and_then(|rest| rest.split(['/', '?', '&', '#']).next())
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        // Url of music being played from playlist (or from Mix) has both v= and list=. Music is
+        // what was shared there so list= is only used by the playlist page itself
+        let music_id = param("v")
+            .or_else(|| music_id_from_url(input))
+            .or_else(|| segment_after("/shorts/"));
+        return if let Some(music_id) = music_id {
+            Some(OpenTarget::Music(music_id))
+        } else if let Some(playlist_id) = param("list") {
+            Some(OpenTarget::Playlist(playlist_id))
+        } else {
+            segment_after("/channel/").map(OpenTarget::Artist)
+        };
+    }
+
+    if !bare_id || input.is_empty() || !input.chars().all(is_id_char) {
+        None
+    } else if input.starts_with("UC") && input.len() == 24 {
+        Some(OpenTarget::Artist(input.to_string()))
+    } else if input.len() == 11 {
+        Some(OpenTarget::Music(input.to_string()))
+    } else if ["PL", "OLAK5uy_", "RD", "UU", "FL"]
+        .iter()
+        .any(|prefix| input.starts_with(prefix))
+    {
+        Some(OpenTarget::Playlist(input.to_string()))
+    } else {
+        None
+    }
+}
+
+// Same as music_url but for playlist. Local playlist is the directory containing music files which
+// can be directly loaded in the player
+pub fn playlist_url(playlist_id: &str) -> String {
+    if Fetcher::is_local_id(playlist_id) || playlist_id.contains("://") {
+        playlist_id.to_string()
+    } else if is_mix_id(playlist_id) {
+        // Mix do not have playlist page of it's own. It is only opened along with the seed music
+        format!(
+            "https://www.youtube.com/watch?v={}&list={}",
+            &playlist_id[2..],
+            playlist_id
+        )
+    } else {
+        format!("https://www.youtube.com/playlist?list={}", playlist_id)
+    }
+}
+
+// Same as music_url but for artist. Artists of other backends have url as id
+pub fn artist_url(artist_id: &str) -> String {
+    if Fetcher::is_local_id(artist_id) || artist_id.contains("://") {
+        artist_id.to_string()
+    } else {
+        format!("https://www.youtube.com/channel/{}", artist_id)
+    }
+}
+
+// Returns true if the playlist of this id is an album (or any other release like EP/single) rather
+// than playlist created by user. YouTube auto-generates such playlist for every release with id
+// starting with OLAK5uy_ and bandcamp have /album/ in url of album
+pub fn is_album_id(playlist_id: &str) -> bool {
+    playlist_id.starts_with("OLAK5uy_")
+        || (bandcamp::is_bandcamp_id(playlist_id) && playlist_id.contains("/album/"))
+}
+
+// Youtube auto-generates a Mix (endless station of similar music) for every music. Id of the mix is
+// "RD" followed by id of the music it is seeded from. Auto-generated playlists of YouTube Music
+// (RDCLAK...) also start with RD but those are regular playlists
+pub fn is_mix_id(playlist_id: &str) -> bool {
+    playlist_id.starts_with("RD") && !playlist_id.starts_with("RDCLAK")
+}
+
+// Id of the Mix seeded from given music. None if music is not from youtube
+pub fn mix_id(music_id: &str) -> Option<String> {
+    if Fetcher::is_local_id(music_id) || music_id.contains("://") || music_id.is_empty() {
+        None
+    } else {
+        Some(format!("RD{}", music_id))
+    }
+}
+
 #[derive(Default)]
 struct SearchRes {
     music: Vec<MusicUnit>,
@@ -95,6 +363,11 @@ struct SearchRes {
     artist: Vec<ArtistUnit>,
     query: String,
     last_fetched: i8,
+    // Next page to fetch from backend, whether backend have nothing more and hash of last page
+    // it returned. See search! in utils.rs
+    backend_page: usize,
+    is_exhausted: bool,
+    last_page: Option<u64>,
 }
 
 #[derive(Default)]
@@ -103,12 +376,119 @@ struct ArtistRes {
     playlist: (String, Vec<PlaylistUnit>),
 }
 
+// Charts of a country as listed in charts page of YouTube Music. Top songs and trending music are
+// in `music`, video charts in `playlist` and top artists in `artist`
+#[derive(Default)]
+struct ChartsRes {
+    country: String,
+    music: Vec<MusicUnit>,
+    playlist: Vec<PlaylistUnit>,
+    artist: Vec<ArtistUnit>,
+}
+
 #[derive(Default)]
 struct PlaylistRes {
     music: Vec<MusicUnit>,
     id: String,
 }
 
+// Backend to which the search query is sent to.
+// Invidious is the default backend and serves plain youtube video metadata.
+// YtMusic talks directly to YouTube Music (InnerTube api) which returns structured song/album/artist
+// result instead of generic video. This gives more relevant result for music search and separates
+// songs from regular videos.
+// Local searches the music files indexed from local music directory and works without internet.
+// SoundCloud searches tracks, sets (as playlist) and users (as artist) from soundcloud.
+// Bandcamp searches tracks, albums (as playlist) and bands (as artist) from bandcamp.
+// Default backend is configured in config file but can be overridden per query by prefixing the
+// query with name of backend followed by colon. eg: `ytmusic:Bartika Eam Rai`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    Invidious,
+    YtMusic,
+    Local,
+    SoundCloud,
+    Bandcamp,
+}
+
+impl Backend {
+    pub const ALL: [Backend; 5] = [
+        Backend::Invidious,
+        Backend::YtMusic,
+        Backend::Local,
+        Backend::SoundCloud,
+        Backend::Bandcamp,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Backend::Invidious => "invidious",
+            Backend::YtMusic => "ytmusic",
+            Backend::Local => "local",
+            Backend::SoundCloud => "soundcloud",
+            Backend::Bandcamp => "bandcamp",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|backend| backend.name().eq_ignore_ascii_case(name.trim()))
+            .copied()
+    }
+
+    // Split the backend prefix from the query if any. When query is not prefixed by any backend
+    // name, `default` is returned along with the untouched query
+    pub fn from_query(query: &str, default: Backend) -> (Backend, &str) {
+        if let Some((prefix, rest)) = query.split_once(':') {
+            if let Some(backend) = Self::from_name(prefix) {
+                return (backend, rest.trim());
+            }
+        }
+        (default, query)
+    }
+}
+
+// Category of trending page as understood by invidious. Trending sidebar option cycles through
+// these in this same order starting from Music
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrendingCategory {
+    Music,
+    Gaming,
+    Movies,
+    All,
+}
+
+impl TrendingCategory {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TrendingCategory::Music => "Music",
+            TrendingCategory::Gaming => "Gaming",
+            TrendingCategory::Movies => "Movies",
+            TrendingCategory::All => "All",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            TrendingCategory::Music => TrendingCategory::Gaming,
+            TrendingCategory::Gaming => TrendingCategory::Movies,
+            TrendingCategory::Movies => TrendingCategory::All,
+            TrendingCategory::All => TrendingCategory::Music,
+        }
+    }
+
+    // Value of `type` param in /trending endpoint. Mixed trending is served when no type is given
+    fn type_param(&self) -> &'static str {
+        match self {
+            TrendingCategory::Music => "&type=Music",
+            TrendingCategory::Gaming => "&type=Gaming",
+            TrendingCategory::Movies => "&type=Movies",
+            TrendingCategory::All => "",
+        }
+    }
+}
+
 /*
 Return type of the fetch function. This indicates different reason on why no data
 was returned from the fetcher function as stated below.
@@ -117,11 +497,12 @@ was returned from the fetcher function as stated below.
 pub enum ReturnAction {
     // This variat indicates that the fetch has failed and cannot be resolved on retrying
     // This may be due to several reasons including server down, network failure, parse failure
-    // Also Failed is active when fetcher had retried and now had exceed the retry count
-    Failed,
+    // as told by the FetchError. Also Failed is active when fetcher had retried and now had
+    // exceed the retry count
+    Failed(FetchError),
     // This variant simply indicates that the request has failed but doing the same request for
     // another time may suceed
-    Retry,
+    Retry(FetchError),
     // EOR avvrebration of End Of Result indicates that there is nothing more to fetch
     // At this point the corresponding container have all the data either fetched at once
     // like or had fetched the maximum page in pagination fetch
@@ -132,15 +513,49 @@ pub enum ReturnAction {
     EOR,
 }
 
+// Reason of why the fetch failed. This is what is shown to user when nothing could be fetched
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FetchError {
+    // Could not connect to server or server itself have some error (5xx)
+    Network,
+    // Server responded with something that cannot be understood
+    Parse,
+    // Requested item do not exist (anymore)
+    NotFound,
+    // Server responded with 429 (too many requests)
+    RateLimited,
+    // Error reading storage db or local music directory
+    Storage,
+    // Search filter dropped everything fetched from backend so far though backend have more.
+    // Fetching the same page again continues from where it was left. See search! in utils.rs
+    Unfilled,
+}
+
+impl FetchError {
+    pub fn reason(&self) -> &'static str {
+        match self {
+            FetchError::Network => "Network error..",
+            FetchError::Parse => "Invalid response..",
+            FetchError::NotFound => "Not found..",
+            FetchError::RateLimited => "Rate limited..",
+            FetchError::Storage => "Storage error..",
+            FetchError::Unfilled => "Few results match filter..",
+        }
+    }
+}
+
 pub struct Fetcher {
     // None if nothing of the trending music is selected.
     // Stores the vector of music that is trending in music section in specified region
-    // trending_now, is never cleared in a session. Unlike many others data container
+    // First member of tuple is the region (country code) and category of which trending is stored.
+    // trending_now, is never cleared in a session unless trending of another region or category
+    // is requested.
+    // Unlike many others data container
     // below, this field is only appended and read. When user paginate and there are no more
     // result in container another web request method is made and result is again stored and never cleared.
     // This may bring little delay when user explore for first time in a session but after that everything
     // will be in memory making it smooth.
-    trending_now: Option<Vec<MusicUnit>>,
+    trending_now: Option<((String, TrendingCategory), Vec<MusicUnit>)>,
 
     //playlist_content stores collection of music contained in a playlist
     // first field: (String) holds the unique if of playlist that is being read.
@@ -159,6 +574,10 @@ pub struct Fetcher {
     // last fetched data. Playback stops only when playlist ends.
     playlist_content: PlaylistRes,
 
+    // Title, author and number of music of playlist in playlist_content as told by server. Only
+    // invidious tells these so this is None for playlist of other source
+    playlist_info: Option<PlaylistUnit>,
+
     /*
     artist_content stores collection of music and also the collection of playlists
     from the channel
@@ -167,6 +586,33 @@ pub struct Fetcher {
     */
     artist_content: ArtistRes,
 
+    // Album that was last requested. See `Album`. Similar to playlist_content all the tracks are
+    // fetched at once. None if no album is fetched yet in this session
+    album_content: Option<Album>,
+
+    // Music related to the music of given id. Like playlist_content, all related music are
+    // fetched at once and are served in chunk. `id` of PlaylistRes is the id of music
+    related_content: PlaylistRes,
+
+    // Charts that was last requested. Whole charts is fetched at once and is served in chunk until
+    // charts of another country is requested. None if no charts is fetched yet in this session
+    charts: Option<ChartsRes>,
+
+    // Categories of moods and genres page. Fetched once per session and empty until requested
+    mood_categories: Vec<innertube::MoodCategory>,
+
+    // Playlists of mood/genre category that was last requested along with the params of that
+    // category. All the playlists are fetched at once and served in chunk
+    mood_playlists: (String, Vec<PlaylistUnit>),
+
+    // Latest uploads of all subscribed channels, newest first. This is fetched again when first
+    // page of feed is requested and later pages are served from here
+    subscription_feed: Vec<MusicUnit>,
+
+    // Uploads of favourite and subscribed artists that are newer than last seen. See
+    // subscriptions.rs. This is fetched again when first page is requested
+    new_releases: Vec<MusicUnit>,
+
     // List of available servers powered by invidious youtube data fetcher. All the servers should
     // provide same endpoints to make request to and same pattern of return data. Which actually means
     // all the servers must be powered by the same mahor version of invidious backend.
@@ -180,9 +626,22 @@ pub struct Fetcher {
     // First field: (String) is the query being searched for.
     search_res: SearchRes,
 
+    // Backend to send search query to when query do not explicitly specify one.
+    // See `Backend` for more
+    backend: Backend,
+
+    // YouTube Music do not paginate with page number but instead return a continuation token
+    // that should be sent to get next chunk of result. This stores such token for
+    // music/playlist/artist search respectively along with the query it belongs to.
+    // Token is None when either nothing is fetched yet or there is no more result
+    ytmusic_continuation: [(String, Option<String>); 3],
+
     // The reqwest client itself. This is only initilized once per session.
     client: reqwest::Client,
 
+    // Every request sent with `client` is paced by this. See ratelimit.rs
+    rate_limiter: ratelimit::RateLimiter,
+
     // index that reference the servers[] field.
     // When server need to be changes as described in documentation of servers[] field
     // this index is updated (usually rotated clockwise)
@@ -193,6 +652,175 @@ pub struct Fetcher {
 
     // copy of constants.item_per_list
     item_per_page: usize,
-    // reference to constants.region in config file
+    // reference to constants.region in config file. Used while searching. Trending may be of
+    // different region which is passed by caller
     region: &'static str,
+
+    // Directory from which local music are indexed. Reference to local.path in config file
+    local_dir: &'static str,
+
+    // Index of local music directory. None if local content is not requested yet in this session.
+    // See local.rs for more
+    local_index: Option<local::LocalIndex>,
+
+    // client_id to send along with SoundCloud api request. Initially taken from config file. When
+    // empty it is extracted from soundcloud web page on first request. See soundcloud.rs
+    soundcloud_client_id: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn music(id: &str) -> Option<OpenTarget> {
+        Some(OpenTarget::Music(id.to_string()))
+    }
+
+    fn playlist(id: &str) -> Option<OpenTarget> {
+        Some(OpenTarget::Playlist(id.to_string()))
+    }
+
+    fn artist(id: &str) -> Option<OpenTarget> {
+        Some(OpenTarget::Artist(id.to_string()))
+    }
+
+    #[test]
+    fn youtube_music_urls() {
+        let id = "dQw4w9WgXcQ";
+        assert_eq!(
+            open_target("https://www.youtube.com/watch?v=dQw4w9WgXcQ", false),
+            music(id)
+        );
+        assert_eq!(
+            open_target("https://youtu.be/dQw4w9WgXcQ?si=AbCdEf", false),
+            music(id)
+        );
+        assert_eq!(
+            open_target(
+                "https://youtube.com/shorts/dQw4w9WgXcQ?feature=share",
+                false
+            ),
+            music(id)
+        );
+        assert_eq!(
+            open_target(
+                "https://m.youtube.com/watch?feature=shared&v=dQw4w9WgXcQ",
+                false
+            ),
+            music(id)
+        );
+        assert_eq!(
+            open_target("https://www.youtube.com/watch?v=dQw4w9WgXcQ#t=30", false),
+            music(id)
+        );
+    }
+
+    #[test]
+    fn music_is_preferred_over_list() {
+        // Shared while playing Mix
+        assert_eq!(
+            open_target(
+                "https://www.youtube.com/watch?v=dQw4w9WgXcQ&list=RDdQw4w9WgXcQ&start_radio=1",
+                false
+            ),
+            music("dQw4w9WgXcQ")
+        );
+        assert_eq!(
+            open_target(
+                "https://music.youtube.com/watch?v=dQw4w9WgXcQ&list=RDAMVMdQw4w9WgXcQ",
+                false
+            ),
+            music("dQw4w9WgXcQ")
+        );
+        // Shared while playing some playlist
+        assert_eq!(
+            open_target(
+                "https://www.youtube.com/watch?list=PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI&v=dQw4w9WgXcQ&index=2",
+                false
+            ),
+            music("dQw4w9WgXcQ")
+        );
+    }
+
+    #[test]
+    fn youtube_playlist_and_channel_urls() {
+        assert_eq!(
+            open_target(
+                "https://www.youtube.com/playlist?list=PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI",
+                false
+            ),
+            playlist("PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI")
+        );
+        assert_eq!(
+            open_target(
+                "https://music.youtube.com/playlist?list=OLAK5uy_kGkAxnFSR7pZ6eX4RkVL3ZLRkR2b5yK0Q&si=x",
+                false
+            ),
+            playlist("OLAK5uy_kGkAxnFSR7pZ6eX4RkVL3ZLRkR2b5yK0Q")
+        );
+        assert_eq!(
+            open_target(
+                "https://www.youtube.com/channel/UCuAXFkgsw1L7xaCfnd5JJOw/videos",
+                false
+            ),
+            artist("UCuAXFkgsw1L7xaCfnd5JJOw")
+        );
+        assert_eq!(
+            open_target("https://www.youtube.com/@somehandle", false),
+            None
+        );
+        assert_eq!(open_target("https://www.youtube.com/", false), None);
+    }
+
+    #[test]
+    fn bare_ids() {
+        assert_eq!(open_target("dQw4w9WgXcQ", true), music("dQw4w9WgXcQ"));
+        assert_eq!(open_target("dQw4w9WgXcQ", false), None);
+        assert_eq!(
+            open_target(" UCuAXFkgsw1L7xaCfnd5JJOw ", true),
+            artist("UCuAXFkgsw1L7xaCfnd5JJOw")
+        );
+        assert_eq!(
+            open_target("PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI", true),
+            playlist("PLFgquLnL59alCl_2TQvOiD5Vgm1hCaGSI")
+        );
+        assert_eq!(
+            open_target("RDdQw4w9WgXcQ", true),
+            playlist("RDdQw4w9WgXcQ")
+        );
+        assert_eq!(open_target("never gonna", true), None);
+        assert_eq!(open_target("", true), None);
+        assert_eq!(open_target("lofi", true), None);
+    }
+
+    #[test]
+    fn soundcloud_and_bandcamp_urls() {
+        assert_eq!(
+            open_target(
+                "https://soundcloud.com/artist/some-track?si=abc&utm_source=x",
+                false
+            ),
+            music("https://soundcloud.com/artist/some-track")
+        );
+        assert_eq!(
+            open_target("https://soundcloud.com/artist/sets/some-set", false),
+            playlist("https://soundcloud.com/artist/sets/some-set")
+        );
+        assert_eq!(
+            open_target("https://soundcloud.com/artist/", false),
+            artist("https://soundcloud.com/artist")
+        );
+        assert_eq!(
+            open_target("https://artist.bandcamp.com/album/some-album", false),
+            playlist("https://artist.bandcamp.com/album/some-album")
+        );
+        assert_eq!(
+            open_target("https://artist.bandcamp.com/track/some-track#lyrics", false),
+            music("https://artist.bandcamp.com/track/some-track")
+        );
+        assert_eq!(
+            open_target("https://artist.bandcamp.com", false),
+            artist("https://artist.bandcamp.com")
+        );
+    }
 }
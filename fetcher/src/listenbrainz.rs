@@ -0,0 +1,79 @@
+use crate::{FetchError, Fetcher, ReturnAction};
+use serde_json::json;
+use std::time::Duration;
+
+// Listens are submitted to ListenBrainz which is libre alternative to Last.fm. User token is found
+// in https://listenbrainz.org/settings/ after logging in.
+const SUBMIT_URL: &str = "https://api.listenbrainz.org/1/submit-listens";
+// Music is scrobbled once it have been played for half of it's duration or this long, whichever
+// comes first. Music shorter than MIN_DURATION is never scrobbled. This is the rule that both
+// Last.fm and ListenBrainz recommend so every scrobbler should use scrobble_threshold
+const MAX_THRESHOLD: Duration = Duration::from_secs(4 * 60);
+const MIN_DURATION: Duration = Duration::from_secs(30);
+
+// How long music of given duration have to be played before it is scrobbled. None if it should
+// never be scrobbled (too short or duration is not known. eg: live stream)
+pub fn scrobble_threshold(duration: Duration) -> Option<Duration> {
+    if duration < MIN_DURATION {
+        None
+    } else {
+        Some((duration / 2).min(MAX_THRESHOLD))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Listen {
+    // When music started playing in seconds since unix epoch
+    pub listened_at: u64,
+    pub title: String,
+    pub artist: String,
+    // Url (or path of local music) from which it was played
+    pub url: String,
+    pub duration: Duration,
+}
+
+impl Fetcher {
+    // Submit all the listens in one request. Listen is only accepted by ListenBrainz if it have
+    // both title and artist
+    pub async fn submit_listens(
+        &mut self,
+        token: &str,
+        listens: &[Listen],
+    ) -> Result<(), ReturnAction> {
+        let payload = listens
+            .iter()
+            .map(|listen| {
+                json!({
+                    "listened_at": listen.listened_at,
+                    "track_metadata": {
+                        "artist_name": listen.artist,
+                        "track_name": listen.title,
+                        "additional_info": {
+                            "media_player": "ytui-music",
+                            "submission_client": "ytui-music",
+                            "origin_url": listen.url,
+                            "duration_ms": listen.duration.as_millis() as u64,
+                        },
+                    },
+                })
+            })
+            .collect::<Vec<_>>();
+        let body = json!({
+            "listen_type": if listens.len() == 1 { "single" } else { "import" },
+            "payload": payload,
+        });
+
+        let request = self
+            .client
+            .post(SUBMIT_URL)
+            .header("Authorization", format!("Token {}", token))
+            .json(&body);
+        let response = self.execute(request).await?;
+        // Invalid token (401) or listen (400) is not something that can be retried
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(ReturnAction::Failed(FetchError::Parse))
+        }
+    }
+}
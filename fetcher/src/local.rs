@@ -0,0 +1,385 @@
+use crate::{ExtendDuration, FetchError, Fetcher, ReturnAction};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use symphonia::core::{
+    formats::FormatOptions,
+    io::MediaSourceStream,
+    meta::{MetadataOptions, StandardTagKey, Tag},
+    probe::Hint,
+};
+
+// Only the files with these extensions are indexed. Anything else in music directory like cover
+// images, lyrics or playlists files are ignored
+const AUDIO_EXTENSIONS: [&str; 9] = [
+    "mp3", "flac", "ogg", "oga", "opus", "m4a", "mp4", "wav", "aac",
+];
+
+// In-memory index of the local music directory. This is built once per session when local content
+// is first requested. Similar to trending, it is never cleared after that.
+// Music are sorted by their directory and then path so that tracks of same album stays together and in order of
+// their file name (which usually starts with track number)
+#[derive(Default)]
+pub struct LocalIndex {
+    music: Vec<super::MusicUnit>,
+    // Every directory that directly contains at least one audio file is considered an album.
+    // `id` of such playlist unit is the path of directory
+    albums: Vec<super::PlaylistUnit>,
+}
+
+// Information read from the tags of audio file. Any of these may be missing when file is not
+// tagged properly. In that case file/directory name are used instead
+#[derive(Default)]
+struct AudioTags {
+    title: Option<String>,
+    artist: Option<String>,
+    album: Option<String>,
+    album_artist: Option<String>,
+    duration: Option<Duration>,
+}
+
+fn apply_tags(result: &mut AudioTags, tags: &[Tag]) {
+    for tag in tags {
+        let value = tag.value.to_string();
+        let target = match tag.std_key {
+            Some(StandardTagKey::TrackTitle) => &mut result.title,
+            Some(StandardTagKey::Artist) => &mut result.artist,
+            Some(StandardTagKey::Album) => &mut result.album,
+            Some(StandardTagKey::AlbumArtist) => &mut result.album_artist,
+            _ => continue,
+        };
+        if target.is_none() && !value.trim().is_empty() {
+            *target = Some(value);
+        }
+    }
+}
+
+fn read_tags(path: &Path) -> AudioTags {
+    let mut result = AudioTags::default();
+
+    let file = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(_) => return result,
+    };
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|ext| ext.to_str()) {
+        hint.with_extension(ext);
+    }
+    let source = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut probed = match symphonia::default::get_probe().format(
+        &hint,
+        source,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    ) {
+        Ok(probed) => probed,
+        Err(_) => return result,
+    };
+
+    // Tags may be found either before the container (eg: ID3v2 in mp3) or inside the container
+    // itself (eg: vorbis comments in flac). Read both
+    if let Some(metadata) = probed.metadata.get() {
+        if let Some(revision) = metadata.current() {
+            apply_tags(&mut result, revision.tags());
+        }
+    }
+    if let Some(revision) = probed.format.metadata().current() {
+        apply_tags(&mut result, revision.tags());
+    }
+
+    if let Some(track) = probed.format.default_track() {
+        let params = &track.codec_params;
+        if let (Some(frames), Some(time_base)) = (params.n_frames, params.time_base) {
+            let time = time_base.calc_time(frames);
+            result.duration = Some(Duration::from_secs(time.seconds));
+        }
+    }
+
+    result
+}
+
+fn is_audio_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| {
+            AUDIO_EXTENSIONS
+                .iter()
+                .any(|known| known.eq_ignore_ascii_case(ext))
+        })
+        .unwrap_or(false)
+}
+
+// Recursively collect all the audio files inside given directory.
+// Unreadable directories are silently skipped.
+fn collect_audio_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_audio_files(&path, files);
+        } else if is_audio_file(&path) {
+            files.push(path);
+        }
+    }
+}
+
+fn file_name_of(path: &Path) -> String {
+    path.file_stem()
+        .or_else(|| path.file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+impl LocalIndex {
+    pub fn build(root: &Path) -> Self {
+        let mut files = Vec::new();
+        collect_audio_files(root, &mut files);
+        // Sorting by path alone would put files of subdirectory in between the files of its parent
+        // directory (dir/a, dir/sub/b, dir/z) splitting that album in two
+        files.sort_by(|a, b| (a.parent(), a).cmp(&(b.parent(), b)));
+
+        let mut index = LocalIndex::default();
+        for path in files {
+            let tags = read_tags(&path);
+            let parent = path.parent().unwrap_or(root);
+            let parent_id = parent.to_string_lossy().to_string();
+
+            let artist = tags
+                .artist
+                .clone()
+                .or_else(|| tags.album_artist.clone())
+                .unwrap_or_else(|| "Unknown artist".to_string());
+            index.music.push(super::MusicUnit {
+                name: tags.title.unwrap_or_else(|| file_name_of(&path)),
+                artist: artist.clone(),
+                duration: tags.duration.unwrap_or_default().to_string(),
+                id: path.to_string_lossy().to_string(),
+                live: false,
+                views: String::new(),
+                published: String::new(),
+            });
+
+            // As files are sorted by path, all files of same directory are adjacent.
+            // So only last album needs to be checked
+            match index.albums.last_mut() {
+                Some(album) if album.id == parent_id => {
+                    let count = album.video_count.parse::<usize>().unwrap_or_default();
+                    album.video_count = (count + 1).to_string();
+                }
+                _ => index.albums.push(super::PlaylistUnit {
+                    name: tags.album.unwrap_or_else(|| file_name_of(parent)),
+                    id: parent_id,
+                    author: tags.album_artist.unwrap_or(artist),
+                    video_count: 1.to_string(),
+                }),
+            }
+        }
+
+        index.music.shrink_to_fit();
+        index.albums.shrink_to_fit();
+        index
+    }
+}
+
+// Returns the item in given page or EOR if page do not exists
+fn paginate<T: Clone>(
+    list: &[T],
+    page: usize,
+    item_per_page: usize,
+) -> Result<Vec<T>, ReturnAction> {
+    let lower_limit = page * item_per_page;
+    let upper_limit = std::cmp::min(list.len(), lower_limit + item_per_page);
+    if lower_limit >= upper_limit {
+        Err(ReturnAction::EOR)
+    } else {
+        Ok(list[lower_limit..upper_limit].to_vec())
+    }
+}
+
+fn matches_query(query: &str, fields: &[&str]) -> bool {
+    let query = query.to_lowercase();
+    fields
+        .iter()
+        .any(|field| field.to_lowercase().contains(&query))
+}
+
+impl Fetcher {
+    // Index the local music directory if it is not indexed yet.
+    // Walking the directory and reading tags is blocking io so it is done in blocking thread
+    async fn local_index(&mut self) -> Result<&LocalIndex, ReturnAction> {
+        if self.local_index.is_none() {
+            let root = PathBuf::from(self.local_dir);
+            if !root.is_dir() {
+                return Err(ReturnAction::Failed(FetchError::Storage));
+            }
+            let index = tokio::task::spawn_blocking(move || LocalIndex::build(&root))
+                .await
+                .map_err(|_| ReturnAction::Failed(FetchError::Storage))?;
+            self.local_index = Some(index);
+        }
+
+        Ok(self.local_index.as_ref().unwrap())
+    }
+
+    // Returns true if the id points to local file or directory instead of youtube id
+    pub fn is_local_id(id: &str) -> bool {
+        Path::new(id).is_absolute()
+    }
+
+    pub async fn get_local_music(
+        &mut self,
+        page: usize,
+    ) -> Result<Vec<super::MusicUnit>, ReturnAction> {
+        let item_per_page = self.item_per_page;
+        let index = self.local_index().await?;
+        paginate(&index.music, page, item_per_page)
+    }
+
+    pub async fn get_local_albums(
+        &mut self,
+        page: usize,
+    ) -> Result<Vec<super::PlaylistUnit>, ReturnAction> {
+        let item_per_page = self.item_per_page;
+        let index = self.local_index().await?;
+        paginate(&index.albums, page, item_per_page)
+    }
+
+    // Content of local album. i.e the audio files directly inside given directory
+    pub async fn get_local_album_content(
+        &mut self,
+        album_dir: &str,
+        page: usize,
+    ) -> Result<Vec<super::MusicUnit>, ReturnAction> {
+        let item_per_page = self.item_per_page;
+        let index = self.local_index().await?;
+        let album_dir = Path::new(album_dir);
+        let tracks = index
+            .music
+            .iter()
+            .filter(|music| Path::new(&music.id).parent() == Some(album_dir))
+            .cloned()
+            .collect::<Vec<_>>();
+        paginate(&tracks, page, item_per_page)
+    }
+
+    pub async fn search_local_music(
+        &mut self,
+        query: &str,
+        page: usize,
+    ) -> Result<Vec<super::MusicUnit>, ReturnAction> {
+        let item_per_page = self.item_per_page;
+        let index = self.local_index().await?;
+        let found = index
+            .music
+            .iter()
+            .filter(|music| matches_query(query, &[&music.name, &music.artist]))
+            .cloned()
+            .collect::<Vec<_>>();
+        // search! macro appends whatever is returned to the previous result so only return the
+        // requested chunk. Empty chunk is treated as EOR by the macro
+        Ok(found
+            .into_iter()
+            .skip(page * item_per_page)
+            .take(item_per_page)
+            .collect())
+    }
+
+    pub async fn search_local_playlist(
+        &mut self,
+        query: &str,
+        page: usize,
+    ) -> Result<Vec<super::PlaylistUnit>, ReturnAction> {
+        let item_per_page = self.item_per_page;
+        let index = self.local_index().await?;
+        let found = index
+            .albums
+            .iter()
+            .filter(|album| matches_query(query, &[&album.name, &album.author]))
+            .cloned()
+            .collect::<Vec<_>>();
+        Ok(found
+            .into_iter()
+            .skip(page * item_per_page)
+            .take(item_per_page)
+            .collect())
+    }
+
+    // Local files do not have any channel to navigate to. So there is never an artist result
+    pub async fn search_local_artist(
+        &mut self,
+        _query: &str,
+        _page: usize,
+    ) -> Result<Vec<super::ArtistUnit>, ReturnAction> {
+        Ok(Vec::new())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pages() {
+        let list = (0..5).collect::<Vec<i32>>();
+        assert_eq!(paginate(&list, 0, 2).ok(), Some(vec![0, 1]));
+        assert_eq!(paginate(&list, 2, 2).ok(), Some(vec![4]));
+        assert!(matches!(paginate(&list, 3, 2), Err(ReturnAction::EOR)));
+        assert!(matches!(paginate(&list, 0, 0), Err(ReturnAction::EOR)));
+        assert!(matches!(
+            paginate::<i32>(&[], 0, 10),
+            Err(ReturnAction::EOR)
+        ));
+    }
+
+    #[test]
+    fn query_match() {
+        assert!(matches_query("beat", &["Abbey Road", "The Beatles"]));
+        assert!(matches_query("ROAD", &["Abbey Road"]));
+        assert!(matches_query("", &["anything"]));
+        assert!(!matches_query("queen", &["Abbey Road", "The Beatles"]));
+        assert!(!matches_query("queen", &[]));
+    }
+
+    #[test]
+    fn albums_are_grouped_by_directory() {
+        let root = std::env::temp_dir().join(format!("ytui-local-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let album = root.join("Artist").join("Album");
+        let bonus = album.join("Bonus");
+        std::fs::create_dir_all(&bonus).unwrap();
+        // Not valid audio. Names then come from file and directory name
+        for path in [
+            album.join("01 First.mp3"),
+            album.join("02 Second.FLAC"),
+            album.join("cover.jpg"),
+            album.join("zz Last.ogg"),
+            bonus.join("Extra.opus"),
+        ] {
+            std::fs::write(path, b"not audio").unwrap();
+        }
+
+        let index = LocalIndex::build(&root);
+        std::fs::remove_dir_all(&root).unwrap();
+
+        let names = index
+            .music
+            .iter()
+            .map(|music| music.name.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(names, vec!["01 First", "02 Second", "zz Last", "Extra"]);
+        assert!(index
+            .music
+            .iter()
+            .all(|music| music.artist == "Unknown artist"));
+
+        let albums = index
+            .albums
+            .iter()
+            .map(|album| (album.name.as_str(), album.video_count.as_str()))
+            .collect::<Vec<(&str, &str)>>();
+        assert_eq!(albums, vec![("Album", "3"), ("Bonus", "1")]);
+        assert_eq!(index.albums[0].id, album.to_string_lossy());
+    }
+}
@@ -0,0 +1,221 @@
+use crate::{FetchError, Fetcher, ReturnAction};
+use serde::Deserialize;
+use std::time::Duration;
+
+// Lyrics are fetched from LRCLIB. This is free service and do not need any key. It provides the
+// synced lyrics (in LRC format) for most of popular songs and plain lyrics for some others.
+const LRCLIB_SEARCH_URL: &str = "https://lrclib.net/api/search";
+// Result with duration differing more than this from the playing music is considered to be of
+// different version of song (eg: live or extended) so synced lyrics will not match
+const DURATION_TOLERANCE_SECS: f64 = 5.0;
+
+#[derive(Deserialize)]
+struct LrclibTrack {
+    #[serde(default)]
+    duration: f64,
+    #[serde(default, rename = "syncedLyrics")]
+    synced_lyrics: Option<String>,
+    #[serde(default, rename = "plainLyrics")]
+    plain_lyrics: Option<String>,
+}
+
+// Lines of lyrics. When lyrics is synced every line have the time from which it is sung.
+// For plain lyrics time of every line is zero
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Lyrics {
+    pub synced: bool,
+    pub lines: Vec<(Duration, String)>,
+}
+
+impl Lyrics {
+    // Index of line being sung at given time. None if lyrics is not synced or first line is not
+    // reached yet
+    pub fn current_line(&self, elapsed: Duration) -> Option<usize> {
+        if !self.synced {
+            return None;
+        }
+        self.lines
+            .iter()
+            .rposition(|(start_at, _)| *start_at <= elapsed)
+    }
+
+    // Parse the LRC formatted lyrics. Single line may have multiple time tag when same line is
+    // repeated. eg: `[00:12.30][01:40.00]Some line`
+    // Lines without any time tag (like metadata tags [ar:Artist]) are ignored. Lyrics without any
+    // time tag at all is taken as plain lyrics
+    fn from_lrc(lrc: &str) -> Self {
+        let mut lines = Vec::new();
+        for raw_line in lrc.lines() {
+            let mut rest = raw_line.trim();
+            let mut times = Vec::new();
+            while let Some(tag_end) = rest.strip_prefix('[').and_then(|tag| tag.find(']')) {
+                match parse_lrc_time(&rest[1..=tag_end]) {
+                    Some(time) => times.push(time),
+                    None => break,
+                }
+                rest = &rest[tag_end + 2..];
+            }
+            for time in times {
+                lines.push((time, rest.trim().to_string()));
+            }
+        }
+        if lines.is_empty() {
+            return Lyrics::from_plain(lrc);
+        }
+        lines.sort_by_key(|(time, _)| *time);
+
+        Lyrics {
+            synced: true,
+            lines,
+        }
+    }
+
+    fn from_plain(plain: &str) -> Self {
+        Lyrics {
+            synced: false,
+            lines: plain
+                .lines()
+                .map(|line| (Duration::default(), line.trim().to_string()))
+                .collect(),
+        }
+    }
+}
+
+// Parse time in form of mm:ss.xx
+fn parse_lrc_time(time: &str) -> Option<Duration> {
+    let (minutes, seconds) = time.split_once(':')?;
+    // Lyrics come from anywhere so out of range time (like [00:inf]) is not a time at all
+    let minutes = minutes.trim().parse::<u64>().ok()?;
+    let seconds = seconds
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|seconds| seconds.is_finite())?;
+    let seconds = Duration::try_from_secs_f64(seconds.max(0.0)).ok()?;
+    Duration::from_secs(minutes.checked_mul(60)?).checked_add(seconds)
+}
+
+// Title of youtube videos usually have extra info like "(Official Video)" or "[Lyrics]" which
+// only make the search worse. Remove everything inside brackets
+fn clean_title(title: &str) -> String {
+    let mut cleaned = String::with_capacity(title.len());
+    let mut depth = 0;
+    for ch in title.chars() {
+        match ch {
+            '(' | '[' => depth += 1,
+            ')' | ']' if depth > 0 => depth -= 1,
+            _ if depth == 0 => cleaned.push(ch),
+            _ => {}
+        }
+    }
+    cleaned.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+impl Fetcher {
+    // Search lyrics of music with given title. Title is usually in the form of "Artist - Song" as
+    // reported by player. Duration of music is used to pick the correct version when known
+    pub async fn get_lyrics(
+        &mut self,
+        title: &str,
+        duration: Duration,
+    ) -> Result<Lyrics, ReturnAction> {
+        let query = clean_title(title);
+        if query.is_empty() {
+            return Err(ReturnAction::EOR);
+        }
+
+        let request = self
+            .client
+            .get(LRCLIB_SEARCH_URL)
+            .query(&[("q", query.as_str())]);
+        let res = self.execute(request).await;
+        let tracks = match res {
+            Ok(response) => response
+                .json::<Vec<LrclibTrack>>()
+                .await
+                .map_err(|_| ReturnAction::Failed(FetchError::Parse))?,
+            Err(action) => return Err(action),
+        };
+
+        let duration = duration.as_secs_f64();
+        let is_same_version = |track: &&LrclibTrack| {
+            duration <= 0.0 || (track.duration - duration).abs() <= DURATION_TOLERANCE_SECS
+        };
+
+        // Prefer synced lyrics of same version, then any synced lyrics and finally plain lyrics
+        let synced = tracks
+            .iter()
+            .filter(|track| track.synced_lyrics.is_some())
+            .find(is_same_version)
+            .or_else(|| tracks.iter().find(|track| track.synced_lyrics.is_some()));
+        if let Some(lrc) = synced.and_then(|track| track.synced_lyrics.as_deref()) {
+            return Ok(Lyrics::from_lrc(lrc));
+        }
+
+        tracks
+            .iter()
+            .find_map(|track| track.plain_lyrics.as_deref())
+            .map(Lyrics::from_plain)
+            .ok_or(ReturnAction::EOR)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(millis: u64, text: &str) -> (Duration, String) {
+        (Duration::from_millis(millis), text.to_string())
+    }
+
+    #[test]
+    fn repeated_line() {
+        let lyrics = Lyrics::from_lrc("[00:12.30][01:40.00]Chorus\n[00:20.00]Verse");
+        assert!(lyrics.synced);
+        assert_eq!(
+            lyrics.lines,
+            [
+                line(12_300, "Chorus"),
+                line(20_000, "Verse"),
+                line(100_000, "Chorus")
+            ]
+        );
+    }
+
+    #[test]
+    fn time_without_fraction() {
+        let lyrics = Lyrics::from_lrc("[01:05]First\n[01:05.50]Second");
+        assert_eq!(
+            lyrics.lines,
+            [line(65_000, "First"), line(65_500, "Second")]
+        );
+    }
+
+    #[test]
+    fn invalid_time_is_ignored() {
+        let lyrics = Lyrics::from_lrc(
+            "[00:inf]Infinite\n[00:NaN]Not a number\n[00:1e30]Too long\n\
+             [18446744073709551615:00]Too many minutes\n[00:02]Valid",
+        );
+        assert_eq!(lyrics.lines, [line(2_000, "Valid")]);
+    }
+
+    #[test]
+    fn metadata_is_ignored() {
+        let lyrics = Lyrics::from_lrc("[ar:Someone]\n[ti:Song]\n[00:01.00]Only line");
+        assert_eq!(lyrics.lines, [line(1_000, "Only line")]);
+        assert_eq!(lyrics.current_line(Duration::from_millis(500)), None);
+        assert_eq!(lyrics.current_line(Duration::from_secs(2)), Some(0));
+    }
+
+    #[test]
+    fn plain_fallback() {
+        let lyrics = Lyrics::from_lrc("First line\n  Second line ");
+        assert!(!lyrics.synced);
+        assert_eq!(
+            lyrics.lines,
+            [line(0, "First line"), line(0, "Second line")]
+        );
+        assert_eq!(lyrics.current_line(Duration::from_secs(10)), None);
+    }
+}
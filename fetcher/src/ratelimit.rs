@@ -0,0 +1,223 @@
+use crate::{FetchError, Fetcher, ReturnAction};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+// Every outgoing request goes through the rate limiter so that rapidly paging through the list
+// does not flood the server (and get the user temporarily banned). Limit is kept per host so that
+// each invidious instance and other backends are limited independently.
+// Requests to same host are spaced by at least MIN_INTERVAL. When host responds with 429 (too many
+// requests) or 5xx, no request is sent to that host for a backoff period which is doubled on every
+// consecutive failure up to MAX_BACKOFF and is reset on first success.
+const MIN_INTERVAL: Duration = Duration::from_millis(250);
+const BASE_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+#[derive(Default)]
+struct HostState {
+    // No request should be sent to this host before this time
+    next_allowed: Option<Instant>,
+    // Number of consecutive 429/5xx responses
+    failures: u32,
+}
+
+#[derive(Default)]
+pub struct RateLimiter {
+    hosts: HashMap<String, HostState>,
+}
+
+impl RateLimiter {
+    // true if host have failed recently and is still waiting for backoff to end
+    pub fn is_backing_off(&self, host: &str) -> bool {
+        match self.hosts.get(host) {
+            Some(state) => {
+                state.failures > 0
+                    && matches!(state.next_allowed, Some(time) if time > Instant::now())
+            }
+            None => false,
+        }
+    }
+
+    // Wait until request to this host is allowed and reserve the slot for this request
+    async fn wait_turn(&mut self, host: &str) {
+        let state = self.hosts.entry(host.to_string()).or_default();
+        if let Some(next_allowed) = state.next_allowed {
+            let now = Instant::now();
+            if next_allowed > now {
+                tokio::time::sleep(next_allowed - now).await;
+            }
+        }
+        state.next_allowed = Some(Instant::now() + MIN_INTERVAL);
+    }
+
+    // Record the response status of request to this host. Retry-After header (in seconds) is
+    // honoured when server tells how long to wait
+    fn report(&mut self, host: &str, status: reqwest::StatusCode, retry_after: Option<u64>) {
+        let state = self.hosts.entry(host.to_string()).or_default();
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+            state.failures += 1;
+            let backoff = BASE_BACKOFF
+                .saturating_mul(2u32.saturating_pow(state.failures - 1))
+                .min(MAX_BACKOFF);
+            let backoff = retry_after
+                .map(Duration::from_secs)
+                .map_or(backoff, |wait| wait.min(MAX_BACKOFF));
+            state.next_allowed = Some(Instant::now() + backoff);
+        } else {
+            state.failures = 0;
+        }
+    }
+}
+
+impl Fetcher {
+    // All the requests (to any backend) should be send from this function. Rate limit and backoff
+    // is applied here. Connection failure and response with 429/5xx status results in Retry so that
+    // caller can try again (possibly to another server) later
+    pub(crate) async fn execute(
+        &mut self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, ReturnAction> {
+        let request = request
+            .build()
+            .map_err(|_| ReturnAction::Failed(FetchError::Network))?;
+        let host = request.url().host_str().unwrap_or_default().to_string();
+
+        self.rate_limiter.wait_turn(&host).await;
+        let response = self
+            .client
+            .execute(request)
+            .await
+            .map_err(|_| ReturnAction::Retry(FetchError::Network))?;
+
+        self.rate_limiter
+            .report(&host, response.status(), retry_after(&response));
+        check_status(response.status()).map(|_| response)
+    }
+
+    // Send all the requests at once instead of one after another. Rate limit still applies as in
+    // execute so requests should be spread across servers for them to really be concurrent.
+    // Body of every response (or why it failed) is returned in same order as requests
+    pub(crate) async fn execute_many(
+        &mut self,
+        requests: Vec<reqwest::RequestBuilder>,
+    ) -> Vec<Result<String, ReturnAction>> {
+        let mut tasks = Vec::with_capacity(requests.len());
+        for request in requests {
+            let request = match request.build() {
+                Ok(request) => request,
+                Err(_) => {
+                    tasks.push(None);
+                    continue;
+                }
+            };
+            let host = request.url().host_str().unwrap_or_default().to_string();
+
+            self.rate_limiter.wait_turn(&host).await;
+            let client = self.client.clone();
+            let task = tokio::spawn(async move {
+                let response = client.execute(request).await.ok()?;
+                let status = response.status();
+                let retry_after = retry_after(&response);
+                let body = response.text().await.ok()?;
+                Some((status, retry_after, body))
+            });
+            tasks.push(Some((host, task)));
+        }
+
+        let mut results = Vec::with_capacity(tasks.len());
+        for task in tasks {
+            let (host, task) = match task {
+                Some(task) => task,
+                None => {
+                    results.push(Err(ReturnAction::Failed(FetchError::Network)));
+                    continue;
+                }
+            };
+            results.push(match task.await.ok().flatten() {
+                Some((status, retry_after, body)) => {
+                    self.rate_limiter.report(&host, status, retry_after);
+                    check_status(status).map(|_| body)
+                }
+                None => Err(ReturnAction::Retry(FetchError::Network)),
+            });
+        }
+        results
+    }
+}
+
+// Retry-After header (in seconds) of response if any
+fn retry_after(response: &reqwest::Response) -> Option<u64> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+}
+
+fn check_status(status: reqwest::StatusCode) -> Result<(), ReturnAction> {
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        Err(ReturnAction::Retry(FetchError::RateLimited))
+    } else if status.is_server_error() {
+        Err(ReturnAction::Retry(FetchError::Network))
+    } else if status == reqwest::StatusCode::NOT_FOUND || status == reqwest::StatusCode::GONE {
+        Err(ReturnAction::Failed(FetchError::NotFound))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+
+    const HOST: &str = "example.com";
+
+    // Time left until host can be requested again. Test runs fast enough to not lose a second
+    fn backoff_secs(limiter: &RateLimiter) -> u64 {
+        let next_allowed = limiter.hosts[HOST].next_allowed.unwrap();
+        next_allowed
+            .saturating_duration_since(Instant::now())
+            .as_secs()
+            + 1
+    }
+
+    #[test]
+    fn backoff_doubles() {
+        let mut limiter = RateLimiter::default();
+        for expected in [1, 2, 4, 8] {
+            limiter.report(HOST, StatusCode::TOO_MANY_REQUESTS, None);
+            assert_eq!(backoff_secs(&limiter), expected);
+        }
+        assert!(limiter.is_backing_off(HOST));
+        assert!(!limiter.is_backing_off("other.com"));
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        let mut limiter = RateLimiter::default();
+        for _ in 0..40 {
+            limiter.report(HOST, StatusCode::SERVICE_UNAVAILABLE, None);
+        }
+        assert_eq!(backoff_secs(&limiter), MAX_BACKOFF.as_secs());
+    }
+
+    #[test]
+    fn retry_after_overrides() {
+        let mut limiter = RateLimiter::default();
+        limiter.report(HOST, StatusCode::TOO_MANY_REQUESTS, Some(30));
+        assert_eq!(backoff_secs(&limiter), 30);
+        limiter.report(HOST, StatusCode::TOO_MANY_REQUESTS, Some(3600));
+        assert_eq!(backoff_secs(&limiter), MAX_BACKOFF.as_secs());
+    }
+
+    #[test]
+    fn success_resets() {
+        let mut limiter = RateLimiter::default();
+        limiter.report(HOST, StatusCode::INTERNAL_SERVER_ERROR, None);
+        limiter.report(HOST, StatusCode::INTERNAL_SERVER_ERROR, None);
+        limiter.report(HOST, StatusCode::OK, None);
+        assert!(!limiter.is_backing_off(HOST));
+        limiter.report(HOST, StatusCode::INTERNAL_SERVER_ERROR, None);
+        assert_eq!(backoff_secs(&limiter), 1);
+    }
+}
@@ -0,0 +1,423 @@
+use crate::{ExtendDuration, FetchError, Fetcher, ReturnAction};
+use serde::{de::DeserializeOwned, Deserialize};
+use std::time::Duration;
+
+// SoundCloud do not have any public api anymore. Instead the same api used by their web client is
+// used here which requires a `client_id`. This id is either taken from config file or extracted
+// from the javascript of soundcloud.com web page on first request.
+const API_BASE_URL: &str = "https://api-v2.soundcloud.com";
+const WEB_URL: &str = "https://soundcloud.com";
+const CLIENT_ID_MARKER: &str = "client_id:\"";
+// Maximum number of ids that can be passed in single /tracks?ids= request
+const TRACK_IDS_PER_REQUEST: usize = 50;
+
+#[derive(Deserialize)]
+struct ScUser {
+    username: String,
+    permalink_url: String,
+    #[serde(default)]
+    track_count: usize,
+}
+
+// When fetching the content of set (playlist), only first few tracks have full information.
+// Others only have the id and remaining fields should be fetched separately
+#[derive(Deserialize)]
+struct ScTrack {
+    id: u64,
+    title: Option<String>,
+    #[serde(default)]
+    duration: u64,
+    user: Option<ScUser>,
+    permalink_url: Option<String>,
+    playback_count: Option<u64>,
+    // Upload time like 2019-05-01T10:00:00Z
+    created_at: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ScPlaylist {
+    title: String,
+    permalink_url: String,
+    user: ScUser,
+    #[serde(default)]
+    track_count: usize,
+    #[serde(default)]
+    tracks: Vec<ScTrack>,
+}
+
+#[derive(Deserialize)]
+struct ScCollection<T> {
+    collection: Vec<T>,
+}
+
+// Id of all the SoundCloud units is the permalink url of that unit. This is directly playable by
+// the player and is also what should be shown to the user as url.
+pub fn is_soundcloud_id(id: &str) -> bool {
+    id.starts_with(WEB_URL)
+}
+
+impl From<ScTrack> for super::MusicUnit {
+    fn from(track: ScTrack) -> Self {
+        super::MusicUnit {
+            artist: track.user.map(|user| user.username).unwrap_or_default(),
+            name: track.title.unwrap_or_default(),
+            duration: Duration::from_millis(track.duration).to_string(),
+            id: track.permalink_url.unwrap_or_default(),
+            live: false,
+            views: track
+                .playback_count
+                .map(crate::compact_count)
+                .unwrap_or_default(),
+            published: track
+                .created_at
+                .and_then(|time| Some(time.get(..10)?.to_string()))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl From<ScPlaylist> for super::PlaylistUnit {
+    fn from(playlist: ScPlaylist) -> Self {
+        super::PlaylistUnit {
+            name: playlist.title,
+            id: playlist.permalink_url,
+            author: playlist.user.username,
+            video_count: playlist.track_count.to_string(),
+        }
+    }
+}
+
+impl From<ScUser> for super::ArtistUnit {
+    fn from(user: ScUser) -> Self {
+        super::ArtistUnit {
+            name: user.username,
+            id: user.permalink_url,
+            video_count: user.track_count.to_string(),
+        }
+    }
+}
+
+// Source of all the scripts loaded by soundcloud.com web page in the order they appear
+fn script_urls(page: &str) -> Vec<String> {
+    page.split("<script crossorigin src=\"")
+        .skip(1)
+        .filter_map(|part| part.split('"').next())
+        .filter(|src| src.ends_with(".js"))
+        .map(str::to_string)
+        .collect()
+}
+
+// client_id as written in the javascript of web client. eg: `client_id:"abc123"`
+fn find_client_id(script: &str) -> Option<String> {
+    let start = script.find(CLIENT_ID_MARKER)? + CLIENT_ID_MARKER.len();
+    let client_id = &script[start..][..script[start..].find('"')?];
+    if client_id.is_empty() {
+        None
+    } else {
+        Some(client_id.to_string())
+    }
+}
+
+// Keep the order of set as is while replacing incomplete tracks with fetched one. Tracks that
+// could not be fetched are left out
+fn fill_tracks(mut tracks: Vec<ScTrack>, mut fetched: Vec<ScTrack>) -> Vec<super::MusicUnit> {
+    for track in tracks.iter_mut() {
+        if track.title.is_none() {
+            if let Some(index) = fetched.iter().position(|full| full.id == track.id) {
+                *track = fetched.swap_remove(index);
+            }
+        }
+    }
+
+    tracks
+        .into_iter()
+        .filter(|track| track.title.is_some())
+        .map(Into::into)
+        .collect()
+}
+
+impl Fetcher {
+    // Find the client_id from the javascript assets of web page. The id is usually inside the
+    // last script so scripts are checked in reverse order
+    async fn scrape_soundcloud_client_id(&mut self) -> Option<String> {
+        let request = self.client.get(WEB_URL);
+        let page = self.execute(request).await.ok()?.text().await.ok()?;
+
+        for script in script_urls(&page).into_iter().rev() {
+            let request = self.client.get(script);
+            let content = match self.execute(request).await {
+                Ok(response) => response.text().await.unwrap_or_default(),
+                Err(_) => continue,
+            };
+            if let Some(client_id) = find_client_id(&content) {
+                return Some(client_id);
+            }
+        }
+
+        None
+    }
+
+    // All the request to SoundCloud api should be send from this function
+    async fn send_soundcloud_request<Res>(
+        &mut self,
+        path: &str,
+        params: &[(&str, String)],
+    ) -> Result<Res, ReturnAction>
+    where
+        Res: DeserializeOwned,
+    {
+        if self.soundcloud_client_id.is_empty() {
+            match self.scrape_soundcloud_client_id().await {
+                Some(client_id) => self.soundcloud_client_id = client_id,
+                None => return Err(ReturnAction::Failed(FetchError::Network)),
+            }
+        }
+
+        let request = self
+            .client
+            .get(format!("{}{}", API_BASE_URL, path))
+            .query(params)
+            .query(&[("client_id", &self.soundcloud_client_id)]);
+        let res = self.execute(request).await;
+
+        match res {
+            Ok(response) if response.status() == reqwest::StatusCode::UNAUTHORIZED => {
+                // client_id have been rotated. Forget it so that fresh one is scraped on next
+                // request
+                self.soundcloud_client_id.clear();
+                Err(ReturnAction::Retry(FetchError::Network))
+            }
+            Ok(response) => response
+                .json::<Res>()
+                .await
+                .map_err(|_| ReturnAction::Failed(FetchError::Parse)),
+            Err(action) => Err(action),
+        }
+    }
+
+    // search! macro appends the result to the previous one so only requested chunk is fetched
+    async fn soundcloud_search<Res>(
+        &mut self,
+        kind: &str,
+        query: &str,
+        page: usize,
+    ) -> Result<Vec<Res>, ReturnAction>
+    where
+        Res: DeserializeOwned,
+    {
+        let params = [
+            ("q", query.to_string()),
+            ("limit", self.item_per_page.to_string()),
+            ("offset", (page * self.item_per_page).to_string()),
+        ];
+        let path = format!("/search/{}", kind);
+        self.send_soundcloud_request::<ScCollection<Res>>(&path, &params)
+            .await
+            .map(|res| res.collection)
+    }
+
+    pub async fn soundcloud_search_music(
+        &mut self,
+        query: &str,
+        page: usize,
+    ) -> Result<Vec<super::MusicUnit>, ReturnAction> {
+        self.soundcloud_search::<ScTrack>("tracks", query, page)
+            .await
+            .map(|tracks| tracks.into_iter().map(Into::into).collect())
+    }
+
+    pub async fn soundcloud_search_playlist(
+        &mut self,
+        query: &str,
+        page: usize,
+    ) -> Result<Vec<super::PlaylistUnit>, ReturnAction> {
+        self.soundcloud_search::<ScPlaylist>("playlists", query, page)
+            .await
+            .map(|sets| sets.into_iter().map(Into::into).collect())
+    }
+
+    pub async fn soundcloud_search_artist(
+        &mut self,
+        query: &str,
+        page: usize,
+    ) -> Result<Vec<super::ArtistUnit>, ReturnAction> {
+        self.soundcloud_search::<ScUser>("users", query, page)
+            .await
+            .map(|users| users.into_iter().map(Into::into).collect())
+    }
+
+    async fn soundcloud_resolve<Res>(&mut self, url: &str) -> Result<Res, ReturnAction>
+    where
+        Res: DeserializeOwned,
+    {
+        self.send_soundcloud_request("/resolve", &[("url", url.to_string())])
+            .await
+    }
+
+    // Get all the tracks of set. Tracks that only have id in resolved set are filled by fetching
+    // them in chunks
+    pub async fn soundcloud_playlist_tracks(
+        &mut self,
+        playlist_url: &str,
+    ) -> Result<Vec<super::MusicUnit>, ReturnAction> {
+        let playlist = self.soundcloud_resolve::<ScPlaylist>(playlist_url).await?;
+
+        let missing_ids = playlist
+            .tracks
+            .iter()
+            .filter(|track| track.title.is_none())
+            .map(|track| track.id.to_string())
+            .collect::<Vec<String>>();
+        let mut fetched = Vec::with_capacity(missing_ids.len());
+        for ids in missing_ids.chunks(TRACK_IDS_PER_REQUEST) {
+            let tracks = self
+                .send_soundcloud_request::<Vec<ScTrack>>("/tracks", &[("ids", ids.join(","))])
+                .await?;
+            fetched.extend(tracks);
+        }
+
+        Ok(fill_tracks(playlist.tracks, fetched))
+    }
+
+    pub async fn soundcloud_user_tracks(
+        &mut self,
+        user_url: &str,
+    ) -> Result<Vec<super::MusicUnit>, ReturnAction> {
+        let path = format!("{}/tracks", self.soundcloud_user_path(user_url).await?);
+        self.send_soundcloud_request::<ScCollection<ScTrack>>(&path, &[("limit", "200".into())])
+            .await
+            .map(|res| res.collection.into_iter().map(Into::into).collect())
+    }
+
+    pub async fn soundcloud_user_playlists(
+        &mut self,
+        user_url: &str,
+    ) -> Result<Vec<super::PlaylistUnit>, ReturnAction> {
+        let path = format!("{}/playlists", self.soundcloud_user_path(user_url).await?);
+        self.send_soundcloud_request::<ScCollection<ScPlaylist>>(&path, &[("limit", "200".into())])
+            .await
+            .map(|res| res.collection.into_iter().map(Into::into).collect())
+    }
+
+    // Api path of user as in /users/<numeric-id> from the permalink url of user
+    async fn soundcloud_user_path(&mut self, user_url: &str) -> Result<String, ReturnAction> {
+        #[derive(Deserialize)]
+        struct ScUserId {
+            id: u64,
+        }
+        let user = self.soundcloud_resolve::<ScUserId>(user_url).await?;
+        Ok(format!("/users/{}", user.id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Trimmed down response of /search/tracks
+    const SEARCH_TRACKS: &str = r#"{
+        "collection": [
+            {
+                "id": 1,
+                "title": "Some track",
+                "duration": 215400,
+                "user": { "username": "someone", "permalink_url": "https://soundcloud.com/someone" },
+                "permalink_url": "https://soundcloud.com/someone/some-track",
+                "playback_count": 1234567,
+                "created_at": "2019-05-01T10:00:00Z",
+                "media": { "transcodings": [] }
+            },
+            { "id": 2, "title": "Bare track", "permalink_url": "https://soundcloud.com/x/bare" }
+        ],
+        "next_href": null
+    }"#;
+
+    // Resolved set where only the first track has full information
+    const RESOLVED_SET: &str = r#"{
+        "title": "Some set",
+        "permalink_url": "https://soundcloud.com/someone/sets/some-set",
+        "user": { "username": "someone", "permalink_url": "https://soundcloud.com/someone", "track_count": 3 },
+        "track_count": 3,
+        "tracks": [
+            { "id": 1, "title": "First", "duration": 60000, "permalink_url": "https://soundcloud.com/someone/first" },
+            { "id": 2 },
+            { "id": 3 }
+        ]
+    }"#;
+
+    #[test]
+    fn client_id_is_scraped() {
+        let page = r#"<html><script crossorigin src="https://a-v2.sndcdn.com/assets/0-abc.js"></script>
+            <script src="https://other.example/x.js"></script>
+            <script crossorigin src="https://a-v2.sndcdn.com/assets/49-def.js"></script>
+            <script crossorigin src="https://a-v2.sndcdn.com/assets/style.css"></script></html>"#;
+        assert_eq!(
+            script_urls(page),
+            vec![
+                "https://a-v2.sndcdn.com/assets/0-abc.js",
+                "https://a-v2.sndcdn.com/assets/49-def.js"
+            ]
+        );
+
+        let script = r#"n.prototype={baseUrl:"x",client_id:"AbC123xYz",env:"production"}"#;
+        assert_eq!(find_client_id(script).as_deref(), Some("AbC123xYz"));
+        assert_eq!(find_client_id(r#"client_id:"" "#), None);
+        assert_eq!(find_client_id(r#"client_id:"unterminated"#), None);
+        assert_eq!(find_client_id("no id here"), None);
+    }
+
+    #[test]
+    fn tracks_are_parsed() {
+        let res: ScCollection<ScTrack> = serde_json::from_str(SEARCH_TRACKS).unwrap();
+        let music = res
+            .collection
+            .into_iter()
+            .map(crate::MusicUnit::from)
+            .collect::<Vec<_>>();
+        assert_eq!(music[0].name, "Some track");
+        assert_eq!(music[0].artist, "someone");
+        assert_eq!(music[0].duration, "03:35");
+        assert_eq!(music[0].id, "https://soundcloud.com/someone/some-track");
+        assert_eq!(music[0].views, "1.2M");
+        assert_eq!(music[0].published, "2019-05-01");
+        assert!(is_soundcloud_id(&music[0].id));
+
+        assert_eq!(music[1].artist, "");
+        assert_eq!(music[1].duration, "00:00");
+        assert_eq!(music[1].views, "");
+        assert_eq!(music[1].published, "");
+    }
+
+    #[test]
+    fn playlist_is_parsed() {
+        let mut playlist: ScPlaylist = serde_json::from_str(RESOLVED_SET).unwrap();
+        let tracks = std::mem::take(&mut playlist.tracks);
+        let unit = crate::PlaylistUnit::from(playlist);
+        assert_eq!(unit.name, "Some set");
+        assert_eq!(unit.id, "https://soundcloud.com/someone/sets/some-set");
+        assert_eq!(unit.author, "someone");
+        assert_eq!(unit.video_count, "3");
+
+        // Track 3 could not be fetched
+        let fetched: Vec<ScTrack> = serde_json::from_str(
+            r#"[{ "id": 2, "title": "Second", "permalink_url": "https://soundcloud.com/someone/second" }]"#,
+        )
+        .unwrap();
+        let names = fill_tracks(tracks, fetched)
+            .into_iter()
+            .map(|music| music.name)
+            .collect::<Vec<String>>();
+        assert_eq!(names, vec!["First", "Second"]);
+    }
+
+    #[test]
+    fn user_is_parsed() {
+        let user: ScUser = serde_json::from_str(
+            r#"{ "username": "someone", "permalink_url": "https://soundcloud.com/someone", "followers_count": 10 }"#,
+        )
+        .unwrap();
+        let artist = crate::ArtistUnit::from(user);
+        assert_eq!(artist.name, "someone");
+        assert_eq!(artist.video_count, "0");
+    }
+}
@@ -0,0 +1,287 @@
+use crate::{bandcamp, cache, soundcloud, utils::FIELDS, FetchError, Fetcher, ReturnAction};
+use config::initilize::{STORAGE, TB_FAVOURATES_ARTIST, TB_LAST_SEEN, TB_SUBSCRIPTIONS};
+use std::collections::HashMap;
+
+// Artists can be subscribed locally. Subscriptions are kept in storage db and the feed is made by
+// fetching latest uploads of every subscribed channel at once and sorting them by upload date.
+// Only youtube channels tell when music was uploaded so other channels are not part of feed.
+// New releases are the uploads of favourite and subscribed artists that are newer than the upload
+// time last seen of that artist. Artist is seen upto the newest upload when new releases are
+// listed. Newly added artist is considered seen upto it's newest upload on first check so that
+// every older upload of it is not reported as new
+
+// Every artist in the table in order of addition. Table should have same columns as favourate
+// artist table
+fn read_artists(table: &str) -> Result<Vec<super::ArtistUnit>, ReturnAction> {
+    let conn = STORAGE.lock().unwrap();
+    let query = format!(
+        "SELECT id, name, count FROM {tb_name} ORDER BY rowid",
+        tb_name = table
+    );
+
+    let mut stmt = match conn.prepare(&query) {
+        Ok(stmt) => stmt,
+        Err(err) => {
+            eprintln!(
+                "Error preparing select statement for {tb_name}. Error: {err}",
+                tb_name = table,
+                err = err
+            );
+            return Err(ReturnAction::Failed(FetchError::Storage));
+        }
+    };
+    let results = stmt.query_map([], |row| {
+        Ok(super::ArtistUnit {
+            id: row.get(0).unwrap_or_default(),
+            name: row.get(1).unwrap_or("SQL_ERROR".into()),
+            video_count: row.get(2).unwrap_or("NaN".into()),
+        })
+    });
+
+    match results {
+        Ok(results) => Ok(results.filter_map(Result::ok).collect()),
+        Err(err) => {
+            eprintln!(
+                "Cannot get artists from {tb_name}. Error: {err}",
+                tb_name = table,
+                err = err
+            );
+            Err(ReturnAction::Failed(FetchError::Storage))
+        }
+    }
+}
+
+fn read_last_seen() -> HashMap<String, i64> {
+    let conn = STORAGE.lock().unwrap();
+    let query = format!(
+        "SELECT id, published FROM {tb_name}",
+        tb_name = TB_LAST_SEEN
+    );
+    let mut stmt = match conn.prepare(&query) {
+        Ok(stmt) => stmt,
+        Err(_) => return HashMap::new(),
+    };
+    let results = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)));
+    match results {
+        Ok(results) => results.filter_map(Result::ok).collect(),
+        Err(_) => HashMap::new(),
+    }
+}
+
+fn write_last_seen(last_seen: &HashMap<String, i64>) {
+    let conn = STORAGE.lock().unwrap();
+    let query = format!(
+        "INSERT OR REPLACE INTO {tb_name} (id, published) VALUES (?, ?)",
+        tb_name = TB_LAST_SEEN
+    );
+    for (id, published) in last_seen {
+        if let Err(err) = conn.execute(&query, [id.clone(), published.to_string()]) {
+            eprintln!("Cannot save last seen upload. Error: {err}", err = err);
+        }
+    }
+}
+
+// Uploads of youtube channels for which upload time is known
+struct Upload {
+    channel_id: String,
+    published: i64,
+    music: super::MusicUnit,
+}
+
+impl Fetcher {
+    pub async fn get_subscriptions(
+        &mut self,
+        page: usize,
+    ) -> Result<Vec<super::ArtistUnit>, ReturnAction> {
+        let res = read_artists(TB_SUBSCRIPTIONS)?
+            .into_iter()
+            .skip(page * self.item_per_page)
+            .take(self.item_per_page)
+            .collect::<Vec<super::ArtistUnit>>();
+        if res.is_empty() {
+            Err(ReturnAction::EOR)
+        } else {
+            Ok(res)
+        }
+    }
+
+    pub async fn get_subscription_feed(
+        &mut self,
+        page: usize,
+    ) -> Result<Vec<super::MusicUnit>, ReturnAction> {
+        if page == 0 || self.subscription_feed.is_empty() {
+            let uploads = self
+                .fetch_uploads(read_artists(TB_SUBSCRIPTIONS)?, true)
+                .await?;
+            self.subscription_feed = uploads.into_iter().map(|upload| upload.music).collect();
+        }
+
+        let lower_limit = page * self.item_per_page;
+        let upper_limit = std::cmp::min(
+            self.subscription_feed.len(),
+            lower_limit + self.item_per_page,
+        );
+        if lower_limit >= upper_limit {
+            Err(ReturnAction::EOR)
+        } else {
+            Ok(self.subscription_feed[lower_limit..upper_limit].to_vec())
+        }
+    }
+
+    // Check for new releases without marking them as seen. Returns the number of new releases
+    pub async fn check_new_releases(&mut self) -> Result<usize, ReturnAction> {
+        let (new_releases, _) = self.collect_new_releases().await?;
+        self.new_releases = new_releases;
+        Ok(self.new_releases.len())
+    }
+
+    // New releases are listed from the first page after which they are marked as seen
+    pub async fn get_new_releases(
+        &mut self,
+        page: usize,
+    ) -> Result<Vec<super::MusicUnit>, ReturnAction> {
+        if page == 0 {
+            let (new_releases, newest) = self.collect_new_releases().await?;
+            self.new_releases = new_releases;
+            write_last_seen(&newest);
+        }
+
+        let lower_limit = page * self.item_per_page;
+        let upper_limit = std::cmp::min(self.new_releases.len(), lower_limit + self.item_per_page);
+        if lower_limit >= upper_limit {
+            Err(ReturnAction::EOR)
+        } else {
+            Ok(self.new_releases[lower_limit..upper_limit].to_vec())
+        }
+    }
+
+    // Uploads newer than last seen (newest first) along with the newest upload time of every
+    // artist. Artists never seen before are marked as seen here. Cached uploads may be older than
+    // the latest upload so those are always requested again
+    async fn collect_new_releases(
+        &mut self,
+    ) -> Result<(Vec<super::MusicUnit>, HashMap<String, i64>), ReturnAction> {
+        let mut artists = read_artists(TB_FAVOURATES_ARTIST)?;
+        for artist in read_artists(TB_SUBSCRIPTIONS)? {
+            if !artists.iter().any(|known| known.id == artist.id) {
+                artists.push(artist);
+            }
+        }
+        let uploads = self.fetch_uploads(artists, false).await?;
+
+        let mut newest = HashMap::<String, i64>::new();
+        for upload in &uploads {
+            let time = newest.entry(upload.channel_id.clone()).or_default();
+            *time = upload.published.max(*time);
+        }
+        let last_seen = read_last_seen();
+        let never_seen = newest
+            .iter()
+            .filter(|(id, _)| !last_seen.contains_key(*id))
+            .map(|(id, published)| (id.clone(), *published))
+            .collect::<HashMap<String, i64>>();
+        write_last_seen(&never_seen);
+
+        let new_releases = uploads
+            .into_iter()
+            .filter(|upload| {
+                matches!(last_seen.get(&upload.channel_id), Some(seen) if upload.published > *seen)
+            })
+            .map(|upload| upload.music)
+            .collect();
+        Ok((new_releases, newest))
+    }
+
+    // Latest uploads of all the artists sorted newest first. Cached response is not used unless
+    // `use_cache` is set but fresh response is cached either way
+    async fn fetch_uploads(
+        &mut self,
+        artists: Vec<super::ArtistUnit>,
+        use_cache: bool,
+    ) -> Result<Vec<Upload>, ReturnAction> {
+        let channel_ids = artists
+            .into_iter()
+            .map(|artist| artist.id)
+            .filter(|id| {
+                !(soundcloud::is_soundcloud_id(id)
+                    || bandcamp::is_bandcamp_id(id)
+                    || Self::is_local_id(id))
+            })
+            .collect::<Vec<String>>();
+        if channel_ids.is_empty() {
+            return Err(ReturnAction::EOR);
+        }
+        let paths = channel_ids
+            .iter()
+            .map(|channel_id| {
+                format!(
+                    "/channels/{channel_id}/videos?sort_by=newest&fields=published,{music_field}",
+                    channel_id = channel_id,
+                    music_field = FIELDS[0]
+                )
+            })
+            .collect::<Vec<String>>();
+
+        // Cached uploads are used as they are (when allowed) and rest are requested together.
+        // Every request is sent to next server so that they are not waiting for each other due to
+        // rate limit
+        let mut bodies = paths
+            .iter()
+            .map(|path| use_cache.then(|| cache::lookup(path)).flatten())
+            .collect::<Vec<Option<String>>>();
+        let mut requests = Vec::new();
+        for path in paths.iter().zip(&bodies).filter(|(_, body)| body.is_none()) {
+            self.change_server();
+            let url = self.servers[self.active_server_index].to_string() + path.0;
+            requests.push(self.client.get(url));
+        }
+        let mut responses = self.execute_many(requests).await.into_iter();
+
+        let mut last_error = None;
+        for (path, body) in paths.iter().zip(bodies.iter_mut()) {
+            if body.is_some() {
+                continue;
+            }
+            match responses.next() {
+                Some(Ok(response)) => {
+                    if serde_json::from_str::<serde_json::Value>(&response).is_ok() {
+                        cache::store(path, &response);
+                    }
+                    *body = Some(response);
+                }
+                Some(Err(ReturnAction::Failed(reason) | ReturnAction::Retry(reason))) => {
+                    last_error = Some(reason)
+                }
+                _ => {}
+            }
+        }
+
+        let mut uploads = Vec::new();
+        for (channel_id, body) in channel_ids.into_iter().zip(bodies) {
+            let items = body
+                .and_then(|body| serde_json::from_str::<Vec<serde_json::Value>>(&body).ok())
+                .unwrap_or_default();
+            for item in items {
+                let published = match item.get("published").and_then(|time| time.as_i64()) {
+                    Some(published) => published,
+                    None => continue,
+                };
+                if let Ok(music) = serde_json::from_value::<super::MusicUnit>(item) {
+                    uploads.push(Upload {
+                        channel_id: channel_id.clone(),
+                        published,
+                        music,
+                    });
+                }
+            }
+        }
+        uploads.sort_by_key(|upload| std::cmp::Reverse(upload.published));
+
+        match (uploads.is_empty(), last_error) {
+            // Show why nothing could be fetched rather than saying there is nothing
+            (true, Some(reason)) => Err(ReturnAction::Failed(reason)),
+            (true, None) => Err(ReturnAction::EOR),
+            (false, _) => Ok(uploads),
+        }
+    }
+}
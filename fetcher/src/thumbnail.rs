@@ -0,0 +1,27 @@
+use crate::{FetchError, Fetcher, ReturnAction};
+
+// Thumbnail of every youtube music is served from youtube's image server by the id of music.
+// mqdefault is 320x180 which is already more than what fits in corner of terminal
+const THUMBNAIL_URL: &str = "https://i.ytimg.com/vi";
+
+// Url of thumbnail of youtube music. Also given to others (eg: desktop media controls) as art url
+pub fn thumbnail_url(music_id: &str) -> String {
+    format!(
+        "{base}/{music_id}/mqdefault.jpg",
+        base = THUMBNAIL_URL,
+        music_id = music_id
+    )
+}
+
+impl Fetcher {
+    // Thumbnail (jpeg encoded) of youtube music
+    pub async fn get_thumbnail(&mut self, music_id: &str) -> Result<Vec<u8>, ReturnAction> {
+        let response = self
+            .execute(self.client.get(thumbnail_url(music_id)))
+            .await?;
+        match response.bytes().await {
+            Ok(bytes) => Ok(bytes.to_vec()),
+            Err(_) => Err(ReturnAction::Retry(FetchError::Network)),
+        }
+    }
+}
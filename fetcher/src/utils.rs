@@ -1,4 +1,6 @@
-use crate::{Fetcher, ReturnAction};
+use crate::{
+    bandcamp, cache, chapters, comments, ratelimit, soundcloud, FetchError, Fetcher, ReturnAction,
+};
 use config::initilize::{
     CONFIG, STORAGE, TB_FAVOURATES_ARTIST, TB_FAVOURATES_MUSIC, TB_FAVOURATES_PLAYLIST,
 };
@@ -7,8 +9,8 @@ use std::iter::DoubleEndedIterator;
 use std::time::Duration;
 
 const USER_AGENT: &str = "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/92.0.4515.131 Safari/537.36";
-const FIELDS: [&str; 3] = [
-    "videoId,title,author,lengthSeconds",
+pub(crate) const FIELDS: [&str; 3] = [
+    "videoId,title,author,lengthSeconds,liveNow,viewCount,publishedText",
     "title,playlistId,author,videoCount",
     "author,authorId,videoCount",
 ];
@@ -61,22 +63,50 @@ impl crate::ExtendDuration for Duration {
     }
 }
 
+// Client through which every request is sent. Proxy from config file is used if any. Invalid proxy
+// is reported and ignored rather than failing every request
+fn build_client() -> reqwest::Client {
+    let mut builder = reqwest::ClientBuilder::default()
+        .user_agent(USER_AGENT)
+        .gzip(true);
+    let proxy = CONFIG.constants.proxy.trim();
+    if !proxy.is_empty() {
+        match reqwest::Proxy::all(proxy) {
+            Ok(proxy) => builder = builder.proxy(proxy),
+            Err(err) => eprintln!("Ignoring invalid proxy {}. Error: {err}", proxy, err = err),
+        }
+    }
+    builder.build().unwrap()
+}
+
 impl Default for Fetcher {
     fn default() -> Self {
+        cache::purge_expired();
         super::Fetcher {
             trending_now: None,
             playlist_content: super::PlaylistRes::default(),
+            playlist_info: None,
             artist_content: super::ArtistRes::default(),
+            related_content: super::PlaylistRes::default(),
+            charts: None,
+            mood_categories: Vec::new(),
+            mood_playlists: (String::new(), Vec::new()),
+            subscription_feed: Vec::new(),
+            new_releases: Vec::new(),
+            album_content: None,
             search_res: super::SearchRes::default(),
+            backend: super::Backend::from_name(&CONFIG.constants.search_backend)
+                .unwrap_or(super::Backend::Invidious),
+            ytmusic_continuation: Default::default(),
             servers: &CONFIG.servers.list,
-            client: reqwest::ClientBuilder::default()
-                .user_agent(USER_AGENT)
-                .gzip(true)
-                .build()
-                .unwrap(),
+            client: build_client(),
+            rate_limiter: ratelimit::RateLimiter::default(),
             active_server_index: 0,
             region: &CONFIG.constants.region,
             item_per_page: CONFIG.constants.item_per_list,
+            local_dir: &CONFIG.local.path,
+            local_index: None,
+            soundcloud_client_id: CONFIG.constants.soundcloud_client_id.clone(),
         }
     }
 }
@@ -84,74 +114,135 @@ impl Default for Fetcher {
 macro_rules! search {
     ("music", $fetcher: expr, $query: expr, $page: expr) => {
         search!(
-            "@internal-core",
+            "@dispatch",
             $fetcher,
             $query,
             $page,
             $fetcher.search_res.music,
             0,
-            super::MusicUnit
+            super::MusicUnit,
+            ytmusic_search_music,
+            search_local_music,
+            soundcloud_search_music,
+            bandcamp_search_music
         )
     };
     ("playlist", $fetcher: expr, $query: expr, $page: expr) => {
         search!(
-            "@internal-core",
+            "@dispatch",
             $fetcher,
             $query,
             $page,
             $fetcher.search_res.playlist,
             1,
-            super::PlaylistUnit
+            super::PlaylistUnit,
+            ytmusic_search_playlist,
+            search_local_playlist,
+            soundcloud_search_playlist,
+            bandcamp_search_playlist
         )
     };
     ("artist", $fetcher: expr, $query: expr, $page: expr) => {
         search!(
-            "@internal-core",
+            "@dispatch",
             $fetcher,
             $query,
             $page,
             $fetcher.search_res.artist,
             2,
-            super::ArtistUnit
+            super::ArtistUnit,
+            ytmusic_search_artist,
+            search_local_artist,
+            soundcloud_search_artist,
+            bandcamp_search_artist
         )
     };
 
-    ("@internal-core", $fetcher: expr, $query: expr, $page: expr, $store_target: expr, $filter_index: expr, $unit_type: ty) => {{
-        let suffix = format!(
-            "/search?q={query}&type={s_type}&{region}&page={page}&fields={fields}",
-            query = $query,
-            s_type = FILTER_TYPE[$filter_index],
-            region = $fetcher.region,
-            fields = FIELDS[$filter_index],
-            page = $page
-        );
+    // Decide the backend to which query is to be sent and build the corresponding request.
+    // The request expression is only evaluated inside @internal-core when data is really needed
+    ("@dispatch", $fetcher: expr, $query: expr, $page: expr, $store_target: expr, $filter_index: expr, $unit_type: ty, $ytmusic_fn: ident, $local_fn: ident, $soundcloud_fn: ident, $bandcamp_fn: ident) => {{
+        use crate::filter::{FilterUnit, SearchFilter};
+        let (backend, backend_query) = super::Backend::from_query($query, $fetcher.backend);
+        let (search_filter, backend_query) = SearchFilter::from_query(backend_query);
+        let backend_query = backend_query.as_str();
+        search!(
+            "@internal-core",
+            $fetcher,
+            $query,
+            $page,
+            $store_target,
+            $filter_index,
+            |unit: &$unit_type| unit.passes(&search_filter),
+            {
+                let page = $fetcher.search_res.backend_page;
+                match backend {
+                    super::Backend::Invidious => {
+                        let suffix = invidious_search_suffix(
+                            backend_query,
+                            $filter_index,
+                            $fetcher.region,
+                            page,
+                            &search_filter.invidious_params(),
+                        );
+                        $fetcher.send_request::<Vec<$unit_type>>(&suffix, 1).await
+                    }
+                    super::Backend::YtMusic => $fetcher.$ytmusic_fn(backend_query, page).await,
+                    super::Backend::Local => $fetcher.$local_fn(backend_query, page).await,
+                    super::Backend::SoundCloud => {
+                        $fetcher.$soundcloud_fn(backend_query, page).await
+                    }
+                    super::Backend::Bandcamp => $fetcher.$bandcamp_fn(backend_query, page).await,
+                }
+            }
+        )
+    }};
+
+    // Units that filter drops are never stored, so a page shown may need more than one page from
+    // backend. Backend pages are fetched until the requested page is filled or backend have
+    // nothing more, but at most MAX_BACKEND_PAGES at once so that filter matching almost nothing
+    // do not flood the server. Page that is still not filled then is asked to be retried, and
+    // asking for same page again continues from where it was left. Only backend that have nothing
+    // more ends the result. Some invidious instances keep answering with last page, so page same
+    // as the previous one is also taken as end of result
+    ("@internal-core", $fetcher: expr, $query: expr, $page: expr, $store_target: expr, $filter_index: expr, $keep: expr, $request: expr) => {{
         let lower_limit = $page * $fetcher.item_per_page;
-        let mut upper_limit =
-            std::cmp::min($store_target.len(), lower_limit + $fetcher.item_per_page);
 
         let is_new_query = *$query != $fetcher.search_res.query;
         let is_new_type = $fetcher.search_res.last_fetched != $filter_index;
-        let insufficient_data =
-            upper_limit.checked_sub(lower_limit).unwrap_or(0) < $fetcher.item_per_page;
 
         $fetcher.search_res.last_fetched = $filter_index;
-        if is_new_query || insufficient_data || is_new_type {
-            let obj = $fetcher.send_request::<Vec<$unit_type>>(&suffix, 1).await;
-            if is_new_query || is_new_type {
-                $store_target.clear();
-            }
-            match obj {
+        if is_new_query || is_new_type {
+            $store_target.clear();
+            $fetcher.search_res.backend_page = 0;
+            $fetcher.search_res.is_exhausted = false;
+            $fetcher.search_res.last_page = None;
+        }
+        let mut fetched_pages = 0;
+        while $store_target.len() < lower_limit + $fetcher.item_per_page
+            && !$fetcher.search_res.is_exhausted
+            && fetched_pages < MAX_BACKEND_PAGES
+        {
+            match $request {
                 Ok(data) => {
+                    fetched_pages += 1;
+                    let page_hash = hash_page(&data);
+                    let is_repeated = $fetcher.search_res.last_page == Some(page_hash);
                     $fetcher.search_res.query = $query.to_string();
-                    $store_target.extend_from_slice(data.as_slice());
-                    upper_limit =
-                        std::cmp::min($store_target.len(), lower_limit + $fetcher.item_per_page);
+                    $fetcher.search_res.backend_page += 1;
+                    $fetcher.search_res.is_exhausted = data.is_empty() || is_repeated;
+                    $fetcher.search_res.last_page = Some(page_hash);
+                    if !is_repeated {
+                        $store_target.extend(data.into_iter().filter($keep));
+                    }
                 }
                 Err(e) => return Err(e),
             }
         }
 
-        if upper_limit > lower_limit {
+        let upper_limit = std::cmp::min($store_target.len(), lower_limit + $fetcher.item_per_page);
+        if upper_limit < lower_limit + $fetcher.item_per_page && !$fetcher.search_res.is_exhausted {
+            Err(ReturnAction::Retry(crate::FetchError::Unfilled))
+        } else if upper_limit > lower_limit {
             Ok($store_target[lower_limit..upper_limit].to_vec())
         } else {
             Err(ReturnAction::EOR)
@@ -159,12 +250,58 @@ macro_rules! search {
     }};
 }
 
+// Most pages fetched from backend to fill a single page of search result. See search!
+const MAX_BACKEND_PAGES: usize = 4;
+
+// Request suffix of search in invidious. `backend_page` counts from 0 as every other backend does
+// but invidious page starts from 1, where page 0 is same as page 1
+fn invidious_search_suffix(
+    query: &str,
+    filter_index: usize,
+    region: &str,
+    backend_page: usize,
+    filters: &str,
+) -> String {
+    format!(
+        "/search?q={query}&type={s_type}&region={region}&page={page}&fields={fields}{filters}",
+        query = query,
+        s_type = FILTER_TYPE[filter_index],
+        region = region,
+        fields = FIELDS[filter_index],
+        page = backend_page + 1,
+        filters = filters
+    )
+}
+
+// Page fetched from backend is compared with the previous one by this. See search!
+fn hash_page<Unit: serde::Serialize>(units: &[Unit]) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    serde_json::to_string(units)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
 impl Fetcher {
+    // Move to next server. Servers that are backing off from recent failure are skipped. If every
+    // server is backing off, request simply waits for it's turn. See ratelimit.rs
     pub fn change_server(&mut self) {
-        self.active_server_index = (self.active_server_index + 1) % self.servers.len();
+        for _ in 0..self.servers.len() {
+            self.active_server_index = (self.active_server_index + 1) % self.servers.len();
+            let host = reqwest::Url::parse(&self.servers[self.active_server_index])
+                .ok()
+                .and_then(|url| url.host_str().map(str::to_string))
+                .unwrap_or_default();
+            if !self.rate_limiter.is_backing_off(&host) {
+                break;
+            }
+        }
     }
 
-    // All the request should be send from this function
+    // All the request to invidious should be send from this function. Request failing for reason
+    // that may go away (see Fetcher::execute) is retried on next server for retry_for more times
+    // after which it fails with the reason of last failure
     async fn send_request<'de, Res>(
         &mut self,
         path: &str,
@@ -173,37 +310,54 @@ impl Fetcher {
     where
         Res: serde::de::DeserializeOwned,
     {
-        self.change_server();
+        if let Some(obj) = cache::lookup(path).and_then(|body| serde_json::from_str(&body).ok()) {
+            return Ok(obj);
+        }
 
-        let url = self.servers[self.active_server_index].to_string() + path;
-        let res = self.client.get(url).send().await;
+        let mut last_error = FetchError::Network;
+        for _ in 0..=retry_for {
+            self.change_server();
 
-        match res {
-            Ok(response) => {
-                if let Ok(obj) = response.json::<Res>().await {
-                    Ok(obj)
-                } else {
-                    Err(ReturnAction::Failed)
+            let url = self.servers[self.active_server_index].to_string() + path;
+            let request = self.client.get(url);
+            match self.execute(request).await {
+                Ok(response) => {
+                    let body = response.text().await.unwrap_or_default();
+                    return match serde_json::from_str::<Res>(&body) {
+                        Ok(obj) => {
+                            cache::store(path, &body);
+                            Ok(obj)
+                        }
+                        Err(_) => Err(ReturnAction::Failed(FetchError::Parse)),
+                    };
                 }
+                Err(ReturnAction::Retry(reason)) => last_error = reason,
+                Err(action) => return Err(action),
             }
-            Err(_) if retry_for > 0 => {
-                self.change_server();
-                Err(ReturnAction::Retry)
-            }
-            Err(_) => Err(ReturnAction::Failed),
         }
+
+        Err(ReturnAction::Failed(last_error))
     }
 
+    // region is the ISO country code of which trending music is to be fetched
     pub async fn get_trending_music(
         &mut self,
+        region: &str,
+        category: super::TrendingCategory,
         page: usize,
     ) -> Result<Vec<super::MusicUnit>, ReturnAction> {
         let lower_limit = self.item_per_page * page;
 
-        if self.trending_now.is_none() {
+        let is_new_trending = !matches!(
+            &self.trending_now,
+            Some(((fetched_region, fetched_category), _))
+                if fetched_region == region && *fetched_category == category
+        );
+        if is_new_trending {
             let suffix = format!(
-                "/trending?type=Music&region={region}&fields={music_field}",
-                region = self.region,
+                "/trending?region={region}{category}&fields={music_field}",
+                region = region,
+                category = category.type_param(),
                 music_field = FIELDS[0]
             );
 
@@ -211,13 +365,13 @@ impl Fetcher {
             match obj {
                 Ok(mut res) => {
                     res.shrink_to_fit();
-                    self.trending_now = Some(res);
+                    self.trending_now = Some(((region.to_string(), category), res));
                 }
                 Err(e) => return Err(e),
             }
         }
 
-        let trending_now = self.trending_now.as_ref().unwrap();
+        let trending_now = &self.trending_now.as_ref().unwrap().1;
         let upper_limit = std::cmp::min(trending_now.len(), lower_limit + self.item_per_page);
 
         if lower_limit >= upper_limit {
@@ -232,24 +386,68 @@ impl Fetcher {
         playlist_id: &str,
         page: usize,
     ) -> Result<Vec<super::MusicUnit>, ReturnAction> {
+        // Playlist from local directory is served from the local index instead
+        if Self::is_local_id(playlist_id) {
+            return self.get_local_album_content(playlist_id, page).await;
+        }
+        // Album is fetched along with it's info and kept seperately. See get_album
+        if super::is_album_id(playlist_id) {
+            let item_per_page = self.item_per_page;
+            let album = self.get_album(playlist_id).await?;
+            let lower_limit = page * item_per_page;
+            let upper_limit = std::cmp::min(album.tracks.len(), lower_limit + item_per_page);
+            return if lower_limit >= upper_limit {
+                Err(ReturnAction::EOR)
+            } else {
+                Ok(album.tracks[lower_limit..upper_limit].to_vec())
+            };
+        }
+
         let lower_limit = page * self.item_per_page;
 
         let is_new_id = *playlist_id != self.playlist_content.id;
         if is_new_id {
-            self.playlist_content.id = playlist_id.to_string();
+            // Mix is served from seperate endpoint but the response is same as of playlist
             let suffix = format!(
-                "/playlists/{playlist_id}?fields=videos({music_field})",
+                "/{endpoint}/{playlist_id}?fields=title,author,videoCount,videos({music_field})",
+                endpoint = if super::is_mix_id(playlist_id) {
+                    "mixes"
+                } else {
+                    "playlists"
+                },
                 playlist_id = playlist_id,
                 music_field = FIELDS[0]
             );
 
-            let obj = self
-                .send_request::<super::FetchPlaylistContentRes>(&suffix, 1)
-                .await;
+            let mut info = None;
+            let obj = if soundcloud::is_soundcloud_id(playlist_id) {
+                self.soundcloud_playlist_tracks(playlist_id).await
+            } else if bandcamp::is_bandcamp_id(playlist_id) {
+                self.bandcamp_album_tracks(playlist_id).await
+            } else {
+                self.send_request::<super::FetchPlaylistContentRes>(&suffix, 1)
+                    .await
+                    .map(|data| {
+                        info = Some(super::PlaylistUnit {
+                            name: data.title,
+                            id: playlist_id.to_string(),
+                            author: data.author,
+                            video_count: match data.video_count {
+                                0 => String::new(),
+                                count => count.to_string(),
+                            },
+                        });
+                        data.videos
+                    })
+            };
             match obj {
-                Ok(mut data) => {
-                    data.videos.shrink_to_fit();
-                    self.playlist_content.music = data.videos;
+                Ok(mut videos) => {
+                    videos.shrink_to_fit();
+                    // id is only updated on success. Otherwise (or when request is cancelled
+                    // midway) content of previous playlist would be served as of this one
+                    self.playlist_content.id = playlist_id.to_string();
+                    self.playlist_content.music = videos;
+                    self.playlist_info = info;
                 }
                 Err(e) => return Err(e),
             }
@@ -268,6 +466,221 @@ impl Fetcher {
         }
     }
 
+    // Title, author and number of music of playlist last fetched by get_playlist_content. None if
+    // server did not tell or other playlist was fetched since
+    pub fn playlist_info(&self, playlist_id: &str) -> Option<&super::PlaylistUnit> {
+        self.playlist_info
+            .as_ref()
+            .filter(|info| info.id == playlist_id)
+    }
+
+    // Get every music of playlist instead of just the first chunk. Invidious only returns limited
+    // number of music per request so next chunks are requested until nothing new is returned.
+    // Other sources are always fetched completely so those are simply read page by page.
+    // Fetched music is also cached so that following get_playlist_content of same id serve from it
+    pub async fn get_full_playlist(
+        &mut self,
+        playlist_id: &str,
+    ) -> Result<Vec<super::MusicUnit>, ReturnAction> {
+        let is_invidious = !(Self::is_local_id(playlist_id)
+            || super::is_album_id(playlist_id)
+            || super::is_mix_id(playlist_id)
+            || soundcloud::is_soundcloud_id(playlist_id)
+            || bandcamp::is_bandcamp_id(playlist_id));
+
+        let mut music = Vec::new();
+        if !is_invidious {
+            for page in 0.. {
+                match self.get_playlist_content(playlist_id, page).await {
+                    Ok(chunk) => music.extend(chunk),
+                    Err(ReturnAction::EOR) => break,
+                    Err(e) => return Err(e),
+                }
+            }
+            return Ok(music);
+        }
+
+        let mut seen_ids = std::collections::HashSet::new();
+        // Invidious page starts from 1. Out of range page may return the last chunk again on some
+        // instances so stop when the chunk have nothing that is not seen already
+        for page in 1.. {
+            let suffix = format!(
+                "/playlists/{playlist_id}?page={page}&fields=videos({music_field})",
+                playlist_id = playlist_id,
+                page = page,
+                music_field = FIELDS[0]
+            );
+            let chunk = self
+                .send_request::<super::FetchPlaylistContentRes>(&suffix, 1)
+                .await?
+                .videos;
+            let fetched_before = music.len();
+            music.extend(
+                chunk
+                    .into_iter()
+                    .filter(|unit| seen_ids.insert(unit.id.clone())),
+            );
+            if music.len() == fetched_before {
+                break;
+            }
+        }
+
+        if music.is_empty() {
+            return Err(ReturnAction::EOR);
+        }
+        music.shrink_to_fit();
+        self.playlist_content.id = playlist_id.to_string();
+        self.playlist_content.music = music.clone();
+        Ok(music)
+    }
+
+    // Get the album of given id. Album is fetched only when it is different than the last one
+    pub async fn get_album(&mut self, album_id: &str) -> Result<&super::Album, ReturnAction> {
+        let is_new_id = !matches!(&self.album_content, Some(album) if album.id == album_id);
+        if is_new_id {
+            let mut album = if bandcamp::is_bandcamp_id(album_id) {
+                self.bandcamp_album(album_id).await?
+            } else {
+                let suffix = format!(
+                    "/playlists/{album_id}?fields=title,author,videos({music_field})",
+                    album_id = album_id,
+                    music_field = FIELDS[0]
+                );
+                let data = self
+                    .send_request::<super::FetchAlbumRes>(&suffix, 1)
+                    .await?;
+                // Youtube names the auto-generated album playlist as "Album - <name>" and the
+                // playlist itself do not have release year. See ytmusic_album_year
+                let year = self.ytmusic_album_year(album_id).await;
+                let artist = data
+                    .videos
+                    .first()
+                    .map(|track| track.artist.clone())
+                    .unwrap_or(data.author);
+                super::Album {
+                    id: album_id.to_string(),
+                    title: data.title.trim_start_matches("Album - ").to_string(),
+                    artist,
+                    year,
+                    tracks: data.videos,
+                }
+            };
+            album.tracks.shrink_to_fit();
+            self.album_content = Some(album);
+        }
+
+        Ok(self.album_content.as_ref().unwrap())
+    }
+
+    // Related music as recommended by youtube for given music. Only youtube music have these.
+    // Music from local directory or other backends never have anything related
+    pub async fn get_related_music(
+        &mut self,
+        music_id: &str,
+        page: usize,
+    ) -> Result<Vec<super::MusicUnit>, ReturnAction> {
+        if Self::is_local_id(music_id) || music_id.contains("://") {
+            return Err(ReturnAction::EOR);
+        }
+
+        let lower_limit = page * self.item_per_page;
+
+        let is_new_id = *music_id != self.related_content.id;
+        if is_new_id {
+            let suffix = format!(
+                "/videos/{music_id}?fields=recommendedVideos({music_field})",
+                music_id = music_id,
+                music_field = FIELDS[0]
+            );
+
+            match self
+                .send_request::<super::FetchRelatedRes>(&suffix, 1)
+                .await
+            {
+                Ok(data) => {
+                    let mut videos = data.recommended_videos;
+                    videos.shrink_to_fit();
+                    self.related_content.id = music_id.to_string();
+                    self.related_content.music = videos;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        let upper_limit = std::cmp::min(
+            self.related_content.music.len(),
+            lower_limit + self.item_per_page,
+        );
+        if lower_limit >= upper_limit {
+            Err(ReturnAction::EOR)
+        } else {
+            Ok(self.related_content.music[lower_limit..upper_limit].to_vec())
+        }
+    }
+
+    // Description of music as written by uploader. Only youtube music have description so music
+    // from other source always result in EOR. EOR is also returned when description is empty
+    pub async fn get_description(&mut self, music_id: &str) -> Result<String, ReturnAction> {
+        if Self::is_local_id(music_id) || music_id.contains("://") {
+            return Err(ReturnAction::EOR);
+        }
+
+        let suffix = format!("/videos/{music_id}?fields=description", music_id = music_id);
+        let res = self
+            .send_request::<chapters::FetchDescriptionRes>(&suffix, 1)
+            .await?;
+        if res.description.trim().is_empty() {
+            Err(ReturnAction::EOR)
+        } else {
+            Ok(res.description)
+        }
+    }
+
+    // Chapters of music as written in it's description. See chapters.rs. EOR is returned when
+    // there is no description or no chapters in it
+    pub async fn get_chapters(
+        &mut self,
+        music_id: &str,
+    ) -> Result<Vec<chapters::Chapter>, ReturnAction> {
+        let description = self.get_description(music_id).await?;
+        let chapters = chapters::parse_chapters(&description);
+        if chapters.is_empty() {
+            Err(ReturnAction::EOR)
+        } else {
+            Ok(chapters)
+        }
+    }
+
+    // Chunk of comments of music. First chunk is fetched when continuation is None. Only youtube
+    // music have comments
+    pub async fn get_comments(
+        &mut self,
+        music_id: &str,
+        continuation: Option<&str>,
+    ) -> Result<comments::CommentsChunk, ReturnAction> {
+        if Self::is_local_id(music_id) || music_id.contains("://") {
+            return Err(ReturnAction::EOR);
+        }
+
+        let mut suffix = format!(
+            "/comments/{music_id}?fields=comments(author,content,likeCount,publishedText),continuation",
+            music_id = music_id
+        );
+        if let Some(continuation) = continuation {
+            suffix.push_str("&continuation=");
+            suffix.push_str(continuation);
+        }
+
+        let chunk = self
+            .send_request::<comments::CommentsChunk>(&suffix, 1)
+            .await?;
+        if chunk.comments.is_empty() {
+            Err(ReturnAction::EOR)
+        } else {
+            Ok(chunk)
+        }
+    }
+
     pub async fn get_playlist_of_channel(
         &mut self,
         channel_id: &str,
@@ -277,20 +690,48 @@ impl Fetcher {
 
         let is_new_id = *channel_id != self.artist_content.playlist.0;
         if is_new_id || self.artist_content.playlist.1.is_empty() {
-            self.artist_content.playlist.0 = channel_id.to_string();
             let suffix = format!(
                 "/channels/{channel_id}/playlists?fields=playlists({channel_fields})",
                 channel_id = channel_id,
                 channel_fields = FIELDS[1],
             );
 
-            let obj = self
-                .send_request::<super::FetchArtistPlaylist>(&suffix, 1)
-                .await;
+            let obj = if soundcloud::is_soundcloud_id(channel_id) {
+                self.soundcloud_user_playlists(channel_id).await
+            } else if bandcamp::is_bandcamp_id(channel_id) {
+                self.bandcamp_band_albums(channel_id).await
+            } else {
+                // Albums and other releases of artist are listed seperately from the playlists
+                // in channel. Show them first followed by regular playlists. Older invidious
+                // instances do not have releases endpoint so failure is simply ignored
+                let releases_suffix = format!(
+                    "/channels/{channel_id}/releases?fields=playlists({channel_fields})",
+                    channel_id = channel_id,
+                    channel_fields = FIELDS[1],
+                );
+                let releases = self
+                    .send_request::<super::FetchArtistPlaylist>(&releases_suffix, 0)
+                    .await
+                    .map(|data| data.playlists)
+                    .unwrap_or_default();
+
+                self.send_request::<super::FetchArtistPlaylist>(&suffix, 1)
+                    .await
+                    .map(|data| {
+                        let mut playlists = releases;
+                        for playlist in data.playlists {
+                            if !playlists.iter().any(|release| release.id == playlist.id) {
+                                playlists.push(playlist);
+                            }
+                        }
+                        playlists
+                    })
+            };
             match obj {
-                Ok(mut data) => {
-                    data.playlists.shrink_to_fit();
-                    self.artist_content.playlist.1 = data.playlists;
+                Ok(mut playlists) => {
+                    playlists.shrink_to_fit();
+                    self.artist_content.playlist.0 = channel_id.to_string();
+                    self.artist_content.playlist.1 = playlists;
                 }
                 Err(e) => return Err(e),
             }
@@ -309,6 +750,30 @@ impl Fetcher {
         }
     }
 
+    // Name, subscriber count and description of channel. Only youtube channels have these so
+    // channels from other source always result in EOR
+    pub async fn get_channel_info(
+        &mut self,
+        channel_id: &str,
+    ) -> Result<super::ChannelInfo, ReturnAction> {
+        if Self::is_local_id(channel_id)
+            || soundcloud::is_soundcloud_id(channel_id)
+            || bandcamp::is_bandcamp_id(channel_id)
+        {
+            return Err(ReturnAction::EOR);
+        }
+
+        let suffix = format!(
+            "/channels/{channel_id}?fields=author,subCount,description",
+            channel_id = channel_id
+        );
+        let mut info = self.send_request::<super::ChannelInfo>(&suffix, 1).await?;
+        info.id = channel_id.to_string();
+        Ok(info)
+    }
+
+    // Videos are always listed newest first. This is also what is used to list episodes of
+    // podcast channel
     pub async fn get_videos_of_channel(
         &mut self,
         channel_id: &str,
@@ -318,17 +783,23 @@ impl Fetcher {
 
         let is_new_id = *channel_id != self.artist_content.music.0;
         if is_new_id || self.artist_content.music.1.is_empty() {
-            self.artist_content.music.0 = channel_id.to_string();
             let suffix = format!(
-                "/channels/{channel_id}/videos&fields={music_field}",
+                "/channels/{channel_id}/videos?sort_by=newest&fields={music_field}",
                 channel_id = channel_id,
                 music_field = FIELDS[0]
             );
 
-            let obj = self.send_request::<Vec<super::MusicUnit>>(&suffix, 1).await;
+            let obj = if soundcloud::is_soundcloud_id(channel_id) {
+                self.soundcloud_user_tracks(channel_id).await
+            } else if bandcamp::is_bandcamp_id(channel_id) {
+                self.bandcamp_band_tracks(channel_id).await
+            } else {
+                self.send_request::<Vec<super::MusicUnit>>(&suffix, 1).await
+            };
             match obj {
                 Ok(mut data) => {
                     data.shrink_to_fit();
+                    self.artist_content.music.0 = channel_id.to_string();
                     self.artist_content.music.1 = data;
                 }
                 Err(e) => return Err(e),
@@ -348,6 +819,62 @@ impl Fetcher {
         }
     }
 
+    // Search music only within uploads of given channel. Youtube channel is searched with channel
+    // search endpoint. Other backends do not have such endpoint so all the tracks of artist is
+    // fetched (and cached as in get_videos_of_channel) and filtered by title instead
+    pub async fn search_channel_music(
+        &mut self,
+        channel_id: &str,
+        query: &str,
+        page: usize,
+    ) -> Result<Vec<super::MusicUnit>, ReturnAction> {
+        if soundcloud::is_soundcloud_id(channel_id) || bandcamp::is_bandcamp_id(channel_id) {
+            if *channel_id != self.artist_content.music.0 || self.artist_content.music.1.is_empty()
+            {
+                self.get_videos_of_channel(channel_id, 0).await?;
+            }
+            let query = query.to_lowercase();
+            let res = self
+                .artist_content
+                .music
+                .1
+                .iter()
+                .filter(|music| music.name.to_lowercase().contains(&query))
+                .skip(page * self.item_per_page)
+                .take(self.item_per_page)
+                .cloned()
+                .collect::<Vec<super::MusicUnit>>();
+            return if res.is_empty() {
+                Err(ReturnAction::EOR)
+            } else {
+                Ok(res)
+            };
+        }
+
+        // Result also contains playlists of channel. Those are distinguished by `type` and
+        // ignored. Invidious page starts from 1
+        let suffix = format!(
+            "/channels/{channel_id}/search?q={query}&page={page}&fields=type,{music_field}",
+            channel_id = channel_id,
+            query = query,
+            page = page + 1,
+            music_field = FIELDS[0]
+        );
+        let items = self
+            .send_request::<Vec<serde_json::Value>>(&suffix, 1)
+            .await?;
+        let res = items
+            .into_iter()
+            .filter(|item| item.get("type").and_then(|kind| kind.as_str()) == Some("video"))
+            .filter_map(|item| serde_json::from_value::<super::MusicUnit>(item).ok())
+            .collect::<Vec<super::MusicUnit>>();
+        if res.is_empty() {
+            Err(ReturnAction::EOR)
+        } else {
+            Ok(res)
+        }
+    }
+
     pub async fn get_favourates_music(
         &mut self,
         page: usize,
@@ -374,7 +901,7 @@ impl Fetcher {
                     "Error preparing select statement for favourates music. Error: {err}",
                     err = err
                 );
-                return Err(ReturnAction::Failed);
+                return Err(ReturnAction::Failed(FetchError::Storage));
             }
         };
 
@@ -384,6 +911,9 @@ impl Fetcher {
                 name: row.get(1).unwrap_or("SQL_ERROR".into()),
                 artist: row.get(2).unwrap_or("SQL_ERROR".into()),
                 duration: row.get(3).unwrap_or("3:0".into()),
+                live: false,
+                views: String::new(),
+                published: String::new(),
             })
         });
 
@@ -393,7 +923,7 @@ impl Fetcher {
                     "Cannot get results of favourates music. Error: {err}",
                     err = err
                 );
-                return Err(ReturnAction::Failed);
+                return Err(ReturnAction::Failed(FetchError::Storage));
             }
             Ok(results) => {
                 let mut return_res: Vec<super::MusicUnit> = Vec::with_capacity(self.item_per_page);
@@ -438,7 +968,7 @@ impl Fetcher {
                     "Error preparing select statement for favourates playlist. Error: {err}",
                     err = err
                 );
-                return Err(ReturnAction::Failed);
+                return Err(ReturnAction::Failed(FetchError::Storage));
             }
         };
 
@@ -457,7 +987,7 @@ impl Fetcher {
                     "Cannot get results of favourates music. Error: {err}",
                     err = err
                 );
-                return Err(ReturnAction::Failed);
+                return Err(ReturnAction::Failed(FetchError::Storage));
             }
             Ok(results) => {
                 let mut return_res: Vec<super::PlaylistUnit> =
@@ -503,7 +1033,7 @@ impl Fetcher {
                     "Error preparing select statement for favourates artist. Error: {err}",
                     err = err
                 );
-                return Err(ReturnAction::Failed);
+                return Err(ReturnAction::Failed(FetchError::Storage));
             }
         };
 
@@ -521,7 +1051,7 @@ impl Fetcher {
                     "Cannot get results of favourates artist. Error: {err}",
                     err = err
                 );
-                return Err(ReturnAction::Failed);
+                return Err(ReturnAction::Failed(FetchError::Storage));
             }
             Ok(results) => {
                 let mut return_res: Vec<super::ArtistUnit> = Vec::with_capacity(self.item_per_page);
@@ -540,6 +1070,36 @@ impl Fetcher {
         Ok(res)
     }
 
+    // Suggestions for partially typed query. When query is prefixed with backend name the prefix is
+    // also kept in every suggestion so that selected suggestion is searched in same backend.
+    // Suggestions are always taken from invidious except for local backend which have none
+    pub async fn get_search_suggestions(
+        &mut self,
+        query: &str,
+    ) -> Result<Vec<String>, ReturnAction> {
+        #[derive(serde::Deserialize)]
+        struct SuggestionRes {
+            suggestions: Vec<String>,
+        }
+
+        let query = query.trim();
+        let (backend, backend_query) = super::Backend::from_query(query, self.backend);
+        if backend_query.is_empty() || backend == super::Backend::Local {
+            return Ok(Vec::new());
+        }
+        let prefix = query.strip_suffix(backend_query).unwrap_or_default();
+
+        let suffix = format!("/search/suggestions?q={query}", query = backend_query);
+        self.send_request::<SuggestionRes>(&suffix, 1)
+            .await
+            .map(|res| {
+                res.suggestions
+                    .into_iter()
+                    .map(|suggestion| format!("{}{}", prefix, suggestion))
+                    .collect()
+            })
+    }
+
     pub async fn search_music(
         &mut self,
         query: &str,
@@ -548,6 +1108,24 @@ impl Fetcher {
         search!("music", self, query, page)
     }
 
+    // Same as search_music but only finds songs. Invidious cannot tell songs from other videos so
+    // such query is sent to YouTube Music instead unless invidious is asked for by prefix. Music
+    // of other backends is always a song
+    pub async fn search_songs(
+        &mut self,
+        query: &str,
+        page: usize,
+    ) -> Result<Vec<super::MusicUnit>, ReturnAction> {
+        let (backend, backend_query) = super::Backend::from_query(query, self.backend);
+        let is_prefixed = backend_query != query;
+        if backend == super::Backend::Invidious && !is_prefixed {
+            let query = format!("{}:{}", super::Backend::YtMusic.name(), query);
+            search!("music", self, &query, page)
+        } else {
+            search!("music", self, query, page)
+        }
+    }
+
     pub async fn search_playlist(
         &mut self,
         query: &str,
@@ -564,3 +1142,151 @@ impl Fetcher {
         search!("artist", self, query, page)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Only the fields of Fetcher that search! uses
+    struct MockFetcher {
+        item_per_page: usize,
+        search_res: crate::SearchRes,
+    }
+
+    // Search for even numbers out of backend pages given by `page_of`. Returns the result of every
+    // page asked in `shown_pages` order along with number of requests sent
+    fn search_even(
+        page_of: impl Fn(usize) -> Vec<u32>,
+        shown_pages: &[usize],
+    ) -> (Vec<Result<Vec<u32>, ReturnAction>>, usize) {
+        let mut fetcher = MockFetcher {
+            item_per_page: 3,
+            search_res: crate::SearchRes::default(),
+        };
+        let mut store: Vec<u32> = Vec::new();
+        let mut requests = 0;
+        let mut request = |page: usize| {
+            requests += 1;
+            Ok::<_, ReturnAction>(page_of(page))
+        };
+        let mut search = |page: usize| -> Result<Vec<u32>, ReturnAction> {
+            let query = "query".to_string();
+            search!(
+                "@internal-core",
+                fetcher,
+                &query,
+                page,
+                store,
+                0,
+                |unit: &u32| unit.is_multiple_of(2),
+                request(fetcher.search_res.backend_page)
+            )
+        };
+        let results = shown_pages.iter().copied().map(&mut search).collect();
+        (results, requests)
+    }
+
+    #[test]
+    fn filtered_page_is_filled() {
+        // Backend page n have n*10..n*10+5 of which only few are even
+        let (results, requests) = search_even(
+            |page| (page as u32 * 10..page as u32 * 10 + 5).collect(),
+            &[0, 1],
+        );
+        assert!(matches!(&results[0], Ok(units) if units == &[0, 2, 4]));
+        assert!(matches!(&results[1], Ok(units) if units == &[10, 12, 14]));
+        assert_eq!(requests, 2);
+    }
+
+    #[test]
+    fn requests_are_capped() {
+        // Nothing ever matches and backend never ends
+        let (results, requests) = search_even(|page| vec![page as u32 * 2 + 1], &[0]);
+        assert!(matches!(
+            results[0],
+            Err(ReturnAction::Retry(crate::FetchError::Unfilled))
+        ));
+        assert_eq!(requests, MAX_BACKEND_PAGES);
+    }
+
+    #[test]
+    fn capped_page_continues_on_retry() {
+        // Only the sixth backend page have a match and backend ends after it. That is reached when
+        // same page is asked again
+        let page_of = |page: usize| match page {
+            0..=4 => vec![page as u32 * 2 + 1],
+            5 => vec![2],
+            _ => Vec::new(),
+        };
+        let (results, requests) = search_even(page_of, &[0, 0]);
+        assert!(matches!(
+            results[0],
+            Err(ReturnAction::Retry(crate::FetchError::Unfilled))
+        ));
+        assert!(matches!(&results[1], Ok(units) if units == &[2]));
+        assert_eq!(requests, 7);
+    }
+
+    #[test]
+    fn repeated_page_ends_result() {
+        let (results, requests) =
+            search_even(|page| if page < 1 { vec![2, 4] } else { vec![6] }, &[0, 1]);
+        assert!(matches!(&results[0], Ok(units) if units == &[2, 4, 6]));
+        assert!(matches!(results[1], Err(ReturnAction::EOR)));
+        // Third page is same as second so nothing after it is asked
+        assert_eq!(requests, 3);
+    }
+
+    #[test]
+    fn music_fields_keep_views_and_published() {
+        // Video as invidious sends it without `fields`. Server keeps only the fields asked for
+        let video = serde_json::json!({
+            "type": "video",
+            "title": "Song",
+            "videoId": "abc",
+            "author": "Singer",
+            "authorId": "UCabc",
+            "lengthSeconds": 215,
+            "liveNow": false,
+            "viewCount": 1_234_567,
+            "publishedText": "3 years ago",
+            "description": "..."
+        });
+        let filtered = FIELDS[0]
+            .split(',')
+            .filter_map(|field| Some((field.to_string(), video.get(field)?.clone())))
+            .collect::<serde_json::Map<_, _>>();
+
+        let music: crate::MusicUnit =
+            serde_json::from_value(serde_json::Value::Object(filtered)).unwrap();
+        assert_eq!(music.id, "abc");
+        assert_eq!(music.duration, "03:35");
+        assert_eq!(music.views, "1.2M");
+        assert_eq!(music.published, "3 years ago");
+    }
+
+    #[test]
+    fn invidious_page_starts_from_one() {
+        // Invidious answers page 0 same as page 1. Were page 0 ever asked, second request would
+        // be taken as repeated page and search would end after first backend page
+        let invidious = |page: usize| match page {
+            0 | 1 => vec![2, 4],
+            2 => vec![6, 8],
+            _ => Vec::new(),
+        };
+        let (results, requests) = search_even(
+            |backend_page| {
+                let suffix = invidious_search_suffix("query", 0, "US", backend_page, "");
+                let page = suffix
+                    .split('&')
+                    .find_map(|param| param.strip_prefix("page="))
+                    .unwrap();
+                invidious(page.parse().unwrap())
+            },
+            &[0, 1],
+        );
+        assert!(matches!(&results[0], Ok(units) if units == &[2, 4, 6]));
+        assert!(matches!(&results[1], Ok(units) if units == &[8]));
+        assert_eq!(requests, 3);
+    }
+}
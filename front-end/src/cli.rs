@@ -1,8 +1,39 @@
 use config::initilize::CONFIG;
+use fetcher::ExtendDuration;
 use reqwest;
 use serde::{self, Deserialize};
+use std::time::Duration;
 use tokio;
 
+// Request (action and parameters as understood by control socket) given on command line to be done
+// as soon as app starts. Set when no other instance was running to forward it to. See forward_or_run
+pub static STARTUP_REQUEST: std::sync::OnceLock<(String, Vec<(String, String)>)> =
+    std::sync::OnceLock::new();
+
+// Set by `run --daemon` to run without the terminal ui. See __ui/daemon.rs__
+pub static DAEMON: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+// Action and parameters of ctl command as sent over control socket
+fn ctl_request(command: &str, argument: &str) -> (String, Vec<(String, String)>) {
+    let (action, params) = match (command, argument) {
+        ("now", _) => ("status", vec![]),
+        ("prev", _) => ("previous", vec![]),
+        ("play", index) if !index.is_empty() && index.parse::<usize>().is_ok() => {
+            ("play", vec![("index", index)])
+        }
+        ("play" | "queue", id) if !id.is_empty() => (command, vec![("id", id)]),
+        ("seek", secs) => ("seek", vec![("secs", secs)]),
+        ("volume", level) => ("volume", vec![("level", level)]),
+        ("search", query) => ("search", vec![("q", query)]),
+        _ => (command, vec![]),
+    };
+    let params = params
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    (action.to_string(), params)
+}
+
 #[derive(Default)]
 pub struct Options {
     exec_name: String,
@@ -41,6 +72,26 @@ impl Options {
     }
 
     pub fn evaluate(self) -> bool {
+        // Output of ctl is read by scripts so nothing else is printed. Same for the requests that
+        // are forwarded to running app and for daemon whose output goes to log
+        match self.sub_command.trim() {
+            "ctl" => {
+                self.control();
+                return false;
+            }
+            "play" | "queue" | "search" => return self.forward_or_run(),
+            "status" => {
+                self.show_status();
+                return false;
+            }
+            "run" | "--daemon" if self.is_daemon() => {
+                DAEMON.store(true, std::sync::atomic::Ordering::Relaxed);
+                self.initialize_globals();
+                return true;
+            }
+            _ => {}
+        }
+
         let ascii_art = r##"
 __   ___         _                           _
 \ \ / / |_ _   _(_)      _ __ ___  _   _ ___(_) ___
@@ -70,6 +121,8 @@ __   ___         _                           _
                 _ => self.show_help(),
             },
 
+            "import" => self.import_subscriptions(),
+
             "info" => match &self.arguments.first() {
                 Some(arg) => match arg.as_str() {
                     "version" => self.show_version(),
@@ -108,6 +161,7 @@ __   ___         _                           _
     pub fn describe_keys(self) {
         self.initialize_globals();
         let keys = &CONFIG.shortcut_keys;
+        let chords = &CONFIG.chord_keys;
         println!(
             include_str!("help_keys.txt"),
             toggle = keys.toggle_play,
@@ -125,6 +179,52 @@ __   ___         _                           _
             quit = keys.quit,
             v_inc = keys.vol_increase,
             v_dec = keys.vol_decrease,
+            mute = keys.mute,
+            hints = keys.hints,
+            details = keys.details,
+            scope = keys.search_scope,
+            retry = keys.retry,
+            contrast = keys.contrast,
+            fav = keys.favourite,
+            rel = keys.related,
+            lyr = keys.lyrics,
+            pod = keys.podcast,
+            all = keys.load_all,
+            ch_next = keys.next_chapter,
+            ch_prev = keys.prev_chapter,
+            ch_list = keys.chapters,
+            mix = keys.mix,
+            cmnt = keys.comments,
+            sub = keys.subscribe,
+            help = keys.help,
+            queue = keys.queue,
+            filter = keys.filter,
+            sort = keys.sort_music,
+            cmd = keys.command,
+            mark = keys.mark,
+            enq = keys.enqueue,
+            to_pl = keys.add_to_playlist,
+            acts = keys.actions,
+            sb_less = keys.shrink_sidebar,
+            sb_more = keys.grow_sidebar,
+            mu_less = keys.shrink_music,
+            mu_more = keys.grow_music,
+            max = keys.maximize,
+            layout = keys.layout,
+            clr_hist = keys.clear_history,
+            yank = keys.yank_url,
+            browse = keys.open_in_browser,
+            g_trend = chords.goto_trending,
+            g_chart = chords.goto_charts,
+            g_mood = chords.goto_moods,
+            g_like = chords.goto_liked,
+            g_save = chords.goto_saved,
+            g_follow = chords.goto_following,
+            g_sub = chords.goto_subscriptions,
+            g_new = chords.goto_new_releases,
+            g_local = chords.goto_local,
+            g_hist = chords.goto_history,
+            g_yank = chords.yank_url,
         );
     }
 
@@ -132,6 +232,189 @@ __   ___         _                           _
         println!(include_str!("help_message.txt"));
     }
 
+    // import <file> [favourites]
+    pub fn import_subscriptions(self) {
+        let (path, as_favourite) = match self.arguments.as_slice() {
+            [path] => (path, false),
+            [path, kind] if kind == "favourites" || kind == "favorites" => (path, true),
+            _ => return self.show_help(),
+        };
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) => {
+                eprintln!("Cannot read {path}. Error: {err}", path = path, err = err);
+                return;
+            }
+        };
+
+        match fetcher::import::parse_subscriptions(&content) {
+            Ok(artists) => {
+                self.initialize_globals();
+                let saved = fetcher::import::save_artists(&artists, as_favourite);
+                println!(
+                    "Found {found} artists. Imported {saved} as {kind} ({skipped} already there)",
+                    found = artists.len(),
+                    saved = saved,
+                    kind = if as_favourite {
+                        "favourite artists"
+                    } else {
+                        "subscriptions"
+                    },
+                    skipped = artists.len() - saved
+                );
+            }
+            Err(err) => eprintln!("Cannot import {path}. {err}", path = path, err = err),
+        }
+    }
+
+    // ctl <command> [argument]. Command is sent to the running app through control socket. Json
+    // response of queries is printed as is. `now` prints just "artist - title" for status bars
+    pub fn control(self) {
+        let (command, argument) = match self.arguments.split_first() {
+            Some((command, rest)) => (command.as_str(), rest.join(" ")),
+            None => return self.show_help(),
+        };
+        let (action, params) = ctl_request(command, &argument);
+        match Self::send_to_socket(&crate::ui::ctl::request(&action, &params)) {
+            Ok(response) => Self::print_response(command, &response),
+            Err(err) => {
+                eprintln!("Cannot reach running ytui-music. Error: {err}", err = err);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // status [--json]. What running app is playing. Json is same as of `ctl status` and is meant
+    // for scripts while plain output is one line for status bars
+    pub fn show_status(self) {
+        let response = match Self::send_to_socket(&crate::ui::ctl::request("status", &[])) {
+            Ok(response) => response,
+            Err(err) => {
+                eprintln!("Cannot reach running ytui-music. Error: {err}", err = err);
+                std::process::exit(1);
+            }
+        };
+        if self.arguments.iter().any(|arg| arg == "--json") {
+            return Self::print_response("status", &response);
+        }
+
+        let status = serde_json::from_str::<serde_json::Value>(&response).unwrap_or_default();
+        if let Some(reason) = status["error"].as_str() {
+            eprintln!("{}", reason);
+            std::process::exit(1);
+        }
+        let playback = status["state"].as_str().unwrap_or_default();
+        if playback == "stopped" {
+            return println!("stopped");
+        }
+        let music = match (status["artist"].as_str(), status["title"].as_str()) {
+            (None | Some(""), title) => title.unwrap_or_default().to_string(),
+            (Some(artist), title) => format!("{} - {}", artist, title.unwrap_or_default()),
+        };
+        let time = |key: &str| Duration::from_secs_f64(status[key].as_f64().unwrap_or_default());
+        let progress = if status["live"].as_bool().unwrap_or_default() {
+            "live".to_string()
+        } else {
+            format!(
+                "{}/{}",
+                time("position").to_string(),
+                time("duration").to_string()
+            )
+        };
+        println!(
+            "{}: {} [{}] volume {}% queue {}/{}",
+            playback,
+            music,
+            progress,
+            status["volume"],
+            status["queue_position"],
+            status["queue_length"]
+        );
+    }
+
+    // play/queue/search <argument>. Request is forwarded to ytui-music that is already running
+    // instead of starting another one. When none is running, app is started and does it as soon as
+    // it starts. Returns whether app should be started
+    pub fn forward_or_run(self) -> bool {
+        let argument = self.arguments.join(" ");
+        if argument.trim().is_empty() {
+            self.show_help();
+            return false;
+        }
+        let (action, params) = ctl_request(&self.sub_command, &argument);
+        match Self::send_to_socket(&crate::ui::ctl::request(&action, &params)) {
+            Ok(response) => {
+                Self::print_response(&self.sub_command, &response);
+                false
+            }
+            Err(_) => {
+                STARTUP_REQUEST.set((action, params)).ok();
+                self.initialize_globals();
+                true
+            }
+        }
+    }
+
+    fn print_response(command: &str, response: &str) {
+        let response = serde_json::from_str::<serde_json::Value>(response).unwrap_or_default();
+        if let Some(reason) = response["error"].as_str() {
+            eprintln!("{}", reason);
+            std::process::exit(1);
+        } else if command == "now" {
+            match (response["artist"].as_str(), response["title"].as_str()) {
+                (_, None | Some("")) => {}
+                (None | Some(""), Some(title)) => println!("{}", title),
+                (Some(artist), Some(title)) => println!("{} - {}", artist, title),
+            }
+        } else if response.get("ok").is_none() {
+            println!("{}", response);
+        }
+    }
+
+    #[cfg(unix)]
+    fn send_to_socket(request: &str) -> std::io::Result<String> {
+        use std::io::{BufRead, Write};
+        let path = crate::ui::ctl::socket_path();
+        if path.symlink_metadata().is_ok() && !crate::ui::ctl::is_owned(&path) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                "Socket is owned by other user",
+            ));
+        }
+        let mut stream = std::os::unix::net::UnixStream::connect(path)?;
+        stream.write_all(format!("{}\n", request).as_bytes())?;
+        let mut response = String::new();
+        std::io::BufReader::new(stream).read_line(&mut response)?;
+        Ok(response)
+    }
+
+    // Named pipe is opened same as a file
+    #[cfg(windows)]
+    fn send_to_socket(request: &str) -> std::io::Result<String> {
+        use std::io::{BufRead, Write};
+        let mut pipe = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(crate::ui::ctl::PIPE_NAME)?;
+        pipe.write_all(format!("{}\n", request).as_bytes())?;
+        let mut response = String::new();
+        std::io::BufReader::new(pipe).read_line(&mut response)?;
+        Ok(response)
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    fn send_to_socket(_request: &str) -> std::io::Result<String> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "control socket is not supported on this platform",
+        ))
+    }
+
+    // `run --daemon` or just `--daemon`
+    fn is_daemon(&self) -> bool {
+        self.sub_command.trim() == "--daemon" || self.arguments.iter().any(|arg| arg == "--daemon")
+    }
+
     pub fn initialize_globals(&self) {
         lazy_static::initialize(&config::initilize::INIT);
     }
@@ -2,26 +2,41 @@ use crate::ui::{
     self,
     event::{MIDDLE_ARTIST_INDEX, MIDDLE_MUSIC_INDEX, MIDDLE_PLAYLIST_INDEX},
 };
+use config::initilize::CONFIG;
 use std::sync::{Arc, Condvar, Mutex};
 
 macro_rules! handle_response {
-    ($response: expr, $state_original: expr, $win_index: expr, $target: ident) => {{
+    ($response: expr, $state_original: expr, $win_index: expr, $target: ident, $retried: expr) => {{
         let mut state = $state_original.lock().unwrap();
-        // return the boolean which is only truw when response is RETRY
+        state.loading[$win_index] = None;
+        state.fetch_error[$win_index] = None;
+        state.end_of_results[$win_index] = matches!($response, Err(fetcher::ReturnAction::EOR));
+        // return the boolean which is only truw when response is RETRY and list have not been
+        // retried for MAX_LIST_RETRY times already
         let mut need_retry = false;
         match $response {
             Ok(mut data) => {
-                state.status = "Success..";
+                state.info("Success..");
                 data.shrink_to_fit();
                 state.$target.0 = data;
             }
             Err(e) => {
                 match e {
-                    fetcher::ReturnAction::Failed => {
-                        state.status = "Fetch error..";
+                    fetcher::ReturnAction::Retry(_) if $retried[$win_index] < MAX_LIST_RETRY => {
+                        // the respective function from which the data is exptracted
+                        // specify the no of times to retry. Simple rerun the loop if retry is feasible
+                        state.info("Retrying..");
+                        $retried[$win_index] += 1;
+                        need_retry = true;
+                    }
+                    // Show why nothing could be fetched rather than leaving the list empty silently
+                    // Reason is also shown inside the list until it is retried
+                    fetcher::ReturnAction::Failed(reason) | fetcher::ReturnAction::Retry(reason) => {
+                        state.error(reason.reason());
+                        state.fetch_error[$win_index] = Some(reason.reason());
                     }
                     fetcher::ReturnAction::EOR => {
-                        state.status = "Result end..";
+                        state.info("Result end..");
                         // TODO: Setting this to None means that the next page will always be 0.
                         // That being said when user tries to navigate to previous page after seeing
                         // EOR then still the fetched page will be 0. i.e again started from beginning.
@@ -41,22 +56,134 @@ macro_rules! handle_response {
                         // next page or zeroth page after EOR
                         state.fetched_page[$win_index] = None;
                     }
-                    fetcher::ReturnAction::Retry => {
-                        // the respective function from which the data is exptracted
-                        // specify the no of times to retry. Simple rerun the loop if retry is feasible
-                        state.status = "Retrying..";
-                        need_retry = true;
-                    }
                 }
             }
         }
+        if !need_retry {
+            $retried[$win_index] = 0;
+        }
         std::mem::drop(state);
         need_retry
     }};
 }
 
+// Request that fails with Retry is sent again for at most this many times after which the reason of
+// failure is shown. See handle_response!
+const MAX_LIST_RETRY: u8 = 2;
+const STALE_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+// How often release_checker looks whether app is quit while waiting for next check
+const QUIT_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+// Write thumbnail of notification to a new file that only this user can read. It goes in the
+// private directory of control socket (see ui/ctl.rs) as anyone can plant files (or symlinks) in
+// /tmp. Temporary directory of windows is already per user. Every notification gets it's own file
+// and the file of previous one is removed
+#[cfg(not(target_os = "macos"))]
+fn write_thumbnail(bytes: &[u8]) -> Option<std::path::PathBuf> {
+    use std::io::Write;
+    #[cfg(unix)]
+    use std::os::unix::fs::OpenOptionsExt;
+    static WRITTEN: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    static LAST: Mutex<Option<std::path::PathBuf>> = Mutex::new(None);
+
+    #[cfg(unix)]
+    let dir = ui::ctl::private_dir()?;
+    #[cfg(not(unix))]
+    let dir = std::env::temp_dir();
+    let path = dir.join(format!(
+        "ytui_music_thumbnail-{}-{}.jpg",
+        std::process::id(),
+        WRITTEN.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+    ));
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let written = options
+        .open(&path)
+        .and_then(|mut file| file.write_all(bytes));
+    if written.is_err() {
+        std::fs::remove_file(&path).ok();
+        return None;
+    }
+    if let Some(previous) = LAST.lock().unwrap().replace(path.clone()) {
+        std::fs::remove_file(previous).ok();
+    }
+    Some(path)
+}
+
+// Show desktop notification (of music that started playing or of new releases). Thumbnail is
+// written to a file as that is how notification servers take image. Showing may block (eg: while
+// waiting for notification server to start) so it is done outside of communicator
+fn desktop_notification(summary: String, body: String, thumbnail: Option<Vec<u8>>) {
+    let mut notification = notify_rust::Notification::new();
+    notification
+        .appname("ytui-music")
+        .summary(&summary)
+        .body(&body);
+    #[cfg(not(target_os = "macos"))]
+    if let Some(path) = thumbnail.as_deref().and_then(write_thumbnail) {
+        notification.image_path(&path.to_string_lossy());
+    }
+    #[cfg(target_os = "macos")]
+    std::mem::drop(thumbnail);
+    tokio::task::spawn_blocking(move || notification.show().ok());
+}
+
+// Check favourite and subscribed artists for new releases every new_release_check_mins. User is
+// only told when number of new releases have changed since last check so that same releases are
+// not reported again. Checking every artist takes a while so it is done with fetcher of it's own
+// next to communicator rather than holding up the lists being filled
+pub async fn release_checker(
+    state_original: &mut Arc<Mutex<ui::State>>,
+    notifier: &mut Arc<Condvar>,
+) {
+    let check_interval =
+        std::time::Duration::from_secs(CONFIG.constants.new_release_check_mins * 60);
+    if check_interval.is_zero() {
+        return;
+    }
+    let is_quit = || state_original.lock().unwrap().active == ui::Window::None;
+
+    let mut fetcher = fetcher::Fetcher::default();
+    let mut reported = 0;
+    loop {
+        let found = fetcher.check_new_releases().await.unwrap_or_default();
+        let checked_at = std::time::Instant::now();
+        {
+            let mut state = state_original.lock().unwrap();
+            if state.active == ui::Window::None {
+                return;
+            }
+            if found > 0 && found != reported {
+                state.info("New releases..");
+                notifier.notify_one();
+                if CONFIG.constants.notify_new_releases {
+                    desktop_notification(
+                        "New releases".to_string(),
+                        format!("{} new releases from your artists", found),
+                        None,
+                    );
+                }
+            }
+        }
+        reported = found;
+
+        while checked_at.elapsed() < check_interval {
+            if is_quit() {
+                return;
+            }
+            tokio::time::sleep(QUIT_CHECK_INTERVAL).await;
+        }
+    }
+}
+
+type FetchResult<Unit> = Result<Vec<Unit>, fetcher::ReturnAction>;
+// Prefetched page along with the source and page number it belongs to
+type Prefetched<Source, Unit> = Option<(Source, usize, FetchResult<Unit>)>;
+
 pub async fn communicator<'st, 'nt>(
-    state_original: &'st mut Arc<Mutex<ui::State<'_>>>,
+    state_original: &'st mut Arc<Mutex<ui::State>>,
     notifier: &'nt mut Arc<Condvar>,
 ) {
     let mut fetcher = fetcher::Fetcher::default();
@@ -80,13 +207,327 @@ pub async fn communicator<'st, 'nt>(
     // set these booleans to true when request handeling failed with RETREY response. if this is
     // true then other condition should not have to be true
     let mut need_retry = [false; 3];
+    // Number of times each list have been retried in a row
+    let mut retried = [0u8; 3];
+    // Text of searchbar for which suggestions were last fetched
+    let mut prev_suggestion_query = String::new();
+    // Next page of musicbar, playlistbar and artistbar fetched in advance. need_prefetch is set when respective list is filled in this
+    // iteration. See the end of loop
+    let mut prefetched: (
+        Prefetched<ui::MusicbarSource, fetcher::MusicUnit>,
+        Prefetched<ui::PlaylistbarSource, fetcher::PlaylistUnit>,
+        Prefetched<ui::ArtistbarSource, fetcher::ArtistUnit>,
+    ) = (None, None, None);
+    let mut need_prefetch = [false; 3];
+    let mut skip_wait = false;
+    // Path of music for which desktop notification was last considered. See desktop_notification
+    let mut notified_path = String::new();
 
     'communicator_loop: loop {
-        let mut state = notifier.wait(state_original.lock().unwrap()).unwrap();
+        // App may have been quit while this loop was busy fetching. Nobody notifies after that so
+        // it is checked under the same lock before waiting
+        let mut state = state_original.lock().unwrap();
+        if !std::mem::take(&mut skip_wait) && state.active != ui::Window::None {
+            state = notifier.wait(state).unwrap();
+        }
         if state.active == ui::Window::None {
             break 'communicator_loop;
         }
 
+        // Fetch the suggestions when user have typed something new in searchbar. When search_by_type
+        // prefix is used, it is removed before sending to fetcher and is added back to suggestions
+        if state.active == ui::Window::Searchbar && state.suggestions.0 != prev_suggestion_query {
+            prev_suggestion_query = state.suggestions.0.clone();
+            std::mem::drop(state);
+
+            let type_prefix = CONFIG
+                .constants
+                .search_by_type
+                .iter()
+                .find(|prefix| prev_suggestion_query.starts_with(prefix.as_str()))
+                .map(String::as_str)
+                .unwrap_or_default();
+            let suggestions = fetcher
+                .get_search_suggestions(&prev_suggestion_query[type_prefix.len()..])
+                .await
+                .unwrap_or_default();
+
+            state = state_original.lock().unwrap();
+            // Discard the response if user have typed something else or started the search
+            // meanwhile. Newer text will be fetched in next iteration
+            if state.suggestions.0 == prev_suggestion_query {
+                state.suggestions.1 = suggestions
+                    .into_iter()
+                    .map(|suggestion| format!("{}{}", type_prefix, suggestion))
+                    .collect();
+                state.suggestions.2.select(None);
+                notifier.notify_one();
+            }
+        }
+
+        // Fetch the categories of moods and genres and show them in list once fetched
+        if std::mem::take(&mut state.moods.requested) {
+            std::mem::drop(state);
+            let categories = fetcher.get_mood_categories().await;
+
+            state = state_original.lock().unwrap();
+            match categories {
+                Ok(categories) => {
+                    state.info("Success..");
+                    state.moods.list = categories;
+                    let mut list_state = tui::widgets::ListState::default();
+                    list_state.select(Some(0));
+                    state.moods.popup = Some(list_state);
+                }
+                Err(
+                    fetcher::ReturnAction::Failed(reason) | fetcher::ReturnAction::Retry(reason),
+                ) => {
+                    state.error(reason.reason());
+                }
+                Err(fetcher::ReturnAction::EOR) => state.info("Result end.."),
+            }
+            notifier.notify_one();
+        }
+
+        // Info of artist is fetched once when music of that artist is shown
+        let header_id = state
+            .header_artist()
+            .and_then(|channel_id| match &state.artist_header {
+                Some(info) if info.id == channel_id => None,
+                _ => Some(channel_id.to_string()),
+            });
+        if let Some(channel_id) = header_id {
+            std::mem::drop(state);
+            // Info with only id is kept on failure so that it is not fetched again
+            let info = fetcher
+                .get_channel_info(&channel_id)
+                .await
+                .unwrap_or_else(|_| fetcher::ChannelInfo {
+                    id: channel_id.clone(),
+                    ..Default::default()
+                });
+
+            state = state_original.lock().unwrap();
+            if state.header_artist() == Some(channel_id.as_str()) {
+                state.artist_header = Some(info);
+                notifier.notify_one();
+            }
+        }
+
+        // Description is only fetched once details popup is shown
+        let details_id = match &state.details {
+            Some(details) if details.description.is_none() => Some(details.music.id.clone()),
+            _ => None,
+        };
+        if let Some(music_id) = details_id {
+            std::mem::drop(state);
+            let description = fetcher.get_description(&music_id).await;
+
+            state = state_original.lock().unwrap();
+            // Popup may have been closed or opened for other music meanwhile
+            if let Some(details) = state.details.as_mut() {
+                if details.music.id == music_id {
+                    details.description = Some(description.unwrap_or_default());
+                    notifier.notify_one();
+                }
+            }
+        }
+
+        // Fetch the lyrics when lyrics pane is shown and music being played have changed
+        if state.lyrics.shown {
+            if let Some((title, duration)) = state.playing_info() {
+                if title != state.lyrics.title {
+                    state.lyrics.title = title.clone();
+                    state.lyrics.lyrics = None;
+                    state.info("Fetch lyrics..");
+                    std::mem::drop(state);
+
+                    let lyrics = fetcher.get_lyrics(&title, duration).await;
+
+                    state = state_original.lock().unwrap();
+                    // Music may have been changed while fetching
+                    if state.lyrics.title == title {
+                        match lyrics {
+                            Ok(lyrics) => {
+                                state.info("Success..");
+                                state.lyrics.lyrics = Some(lyrics);
+                            }
+                            Err(_) => state.warn("No lyrics.."),
+                        }
+                    }
+                    notifier.notify_one();
+                }
+            }
+        }
+
+        // Fetch the first chunk of comments when comments pane is shown and music being played have
+        // changed. Later chunks are fetched when asked by scrolling. See ui::CommentsState
+        if state.comments.shown {
+            let playing_id = fetcher::music_id_from_url(
+                &state
+                    .player
+                    .get_property::<String>("path")
+                    .unwrap_or_default(),
+            )
+            .unwrap_or_default();
+            let is_new_music = playing_id != state.comments.music_id;
+            if is_new_music {
+                state.comments.music_id = playing_id.clone();
+                state.comments.list.clear();
+                state.comments.continuation = None;
+                state.comments.scroll = 0;
+            }
+            let more_requested = std::mem::take(&mut state.comments.more_requested);
+
+            if (is_new_music && !playing_id.is_empty())
+                || (more_requested && state.comments.continuation.is_some())
+            {
+                let continuation = state.comments.continuation.clone();
+                state.info("Fetch comments..");
+                notifier.notify_one();
+                std::mem::drop(state);
+
+                let chunk = fetcher
+                    .get_comments(&playing_id, continuation.as_deref())
+                    .await;
+
+                state = state_original.lock().unwrap();
+                // Music may have been changed while fetching
+                if state.comments.music_id == playing_id {
+                    match chunk {
+                        Ok(chunk) => {
+                            state.info("Success..");
+                            state.comments.list.extend(chunk.comments);
+                            state.comments.continuation = chunk.continuation;
+                        }
+                        Err(fetcher::ReturnAction::EOR) => {
+                            state.warn("No comments..");
+                            state.comments.continuation = None;
+                        }
+                        Err(
+                            fetcher::ReturnAction::Failed(reason)
+                            | fetcher::ReturnAction::Retry(reason),
+                        ) => state.error(reason.reason()),
+                    }
+                }
+                notifier.notify_one();
+            }
+        }
+
+        // Load whole playlist when requested. Once loaded, music section is filled from this
+        // playlist again so that it is served from the fetcher cache which is now complete
+        if let Some(playlist_id) = state.full_playlist.requested.take() {
+            state.info("Loading all..");
+            notifier.notify_one();
+            std::mem::drop(state);
+
+            let music = fetcher.get_full_playlist(&playlist_id).await;
+
+            state = state_original.lock().unwrap();
+            match music {
+                Ok(music) => {
+                    state.info("All loaded..");
+                    state.full_playlist.id = playlist_id.clone();
+                    state.full_playlist.music = music;
+                    state.filled_source.0 = ui::MusicbarSource::Playlist(playlist_id);
+                    state.fetched_page[MIDDLE_MUSIC_INDEX] = Some(0);
+                    // Same source may already be shown in which case it is not fetched again
+                    // unless page is changed
+                    prev_music_page = None;
+                }
+                Err(
+                    fetcher::ReturnAction::Failed(reason) | fetcher::ReturnAction::Retry(reason),
+                ) => state.error(reason.reason()),
+                Err(fetcher::ReturnAction::EOR) => state.info("Result end.."),
+            }
+            notifier.notify_one();
+        }
+
+        // Chapters are read from description when music being played changes. Player may still
+        // find it's own chapters later which are then preferred. See ui::ChapterState
+        let playing_path = state
+            .player
+            .get_property::<String>("path")
+            .unwrap_or_default();
+        if playing_path != state.chapters.path {
+            state.chapters.path = playing_path.clone();
+            state.chapters.list.clear();
+            state.chapters.from_player = false;
+            state.chapters.popup = None;
+
+            if let Some(music_id) = fetcher::music_id_from_url(&playing_path) {
+                std::mem::drop(state);
+                let chapters = fetcher.get_chapters(&music_id).await;
+
+                state = state_original.lock().unwrap();
+                // Music may have changed or player may have found chapters meanwhile
+                if state.chapters.path == playing_path && state.chapters.list.is_empty() {
+                    if let Ok(chapters) = chapters {
+                        state.chapters.list = chapters;
+                        notifier.notify_one();
+                    }
+                }
+            }
+        }
+
+        // So is the thumbnail unless album art is turned off. See ui::AlbumArtState
+        if CONFIG.constants.album_art != "off" && playing_path != state.album_art.path {
+            state.album_art.path = playing_path.clone();
+            state.album_art.image = None;
+            notifier.notify_one();
+
+            if let Some(music_id) = fetcher::music_id_from_url(&playing_path) {
+                std::mem::drop(state);
+                let image = fetcher
+                    .get_thumbnail(&music_id)
+                    .await
+                    .ok()
+                    .and_then(|bytes| image::load_from_memory(&bytes).ok())
+                    .map(|image| image.to_rgb8());
+
+                state = state_original.lock().unwrap();
+                // Music may have changed meanwhile
+                if state.album_art.path == playing_path {
+                    state.album_art.image = image;
+                    notifier.notify_one();
+                }
+            }
+        }
+
+        // Desktop notification is sent once title of new music is known. Music that started while
+        // notifications were off is not notified when they are turned on
+        if playing_path != notified_path {
+            if let Some((title, _)) = state.playing_info() {
+                notified_path = playing_path.clone();
+                if state.track_notifications {
+                    let artist = state.playing_artist_of(&playing_path);
+                    std::mem::drop(state);
+                    let thumbnail = match fetcher::music_id_from_url(&playing_path) {
+                        Some(music_id) => fetcher.get_thumbnail(&music_id).await.ok(),
+                        None => None,
+                    };
+                    desktop_notification(title, artist, thumbnail);
+                    state = state_original.lock().unwrap();
+                }
+            }
+        }
+
+        // Music played long enough is submitted to ListenBrainz as it is queued. Listens that fail
+        // to submit are dropped instead of being tried again on every iteration
+        if !state.listen.pending.is_empty() {
+            let listens = std::mem::take(&mut state.listen.pending);
+            std::mem::drop(state);
+            let res = fetcher
+                .submit_listens(&CONFIG.constants.listenbrainz_token, &listens)
+                .await;
+
+            state = state_original.lock().unwrap();
+            if res.is_err() {
+                state.error("Scrobble failed..");
+                notifier.notify_one();
+            }
+        }
+
         // This block is executed when the source of playlist has changed from previous iteration
         // or new page is requested from the same source. Same pattern is repeated to fill musicbar
         // amd artistbar too.
@@ -95,13 +536,14 @@ pub async fn communicator<'st, 'nt>(
         // 1) the source of to fill playlist is different. i.e in previous loop data was shown from
         //    search and now is needed to fetch the result of trending or seperate search query.
         //    See PlaylistbarSource in ui/mod.rs
-        // 2) corresponsing retry is set to true
+        // 2) corresponsing retry is set to true or user asked to retry the failed request
         // 3) or the source is same but the different page is requested. An extra condition is
         //    added to ensure that it is requesting at least Some page not nothing. eg: when EOR is
         //    reached fetched_page is set to None and for None there is nothing to fetch. See EOR
         //    condition in handle_response! macro
         // UGH!! this if statement condition check is too ugly. I hate it
-        if state.filled_source.1 != prev_playlistbar_source
+        if std::mem::take(&mut state.retry_requested[MIDDLE_PLAYLIST_INDEX])
+            || state.filled_source.1 != prev_playlistbar_source
             || need_retry[MIDDLE_PLAYLIST_INDEX]
             || (state.fetched_page[MIDDLE_PLAYLIST_INDEX] != prev_playlist_page
                 && state.fetched_page[MIDDLE_PLAYLIST_INDEX].is_some())
@@ -109,7 +551,8 @@ pub async fn communicator<'st, 'nt>(
             // clear the target so that noone gets confused if it the response from previous or
             // current request
             state.playlistbar.0.clear();
-            state.status = "Fetch playlist..";
+            state.drop_filter(MIDDLE_PLAYLIST_INDEX);
+            state.loading[MIDDLE_PLAYLIST_INDEX] = Some(std::time::Instant::now());
 
             notifier.notify_one();
 
@@ -126,36 +569,44 @@ pub async fn communicator<'st, 'nt>(
             std::mem::drop(state);
 
             // This is the variable from which the response from matching source is set and later
-            // handled with handle_response! macro
-            let playlist_content;
-
-            // At this point state.filled.source.1 and prev_playlistbar_source is same. As state is
-            // already dropped we cant match state.filled.source.1 so match this
-            match prev_playlistbar_source {
-                ui::PlaylistbarSource::Search(ref term) => {
-                    playlist_content = fetcher.search_playlist(term, page).await;
+            // handled with handle_response! macro. At this point state.filled.source.1 and
+            // prev_playlistbar_source is same. As state is already dropped we cant use
+            // state.filled.source.1 so use this. Page may already be prefetched
+            let playlist_content = match prefetched.1.take() {
+                Some((source, prefetched_page, content))
+                    if source == prev_playlistbar_source && prefetched_page == page =>
+                {
+                    content
                 }
-                ui::PlaylistbarSource::Artist(ref artist_id) => {
-                    playlist_content = fetcher.get_playlist_of_channel(artist_id, page).await;
-                }
-                ui::PlaylistbarSource::Favourates => {
-                    playlist_content = fetcher.get_favourates_playlist(page).await;
-                }
-                ui::PlaylistbarSource::RecentlyPlayed => {
-                    // TODO
-                    playlist_content = Ok(Vec::new());
+                _ => {
+                    // Request is abandoned if user requests other source or page meanwhile so
+                    // that older response do not overwrite the newer one
+                    let fetch = fetch_playlist(&mut fetcher, &prev_playlistbar_source, page);
+                    let stale = until_stale(state_original, |state| {
+                        state.filled_source.1 != prev_playlistbar_source
+                            || state.fetched_page[MIDDLE_PLAYLIST_INDEX] != prev_playlist_page
+                    });
+                    match cancellable(fetch, stale).await {
+                        Some(content) => content,
+                        None => {
+                            skip_wait = true;
+                            continue 'communicator_loop;
+                        }
+                    }
                 }
-            }
+            };
+            need_prefetch[MIDDLE_PLAYLIST_INDEX] = playlist_content.is_ok();
 
             // if return action is RETRY set so in need_retry so that nex interation will try again
             let retry = handle_response!(
                 playlist_content,
                 state_original,
                 MIDDLE_PLAYLIST_INDEX,
-                playlistbar
+                playlistbar,
+                retried
             );
             need_retry[MIDDLE_PLAYLIST_INDEX] = retry;
-            state_original.lock().unwrap().active = ui::Window::Playlistbar;
+            focus_filled(&mut state_original.lock().unwrap(), ui::Window::Playlistbar);
             notifier.notify_one();
         } else {
             // State is always unlocked in above block and dropped in if block. But when if block
@@ -171,13 +622,15 @@ pub async fn communicator<'st, 'nt>(
 
         // Checks and fills the artistbar.
         let mut state = state_original.lock().unwrap();
-        if state.filled_source.2 != prev_artistbar_source
+        if std::mem::take(&mut state.retry_requested[MIDDLE_ARTIST_INDEX])
+            || state.filled_source.2 != prev_artistbar_source
             || need_retry[MIDDLE_ARTIST_INDEX]
             || (state.fetched_page[MIDDLE_ARTIST_INDEX] != prev_artist_page
                 && state.fetched_page[MIDDLE_ARTIST_INDEX].is_some())
         {
             state.artistbar.0.clear();
-            state.status = "Fetch artists..";
+            state.drop_filter(MIDDLE_ARTIST_INDEX);
+            state.loading[MIDDLE_ARTIST_INDEX] = Some(std::time::Instant::now());
             notifier.notify_one();
 
             let page = state.fetched_page[MIDDLE_ARTIST_INDEX].unwrap();
@@ -185,28 +638,38 @@ pub async fn communicator<'st, 'nt>(
             prev_artist_page = Some(page);
             std::mem::drop(state);
 
-            let artist_content;
-            match prev_artistbar_source {
-                ui::ArtistbarSource::Search(ref term) => {
-                    artist_content = fetcher.search_artist(term, page).await;
-                }
-                ui::ArtistbarSource::Favourates => {
-                    artist_content = fetcher.get_favourates_artist(page).await;
+            let artist_content = match prefetched.2.take() {
+                Some((source, prefetched_page, content))
+                    if source == prev_artistbar_source && prefetched_page == page =>
+                {
+                    content
                 }
-                ui::ArtistbarSource::RecentlyPlayed => {
-                    // TODO:
-                    artist_content = Ok(Vec::new());
+                _ => {
+                    let fetch = fetch_artist(&mut fetcher, &prev_artistbar_source, page);
+                    let stale = until_stale(state_original, |state| {
+                        state.filled_source.2 != prev_artistbar_source
+                            || state.fetched_page[MIDDLE_ARTIST_INDEX] != prev_artist_page
+                    });
+                    match cancellable(fetch, stale).await {
+                        Some(content) => content,
+                        None => {
+                            skip_wait = true;
+                            continue 'communicator_loop;
+                        }
+                    }
                 }
-            }
+            };
+            need_prefetch[MIDDLE_ARTIST_INDEX] = artist_content.is_ok();
 
             let retry = handle_response!(
                 artist_content,
                 state_original,
                 MIDDLE_ARTIST_INDEX,
-                artistbar
+                artistbar,
+                retried
             );
             need_retry[MIDDLE_ARTIST_INDEX] = retry;
-            state_original.lock().unwrap().active = ui::Window::Artistbar;
+            focus_filled(&mut state_original.lock().unwrap(), ui::Window::Artistbar);
             notifier.notify_one();
         } else {
             std::mem::drop(state);
@@ -214,13 +677,16 @@ pub async fn communicator<'st, 'nt>(
 
         // Checks and fills the musicbar
         let mut state = state_original.lock().unwrap();
-        if state.filled_source.0 != prev_musicbar_source
+        if std::mem::take(&mut state.retry_requested[MIDDLE_MUSIC_INDEX])
+            || state.filled_source.0 != prev_musicbar_source
             || need_retry[MIDDLE_MUSIC_INDEX]
             || (state.fetched_page[MIDDLE_MUSIC_INDEX] != prev_music_page
                 && state.fetched_page[MIDDLE_MUSIC_INDEX].is_some())
         {
             state.musicbar.0.clear();
-            state.status = "Fetch music..";
+            state.drop_filter(MIDDLE_MUSIC_INDEX);
+            state.music_sort.0 = ui::MusicSort::Original;
+            state.loading[MIDDLE_MUSIC_INDEX] = Some(std::time::Instant::now());
             notifier.notify_one();
 
             let page = state.fetched_page[MIDDLE_MUSIC_INDEX].unwrap();
@@ -228,38 +694,250 @@ pub async fn communicator<'st, 'nt>(
             prev_music_page = Some(page);
             std::mem::drop(state);
             // prev_musicbar_source and current musicbar_source are equal at this point
-            let music_content;
-            match prev_musicbar_source {
-                ui::MusicbarSource::Trending => {
-                    music_content = fetcher.get_trending_music(page).await;
-                }
-                ui::MusicbarSource::Search(ref term) => {
-                    music_content = fetcher.search_music(term, page).await;
+            let music_content = match prefetched.0.take() {
+                Some((source, prefetched_page, content))
+                    if source == prev_musicbar_source && prefetched_page == page =>
+                {
+                    content
                 }
-                ui::MusicbarSource::Playlist(ref playlist_id) => {
-                    music_content = fetcher.get_playlist_content(playlist_id, page).await;
-                }
-                ui::MusicbarSource::Artist(ref artist_id) => {
-                    music_content = fetcher.get_videos_of_channel(artist_id, page).await;
+                _ => {
+                    let fetch = fetch_music(&mut fetcher, &prev_musicbar_source, page);
+                    let stale = until_stale(state_original, |state| {
+                        state.filled_source.0 != prev_musicbar_source
+                            || state.fetched_page[MIDDLE_MUSIC_INDEX] != prev_music_page
+                    });
+                    match cancellable(fetch, stale).await {
+                        Some(content) => content,
+                        None => {
+                            skip_wait = true;
+                            continue 'communicator_loop;
+                        }
+                    }
                 }
-                ui::MusicbarSource::Favourates => {
-                    music_content = fetcher.get_favourates_music(page).await;
+            };
+            need_prefetch[MIDDLE_MUSIC_INDEX] = music_content.is_ok();
+
+            // When album is shown, also keep the info of album to show along with tracks. Album is
+            // already fetched by get_playlist_content so this do not make another request
+            let album = match prev_musicbar_source {
+                ui::MusicbarSource::Playlist(ref playlist_id)
+                    if music_content.is_ok() && fetcher::is_album_id(playlist_id) =>
+                {
+                    fetcher.get_album(playlist_id).await.ok().cloned()
                 }
-                ui::MusicbarSource::RecentlyPlayed => {
-                    // TODO: handle each variant with accurate function
-                    music_content = Ok(Vec::new());
+                _ => None,
+            };
+            // So is the info of playlist to show in header
+            let playlist_info = match prev_musicbar_source {
+                ui::MusicbarSource::Playlist(ref playlist_id) => {
+                    fetcher.playlist_info(playlist_id).cloned()
                 }
+                _ => None,
+            };
+            let mut state = state_original.lock().unwrap();
+            state.album = album;
+            if playlist_info.is_some() {
+                state.playlist_header = playlist_info;
             }
+            std::mem::drop(state);
 
-            let retry =
-                handle_response!(music_content, state_original, MIDDLE_MUSIC_INDEX, musicbar);
+            let retry = handle_response!(
+                music_content,
+                state_original,
+                MIDDLE_MUSIC_INDEX,
+                musicbar,
+                retried
+            );
             need_retry[MIDDLE_MUSIC_INDEX] = retry;
-            state_original.lock().unwrap().active = ui::Window::Musicbar;
+            focus_filled(&mut state_original.lock().unwrap(), ui::Window::Musicbar);
             notifier.notify_one();
         } else {
             // If above if block is not executed state lock should however be released
             // so that state can be lock again for following if block
             std::mem::drop(state);
         }
+
+        // Fetch the next page of lists just filled so that it is shown immediately when user
+        // goes to next page. This is done only after every list is filled so that it do not
+        // delay the list user is actually waiting for. Only the result which can be shown as it is
+        // (data or EOR) is kept. Lists that can change meanwhile are not prefetched. See
+        // is_music_prefetchable
+        // Prefetching is abandoned as soon as user requests anything for any of the list
+        if need_prefetch.iter().any(|need| *need) {
+            let is_requested = |state: &ui::State| {
+                state.filled_source.0 != prev_musicbar_source
+                    || state.filled_source.1 != prev_playlistbar_source
+                    || state.filled_source.2 != prev_artistbar_source
+                    || state.fetched_page[MIDDLE_MUSIC_INDEX] != prev_music_page
+                    || state.fetched_page[MIDDLE_PLAYLIST_INDEX] != prev_playlist_page
+                    || state.fetched_page[MIDDLE_ARTIST_INDEX] != prev_artist_page
+            };
+            if std::mem::take(&mut need_prefetch[MIDDLE_MUSIC_INDEX])
+                && is_music_prefetchable(&prev_musicbar_source)
+            {
+                let page = prev_music_page.unwrap_or_default() + 1;
+                let fetch = fetch_music(&mut fetcher, &prev_musicbar_source, page);
+                let stale = until_stale(state_original, is_requested);
+                if let Some(content) = cancellable(fetch, stale).await {
+                    if is_showable(&content) {
+                        prefetched.0 = Some((prev_musicbar_source.clone(), page, content));
+                    }
+                }
+            }
+            if std::mem::take(&mut need_prefetch[MIDDLE_PLAYLIST_INDEX])
+                && is_playlist_prefetchable(&prev_playlistbar_source)
+            {
+                let page = prev_playlist_page.unwrap_or_default() + 1;
+                let fetch = fetch_playlist(&mut fetcher, &prev_playlistbar_source, page);
+                let stale = until_stale(state_original, is_requested);
+                if let Some(content) = cancellable(fetch, stale).await {
+                    if is_showable(&content) {
+                        prefetched.1 = Some((prev_playlistbar_source.clone(), page, content));
+                    }
+                }
+            }
+            if std::mem::take(&mut need_prefetch[MIDDLE_ARTIST_INDEX])
+                && is_artist_prefetchable(&prev_artistbar_source)
+            {
+                let page = prev_artist_page.unwrap_or_default() + 1;
+                let fetch = fetch_artist(&mut fetcher, &prev_artistbar_source, page);
+                let stale = until_stale(state_original, is_requested);
+                if let Some(content) = cancellable(fetch, stale).await {
+                    if is_showable(&content) {
+                        prefetched.2 = Some((prev_artistbar_source.clone(), page, content));
+                    }
+                }
+            }
+            // User may have requested something while prefetching. That notification is missed
+            // as this thread was not waiting so check the state again without waiting
+            skip_wait = true;
+        }
+    }
+}
+
+// Favourites and history change as user adds to them or plays music. Subscriptions and new releases
+// change as artists upload and as new releases are seen. Prefetched page of those would then be
+// outdated by the time it is shown
+fn is_music_prefetchable(source: &ui::MusicbarSource) -> bool {
+    !matches!(
+        source,
+        ui::MusicbarSource::Favourates
+            | ui::MusicbarSource::RecentlyPlayed
+            | ui::MusicbarSource::Subscriptions
+            | ui::MusicbarSource::NewReleases
+    )
+}
+
+fn is_playlist_prefetchable(source: &ui::PlaylistbarSource) -> bool {
+    !matches!(
+        source,
+        ui::PlaylistbarSource::Favourates | ui::PlaylistbarSource::RecentlyPlayed
+    )
+}
+
+fn is_artist_prefetchable(source: &ui::ArtistbarSource) -> bool {
+    !matches!(
+        source,
+        ui::ArtistbarSource::Favourates
+            | ui::ArtistbarSource::RecentlyPlayed
+            | ui::ArtistbarSource::Subscriptions
+    )
+}
+
+// Run the request until it is complete or until it is no longer needed whichever happens first.
+// Dropping the request future also drops the connection so nothing is received afterwards
+async fn cancellable<T>(
+    request: impl std::future::Future<Output = T>,
+    stale: impl std::future::Future<Output = ()>,
+) -> Option<T> {
+    tokio::select! {
+        res = request => Some(res),
+        _ = stale => None,
+    }
+}
+
+// Complete only when is_stale returns true for the state. State is checked every
+// STALE_CHECK_INTERVAL
+async fn until_stale(state_original: &Mutex<ui::State>, is_stale: impl Fn(&ui::State) -> bool) {
+    loop {
+        tokio::time::sleep(STALE_CHECK_INTERVAL).await;
+        if is_stale(&state_original.lock().unwrap()) {
+            return;
+        }
+    }
+}
+
+fn is_showable<T>(content: &Result<T, fetcher::ReturnAction>) -> bool {
+    matches!(content, Ok(_) | Err(fetcher::ReturnAction::EOR))
+}
+
+// Filled list is focused unless it is filled by live search while user is still typing
+fn focus_filled(state: &mut ui::State, window: ui::Window) {
+    if !(state.live_search && state.active == ui::Window::Searchbar) {
+        state.active = window;
+    }
+}
+
+async fn fetch_music(
+    fetcher: &mut fetcher::Fetcher,
+    source: &ui::MusicbarSource,
+    page: usize,
+) -> FetchResult<fetcher::MusicUnit> {
+    match source {
+        ui::MusicbarSource::Charts(country) => fetcher.get_chart_music(country, page).await,
+        ui::MusicbarSource::Trending(region, category) => {
+            fetcher.get_trending_music(region, *category, page).await
+        }
+        ui::MusicbarSource::Search(term, false) => fetcher.search_music(term, page).await,
+        ui::MusicbarSource::Search(term, true) => fetcher.search_songs(term, page).await,
+        ui::MusicbarSource::Playlist(playlist_id) => {
+            fetcher.get_playlist_content(playlist_id, page).await
+        }
+        ui::MusicbarSource::Artist(artist_id) | ui::MusicbarSource::Podcast(artist_id) => {
+            fetcher.get_videos_of_channel(artist_id, page).await
+        }
+        ui::MusicbarSource::Favourates => fetcher.get_favourates_music(page).await,
+        ui::MusicbarSource::Local => fetcher.get_local_music(page).await,
+        ui::MusicbarSource::ArtistSearch(artist_id, term) => {
+            fetcher.search_channel_music(artist_id, term, page).await
+        }
+        ui::MusicbarSource::Related(music_id) => fetcher.get_related_music(music_id, page).await,
+        ui::MusicbarSource::Subscriptions => fetcher.get_subscription_feed(page).await,
+        ui::MusicbarSource::NewReleases => fetcher.get_new_releases(page).await,
+        ui::MusicbarSource::RecentlyPlayed => fetcher.get_history(page).await,
+    }
+}
+
+async fn fetch_playlist(
+    fetcher: &mut fetcher::Fetcher,
+    source: &ui::PlaylistbarSource,
+    page: usize,
+) -> FetchResult<fetcher::PlaylistUnit> {
+    match source {
+        ui::PlaylistbarSource::Search(term) => fetcher.search_playlist(term, page).await,
+        ui::PlaylistbarSource::Artist(artist_id) => {
+            fetcher.get_playlist_of_channel(artist_id, page).await
+        }
+        ui::PlaylistbarSource::Favourates => fetcher.get_favourates_playlist(page).await,
+        ui::PlaylistbarSource::Local => fetcher.get_local_albums(page).await,
+        ui::PlaylistbarSource::Charts(country) => fetcher.get_chart_playlists(country, page).await,
+        ui::PlaylistbarSource::Mood(params, _) => fetcher.get_mood_playlists(params, page).await,
+        // TODO
+        ui::PlaylistbarSource::RecentlyPlayed => Ok(Vec::new()),
+    }
+}
+
+async fn fetch_artist(
+    fetcher: &mut fetcher::Fetcher,
+    source: &ui::ArtistbarSource,
+    page: usize,
+) -> FetchResult<fetcher::ArtistUnit> {
+    match source {
+        ui::ArtistbarSource::Search(term) => fetcher.search_artist(term, page).await,
+        ui::ArtistbarSource::Favourates => fetcher.get_favourates_artist(page).await,
+        ui::ArtistbarSource::Subscriptions => fetcher.get_subscriptions(page).await,
+        ui::ArtistbarSource::Charts(country) => fetcher.get_chart_artists(country, page).await,
+        // TODO:
+        ui::ArtistbarSource::RecentlyPlayed => Ok(Vec::new()),
     }
 }
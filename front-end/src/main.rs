@@ -14,9 +14,19 @@ mod ui;
 * 2) comminucate is the sync thread for the comminucator which act as the bridge bwteen backend and
 *    front-end. It checks for data required, get data from fetcher and also handles the filling of
 *    data in respective place
+* 3) mpris is the thread that exposes player on D-Bus so that desktop media controls can control it.
+*    See ui/mpris.rs
+* 4) remote is the thread for http server through which player is controlled from other devices or
+*    scripts, control socket used by `ytui_music ctl` and the status file read by status bars.
+*    See ui/remote.rs, ui/ctl.rs and ui/status_file.rs
+* 5) cast is the thread that finds Chromecast devices and makes the one being cast to follow the
+*    player. See ui/cast.rs
 * And the main thread itself will pass the control to `draw_ui` which handles rendering or painting
 * to the terminal. This painter function as well as other 2 spawned thread above runs in a loop and
 * all those loop and terminated when user hits key to quit the application.
+* When ran with --daemon there is no terminal ui. Neither handler is spawned nor `draw_ui` is called
+* and main thread instead runs the loop in ui/daemon.rs until app is quit from remote/control socket
+* or by a signal
 *
 * See below files for respective function
 * __ui/mod.rs__: Defines structures as well as draw_ui function which render the content. This files
@@ -57,49 +67,153 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
+    let is_daemon = cli::DAEMON.load(std::sync::atomic::Ordering::Relaxed);
+    // Daemon that nothing can control would only be stopped by killing it
+    if is_daemon
+        && config::initilize::CONFIG.constants.remote_port == 0
+        && !config::initilize::CONFIG.constants.control_socket
+    {
+        eprintln!("Daemon needs remote_port or control_socket to be enabled in config");
+        std::process::exit(1)
+    }
+
+    // Panic in any thread leaves the other threads waiting forever with terminal in raw mode. So
+    // terminal is restored before panic message is printed and whole app is exited
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if !is_daemon {
+            ui::restore_terminal();
+            crossterm::execute!(std::io::stdout(), crossterm::cursor::Show).ok();
+        }
+        default_hook(info);
+        std::process::exit(101);
+    }));
+
     let state = Arc::new(Mutex::new(ui::State::default()));
+    // Request given on command line (eg: `ytui_music play <url>`) when no other instance was running
+    if let Some((action, params)) = cli::STARTUP_REQUEST.get() {
+        ui::remote::control(&mut state.lock().unwrap(), action, params);
+    }
     let cvar = Arc::new(Condvar::new());
 
-    let (handler, communicate);
+    let (handler, communicate, mpris, remote, cast);
     {
         // same state is shared among all thread
         let mut state_for_painter = Arc::clone(&state);
         let mut state_for_handler = Arc::clone(&state);
         let mut state_for_communicator = Arc::clone(&state);
+        let mut state_for_releases = Arc::clone(&state);
+        let mut state_for_mpris = Arc::clone(&state);
+        let mut state_for_remote = Arc::clone(&state);
+        let mut state_for_ctl = Arc::clone(&state);
+        let mut state_for_status = Arc::clone(&state);
+        let mut state_for_webhook = Arc::clone(&state);
+        let mut state_for_mqtt = Arc::clone(&state);
+        let mut state_for_cast = Arc::clone(&state);
         let mut cvar_for_painter = Arc::clone(&cvar);
         let mut cvar_for_handler = Arc::clone(&cvar);
         let mut cvar_for_communicator = Arc::clone(&cvar);
+        let mut cvar_for_releases = Arc::clone(&cvar);
+        let mut cvar_for_mpris = Arc::clone(&cvar);
+        let mut cvar_for_remote = Arc::clone(&cvar);
+        let mut cvar_for_ctl = Arc::clone(&cvar);
+        let mut cvar_for_status = Arc::clone(&cvar);
+        let mut cvar_for_webhook = Arc::clone(&cvar);
+        let mut cvar_for_mqtt = Arc::clone(&cvar);
+        let mut cvar_for_cast = Arc::clone(&cvar);
+
+        handler = (!is_daemon).then(|| {
+            thread::spawn(move || {
+                tokio::runtime::Builder::new_multi_thread()
+                    .enable_all()
+                    .build()
+                    .unwrap()
+                    .block_on(async move {
+                        ui::event::event_sender(&mut state_for_handler, &mut cvar_for_handler)
+                            .await;
+                    });
+            })
+        });
 
-        handler = thread::spawn(move || {
+        communicate = thread::spawn(move || {
             tokio::runtime::Builder::new_multi_thread()
                 .enable_all()
                 .build()
                 .unwrap()
                 .block_on(async move {
-                    ui::event::event_sender(&mut state_for_handler, &mut cvar_for_handler).await;
+                    tokio::join!(
+                        communicator::communicator(
+                            &mut state_for_communicator,
+                            &mut cvar_for_communicator,
+                        ),
+                        communicator::release_checker(
+                            &mut state_for_releases,
+                            &mut cvar_for_releases
+                        ),
+                    );
                 });
         });
 
-        communicate = thread::spawn(move || {
+        mpris = thread::spawn(move || {
+            tokio::runtime::Builder::new_multi_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+                .block_on(async move {
+                    ui::mpris::serve(&mut state_for_mpris, &mut cvar_for_mpris).await;
+                });
+        });
+
+        remote = thread::spawn(move || {
+            tokio::runtime::Builder::new_multi_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+                .block_on(async move {
+                    tokio::join!(
+                        ui::remote::serve(&mut state_for_remote, &mut cvar_for_remote),
+                        ui::ctl::serve(&mut state_for_ctl, &mut cvar_for_ctl),
+                        ui::status_file::serve(&mut state_for_status, &mut cvar_for_status),
+                        ui::webhook::serve(&mut state_for_webhook, &mut cvar_for_webhook),
+                        ui::mqtt::serve(&mut state_for_mqtt, &mut cvar_for_mqtt),
+                    );
+                });
+        });
+
+        cast = thread::spawn(move || {
             tokio::runtime::Builder::new_multi_thread()
                 .enable_all()
                 .build()
                 .unwrap()
                 .block_on(async move {
-                    communicator::communicator(
-                        &mut state_for_communicator,
-                        &mut cvar_for_communicator,
-                    )
-                    .await;
+                    ui::cast::serve(&mut state_for_cast, &mut cvar_for_cast).await;
                 });
         });
 
-        ui::draw_ui(&mut state_for_painter, &mut cvar_for_painter);
+        if is_daemon {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+                .block_on(ui::daemon::run(
+                    &mut state_for_painter,
+                    &mut cvar_for_painter,
+                ));
+        } else {
+            ui::draw_ui(&mut state_for_painter, &mut cvar_for_painter);
+        }
     }
 
-    handler.join().unwrap();
+    if let Some(handler) = handler {
+        handler.join().unwrap();
+    }
     communicate.join().unwrap();
+    mpris.join().unwrap();
+    remote.join().unwrap();
+    cast.join().unwrap();
 
-    println!();
+    if !is_daemon {
+        println!();
+    }
     Ok(())
 }
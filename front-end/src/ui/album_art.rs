@@ -0,0 +1,274 @@
+use super::shared_import::*;
+use config::initilize::CONFIG;
+use image::{imageops, RgbImage};
+use std::io::Write;
+use tui::buffer::Buffer;
+
+// Thumbnail of music being played is shown in the bottom corner of sidebar (below the shortcuts).
+// Terminals that understand some graphics protocol get the real image which is written directly
+// to the terminal after ui is drawn. Area of image is left blank in ui so that it is not drawn over.
+// Every other terminal gets the image drawn with text where every cell is an upper half block
+// coloured with two vertically adjacent pixels.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    Kitty,
+    Sixel,
+    Iterm,
+    HalfBlock,
+}
+
+impl Protocol {
+    // Protocol set in config. None when album art is turned off. Half blocks are not drawn when
+    // only ascii is to be used
+    pub fn from_config() -> Option<Self> {
+        let protocol = match CONFIG.constants.album_art.as_str() {
+            "off" => None,
+            "kitty" => Some(Protocol::Kitty),
+            "sixel" => Some(Protocol::Sixel),
+            "iterm" => Some(Protocol::Iterm),
+            "halfblock" => Some(Protocol::HalfBlock),
+            _ => Some(Self::detect()),
+        };
+        protocol.filter(|protocol| protocol.is_graphics() || !CONFIG.constants.ascii_only)
+    }
+
+    // Terminal can be asked what it supports but reply is read from stdin which is already being
+    // read for key events. So guess from the environment instead
+    fn detect() -> Self {
+        let var = |name| std::env::var(name).unwrap_or_default();
+        let (term, program) = (var("TERM"), var("TERM_PROGRAM"));
+        // Multiplexers do not pass the graphics through to the terminal
+        if !var("TMUX").is_empty() || term.starts_with("screen") || term.starts_with("tmux") {
+            Protocol::HalfBlock
+        } else if term == "xterm-kitty"
+            || !var("KITTY_WINDOW_ID").is_empty()
+            || program == "ghostty"
+        {
+            Protocol::Kitty
+        } else if program == "iTerm.app" || program == "WezTerm" {
+            Protocol::Iterm
+        } else if term.starts_with("foot") || term.contains("mlterm") || term.contains("sixel") {
+            Protocol::Sixel
+        } else {
+            Protocol::HalfBlock
+        }
+    }
+
+    pub fn is_graphics(self) -> bool {
+        self != Protocol::HalfBlock
+    }
+}
+
+// Area to draw the image of given size in. Image is kept in it's aspect ratio (a cell is roughly
+// twice as tall as wide) and is only shown in the space left below the shortcuts. None if there
+// is not enough space
+pub fn get_area(shortcut: &Rect, image: &RgbImage, shortcut_count: usize) -> Option<Rect> {
+    let max_width = shortcut.width.saturating_sub(2);
+    let max_height = shortcut
+        .height
+        .saturating_sub(2 + shortcut_count as u16 + 1);
+    let (image_width, image_height) = image.dimensions();
+    if image_width == 0 || image_height == 0 {
+        return None;
+    }
+
+    let mut width = max_width;
+    let mut height = (width as u32 * image_height / image_width / 2) as u16;
+    if height > max_height {
+        height = max_height;
+        width = (height as u32 * 2 * image_width / image_height) as u16;
+    }
+    if width < 4 || height < 2 {
+        return None;
+    }
+    Some(Rect {
+        x: shortcut.x + 1 + (max_width - width) / 2,
+        y: shortcut.y + shortcut.height - 1 - height,
+        width,
+        height,
+    })
+}
+
+// Image drawn with upper half blocks. See the top of file
+pub struct HalfBlock<'a>(pub &'a RgbImage);
+
+impl Widget for HalfBlock<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let resized = imageops::resize(
+            self.0,
+            area.width as u32,
+            area.height as u32 * 2,
+            imageops::FilterType::Triangle,
+        );
+        let color = |x: u16, y: u16| {
+            let [r, g, b] = resized.get_pixel(x as u32, y as u32).0;
+            Color::Rgb(r, g, b)
+        };
+        for y in 0..area.height {
+            for x in 0..area.width {
+                buf.get_mut(area.x + x, area.y + y)
+                    .set_symbol("▀")
+                    .set_fg(color(x, y * 2))
+                    .set_bg(color(x, y * 2 + 1));
+            }
+        }
+    }
+}
+
+// Write the image to terminal at given area with graphics protocol
+pub fn write_image(protocol: Protocol, image: &RgbImage, area: Rect) -> std::io::Result<()> {
+    let mut stdout = std::io::stdout();
+    crossterm::queue!(stdout, crossterm::cursor::MoveTo(area.x, area.y))?;
+    match protocol {
+        Protocol::Kitty => write_kitty(&mut stdout, image, area)?,
+        Protocol::Iterm => write_iterm(&mut stdout, image, area)?,
+        Protocol::Sixel => write_sixel(&mut stdout, image, area)?,
+        Protocol::HalfBlock => {}
+    }
+    stdout.flush()
+}
+
+// Remove every image written by kitty protocol. Images written by other protocols are just the
+// pixels on screen which are removed by clearing the screen
+pub fn remove_kitty_images() -> std::io::Result<()> {
+    let mut stdout = std::io::stdout();
+    write!(stdout, "\x1b_Ga=d,q=2\x1b\\")?;
+    stdout.flush()
+}
+
+fn encode_png(image: &RgbImage) -> std::io::Result<Vec<u8>> {
+    let mut png = std::io::Cursor::new(Vec::new());
+    image
+        .write_to(&mut png, image::ImageFormat::Png)
+        .map_err(std::io::Error::other)?;
+    Ok(png.into_inner())
+}
+
+// Image is sent as png in chunks of at most 4096 bytes and is scaled by terminal to fill the area
+fn write_kitty(out: &mut impl Write, image: &RgbImage, area: Rect) -> std::io::Result<()> {
+    use base64::Engine;
+    let encoded = base64::engine::general_purpose::STANDARD.encode(encode_png(image)?);
+    let chunks = encoded.as_bytes().chunks(4096).collect::<Vec<&[u8]>>();
+    for (index, chunk) in chunks.iter().enumerate() {
+        let more = (index + 1 < chunks.len()) as u8;
+        if index == 0 {
+            write!(
+                out,
+                "\x1b_Ga=T,f=100,q=2,C=1,c={},r={},m={};",
+                area.width, area.height, more
+            )?;
+        } else {
+            write!(out, "\x1b_Gm={};", more)?;
+        }
+        out.write_all(chunk)?;
+        write!(out, "\x1b\\")?;
+    }
+    Ok(())
+}
+
+fn write_iterm(out: &mut impl Write, image: &RgbImage, area: Rect) -> std::io::Result<()> {
+    use base64::Engine;
+    let png = encode_png(image)?;
+    write!(
+        out,
+        "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=0:{}\x07",
+        png.len(),
+        area.width,
+        area.height,
+        base64::engine::general_purpose::STANDARD.encode(&png)
+    )
+}
+
+// Size of a cell in pixels. Sixel image is sized in pixels so this is needed to make it fit in the
+// area. Terminals that do not report it are assumed to have a common 10x20 cell
+fn cell_size() -> (u32, u32) {
+    #[cfg(unix)]
+    unsafe {
+        let mut size: libc::winsize = std::mem::zeroed();
+        if libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) == 0
+            && size.ws_col > 0
+            && size.ws_row > 0
+            && size.ws_xpixel > 0
+            && size.ws_ypixel > 0
+        {
+            return (
+                (size.ws_xpixel / size.ws_col) as u32,
+                (size.ws_ypixel / size.ws_row) as u32,
+            );
+        }
+    }
+    (10, 20)
+}
+
+// Colours are reduced to 6 levels for each of red, green and blue (216 colours in total) which is
+// good enough for a thumbnail and avoids building palette for every image
+fn write_sixel(out: &mut impl Write, image: &RgbImage, area: Rect) -> std::io::Result<()> {
+    const LEVELS: u32 = 6;
+    let (cell_width, cell_height) = cell_size();
+    let (width, height) = (
+        area.width as u32 * cell_width,
+        area.height as u32 * cell_height,
+    );
+    let resized = imageops::resize(image, width, height, imageops::FilterType::Triangle);
+    let level = |value: u8| (value as u32 * (LEVELS - 1) + 127) / 255;
+    let colors = resized
+        .pixels()
+        .map(|pixel| {
+            let [r, g, b] = pixel.0;
+            (level(r) * LEVELS + level(g)) * LEVELS + level(b)
+        })
+        .collect::<Vec<u32>>();
+
+    write!(out, "\x1bP0;1;0q\"1;1;{};{}", width, height)?;
+    for color in 0..LEVELS.pow(3) {
+        let percent = |level: u32| level * 100 / (LEVELS - 1);
+        write!(
+            out,
+            "#{};2;{};{};{}",
+            color,
+            percent(color / (LEVELS * LEVELS)),
+            percent(color / LEVELS % LEVELS),
+            percent(color % LEVELS)
+        )?;
+    }
+
+    // Every band is 6 pixel tall and is drawn once for every colour used in it. Repeated sixels
+    // are run length encoded
+    let mut sixels = vec![0u8; width as usize];
+    for band in (0..height).step_by(6) {
+        let rows = band..(band + 6).min(height);
+        let mut used = rows
+            .clone()
+            .flat_map(|y| &colors[(y * width) as usize..((y + 1) * width) as usize])
+            .copied()
+            .collect::<Vec<u32>>();
+        used.sort_unstable();
+        used.dedup();
+
+        for color in used {
+            for (x, sixel) in sixels.iter_mut().enumerate() {
+                *sixel = rows
+                    .clone()
+                    .filter(|y| colors[(y * width) as usize + x] == color)
+                    .fold(0u8, |bits, y| bits | 1 << (y - band));
+            }
+            write!(out, "#{}", color)?;
+            let mut runs = sixels.iter().peekable();
+            while let Some(&bits) = runs.next() {
+                let mut count = 1;
+                while runs.next_if_eq(&&bits).is_some() {
+                    count += 1;
+                }
+                let ch = (63 + bits) as char;
+                if count > 3 {
+                    write!(out, "!{}{}", count, ch)?;
+                } else {
+                    write!(out, "{}", ch.to_string().repeat(count))?;
+                }
+            }
+            write!(out, "$")?;
+        }
+        write!(out, "-")?;
+    }
+    write!(out, "\x1b\\")
+}
@@ -0,0 +1,861 @@
+use super::shared_import::*;
+use config::initilize::CONFIG;
+use serde_json::{json, Value};
+use std::sync::Condvar;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_native_tls::TlsStream;
+
+mod dlna;
+
+// Music can be played on Chromecast (or Google Home, Android TV, ...) in the same network instead
+// of this computer. Ui keeps controlling the player as usual: player here keeps playing muted and
+// device is made to follow it. Stream url resolved by player is loaded on device when music
+// changes and pause, seek and volume are forwarded to it. So queue, shortcuts, mpris and remote
+// all keep working while casting.
+//
+// Chromecasts are found with mDNS. Protocol spoken with them is protobuf messages (with json
+// payload) over tls. Only the few fields of protobuf that are needed are encoded by hand.
+//
+// DLNA renderers (smart TV, networked speaker, ...) are found with SSDP and are listed along with
+// Chromecasts. See dlna.rs
+const SERVICE_TYPE: &str = "_googlecast._tcp.local.";
+const DISCOVERY_TIME: Duration = Duration::from_secs(3);
+const SYNC_INTERVAL: Duration = Duration::from_millis(500);
+// Device closes the connection that have not pinged it for a while
+const PING_INTERVAL: Duration = Duration::from_secs(5);
+const LAUNCH_TIMEOUT: Duration = Duration::from_secs(15);
+// Device buffers for a while after load and seek so it is only seeked when player here is seeked by
+// more than this (and not when it merely drifted)
+const SEEK_THRESHOLD: Duration = Duration::from_secs(3);
+const MAX_MESSAGE_SIZE: usize = 64 * 1024;
+
+// Default Media Receiver which plays any media url it is given
+const RECEIVER_APP_ID: &str = "CC1AD845";
+const SENDER_ID: &str = "sender-0";
+const RECEIVER_ID: &str = "receiver-0";
+const NS_CONNECTION: &str = "urn:x-cast:com.google.cast.tp.connection";
+const NS_HEARTBEAT: &str = "urn:x-cast:com.google.cast.tp.heartbeat";
+const NS_RECEIVER: &str = "urn:x-cast:com.google.cast.receiver";
+const NS_MEDIA: &str = "urn:x-cast:com.google.cast.media";
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct CastDevice {
+    // Name given to device by it's owner. eg: Living Room TV
+    pub name: String,
+    pub address: std::net::SocketAddr,
+    pub kind: DeviceKind,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum DeviceKind {
+    Chromecast,
+    // Control url of AVTransport and RenderingControl service of renderer
+    Dlna {
+        av_transport: String,
+        rendering_control: String,
+    },
+}
+
+impl DeviceKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DeviceKind::Chromecast => "Chromecast",
+            DeviceKind::Dlna { .. } => "DLNA",
+        }
+    }
+}
+
+// Look for devices of both kind in network for DISCOVERY_TIME. Devices are sorted by name
+pub async fn discover() -> Vec<CastDevice> {
+    let (mut devices, renderers) =
+        tokio::join!(discover_chromecasts(), dlna::discover(DISCOVERY_TIME));
+    devices.extend(renderers);
+    devices.sort_by(|a, b| a.name.cmp(&b.name));
+    devices
+}
+
+async fn discover_chromecasts() -> Vec<CastDevice> {
+    let daemon = match mdns_sd::ServiceDaemon::new() {
+        Ok(daemon) => daemon,
+        Err(_) => return Vec::new(),
+    };
+    let receiver = match daemon.browse(SERVICE_TYPE) {
+        Ok(receiver) => receiver,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut devices: Vec<CastDevice> = Vec::new();
+    let deadline = tokio::time::Instant::now() + DISCOVERY_TIME;
+    while let Ok(Ok(event)) = tokio::time::timeout_at(deadline, receiver.recv_async()).await {
+        if let mdns_sd::ServiceEvent::ServiceResolved(info) = event {
+            let address = match info.get_addresses().iter().find(|ip| ip.is_ipv4()) {
+                Some(ip) => std::net::SocketAddr::new(*ip, info.get_port()),
+                None => continue,
+            };
+            // fn is the friendly name. Full name of service is some id
+            let name = info
+                .get_property_val_str("fn")
+                .unwrap_or(info.get_fullname())
+                .to_string();
+            if !devices.iter().any(|device| device.address == address) {
+                devices.push(CastDevice {
+                    name,
+                    address,
+                    kind: DeviceKind::Chromecast,
+                });
+            }
+        }
+    }
+    daemon.shutdown().ok();
+    devices
+}
+
+fn encode_varint(mut value: u64, out: &mut Vec<u8>) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn decode_varint(bytes: &[u8], at: &mut usize) -> Option<u64> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes.get(*at)?;
+        *at += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+// CastMessage with string payload. Fields are protocol_version (1), source_id (2),
+// destination_id (3), namespace (4), payload_type (5) and payload_utf8 (6). Both enums are 0
+// (CASTV2_1_0 and STRING) but are still written as they are required
+fn encode_message(destination: &str, namespace: &str, payload: &str) -> Vec<u8> {
+    let mut message = vec![0x08, 0x00];
+    for (field, text) in [
+        (2, SENDER_ID),
+        (3, destination),
+        (4, namespace),
+        (6, payload),
+    ] {
+        if field == 6 {
+            message.extend_from_slice(&[0x28, 0x00]);
+        }
+        encode_varint(field << 3 | 2, &mut message);
+        encode_varint(text.len() as u64, &mut message);
+        message.extend_from_slice(text.as_bytes());
+    }
+    let mut frame = (message.len() as u32).to_be_bytes().to_vec();
+    frame.extend(message);
+    frame
+}
+
+// Namespace and payload of CastMessage. Binary payloads are never used by the receiver we talk to
+fn decode_message(message: &[u8]) -> Option<(String, String)> {
+    let (mut namespace, mut payload) = (None, None);
+    let mut at = 0;
+    while at < message.len() {
+        let key = decode_varint(message, &mut at)?;
+        match key & 7 {
+            0 => {
+                decode_varint(message, &mut at)?;
+            }
+            // Fixed 64 and 32 bit. Message ending in between is truncated
+            1 | 5 => {
+                let size = if key & 7 == 1 { 8 } else { 4 };
+                at = Some(at + size).filter(|end| *end <= message.len())?;
+            }
+            2 => {
+                let length = decode_varint(message, &mut at)? as usize;
+                let bytes = message.get(at..at.checked_add(length)?)?;
+                at += length;
+                match key >> 3 {
+                    4 => namespace = Some(String::from_utf8_lossy(bytes).into_owned()),
+                    6 => payload = Some(String::from_utf8_lossy(bytes).into_owned()),
+                    _ => {}
+                }
+            }
+            _ => return None,
+        }
+    }
+    Some((namespace?, payload?))
+}
+
+// What is being played here. Device is synced to this
+struct Playing {
+    // Path of music as reported by player. Empty when nothing is loaded
+    path: String,
+    // Url that player actually streams from after resolving path with youtube-dl
+    stream: String,
+    title: String,
+    artist: String,
+    thumbnail: Option<String>,
+    paused: bool,
+    live: bool,
+    position: Duration,
+    volume: u8,
+    muted: bool,
+}
+
+impl Playing {
+    fn of(state: &super::State) -> Self {
+        let path = state.bottom.artist.0.clone();
+        Playing {
+            stream: state
+                .player
+                .get_property::<String>("stream-open-filename")
+                .unwrap_or_default(),
+            title: state
+                .bottom
+                .playing
+                .as_ref()
+                .map(|(title, _)| title.clone())
+                .unwrap_or_default(),
+            artist: state.playing_artist_of(&path),
+            thumbnail: fetcher::music_id_from_url(&path)
+                .map(|music_id| fetcher::thumbnail::thumbnail_url(&music_id)),
+            paused: matches!(state.bottom.playing, Some((_, false))),
+            live: state.playing_live(),
+            position: state.bottom.music_elapse,
+            volume: state.playback_behaviour.volume,
+            muted: state.playback_behaviour.muted,
+            path: if state.bottom.playing.is_some() {
+                path
+            } else {
+                String::new()
+            },
+        }
+    }
+
+    // Stream url is same as path until player have resolved it with youtube-dl. Path of local file
+    // is never resolved
+    fn is_resolved(&self) -> bool {
+        let is_remote = self.path.starts_with("http://") || self.path.starts_with("https://");
+        !self.stream.is_empty() && (self.stream != self.path || !is_remote)
+    }
+
+    // Device have to be able to fetch the stream by itself. Local files and streams that player
+    // merges from separate audio and video (edl://) can't be cast
+    fn castable_url(&self) -> Option<&str> {
+        let is_remote = self.stream.starts_with("http://") || self.stream.starts_with("https://");
+        (is_remote && self.is_resolved()).then_some(self.stream.as_str())
+    }
+
+    // Youtube tells the type in mime parameter of stream url. Anything else is guessed to be mp4
+    fn content_type(&self) -> String {
+        reqwest::Url::parse(&self.stream)
+            .ok()
+            .and_then(|url| {
+                url.query_pairs()
+                    .find(|(key, _)| key == "mime")
+                    .map(|(_, mime)| mime.into_owned())
+            })
+            .unwrap_or_else(|| "audio/mp4".to_string())
+    }
+}
+
+// Connection to Default Media Receiver launched on Chromecast
+struct Chromecast {
+    writer: tokio::io::WriteHalf<TlsStream<TcpStream>>,
+    // Messages read from device by reader task. Closed when connection is lost
+    messages: tokio::sync::mpsc::UnboundedReceiver<(String, Value)>,
+    request_id: u64,
+    // Id of receiver app session and of it's transport to which media messages are sent
+    app_session: String,
+    transport: String,
+    // Id of media loaded on device. Known once device reports it after load
+    media_session: Option<i64>,
+    pinged_at: std::time::Instant,
+}
+
+impl Chromecast {
+    async fn connect(address: std::net::SocketAddr) -> std::io::Result<Self> {
+        let invalid = |_| std::io::Error::from(std::io::ErrorKind::InvalidData);
+        // Devices use self signed certificate
+        let connector = tokio_native_tls::native_tls::TlsConnector::builder()
+            .danger_accept_invalid_certs(true)
+            .danger_accept_invalid_hostnames(true)
+            .build()
+            .map_err(invalid)?;
+        let stream = TcpStream::connect(address).await?;
+        let stream = tokio_native_tls::TlsConnector::from(connector)
+            .connect(&address.ip().to_string(), stream)
+            .await
+            .map_err(invalid)?;
+        let (mut reader, writer) = tokio::io::split(stream);
+
+        let (sender, messages) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            loop {
+                let mut length = [0u8; 4];
+                if reader.read_exact(&mut length).await.is_err() {
+                    break;
+                }
+                let length = u32::from_be_bytes(length) as usize;
+                if length > MAX_MESSAGE_SIZE {
+                    break;
+                }
+                let mut message = vec![0u8; length];
+                if reader.read_exact(&mut message).await.is_err() {
+                    break;
+                }
+                let decoded = decode_message(&message).and_then(|(namespace, payload)| {
+                    Some((namespace, serde_json::from_str::<Value>(&payload).ok()?))
+                });
+                if let Some(decoded) = decoded {
+                    if sender.send(decoded).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        let mut chromecast = Chromecast {
+            writer,
+            messages,
+            request_id: 0,
+            app_session: String::new(),
+            transport: String::new(),
+            media_session: None,
+            pinged_at: std::time::Instant::now(),
+        };
+        chromecast
+            .send(RECEIVER_ID, NS_CONNECTION, json!({ "type": "CONNECT" }))
+            .await?;
+        chromecast
+            .request(
+                RECEIVER_ID,
+                NS_RECEIVER,
+                json!({ "type": "LAUNCH", "appId": RECEIVER_APP_ID }),
+            )
+            .await?;
+
+        // Receiver tells the session and transport of app once it is launched
+        let launched = tokio::time::timeout(LAUNCH_TIMEOUT, async {
+            while let Some((namespace, payload)) = chromecast.messages.recv().await {
+                if namespace == NS_HEARTBEAT && payload["type"] == "PING" {
+                    chromecast
+                        .send(RECEIVER_ID, NS_HEARTBEAT, json!({ "type": "PONG" }))
+                        .await?;
+                }
+                if namespace != NS_RECEIVER || payload["type"] != "RECEIVER_STATUS" {
+                    continue;
+                }
+                let app = payload["status"]["applications"]
+                    .as_array()
+                    .and_then(|apps| apps.iter().find(|app| app["appId"] == RECEIVER_APP_ID));
+                if let Some(app) = app {
+                    chromecast.app_session = app["sessionId"].as_str().unwrap_or_default().into();
+                    chromecast.transport = app["transportId"].as_str().unwrap_or_default().into();
+                    return Ok(true);
+                }
+            }
+            Ok::<bool, std::io::Error>(false)
+        })
+        .await;
+        if !matches!(launched, Ok(Ok(true))) {
+            return Err(std::io::ErrorKind::TimedOut.into());
+        }
+
+        let transport = chromecast.transport.clone();
+        chromecast
+            .send(&transport, NS_CONNECTION, json!({ "type": "CONNECT" }))
+            .await?;
+        Ok(chromecast)
+    }
+
+    async fn send(
+        &mut self,
+        destination: &str,
+        namespace: &str,
+        payload: Value,
+    ) -> std::io::Result<()> {
+        let frame = encode_message(destination, namespace, &payload.to_string());
+        self.writer.write_all(&frame).await
+    }
+
+    // Messages other than connection and heartbeat needs id to which reply refers to
+    async fn request(
+        &mut self,
+        destination: &str,
+        namespace: &str,
+        mut payload: Value,
+    ) -> std::io::Result<()> {
+        self.request_id += 1;
+        payload["requestId"] = self.request_id.into();
+        self.send(destination, namespace, payload).await
+    }
+
+    async fn media(&mut self, payload: Value) -> std::io::Result<()> {
+        let transport = self.transport.clone();
+        self.request(&transport, NS_MEDIA, payload).await
+    }
+
+    // Handle what device have sent since last sync. Error once connection is lost or app is closed
+    // on device (eg: by casting something else to it)
+    async fn read_messages(&mut self) -> std::io::Result<()> {
+        let closed = || std::io::Error::from(std::io::ErrorKind::ConnectionAborted);
+        loop {
+            let (namespace, payload) = match self.messages.try_recv() {
+                Ok(message) => message,
+                Err(tokio::sync::mpsc::error::TryRecvError::Empty) => break,
+                Err(tokio::sync::mpsc::error::TryRecvError::Disconnected) => return Err(closed()),
+            };
+            match (
+                namespace.as_str(),
+                payload["type"].as_str().unwrap_or_default(),
+            ) {
+                (NS_HEARTBEAT, "PING") => {
+                    self.send(RECEIVER_ID, NS_HEARTBEAT, json!({ "type": "PONG" }))
+                        .await?
+                }
+                (NS_CONNECTION, "CLOSE") => return Err(closed()),
+                (NS_RECEIVER, "RECEIVER_STATUS") => {
+                    let is_running =
+                        payload["status"]["applications"]
+                            .as_array()
+                            .is_some_and(|apps| {
+                                apps.iter()
+                                    .any(|app| app["sessionId"] == self.app_session.as_str())
+                            });
+                    if !is_running {
+                        return Err(closed());
+                    }
+                }
+                (NS_MEDIA, "MEDIA_STATUS") => {
+                    if let Some(status) = payload["status"].as_array().and_then(|s| s.first()) {
+                        self.media_session = status["mediaSessionId"].as_i64();
+                    }
+                }
+                _ => {}
+            }
+        }
+        if self.pinged_at.elapsed() >= PING_INTERVAL {
+            self.pinged_at = std::time::Instant::now();
+            self.send(RECEIVER_ID, NS_HEARTBEAT, json!({ "type": "PING" }))
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn load(&mut self, playing: &Playing, stream: &str) -> std::io::Result<()> {
+        self.media_session = None;
+        let mut metadata = json!({
+            "metadataType": 3,
+            "title": playing.title,
+            "artist": playing.artist,
+        });
+        if let Some(thumbnail) = &playing.thumbnail {
+            metadata["images"] = json!([{ "url": thumbnail }]);
+        }
+        let stream_type = if playing.live { "LIVE" } else { "BUFFERED" };
+        let load = json!({
+            "type": "LOAD",
+            "sessionId": self.app_session,
+            "media": {
+                "contentId": stream,
+                "contentType": playing.content_type(),
+                "streamType": stream_type,
+                "metadata": metadata,
+            },
+            "currentTime": playing.position.as_secs_f64(),
+            "autoplay": !playing.paused,
+        });
+        self.media(load).await
+    }
+
+    async fn set_paused(&mut self, paused: bool) -> std::io::Result<()> {
+        let kind = if paused { "PAUSE" } else { "PLAY" };
+        let media_session = self.media_session;
+        self.media(json!({ "type": kind, "mediaSessionId": media_session }))
+            .await
+    }
+
+    async fn seek(&mut self, position: Duration) -> std::io::Result<()> {
+        let seek = json!({
+            "type": "SEEK",
+            "mediaSessionId": self.media_session,
+            "currentTime": position.as_secs_f64(),
+        });
+        self.media(seek).await
+    }
+
+    async fn set_volume(&mut self, volume: u8, muted: bool) -> std::io::Result<()> {
+        let level = volume as f64 / 100.0;
+        self.request(
+            RECEIVER_ID,
+            NS_RECEIVER,
+            json!({ "type": "SET_VOLUME", "volume": { "level": level } }),
+        )
+        .await?;
+        self.request(
+            RECEIVER_ID,
+            NS_RECEIVER,
+            json!({ "type": "SET_VOLUME", "volume": { "muted": muted } }),
+        )
+        .await
+    }
+
+    // Close the app on device so that it do not keep showing music that is no longer cast
+    async fn stop(mut self) {
+        let stop = json!({ "type": "STOP", "sessionId": self.app_session });
+        self.request(RECEIVER_ID, NS_RECEIVER, stop).await.ok();
+        self.writer.shutdown().await.ok();
+    }
+}
+
+// Device being cast to as spoken to with it's own protocol
+enum Receiver {
+    Chromecast(Chromecast),
+    Dlna(dlna::Renderer),
+}
+
+impl Receiver {
+    async fn connect(device: &CastDevice) -> std::io::Result<Self> {
+        match &device.kind {
+            DeviceKind::Chromecast => Ok(Receiver::Chromecast(
+                Chromecast::connect(device.address).await?,
+            )),
+            DeviceKind::Dlna {
+                av_transport,
+                rendering_control,
+            } => Ok(Receiver::Dlna(
+                dlna::Renderer::connect(av_transport, rendering_control).await?,
+            )),
+        }
+    }
+
+    // Error once device is gone
+    async fn poll(&mut self) -> std::io::Result<()> {
+        match self {
+            Receiver::Chromecast(chromecast) => chromecast.read_messages().await,
+            Receiver::Dlna(renderer) => renderer.poll().await,
+        }
+    }
+
+    // Loaded media can be paused and seeked only once Chromecast have reported it's id. Renderer
+    // accepts them right after load
+    fn is_ready(&self) -> bool {
+        match self {
+            Receiver::Chromecast(chromecast) => chromecast.media_session.is_some(),
+            Receiver::Dlna(_) => true,
+        }
+    }
+
+    async fn load(&mut self, playing: &Playing, stream: &str) -> std::io::Result<()> {
+        match self {
+            Receiver::Chromecast(chromecast) => chromecast.load(playing, stream).await,
+            Receiver::Dlna(renderer) => renderer.load(playing, stream).await,
+        }
+    }
+
+    async fn set_paused(&mut self, paused: bool) -> std::io::Result<()> {
+        match self {
+            Receiver::Chromecast(chromecast) => chromecast.set_paused(paused).await,
+            Receiver::Dlna(renderer) => renderer.set_paused(paused).await,
+        }
+    }
+
+    async fn seek(&mut self, position: Duration) -> std::io::Result<()> {
+        match self {
+            Receiver::Chromecast(chromecast) => chromecast.seek(position).await,
+            Receiver::Dlna(renderer) => renderer.seek(position).await,
+        }
+    }
+
+    async fn set_volume(&mut self, volume: u8, muted: bool) -> std::io::Result<()> {
+        match self {
+            Receiver::Chromecast(chromecast) => chromecast.set_volume(volume, muted).await,
+            Receiver::Dlna(renderer) => renderer.set_volume(volume, muted).await,
+        }
+    }
+
+    async fn stop(self) {
+        match self {
+            Receiver::Chromecast(chromecast) => chromecast.stop().await,
+            Receiver::Dlna(renderer) => renderer.stop().await,
+        }
+    }
+}
+
+struct Session {
+    device: CastDevice,
+    receiver: Receiver,
+    // State of player when device was last synced
+    loaded: String,
+    paused: bool,
+    position: Duration,
+    synced_at: std::time::Instant,
+    volume: (u8, bool),
+}
+
+impl Session {
+    async fn connect(device: &CastDevice, playing: &Playing) -> std::io::Result<Self> {
+        Ok(Session {
+            device: device.clone(),
+            receiver: Receiver::connect(device).await?,
+            loaded: String::new(),
+            paused: playing.paused,
+            position: playing.position,
+            synced_at: std::time::Instant::now(),
+            // Volume of device is left as it is until it is changed here
+            volume: (playing.volume, playing.muted),
+        })
+    }
+
+    // Make device follow the player. Returns a warning to be shown when music can't be cast
+    async fn sync(&mut self, playing: &Playing) -> std::io::Result<Option<&'static str>> {
+        self.receiver.poll().await?;
+        let mut warning = None;
+
+        if playing.path != self.loaded && !playing.path.is_empty() && playing.is_resolved() {
+            self.loaded = playing.path.clone();
+            self.paused = playing.paused;
+            match playing.castable_url() {
+                Some(stream) => self.receiver.load(playing, stream).await?,
+                None => warning = Some("Cannot cast this.."),
+            }
+        }
+
+        if self.receiver.is_ready() {
+            if playing.paused != self.paused {
+                self.paused = playing.paused;
+                self.receiver.set_paused(playing.paused).await?;
+            }
+
+            let mut expected = self.position;
+            if !playing.paused {
+                expected += self.synced_at.elapsed();
+            }
+            if !playing.live && playing.position.abs_diff(expected) > SEEK_THRESHOLD {
+                self.receiver.seek(playing.position).await?;
+            }
+        }
+        self.position = playing.position;
+        self.synced_at = std::time::Instant::now();
+
+        if (playing.volume, playing.muted) != self.volume {
+            self.volume = (playing.volume, playing.muted);
+            self.receiver
+                .set_volume(playing.volume, playing.muted)
+                .await?;
+        }
+        Ok(warning)
+    }
+
+    async fn stop(self) {
+        self.receiver.stop().await;
+    }
+}
+
+// Player here is muted while casting. It is given back the mute of ui once casting stops
+fn restore_player(state: &mut super::State) {
+    let muted = state.playback_behaviour.muted;
+    state.player.set_property("mute", muted).ok();
+}
+
+// Serve until app is quit. Devices are looked for when asked to (from `cast` command) and when
+// cast_device is set in config
+pub async fn serve(state_original: &mut Arc<Mutex<super::State>>, notifier: &mut Arc<Condvar>) {
+    let startup_device = CONFIG.constants.cast_device.trim();
+    if !startup_device.is_empty() {
+        let devices = discover().await;
+        let mut state = state_original.lock().unwrap();
+        state.cast.target = devices
+            .iter()
+            .find(|device| device.name == startup_device)
+            .cloned();
+        if state.cast.target.is_none() {
+            state.warn("No such cast device..");
+        }
+        state.cast.devices = devices;
+    }
+
+    let mut session: Option<Session> = None;
+    loop {
+        let (requested, target, playing) = {
+            let mut state = state_original.lock().unwrap();
+            if state.active == super::Window::None {
+                break;
+            }
+            let requested = std::mem::take(&mut state.cast.requested);
+            (requested, state.cast.target.clone(), Playing::of(&state))
+        };
+
+        // Device list is shown once devices are found. This computer is always the first entry
+        if requested {
+            let devices = discover().await;
+            let mut state = state_original.lock().unwrap();
+            if devices.is_empty() {
+                state.warn("No cast device..");
+            } else {
+                let current = state
+                    .cast
+                    .target
+                    .as_ref()
+                    .and_then(|target| devices.iter().position(|device| device == target))
+                    .map_or(0, |index| index + 1);
+                let mut list_state = tui::widgets::ListState::default();
+                list_state.select(Some(current));
+                state.cast.popup = Some(list_state);
+                state.outputs.popup = None;
+                state.chapters.popup = None;
+                state.moods.popup = None;
+                state.queue = None;
+            }
+            state.cast.devices = devices;
+            notifier.notify_all();
+        }
+
+        if session.as_ref().map(|session| &session.device) != target.as_ref() {
+            if let Some(previous) = session.take() {
+                previous.stop().await;
+                restore_player(&mut state_original.lock().unwrap());
+            }
+            if let Some(device) = target {
+                let connected = Session::connect(&device, &playing).await;
+                let mut state = state_original.lock().unwrap();
+                match connected {
+                    Ok(connected) => {
+                        session = Some(connected);
+                        state.info(format!("Casting to {}..", device.name));
+                    }
+                    // Do not try again and again
+                    Err(_) if state.cast.target.as_ref() == Some(&device) => {
+                        state.cast.target = None;
+                        state.error("Cannot cast..");
+                    }
+                    Err(_) => {}
+                }
+                notifier.notify_all();
+            }
+        }
+
+        if let Some(active) = session.as_mut() {
+            let synced = active.sync(&playing).await;
+            let mut state = state_original.lock().unwrap();
+            match synced {
+                Ok(warning) => {
+                    state.player.set_property("mute", true).ok();
+                    if let Some(warning) = warning {
+                        state.warn(warning);
+                        notifier.notify_all();
+                    }
+                }
+                Err(_) => {
+                    session = None;
+                    state.cast.target = None;
+                    restore_player(&mut state);
+                    state.error("Cast stopped..");
+                    notifier.notify_all();
+                }
+            }
+        }
+
+        tokio::time::sleep(SYNC_INTERVAL).await;
+    }
+
+    if let Some(session) = session {
+        session.stop().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Message without the 4 byte length prefix of frame
+    fn body(frame: &[u8]) -> &[u8] {
+        let length = u32::from_be_bytes(frame[..4].try_into().unwrap()) as usize;
+        assert_eq!(length, frame.len() - 4);
+        &frame[4..]
+    }
+
+    #[test]
+    fn varint_round_trip() {
+        for value in [0, 1, 127, 128, 300, 16_384, u32::MAX as u64, u64::MAX] {
+            let mut bytes = Vec::new();
+            encode_varint(value, &mut bytes);
+            let mut at = 0;
+            assert_eq!(decode_varint(&bytes, &mut at), Some(value));
+            assert_eq!(at, bytes.len());
+        }
+
+        let mut bytes = Vec::new();
+        encode_varint(300, &mut bytes);
+        assert_eq!(bytes, vec![0xac, 0x02]);
+    }
+
+    #[test]
+    fn varint_truncated() {
+        let mut at = 0;
+        assert_eq!(decode_varint(&[0xac], &mut at), None);
+        let mut at = 0;
+        assert_eq!(decode_varint(&[], &mut at), None);
+        // More than 10 bytes is not a valid varint
+        let mut at = 0;
+        assert_eq!(decode_varint(&[0xff; 11], &mut at), None);
+    }
+
+    #[test]
+    fn message_round_trip() {
+        let payload = r#"{"type":"PING"}"#;
+        let frame = encode_message(RECEIVER_ID, NS_HEARTBEAT, payload);
+        assert_eq!(
+            decode_message(body(&frame)),
+            Some((NS_HEARTBEAT.to_string(), payload.to_string()))
+        );
+
+        // Payload longer than 127 bytes has multi byte length
+        let payload = "x".repeat(1000);
+        let frame = encode_message(RECEIVER_ID, NS_MEDIA, &payload);
+        assert_eq!(
+            decode_message(body(&frame)),
+            Some((NS_MEDIA.to_string(), payload))
+        );
+    }
+
+    #[test]
+    fn message_truncated() {
+        let frame = encode_message(RECEIVER_ID, NS_MEDIA, r#"{"type":"GET_STATUS"}"#);
+        let message = body(&frame);
+        for end in 0..message.len() {
+            assert_eq!(decode_message(&message[..end]), None);
+        }
+    }
+
+    #[test]
+    fn fixed_size_fields() {
+        let frame = encode_message(RECEIVER_ID, NS_MEDIA, "{}");
+        let mut message = body(&frame).to_vec();
+        // Unknown field 7 as fixed64 and field 8 as fixed32 are skipped
+        message.extend_from_slice(&[7 << 3 | 1, 1, 2, 3, 4, 5, 6, 7, 8]);
+        message.extend_from_slice(&[8 << 3 | 5, 1, 2, 3, 4]);
+        assert_eq!(
+            decode_message(&message),
+            Some((NS_MEDIA.to_string(), "{}".to_string()))
+        );
+
+        // Skipping past the end
+        let mut short = body(&frame).to_vec();
+        short.extend_from_slice(&[7 << 3 | 1, 1, 2, 3]);
+        assert_eq!(decode_message(&short), None);
+        let mut short = body(&frame).to_vec();
+        short.extend_from_slice(&[8 << 3 | 5, 1]);
+        assert_eq!(decode_message(&short), None);
+    }
+
+    #[test]
+    fn unknown_wire_type() {
+        let frame = encode_message(RECEIVER_ID, NS_MEDIA, "{}");
+        let mut message = body(&frame).to_vec();
+        // Wire type 3 (start group) is not used by cast
+        message.extend_from_slice(&[7 << 3 | 3, 0]);
+        assert_eq!(decode_message(&message), None);
+    }
+}
@@ -0,0 +1,359 @@
+use super::{CastDevice, DeviceKind, Playing};
+use std::time::Duration;
+
+// DLNA renderers are found by asking with SSDP (multicast udp) for devices having AVTransport
+// service. Each one answers with url of it's description from which name and control url of
+// services are read. Renderer is then controlled with SOAP requests to those urls: stream url is
+// given to AVTransport and volume is set with RenderingControl.
+//
+// Unlike Chromecast there is no connection kept with renderer. It is asked for it's state every
+// POLL_INTERVAL to know that it is still there
+const SSDP_ADDRESS: &str = "239.255.255.250:1900";
+const AV_TRANSPORT: &str = "urn:schemas-upnp-org:service:AVTransport:1";
+const RENDERING_CONTROL: &str = "urn:schemas-upnp-org:service:RenderingControl:1";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+fn client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+}
+
+// Text inside first <tag>..</tag> of xml. Tags with namespace prefix are not matched but services
+// are described without prefix in practise
+fn tag_text<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{}>", tag))? + tag.len() + 2;
+    let length = xml[start..].find(&format!("</{}>", tag))?;
+    Some(xml[start..start + length].trim())
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+// Look for renderers in network for given time
+pub async fn discover(time: Duration) -> Vec<CastDevice> {
+    let socket = match tokio::net::UdpSocket::bind("0.0.0.0:0").await {
+        Ok(socket) => socket,
+        Err(_) => return Vec::new(),
+    };
+    let search = format!(
+        "M-SEARCH * HTTP/1.1\r\nHOST: {}\r\nMAN: \"ssdp:discover\"\r\nMX: 2\r\nST: {}\r\n\r\n",
+        SSDP_ADDRESS, AV_TRANSPORT
+    );
+    // Udp may get lost so it is asked twice
+    for _ in 0..2 {
+        if socket
+            .send_to(search.as_bytes(), SSDP_ADDRESS)
+            .await
+            .is_err()
+        {
+            return Vec::new();
+        }
+    }
+
+    let mut locations: Vec<String> = Vec::new();
+    let mut buffer = [0u8; 2048];
+    let deadline = tokio::time::Instant::now() + time;
+    while let Ok(Ok((length, _))) =
+        tokio::time::timeout_at(deadline, socket.recv_from(&mut buffer)).await
+    {
+        let response = String::from_utf8_lossy(&buffer[..length]);
+        let location = response.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.trim()
+                .eq_ignore_ascii_case("location")
+                .then(|| value.trim().to_string())
+        });
+        if let Some(location) = location {
+            if !locations.contains(&location) {
+                locations.push(location);
+            }
+        }
+    }
+
+    let client = client();
+    let described = futures_util::future::join_all(
+        locations.iter().map(|location| describe(&client, location)),
+    )
+    .await;
+    let mut devices: Vec<CastDevice> = Vec::new();
+    for device in described.into_iter().flatten() {
+        if !devices.iter().any(|known| known.kind == device.kind) {
+            devices.push(device);
+        }
+    }
+    devices
+}
+
+// Read name and control urls from description of renderer. None when it is not a renderer after all
+async fn describe(client: &reqwest::Client, location: &str) -> Option<CastDevice> {
+    let location = reqwest::Url::parse(location).ok()?;
+    let description = client
+        .get(location.clone())
+        .send()
+        .await
+        .ok()?
+        .text()
+        .await
+        .ok()?;
+    // Control urls are relative to URLBase given by old devices or otherwise to the description
+    let base = tag_text(&description, "URLBase")
+        .and_then(|base| reqwest::Url::parse(base).ok())
+        .unwrap_or(location);
+    let control_url = |service: &str| {
+        description
+            .split("<service>")
+            .skip(1)
+            .find(|block| tag_text(block, "serviceType") == Some(service))
+            .and_then(|block| tag_text(block, "controlURL"))
+            .and_then(|path| base.join(&unescape_xml(path)).ok())
+            .map(String::from)
+    };
+
+    let av_transport = control_url(AV_TRANSPORT)?;
+    // Renderer without volume control is still fine
+    let rendering_control = control_url(RENDERING_CONTROL).unwrap_or_default();
+    let address = std::net::SocketAddr::new(
+        base.host_str()?.parse().ok()?,
+        base.port_or_known_default()?,
+    );
+    let name = tag_text(&description, "friendlyName")
+        .map(unescape_xml)
+        .unwrap_or_else(|| address.to_string());
+    Some(CastDevice {
+        name,
+        address,
+        kind: DeviceKind::Dlna {
+            av_transport,
+            rendering_control,
+        },
+    })
+}
+
+// DIDL-Lite metadata of music so that renderer can show it and knows what the stream is
+fn metadata(playing: &Playing, stream: &str, content_type: &str) -> String {
+    let thumbnail = playing
+        .thumbnail
+        .as_ref()
+        .map(|thumbnail| {
+            format!(
+                "<upnp:albumArtURI>{}</upnp:albumArtURI>",
+                escape_xml(thumbnail)
+            )
+        })
+        .unwrap_or_default();
+    format!(
+        concat!(
+            r#"<DIDL-Lite xmlns="urn:schemas-upnp-org:metadata-1-0/DIDL-Lite/" "#,
+            r#"xmlns:dc="http://purl.org/dc/elements/1.1/" "#,
+            r#"xmlns:upnp="urn:schemas-upnp-org:metadata-1-0/upnp/">"#,
+            r#"<item id="0" parentID="-1" restricted="1">"#,
+            "<dc:title>{}</dc:title><upnp:artist>{}</upnp:artist>{}",
+            "<upnp:class>object.item.audioItem.musicTrack</upnp:class>",
+            r#"<res protocolInfo="http-get:*:{}:*">{}</res></item></DIDL-Lite>"#
+        ),
+        escape_xml(&playing.title),
+        escape_xml(&playing.artist),
+        thumbnail,
+        content_type,
+        escape_xml(stream)
+    )
+}
+
+// Time as understood by Seek of AVTransport. eg: 0:03:25
+fn seek_target(position: Duration) -> String {
+    let secs = position.as_secs();
+    format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+pub struct Renderer {
+    client: reqwest::Client,
+    av_transport: String,
+    rendering_control: String,
+    polled_at: std::time::Instant,
+}
+
+impl Renderer {
+    // Nothing is kept open. Renderer is only checked to be answering
+    pub async fn connect(av_transport: &str, rendering_control: &str) -> std::io::Result<Self> {
+        let mut renderer = Renderer {
+            client: client(),
+            av_transport: av_transport.to_string(),
+            rendering_control: rendering_control.to_string(),
+            polled_at: std::time::Instant::now(),
+        };
+        renderer.transport_info().await?;
+        renderer.polled_at = std::time::Instant::now();
+        Ok(renderer)
+    }
+
+    async fn action(
+        &self,
+        control_url: &str,
+        service: &str,
+        action: &str,
+        arguments: &[(&str, &str)],
+    ) -> std::io::Result<()> {
+        let arguments = arguments
+            .iter()
+            .map(|(name, value)| format!("<{0}>{1}</{0}>", name, escape_xml(value)))
+            .collect::<String>();
+        let body = format!(
+            concat!(
+                r#"<?xml version="1.0" encoding="utf-8"?>"#,
+                r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/" "#,
+                r#"s:encodingStyle="http://schemas.xmlsoap.org/soap/encoding/">"#,
+                r#"<s:Body><u:{0} xmlns:u="{1}">{2}</u:{0}></s:Body></s:Envelope>"#
+            ),
+            action, service, arguments
+        );
+        let response = self
+            .client
+            .post(control_url)
+            .header("Content-Type", r#"text/xml; charset="utf-8""#)
+            .header("SOAPACTION", format!("\"{}#{}\"", service, action))
+            .body(body)
+            .send()
+            .await
+            .map_err(|_| std::io::Error::from(std::io::ErrorKind::ConnectionAborted))?;
+        // Action that renderer refused is answered with SOAP fault and error status
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(std::io::ErrorKind::InvalidData.into())
+        }
+    }
+
+    async fn transport(&self, action: &str, arguments: &[(&str, &str)]) -> std::io::Result<()> {
+        let mut with_instance = vec![("InstanceID", "0")];
+        with_instance.extend_from_slice(arguments);
+        self.action(&self.av_transport, AV_TRANSPORT, action, &with_instance)
+            .await
+    }
+
+    async fn transport_info(&self) -> std::io::Result<()> {
+        self.transport("GetTransportInfo", &[]).await
+    }
+
+    pub async fn poll(&mut self) -> std::io::Result<()> {
+        if self.polled_at.elapsed() >= POLL_INTERVAL {
+            self.polled_at = std::time::Instant::now();
+            self.transport_info().await?;
+        }
+        Ok(())
+    }
+
+    // Renderer is told where to stream from and started unless player here is paused
+    pub async fn load(&mut self, playing: &Playing, stream: &str) -> std::io::Result<()> {
+        let content_type = playing.content_type();
+        let metadata = metadata(playing, stream, &content_type);
+        self.transport(
+            "SetAVTransportURI",
+            &[("CurrentURI", stream), ("CurrentURIMetaData", &metadata)],
+        )
+        .await?;
+        if !playing.paused {
+            self.transport("Play", &[("Speed", "1")]).await?;
+        }
+        // Many renderers do not seek before they have started playing, so this may well fail.
+        // Music being played from start is not worth stopping the cast for
+        if !playing.live && playing.position > super::SEEK_THRESHOLD {
+            self.seek(playing.position).await.ok();
+        }
+        Ok(())
+    }
+
+    pub async fn set_paused(&mut self, paused: bool) -> std::io::Result<()> {
+        if paused {
+            self.transport("Pause", &[]).await
+        } else {
+            self.transport("Play", &[("Speed", "1")]).await
+        }
+    }
+
+    pub async fn seek(&mut self, position: Duration) -> std::io::Result<()> {
+        let target = seek_target(position);
+        self.transport("Seek", &[("Unit", "REL_TIME"), ("Target", &target)])
+            .await
+    }
+
+    pub async fn set_volume(&mut self, volume: u8, muted: bool) -> std::io::Result<()> {
+        if self.rendering_control.is_empty() {
+            return Ok(());
+        }
+        let volume = volume.min(100).to_string();
+        let muted = if muted { "1" } else { "0" };
+        for (action, argument) in [
+            ("SetVolume", ("DesiredVolume", volume.as_str())),
+            ("SetMute", ("DesiredMute", muted)),
+        ] {
+            self.action(
+                &self.rendering_control,
+                RENDERING_CONTROL,
+                action,
+                &[("InstanceID", "0"), ("Channel", "Master"), argument],
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    // Stop renderer so that it do not keep playing music that is no longer cast
+    pub async fn stop(self) {
+        self.transport("Stop", &[]).await.ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_of_tag() {
+        let xml = "<root><device><friendlyName> Living room </friendlyName>\
+            <serviceType>a</serviceType><serviceType>b</serviceType></device></root>";
+        assert_eq!(tag_text(xml, "friendlyName"), Some("Living room"));
+        assert_eq!(tag_text(xml, "serviceType"), Some("a"));
+        assert_eq!(tag_text(xml, "modelName"), None);
+        assert_eq!(tag_text("<open>never closed", "open"), None);
+        assert_eq!(tag_text("<empty></empty>", "empty"), Some(""));
+    }
+
+    #[test]
+    fn escaping() {
+        let text = r#"Rock & Roll <Live> "Don't stop""#;
+        let escaped = escape_xml(text);
+        assert_eq!(
+            escaped,
+            "Rock &amp; Roll &lt;Live&gt; &quot;Don&apos;t stop&quot;"
+        );
+        assert!(!escaped.contains('\''));
+        assert_eq!(unescape_xml(&escaped), text);
+        // Already escaped text is escaped again rather than being taken as is
+        assert_eq!(escape_xml("&amp;"), "&amp;amp;");
+        assert_eq!(unescape_xml("&amp;lt;"), "&lt;");
+    }
+
+    #[test]
+    fn seek_time() {
+        assert_eq!(seek_target(Duration::ZERO), "0:00:00");
+        assert_eq!(seek_target(Duration::from_millis(205_900)), "0:03:25");
+        assert_eq!(seek_target(Duration::from_secs(3 * 3600 + 61)), "3:01:01");
+        assert_eq!(seek_target(Duration::from_secs(100 * 3600)), "100:00:00");
+    }
+}
@@ -0,0 +1,250 @@
+// Command line opened with `:` key. Commands give access to things that are not worth a shortcut
+// key of their own. Command line is drawn over the last row of screen so that the focused list and
+// its selection stay as they were, which some commands (like download) act upon
+use crate::ui;
+
+pub enum Command {
+    // Url or id to open directly. Same as `:open` in searchbar
+    Open(String),
+    Volume(u8),
+    // Seconds to seek to. When second is true it is relative to current position
+    Seek(i64, bool),
+    // One of "dark", "light" or "config"
+    Theme(String),
+    Layout(ui::LayoutPreset),
+    // Download focused (or marked) music/playlist
+    Download,
+    // Country code of which trending music/charts is shown. Same as `region:` and `charts:` query
+    Region(String),
+    Charts(String),
+    // Turn desktop notification of new music on or off
+    Notify(bool),
+    // Look for cast devices to choose from. With false stop casting
+    Cast(bool),
+    // Choose audio device to play to
+    Output,
+}
+
+// Name of every command along with the values its argument can take (only used to complete the
+// argument), how it is used and what it does (shown in help)
+pub const COMMANDS: [(&str, &[&str], &str, &str); 11] = [
+    (
+        "open",
+        &[],
+        "open <url or id>",
+        "Open music, playlist or channel",
+    ),
+    ("volume", &[], "volume <0-100>", "Set the volume"),
+    (
+        "seek",
+        &[],
+        "seek <[h:]m:s>",
+        "Seek to time. Prefix with +/- to seek relatively",
+    ),
+    (
+        "theme",
+        &["dark", "light", "config"],
+        "theme <dark|light|config>",
+        "Switch colors until app is closed",
+    ),
+    (
+        "layout",
+        &["full", "search", "mini"],
+        "layout <full|search|mini>",
+        "Switch the layout",
+    ),
+    (
+        "download",
+        &[],
+        "download",
+        "Download focused or marked items",
+    ),
+    (
+        "region",
+        &[],
+        "region <code>",
+        "Show trending music of this country",
+    ),
+    (
+        "charts",
+        &[],
+        "charts <code|global>",
+        "Show charts of this country",
+    ),
+    (
+        "notify",
+        &["on", "off"],
+        "notify <on|off>",
+        "Notify on desktop when new music starts",
+    ),
+    (
+        "cast",
+        &["off"],
+        "cast [off]",
+        "Choose Chromecast or DLNA device to play on. off to play here",
+    ),
+    ("output", &[], "output", "Choose audio device to play to"),
+];
+
+// Time like 1:23, 1:02:03 or 90 in seconds
+fn parse_time(text: &str) -> Option<i64> {
+    if text.is_empty() {
+        return None;
+    }
+    text.split(':').try_fold(0i64, |secs, part| {
+        if part.is_empty() || !part.chars().all(|ch| ch.is_ascii_digit()) {
+            return None;
+        }
+        secs.checked_mul(60)?.checked_add(part.parse::<i64>().ok()?)
+    })
+}
+
+pub fn parse(line: &str) -> Result<Command, String> {
+    let line = line.trim();
+    let (name, argument) = match line.split_once(char::is_whitespace) {
+        Some((name, argument)) => (name, argument.trim()),
+        None => (line, ""),
+    };
+    let usage = match COMMANDS.iter().find(|(command, ..)| *command == name) {
+        Some((_, _, usage, _)) => format!("Usage: {}", usage),
+        None => return Err(format!("Unknown command: {}", name)),
+    };
+    let command = match name {
+        "open" if !argument.is_empty() => Command::Open(argument.to_string()),
+        "volume" => match argument.parse::<u8>() {
+            Ok(volume) if volume <= 100 => Command::Volume(volume),
+            _ => return Err(usage),
+        },
+        "seek" => {
+            let (sign, time) = match argument.chars().next() {
+                Some('+') => (1, &argument[1..]),
+                Some('-') => (-1, &argument[1..]),
+                _ => (0, argument),
+            };
+            match parse_time(time.trim()) {
+                Some(secs) if sign == 0 => Command::Seek(secs, false),
+                Some(secs) => Command::Seek(sign * secs, true),
+                None => return Err(usage),
+            }
+        }
+        "theme" if ["dark", "light", "config"].contains(&argument) => {
+            Command::Theme(argument.to_string())
+        }
+        "layout" => match argument {
+            "full" => Command::Layout(ui::LayoutPreset::Full),
+            "search" => Command::Layout(ui::LayoutPreset::Search),
+            "mini" => Command::Layout(ui::LayoutPreset::Mini),
+            _ => return Err(usage),
+        },
+        "download" if argument.is_empty() => Command::Download,
+        "region" if !argument.is_empty() => Command::Region(argument.to_string()),
+        "charts" if !argument.is_empty() => Command::Charts(argument.to_string()),
+        "notify" if ["on", "off"].contains(&argument) => Command::Notify(argument == "on"),
+        "cast" if ["", "off"].contains(&argument) => Command::Cast(argument.is_empty()),
+        "output" if argument.is_empty() => Command::Output,
+        _ => return Err(usage),
+    };
+    Ok(command)
+}
+
+// Longest text that every candidate starting with typed text starts with. None when it is not
+// longer than what is already typed
+fn complete_from<'a>(typed: &str, candidates: impl Iterator<Item = &'a str>) -> Option<String> {
+    let mut matching = candidates.filter(|candidate| candidate.starts_with(typed));
+    let mut common = matching.next()?.to_string();
+    let mut is_unique = true;
+    for candidate in matching {
+        is_unique = false;
+        let common_len = common
+            .chars()
+            .zip(candidate.chars())
+            .take_while(|(a, b)| a == b)
+            .map(|(ch, _)| ch.len_utf8())
+            .sum();
+        common.truncate(common_len);
+    }
+    if is_unique {
+        // Nothing else to choose from so argument can be typed right away
+        common.push(' ');
+        Some(common)
+    } else if common.len() > typed.len() {
+        Some(common)
+    } else {
+        None
+    }
+}
+
+// Complete the command name or its argument as much as it can be. Returns the whole completed
+// line or None if nothing can be completed
+pub fn complete(line: &str) -> Option<String> {
+    match line.split_once(' ') {
+        None => complete_from(line, COMMANDS.iter().map(|(name, ..)| *name)),
+        Some((name, argument)) => {
+            let (_, values, ..) = COMMANDS.iter().find(|(command, ..)| *command == name)?;
+            let completed = complete_from(argument.trim_start(), values.iter().copied())?;
+            Some(format!("{} {}", name, completed.trim_end()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seek_time() {
+        assert!(matches!(parse("seek 90"), Ok(Command::Seek(90, false))));
+        assert!(matches!(
+            parse("seek 1:02:03"),
+            Ok(Command::Seek(3723, false))
+        ));
+        assert!(matches!(parse("seek +1:30"), Ok(Command::Seek(90, true))));
+        assert!(matches!(parse("seek -10"), Ok(Command::Seek(-10, true))));
+        assert!(parse("seek").is_err());
+        assert!(parse("seek 1::2").is_err());
+        assert!(parse("seek 999999999999999999:0").is_err());
+    }
+
+    #[test]
+    fn volume() {
+        assert!(matches!(parse("volume 100"), Ok(Command::Volume(100))));
+        assert!(parse("volume 101").is_err());
+        assert!(parse("volume -1").is_err());
+        assert!(parse("volume loud").is_err());
+    }
+
+    #[test]
+    fn unknown_command() {
+        assert_eq!(
+            parse("louder").err(),
+            Some("Unknown command: louder".to_string())
+        );
+    }
+
+    #[test]
+    fn complete_name() {
+        // Only one command starts with re so argument can follow
+        assert_eq!(complete("re"), Some("region ".to_string()));
+        // cast and charts share nothing more than c
+        assert_eq!(complete("c"), None);
+        assert_eq!(complete("ch"), Some("charts ".to_string()));
+        assert_eq!(complete("x"), None);
+    }
+
+    #[test]
+    fn complete_argument() {
+        assert_eq!(complete("theme d"), Some("theme dark".to_string()));
+        assert_eq!(complete("layout "), None);
+        assert_eq!(complete("volume 1"), None);
+    }
+
+    #[test]
+    fn common_prefix() {
+        let candidates = ["download", "downvote"];
+        assert_eq!(
+            complete_from("d", candidates.iter().copied()),
+            Some("down".to_string())
+        );
+        assert_eq!(complete_from("down", candidates.iter().copied()), None);
+    }
+}
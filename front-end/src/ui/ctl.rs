@@ -0,0 +1,260 @@
+use super::shared_import::*;
+#[cfg(any(unix, windows))]
+use config::initilize::CONFIG;
+use serde_json::{json, Value};
+use std::sync::Condvar;
+
+// Control socket through which `ytui_music ctl <command>` (from shell scripts and keybindings of
+// window manager or AutoHotkey) controls the app that is running. Client sends one line of json
+// with the action and its parameters and gets back one line of json which is same as the http
+// response of remote server (see __remote.rs__ for the actions).
+//
+// On unix it is a unix domain socket created in XDG_RUNTIME_DIR (or in directory of it's own
+// inside /tmp when that is not set) which only owner can connect to. On windows it is the named
+// pipe PIPE_NAME which only accepts local clients. Neither needs a token
+#[cfg(any(unix, windows))]
+const QUIT_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+#[cfg(windows)]
+pub const PIPE_NAME: &str = r"\\.\pipe\ytui_music";
+
+#[cfg(unix)]
+pub fn socket_path() -> std::path::PathBuf {
+    match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) if !dir.is_empty() => std::path::PathBuf::from(dir).join("ytui_music.sock"),
+        _ => std::env::temp_dir()
+            .join(format!("ytui_music-{}", unsafe { libc::getuid() }))
+            .join("ytui_music.sock"),
+    }
+}
+
+// Anyone can create files in /tmp. Socket (or it's directory) there may have been created by other
+// user to take the commands (and answer them) so it is only trusted when owned by this user
+#[cfg(unix)]
+pub fn is_owned(path: &std::path::Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::symlink_metadata(path)
+        .is_ok_and(|metadata| metadata.uid() == unsafe { libc::getuid() })
+}
+
+// Create directory of socket that only owner can enter. Directory that already exists (always the
+// case for XDG_RUNTIME_DIR) must not be accessible by others either. Socket created inside is then
+// never reachable by others, not even for the moment between bind and chmod. Thumbnail of desktop
+// notification is also written there (see private_dir)
+#[cfg(unix)]
+fn prepare_socket_dir(dir: &std::path::Path) -> bool {
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+    match std::fs::DirBuilder::new().mode(0o700).create(dir) {
+        Ok(()) => true,
+        Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+            is_owned(dir)
+                && std::fs::symlink_metadata(dir)
+                    .is_ok_and(|metadata| metadata.is_dir() && metadata.mode() & 0o077 == 0)
+        }
+        Err(_) => false,
+    }
+}
+
+// Directory of socket after making sure that only this user can access it. None when it is not safe
+#[cfg(unix)]
+pub fn private_dir() -> Option<std::path::PathBuf> {
+    let path = socket_path();
+    let dir = path.parent()?;
+    prepare_socket_dir(dir).then(|| dir.to_path_buf())
+}
+
+// Body of request sent over socket. Parameters are same as query parameters of http server
+pub fn request(action: &str, params: &[(String, String)]) -> String {
+    let params = params
+        .iter()
+        .map(|(key, value)| (key.clone(), Value::from(value.as_str())))
+        .collect::<serde_json::Map<String, Value>>();
+    json!({ "action": action, "params": params }).to_string()
+}
+
+#[cfg(any(unix, windows))]
+fn respond(line: &str, state_original: &Mutex<super::State>, notifier: &Condvar) -> Value {
+    let request = match serde_json::from_str::<Value>(line) {
+        Ok(request) => request,
+        Err(_) => return json!({ "error": "Invalid request" }),
+    };
+    let action = request["action"].as_str().unwrap_or_default();
+    let params = request["params"]
+        .as_object()
+        .map(|params| {
+            params
+                .iter()
+                .filter_map(|(key, value)| Some((key.clone(), value.as_str()?.to_string())))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    let result = super::remote::control(&mut state_original.lock().unwrap(), action, &params);
+    notifier.notify_all();
+    match result {
+        Some(Ok(body)) => body,
+        Some(Err(reason)) => json!({ "error": reason }),
+        None => json!({ "error": "Unknown command" }),
+    }
+}
+
+#[cfg(any(unix, windows))]
+async fn handle_connection<Stream>(
+    stream: Stream,
+    state_original: Arc<Mutex<super::State>>,
+    notifier: Arc<Condvar>,
+) where
+    Stream: tokio::io::AsyncRead + tokio::io::AsyncWrite,
+{
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut line = String::new();
+    if BufReader::new(reader).read_line(&mut line).await.is_err() {
+        return;
+    }
+    let response = respond(line.trim(), &state_original, &notifier);
+    writer
+        .write_all(format!("{}\n", response).as_bytes())
+        .await
+        .ok();
+}
+
+// Serve until app is quit. Socket left behind by app that was killed is replaced but socket of
+// another running instance is left alone (and that instance keeps being controlled)
+#[cfg(unix)]
+pub async fn serve(state_original: &mut Arc<Mutex<super::State>>, notifier: &mut Arc<Condvar>) {
+    use std::os::unix::fs::PermissionsExt;
+
+    if !CONFIG.constants.control_socket {
+        return;
+    }
+    let path = socket_path();
+    if !path.parent().is_some_and(prepare_socket_dir) {
+        state_original
+            .lock()
+            .unwrap()
+            .error("Socket directory is not safe..");
+        return;
+    }
+    if path.symlink_metadata().is_ok() && !is_owned(&path) {
+        state_original
+            .lock()
+            .unwrap()
+            .error("Socket is of other user..");
+        return;
+    }
+    if std::os::unix::net::UnixStream::connect(&path).is_ok() {
+        state_original
+            .lock()
+            .unwrap()
+            .warn("Other instance is controlled..");
+        return;
+    }
+    std::fs::remove_file(&path).ok();
+    let listener = match tokio::net::UnixListener::bind(&path) {
+        Ok(listener) => listener,
+        Err(_) => {
+            state_original
+                .lock()
+                .unwrap()
+                .error("Cannot create socket..");
+            return;
+        }
+    };
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).ok();
+
+    loop {
+        if state_original.lock().unwrap().active == super::Window::None {
+            break;
+        }
+        if let Ok(Ok((stream, _))) =
+            tokio::time::timeout(QUIT_CHECK_INTERVAL, listener.accept()).await
+        {
+            tokio::spawn(handle_connection(
+                stream,
+                Arc::clone(state_original),
+                Arc::clone(notifier),
+            ));
+        }
+    }
+    std::fs::remove_file(&path).ok();
+}
+
+// Every client gets it's own instance of pipe. New instance is created to wait for next client as
+// soon as one connects. Pipe that already exists belongs to another running instance
+#[cfg(windows)]
+pub async fn serve(state_original: &mut Arc<Mutex<super::State>>, notifier: &mut Arc<Condvar>) {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    if !CONFIG.constants.control_socket {
+        return;
+    }
+    let mut server = match ServerOptions::new()
+        .first_pipe_instance(true)
+        .reject_remote_clients(true)
+        .create(PIPE_NAME)
+    {
+        Ok(server) => server,
+        Err(_) => {
+            state_original
+                .lock()
+                .unwrap()
+                .warn("Other instance is controlled..");
+            return;
+        }
+    };
+
+    loop {
+        if state_original.lock().unwrap().active == super::Window::None {
+            break;
+        }
+        if let Ok(Ok(())) = tokio::time::timeout(QUIT_CHECK_INTERVAL, server.connect()).await {
+            let next = match ServerOptions::new()
+                .reject_remote_clients(true)
+                .create(PIPE_NAME)
+            {
+                Ok(next) => next,
+                Err(_) => break,
+            };
+            tokio::spawn(handle_connection(
+                std::mem::replace(&mut server, next),
+                Arc::clone(state_original),
+                Arc::clone(notifier),
+            ));
+        }
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+pub async fn serve(_state_original: &mut Arc<Mutex<super::State>>, _notifier: &mut Arc<Condvar>) {}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn socket_dir_must_be_private() {
+        let dir = std::env::temp_dir().join(format!("ytui-ctl-test-{}", std::process::id()));
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert!(prepare_socket_dir(&dir));
+        let mode = std::fs::metadata(&dir).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+        assert!(is_owned(&dir));
+        // Already there and still private
+        assert!(prepare_socket_dir(&dir));
+
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(!prepare_socket_dir(&dir));
+
+        // File in place of directory
+        std::fs::remove_dir_all(&dir).unwrap();
+        std::fs::write(&dir, b"").unwrap();
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o600)).unwrap();
+        assert!(!prepare_socket_dir(&dir));
+        std::fs::remove_file(&dir).unwrap();
+
+        assert!(!is_owned(&dir));
+    }
+}
@@ -0,0 +1,41 @@
+use super::shared_import::*;
+use config::initilize::CONFIG;
+use std::sync::Condvar;
+
+// Headless mode (`ytui_music run --daemon`) used on small server or as background music service.
+// Everything except the terminal ui runs as usual and app is controlled only through remote server
+// and control socket. This takes the place of both draw_ui and event loop: player status is
+// refreshed as often as it would have been redrawn and notifications that would have been shown
+// are printed to stderr so that they end up in log of service manager.
+//
+// Runs until quit action is requested or app is asked to terminate by a signal
+pub async fn run(state_original: &mut Arc<Mutex<super::State>>, notifier: &mut Arc<Condvar>) {
+    let refresh_rate = Duration::from_millis(CONFIG.constants.refresh_rate);
+    let terminate = super::event::wait_for_terminate();
+    tokio::pin!(terminate);
+
+    loop {
+        {
+            let mut state = state_original.lock().unwrap();
+            if state.active == super::Window::None {
+                break;
+            }
+            state.refresh_mpv_status();
+            for notification in state.notifications.queue.drain(..) {
+                eprintln!("[{:?}] {}", notification.severity, notification.message);
+            }
+            // Toast only confirms what was just requested which the client already knows
+            state.toast = None;
+        }
+        notifier.notify_all();
+
+        tokio::select! {
+            _ = &mut terminate => {
+                state_original.lock().unwrap().quit();
+                notifier.notify_all();
+                break;
+            }
+            _ = tokio::time::sleep(refresh_rate) => {}
+        }
+    }
+}
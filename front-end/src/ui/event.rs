@@ -1,16 +1,46 @@
 use crate::ui::{self, utils::ExtendMpv};
-use config::initilize::{CONFIG, STORAGE};
-use crossterm::event::{self, Event, KeyCode, KeyModifiers};
+use config::initilize::CONFIG;
+use crossterm::event::{self, Event, KeyCode, KeyModifiers, MouseButton, MouseEventKind};
 use std::{
     convert::TryFrom,
+    io::Write,
     sync::{Arc, Condvar, Mutex},
     time::Duration,
 };
+use tui::layout::Rect;
 
 pub const MIDDLE_MUSIC_INDEX: usize = 0;
 pub const MIDDLE_PLAYLIST_INDEX: usize = 1;
 pub const MIDDLE_ARTIST_INDEX: usize = 2;
 
+// When search query is in form of `region:<country code>` it is not searched but instead changes
+// the region from which trending music is shown. eg: `region:US`
+const REGION_COMMAND_PREFIX: &str = "region:";
+// Same as above but changes the country of which charts is shown. eg: `charts:US` or `charts:global`
+const CHARTS_COMMAND_PREFIX: &str = "charts:";
+// Open the url or id directly instead of searching. eg: `:open dQw4w9WgXcQ`. Url pasted without
+// this prefix is also opened directly. See fetcher::open_target
+const OPEN_COMMAND_PREFIX: &str = ":open";
+// Search everywhere even when music of an artist is shown (where query is otherwise searched only
+// within that artist). eg: `all:lofi`
+const GLOBAL_SEARCH_PREFIX: &str = "all:";
+
+// Next chunk of comments is fetched when scrolled to within this many comments from the end
+const COMMENTS_FETCH_AHEAD: usize = 5;
+
+// Second click on same line within this time is a double click
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(400);
+
+// Second key of chord should be pressed within this time after the first one
+const CHORD_TIMEOUT: Duration = Duration::from_millis(1000);
+
+// Music added to local playlist are appended to this file inside download directory. Any player
+// (including mpv itself) can play it
+const LOCAL_PLAYLIST_FILE: &str = "ytui-music.m3u";
+
+// Percentage by which sidebar and music section is resized on every key press
+const PANE_RESIZE_STEP: u16 = 5;
+
 #[derive(Clone)]
 enum HeadTo {
     Initial,
@@ -18,6 +48,68 @@ enum HeadTo {
     Prev,
 }
 
+// Where to move the selection of list with vim style keys. See `vim_keys` in config
+enum JumpTo {
+    Top,
+    Bottom,
+    HalfPageDown,
+    HalfPageUp,
+}
+
+// What is done by a two key sequence. See config::ChordKeys
+enum Chord {
+    Goto(ui::SidebarOption),
+    Top,
+    YankUrl,
+}
+
+// Shortcut keys that are taken along with CTRL. CTRL+d/CTRL+u of vim style keys give way to these
+// (download is on CTRL+d by default)
+pub fn is_control_shortcut(ch: char) -> bool {
+    let keys = &CONFIG.shortcut_keys;
+    [keys.download, keys.prev, keys.next, keys.quit].contains(&ch)
+}
+
+// Keys of chord as written in config. Spaces in between are allowed. eg: "gt" or "g t"
+fn chord_keys_of(keys: &str) -> Option<[char; 2]> {
+    let mut keys = keys.chars().filter(|ch| !ch.is_whitespace());
+    match (keys.next(), keys.next(), keys.next()) {
+        (Some(first), Some(second), None) => Some([first, second]),
+        _ => None,
+    }
+}
+
+// Index to select in list of `len` items where `visible` items are shown at once
+fn jump_index(current: usize, len: usize, visible: usize, jump: JumpTo) -> usize {
+    let half_page = (visible / 2).max(1);
+    match jump {
+        JumpTo::Top => 0,
+        JumpTo::Bottom => len.saturating_sub(1),
+        JumpTo::HalfPageDown => (current + half_page).min(len.saturating_sub(1)),
+        JumpTo::HalfPageUp => current.saturating_sub(half_page),
+    }
+}
+
+// Index of next item after `current` whose name starts with `letter` wrapping around to the top.
+// Leading symbols like quotes and brackets are skipped and case is ignored
+fn index_starting_with<'a>(
+    names: impl Iterator<Item = &'a str>,
+    current: usize,
+    letter: char,
+) -> Option<usize> {
+    let letter = letter.to_lowercase().collect::<String>();
+    let starts = names
+        .map(|name| {
+            name.chars()
+                .find(|ch| ch.is_alphanumeric())
+                .is_some_and(|first| first.to_lowercase().collect::<String>() == letter)
+        })
+        .collect::<Vec<bool>>();
+    (1..=starts.len())
+        .map(|step| (current + step) % starts.len())
+        .find(|&index| starts[index])
+}
+
 // Helper function to return the index of something depending the current position and direction to
 // move to
 fn advance_index(current: usize, limit: usize, direction: HeadTo) -> usize {
@@ -68,6 +160,147 @@ fn get_page(current: &Option<usize>, direction: HeadTo) -> usize {
     page as usize
 }
 
+// Music marked in music section or the focused one if nothing is marked. Marks are cleared as the
+// action is applied to them. Empty if music section is not focused
+fn marked_or_focused_music(state: &mut ui::State) -> Vec<fetcher::MusicUnit> {
+    if state.active != ui::Window::Musicbar {
+        Vec::new()
+    } else if !state.marked.music.is_empty() {
+        std::mem::take(&mut state.marked.music)
+    } else {
+        state
+            .musicbar
+            .1
+            .selected()
+            .map(|index| vec![state.musicbar.0[index].clone()])
+            .unwrap_or_default()
+    }
+}
+
+// Toast shown after adding/removing every marked row to/from favourites at once
+fn favourites_toast(add: bool, count: usize) -> String {
+    if add {
+        format!("Added {} to favourites", count)
+    } else {
+        format!("Removed {} from favourites", count)
+    }
+}
+
+// Append the music to local playlist file in extended m3u format
+fn append_to_local_playlist(music: &[fetcher::MusicUnit]) -> std::io::Result<()> {
+    use std::io::Write;
+    let path = std::path::Path::new(&CONFIG.download.path).join(LOCAL_PLAYLIST_FILE);
+    let is_new = !path.exists();
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    if is_new {
+        writeln!(file, "#EXTM3U")?;
+    }
+    for music in music {
+        writeln!(file, "#EXTINF:-1,{} - {}", music.artist, music.name)?;
+        writeln!(file, "{}", fetcher::music_url(&music.id))?;
+    }
+    Ok(())
+}
+
+// Text in system clipboard. Terminal cannot be asked for it as reply would come in stdin along
+// with the key events, so whichever clipboard tool is installed is asked instead
+fn clipboard_text() -> Option<String> {
+    let tools: [(&str, &[&str]); 5] = [
+        ("wl-paste", &["--no-newline"]),
+        ("xclip", &["-out", "-selection", "clipboard"]),
+        ("xsel", &["--output", "--clipboard"]),
+        ("pbpaste", &[]),
+        ("powershell", &["-NoProfile", "-Command", "Get-Clipboard"]),
+    ];
+    tools.iter().find_map(|(program, args)| {
+        let output = std::process::Command::new(program)
+            .args(*args)
+            .stderr(std::process::Stdio::null())
+            .output()
+            .ok()?;
+        if output.status.success() {
+            String::from_utf8(output.stdout).ok()
+        } else {
+            None
+        }
+    })
+}
+
+// Put the text in system clipboard with whichever clipboard tool is installed. When there is none,
+// terminal is asked to do so (OSC 52) which also works over ssh but is not allowed by every terminal
+fn copy_to_clipboard(text: &str) -> bool {
+    use std::process::{Command, Stdio};
+    let tools: [(&str, &[&str]); 5] = [
+        ("wl-copy", &[]),
+        ("xclip", &["-in", "-selection", "clipboard"]),
+        ("xsel", &["--input", "--clipboard"]),
+        ("pbcopy", &[]),
+        ("clip", &[]),
+    ];
+    let copied = tools.iter().any(|(program, args)| {
+        let child = Command::new(program)
+            .args(*args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        match child {
+            Ok(mut child) => {
+                let written = child
+                    .stdin
+                    .take()
+                    .is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+                child.wait().is_ok_and(|status| status.success()) && written
+            }
+            Err(_) => false,
+        }
+    });
+    if copied {
+        return true;
+    }
+
+    use base64::Engine;
+    let mut stdout = std::io::stdout();
+    write!(
+        stdout,
+        "\x1b]52;c;{}\x07",
+        base64::engine::general_purpose::STANDARD.encode(text)
+    )
+    .and_then(|_| stdout.flush())
+    .is_ok()
+}
+
+// Open the url in default web browser. Browser is not waited for as it may keep running until closed
+fn open_in_browser(url: &str) -> bool {
+    use std::process::{Command, Stdio};
+    let mut command = if cfg!(target_os = "windows") {
+        // Empty title is needed as start takes first quoted argument as window title
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else {
+        Command::new("xdg-open")
+    };
+    command
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .is_ok()
+}
+
+// Position of windows in current screen. This is same as the one calculated while drawing the ui
+fn screen_position(state: &ui::State) -> ui::Position {
+    let (width, height) = crossterm::terminal::size().unwrap_or_default();
+    ui::Position::caclulate(&Rect::new(0, 0, width, height), &state.panes)
+}
+
 /*
 * The event_sender function is running in it's own seperate thread.
 * -> A loop is initilized where it waits for any event to happen (keypress and resize for now)
@@ -78,10 +311,39 @@ fn get_page(current: &Option<usize>, direction: HeadTo) -> usize {
 * the threads. And another loop is ran in communicator.rs where it wait checks weather anything
 * should be filled from diffrenet source.
 */
-pub async fn event_sender(
-    state_original: &mut Arc<Mutex<ui::State<'_>>>,
-    notifier: &mut Arc<Condvar>,
-) {
+// Set when app is asked to terminate by a signal (eg: kill or closing the terminal). Event loop
+// quits as if quit shortcut was pressed with ALT
+static TERMINATE: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+// Popup shown while quit is waiting to be confirmed. See Constants::confirm_quit
+const QUIT_CONFIRM_TITLE: &str = "Quit?";
+
+pub(super) async fn wait_for_terminate() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        let (interrupt, terminate, hangup) = (
+            signal(SignalKind::interrupt()),
+            signal(SignalKind::terminate()),
+            signal(SignalKind::hangup()),
+        );
+        if let (Ok(mut interrupt), Ok(mut terminate), Ok(mut hangup)) =
+            (interrupt, terminate, hangup)
+        {
+            tokio::select! {
+                _ = interrupt.recv() => {}
+                _ = terminate.recv() => {}
+                _ = hangup.recv() => {}
+            }
+        } else {
+            std::future::pending::<()>().await;
+        }
+    }
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await.ok();
+}
+
+pub async fn event_sender(state_original: &mut Arc<Mutex<ui::State>>, notifier: &mut Arc<Condvar>) {
     // Some predefined source
     let youtube_community_channels = vec![fetcher::ArtistUnit {
         name: "Youtube Music Global Charts".to_string(),
@@ -91,6 +353,11 @@ pub async fn event_sender(
 
     let download_counter: Arc<Mutex<u32>> = Arc::new(Mutex::new(0));
 
+    tokio::spawn(async {
+        wait_for_terminate().await;
+        TERMINATE.store(true, std::sync::atomic::Ordering::Relaxed);
+    });
+
     // There is several option in sidebar like trending/ favourates,
     // this handler will change the selected option from sidebar depending on the direction user
     // move (Up or DOwn).
@@ -159,10 +426,30 @@ pub async fn event_sender(
     // thread)
     let quit = |force_quit: bool| -> bool {
         let mut state = state_original.lock().unwrap();
+        let downloads = *download_counter.lock().unwrap();
+        // When asked to, quitting while something is playing or downloading needs to be confirmed
+        // by pressing quit shortcut again while the confirmation is shown
+        let is_confirmed = matches!(state.active, ui::Window::Popup(QUIT_CONFIRM_TITLE, _));
+        if CONFIG.constants.confirm_quit && !force_quit && !is_confirmed {
+            let is_playing = matches!(state.bottom.playing, Some((_, true)));
+            let reason = match (downloads, is_playing) {
+                (0, false) => None,
+                (0, true) => Some("Music is playing.".to_string()),
+                (downloads, _) => Some(format!("{} download(s) in progress.", downloads)),
+            };
+            if let Some(reason) = reason {
+                state.active = ui::Window::Popup(
+                    QUIT_CONFIRM_TITLE,
+                    format!("{} Press quit shortcut again to quit", reason),
+                );
+                notifier.notify_all();
+                return false;
+            }
+        }
         // Do not quit when some download is in progress as it may leave partial file on the disk.
         // If it is urgent required to quit the application user should also press ALT key along
         // with CTRL and QUIT key
-        if !force_quit && *download_counter.lock().unwrap() > 0 {
+        if !force_quit && !is_confirmed && downloads > 0 {
             state.active = ui::Window::Popup(
                 "Error",
                 "Some download are in progress. Press this shortcut with ALT key to force quit"
@@ -171,13 +458,7 @@ pub async fn event_sender(
             return false;
         }
 
-        // setting active window to None is to quit
-        state.active = ui::Window::None;
-        // Also make sure databse is flushed.
-        if let Err(err) = STORAGE.lock().unwrap().cache_flush() {
-            eprintln!("Cannot flush the storage db. Error: {err}", err = err);
-        }
-
+        state.quit();
         notifier.notify_all();
         true
     };
@@ -185,15 +466,24 @@ pub async fn event_sender(
     // This handler will fire up when user request to move between sections like musicbar, sidebar
     // etc. Similar handler moveto_next_window / moveto_prev_window are not merged as these
     // closures as these handlers are frequently called so avoid more branching
+    // Windows not shown in current layout are skipped
     let moveto_next_window = || {
         let mut state = state_original.lock().unwrap();
-        state.active = state.active.next();
+        let mut next = state.active.next();
+        while !state.panes.is_shown(&next) {
+            next = next.next();
+        }
+        state.active = next;
         notifier.notify_all();
     };
 
     let moveto_prev_window = || {
         let mut state = state_original.lock().unwrap();
-        state.active = state.active.prev();
+        let mut prev = state.active.prev();
+        while !state.panes.is_shown(&prev) {
+            prev = prev.prev();
+        }
+        state.active = prev;
         notifier.notify_all();
     };
 
@@ -205,6 +495,8 @@ pub async fn event_sender(
         match state.active {
             ui::Window::Searchbar | ui::Window::Popup(..) => {
                 state.search.0.clear();
+                state.suggestions.0.clear();
+                state.suggestions.1.clear();
                 drop_and_call!(state, moveto_next_window);
             }
             ui::Window::BottomControl => {
@@ -231,7 +523,7 @@ pub async fn event_sender(
         let mut state = state_original.lock().unwrap();
         match state.active {
             ui::Window::Searchbar => {
-                state.search.0.pop();
+                state.edit_search(ui::SearchEdit::Backspace);
                 notifier.notify_all();
             }
             _ => drop_and_call!(state, moveto_prev_window),
@@ -239,21 +531,108 @@ pub async fn event_sender(
     };
 
     // This is fires when user press any character key
-    // this will simpley push the recived character in search query term and update state
-    // so can the added character becomes visible
+    // this will simpley insert the recived character in search query term at the cursor and update
+    // state so can the added character becomes visible
     let handle_search_input = |ch| {
-        state_original.lock().unwrap().search.0.push(ch);
+        let mut state = state_original.lock().unwrap();
+        state.edit_search(ui::SearchEdit::Insert(ch));
         notifier.notify_all();
     };
 
+    // Paste text from clipboard in search query at the cursor. Searchbar is focused first so that
+    // copied url can be pasted from anywhere. Line breaks are pasted as space as query is a line
+    let paste_to_search = || {
+        let text = clipboard_text();
+        let mut state = state_original.lock().unwrap();
+        match text
+            .as_deref()
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+        {
+            Some(text) => {
+                if state.panes.preset == ui::LayoutPreset::Mini {
+                    state.panes.preset = ui::LayoutPreset::Search;
+                }
+                state.active = ui::Window::Searchbar;
+                for ch in text.chars() {
+                    let ch = if ch.is_control() { ' ' } else { ch };
+                    state.edit_search(ui::SearchEdit::Insert(ch));
+                }
+            }
+            None => state.warn("Nothing to paste. Is wl-paste, xclip or xsel installed?.."),
+        }
+        notifier.notify_all();
+    };
+
+    // Move the cursor in search query or edit the query from there. Returns false if searchbar is
+    // not active so that caller can handle the key as usual
+    let edit_search = |edit: ui::SearchEdit| -> bool {
+        let mut state = state_original.lock().unwrap();
+        if state.active != ui::Window::Searchbar {
+            return false;
+        }
+        state.edit_search(edit);
+        notifier.notify_all();
+        true
+    };
+
+    // Move the selection in suggestions dropdown and put the selected suggestion in searchbar.
+    // Returns false if there is no suggestion to select from so that caller can handle the key
+    // as usual
+    let advance_suggestion = |direction: HeadTo| -> bool {
+        let mut state = state_original.lock().unwrap();
+        if state.active != ui::Window::Searchbar || state.suggestions.1.is_empty() {
+            return false;
+        }
+        let next_index = match (state.suggestions.2.selected(), direction.clone()) {
+            (None, HeadTo::Prev) => state.suggestions.1.len() - 1,
+            (None, _) => 0,
+            (Some(current), direction) => {
+                advance_index(current, state.suggestions.1.len(), direction)
+            }
+        };
+        state.suggestions.2.select(Some(next_index));
+        state.search.0 = state.suggestions.1[next_index].clone();
+        state.search_cursor = 0;
+        notifier.notify_all();
+        true
+    };
+
     // This handler is fired when use press SEARCH_SH_KEY
     // this will move the curson to the searchbar from which user can start to type the query
     let activate_search = || {
         let mut state = state_original.lock().unwrap();
+        // Mini player has no searchbar
+        if state.panes.preset == ui::LayoutPreset::Mini {
+            state.panes.preset = ui::LayoutPreset::Search;
+        }
         state.active = ui::Window::Searchbar;
         notifier.notify_all();
     };
 
+    let change_volume = |direction: HeadTo| {
+        let mut state = state_original.lock().unwrap();
+
+        let increase_by = match direction {
+            HeadTo::Next => CONFIG.constants.volume_step,
+            HeadTo::Prev => -CONFIG.constants.volume_step,
+            HeadTo::Initial => 0
+        };
+
+        let res = state.player.change_volume(increase_by);
+
+        match res {
+            Some(vol) => {
+                state.playback_behaviour.volume = vol;
+            }
+            None => {
+                state.error("Volume error..");
+            }
+        };
+
+        notifier.notify_all();
+    };
+
     // This handler will be fired when user hits UP_ARROW or DOWN_ARROW key
     // UP_ARROW will set the direction to PREV and DOWN_ARROW to NEXT
     // for now, these key will only handle the moving of list
@@ -266,6 +645,12 @@ pub async fn event_sender(
             ui::Window::Musicbar => drop_and_call!(state, advance_music_list, direction),
             ui::Window::Playlistbar => drop_and_call!(state, advance_playlist_list, direction),
             ui::Window::Artistbar => drop_and_call!(state, advance_artist_list, direction),
+            // Bottom bar has no list to move in so it changes the volume. Up is louder
+            ui::Window::BottomControl => match direction {
+                HeadTo::Next => drop_and_call!(state, change_volume, HeadTo::Prev),
+                HeadTo::Prev => drop_and_call!(state, change_volume, HeadTo::Next),
+                HeadTo::Initial => {}
+            },
             _ => match direction {
                 HeadTo::Next => drop_and_call!(state, moveto_next_window),
                 HeadTo::Prev => drop_and_call!(state, moveto_prev_window),
@@ -274,40 +659,218 @@ pub async fn event_sender(
         }
     };
 
-    let start_search = || {
+    // Search the query of searchbar. Live search is started by typing itself (see live_search_ms
+    // in config) so it leaves the suggestions and focus as they are and does nothing for queries
+    // that open something or change region/charts as those may still be half typed
+    let search = |live: bool| {
         let mut state = state_original.lock().unwrap();
-        let search_term = state.search.0.trim();
 
-        // return instantly if query is empty
-        if search_term.is_empty() {
+        // Hide the suggestions. Clearing the typed text also makes sure that suggestion response
+        // which is still in progress is discarded
+        if !live {
+            state.suggestions.0.clear();
+            state.suggestions.1.clear();
+            state.suggestions.2.select(None);
+        }
+
+        let search_term = state.search.0.trim();
+        let (search_term, is_global) = match search_term.strip_prefix(GLOBAL_SEARCH_PREFIX) {
+            Some(query) => (query.trim_start(), true),
+            None => (search_term, false),
+        };
+        // Outer Some if this is a request to open something directly. Inner None if it could not
+        // be understood what to open
+        let to_open = match search_term.strip_prefix(OPEN_COMMAND_PREFIX) {
+            Some(target) => Some(fetcher::open_target(target, true)),
+            None => fetcher::open_target(search_term, false).map(Some),
+        };
+        let previous_sources = state.filled_source.clone();
+        let songs_only = state.songs_only;
+
+        // return instantly if query is empty or is not to be searched live
+        let skip_live = live
+            && (state.active != ui::Window::Searchbar
+                || to_open.is_some()
+                || search_term.starts_with(REGION_COMMAND_PREFIX)
+                || search_term.starts_with(CHARTS_COMMAND_PREFIX));
+        if search_term.is_empty() || skip_live {
             return;
         }
+        // Play the music or open the playlist/artist right away bypassing search
+        else if let Some(target) = to_open {
+            match target {
+                Some(fetcher::OpenTarget::Music(music_id)) => state.play_single(&music_id),
+                Some(fetcher::OpenTarget::Playlist(playlist_id)) => {
+                    state.fetched_page[MIDDLE_MUSIC_INDEX] = Some(0);
+                    state.filled_source.0 = ui::MusicbarSource::Playlist(playlist_id);
+                    state.active = ui::Window::Musicbar;
+                }
+                Some(fetcher::OpenTarget::Artist(artist_id)) => {
+                    state.fetched_page[MIDDLE_MUSIC_INDEX] = Some(0);
+                    state.fetched_page[MIDDLE_PLAYLIST_INDEX] = Some(0);
+                    state.filled_source.0 = ui::MusicbarSource::Artist(artist_id.clone());
+                    state.filled_source.1 = ui::PlaylistbarSource::Artist(artist_id);
+                    state.active = ui::Window::Musicbar;
+                }
+                None => {
+                    state.error("Cannot open..");
+                    notifier.notify_all();
+                    return;
+                }
+            }
+            state.search.0.clear();
+        }
+        // Change the region of trending and show the trending music from that region
+        else if let Some(region) = search_term.strip_prefix(REGION_COMMAND_PREFIX) {
+            let region = region.trim().to_uppercase();
+            if region.len() == 2 && region.chars().all(|ch| ch.is_ascii_alphabetic()) {
+                state.trending_region = region.clone();
+                state.fetched_page[MIDDLE_MUSIC_INDEX] = Some(0);
+                state.filled_source.0 =
+                    ui::MusicbarSource::Trending(region, state.trending_category);
+                state.search.0.clear();
+            } else {
+                state.warn("Invalid region..");
+            }
+        }
+        // Change the country of charts and show the charts of that country
+        else if let Some(country) = search_term.strip_prefix(CHARTS_COMMAND_PREFIX) {
+            let country = country.trim().to_uppercase();
+            let country = if country == "GLOBAL" {
+                fetcher::innertube::GLOBAL_CHARTS.to_string()
+            } else {
+                country
+            };
+            if country.len() == 2 && country.chars().all(|ch| ch.is_ascii_alphabetic()) {
+                state.charts_country = country.clone();
+                state.fetched_page = [Some(0); 3];
+                state.filled_source.0 = ui::MusicbarSource::Charts(country.clone());
+                state.filled_source.1 = ui::PlaylistbarSource::Charts(country.clone());
+                state.filled_source.2 = ui::ArtistbarSource::Charts(country);
+                state.search.0.clear();
+            } else {
+                state.warn("Invalid country..");
+            }
+        }
         // When prefiexed by the string as defined in config only show the specific result type
         // respectively
-        else if let Some(0) = search_term.find(&CONFIG.constants.search_by_type[0]) {
+        // Same is done when query contains `type:` filter. Filter itself is left in the query and
+        // is stripped by the fetcher
+        else if let Some(kind) = fetcher::filter::SearchFilter::from_query(search_term)
+            .0
+            .kind
+        {
+            let search_term = search_term.to_string();
+            state.fetched_page[kind] = Some(0);
+            match kind {
+                0 => state.filled_source.0 = ui::MusicbarSource::Search(search_term, songs_only),
+                1 => state.filled_source.1 = ui::PlaylistbarSource::Search(search_term),
+                _ => state.filled_source.2 = ui::ArtistbarSource::Search(search_term),
+            }
+        } else if let Some(0) = search_term.find(&CONFIG.constants.search_by_type[0]) {
             let search_term =
-                search_term[CONFIG.constants.search_by_type[0].len() - 1..].to_string();
+                search_term[CONFIG.constants.search_by_type[0].len()..].to_string();
             state.fetched_page[0] = Some(0);
-            state.filled_source.0 = ui::MusicbarSource::Search(search_term);
+            state.filled_source.0 = ui::MusicbarSource::Search(search_term, songs_only);
         } else if let Some(0) = search_term.find(&CONFIG.constants.search_by_type[1]) {
             let search_term =
-                search_term[CONFIG.constants.search_by_type[1].len() - 1..].to_string();
+                search_term[CONFIG.constants.search_by_type[1].len()..].to_string();
             state.fetched_page[1] = Some(0);
             state.filled_source.1 = ui::PlaylistbarSource::Search(search_term);
         } else if let Some(0) = search_term.find(&CONFIG.constants.search_by_type[2]) {
             let search_term =
-                search_term[&CONFIG.constants.search_by_type[2].len() - 1..].to_string();
+                search_term[CONFIG.constants.search_by_type[2].len()..].to_string();
             state.fetched_page[2] = Some(0);
             state.filled_source.2 = ui::ArtistbarSource::Search(search_term);
         }
+        // When music of an artist is being shown search only within uploads of that artist.
+        // Query with any prefix or filter (i.e having `:`) is handled above or is still searched
+        // globally below. So is the query after GLOBAL_SEARCH_PREFIX
+        else if let Some(channel_id) = state
+            .search_channel()
+            .filter(|_| !is_global && !search_term.contains(':'))
+        {
+            let filled_source =
+                ui::MusicbarSource::ArtistSearch(channel_id.to_string(), search_term.to_string());
+            state.fetched_page[MIDDLE_MUSIC_INDEX] = Some(0);
+            state.filled_source.0 = filled_source;
+        }
         // If nothing of the prefix is defined then search for all type
         else {
             let search_term = search_term.to_string();
             state.fetched_page = [Some(0); 3];
-            state.filled_source.0 = ui::MusicbarSource::Search(search_term.clone());
+            state.filled_source.0 = ui::MusicbarSource::Search(search_term.clone(), songs_only);
             state.filled_source.1 = ui::PlaylistbarSource::Search(search_term.clone());
             state.filled_source.2 = ui::ArtistbarSource::Search(search_term);
         }
+
+        // Results of live search are filled without leaving the searchbar. When the query was
+        // already searched live, nothing is fetched again so move to results right away
+        if live {
+            state.live_search = true;
+        } else if std::mem::take(&mut state.live_search) && state.filled_source == previous_sources
+        {
+            state.active = ui::Window::Musicbar;
+        }
+        notifier.notify_all();
+    };
+
+    // Switch between searching only songs and all videos. Music being shown from search is searched
+    // again in new scope
+    let toggle_search_scope = || {
+        let mut state = state_original.lock().unwrap();
+        state.songs_only = !state.songs_only;
+        let songs_only = state.songs_only;
+        if let ui::MusicbarSource::Search(query, _) = &state.filled_source.0 {
+            state.filled_source.0 = ui::MusicbarSource::Search(query.clone(), songs_only);
+            state.fetched_page[MIDDLE_MUSIC_INDEX] = Some(0);
+        }
+        state.info(if songs_only {
+            "Searching songs only.."
+        } else {
+            "Searching all videos.."
+        });
+        notifier.notify_all();
+    };
+
+    // Fetch again the list that failed to fetch. Focused list is retried if it has failed,
+    // otherwise every list that has failed is retried
+    let retry_failed = || {
+        let mut state = state_original.lock().unwrap();
+        let focused = match state.active {
+            ui::Window::Musicbar => Some(MIDDLE_MUSIC_INDEX),
+            ui::Window::Playlistbar => Some(MIDDLE_PLAYLIST_INDEX),
+            ui::Window::Artistbar => Some(MIDDLE_ARTIST_INDEX),
+            _ => None,
+        };
+        let failed: Vec<usize> = match focused {
+            Some(index) if state.fetch_error[index].is_some() => vec![index],
+            _ => (0..3)
+                .filter(|index| state.fetch_error[*index].is_some())
+                .collect(),
+        };
+        if failed.is_empty() {
+            state.warn("Nothing to retry..");
+        }
+        for index in failed {
+            state.fetch_error[index] = None;
+            state.retry_requested[index] = true;
+        }
+        notifier.notify_all();
+    };
+
+    let start_search = || search(false);
+    let live_search = || search(true);
+
+    // Fill the musicbar with top songs, playlistbar with video charts and artistbar with top
+    // artists of charts of state.charts_country
+    let fill_charts = || {
+        let mut state = state_original.lock().unwrap();
+        let country = state.charts_country.clone();
+        state.filled_source.0 = ui::MusicbarSource::Charts(country.clone());
+        state.filled_source.1 = ui::PlaylistbarSource::Charts(country.clone());
+        state.filled_source.2 = ui::ArtistbarSource::Charts(country);
+        state.fetched_page = [Some(0); 3];
         notifier.notify_all();
     };
 
@@ -315,7 +878,8 @@ pub async fn event_sender(
         let mut state = state_original.lock().unwrap();
         state.fetched_page[MIDDLE_MUSIC_INDEX] =
             Some(get_page(&state.fetched_page[MIDDLE_MUSIC_INDEX], direction));
-        state.filled_source.0 = ui::MusicbarSource::Trending;
+        state.filled_source.0 =
+            ui::MusicbarSource::Trending(state.trending_region.clone(), state.trending_category);
         notifier.notify_all();
     };
 
@@ -334,6 +898,30 @@ pub async fn event_sender(
         notifier.notify_all();
     };
 
+    // Fill the musicbar with music played before, newest first
+    let fill_history = || {
+        let mut state = state_original.lock().unwrap();
+        state.filled_source.0 = ui::MusicbarSource::RecentlyPlayed;
+        state.fetched_page[MIDDLE_MUSIC_INDEX] = Some(0);
+        notifier.notify_all();
+    };
+
+    // History is only cleared while it is shown so that it is not lost by mistake
+    let clear_history = || {
+        let mut state = state_original.lock().unwrap();
+        let is_shown = state.filled_source.0 == ui::MusicbarSource::RecentlyPlayed
+            && state.fetched_page[MIDDLE_MUSIC_INDEX].is_some();
+        if is_shown {
+            let count = fetcher::history::clear();
+            state.musicbar.0.clear();
+            state.musicbar.1.select(None);
+            state.info(format!("Removed {} music from history..", count));
+        } else {
+            state.warn("Open History from sidebar to clear it..");
+        }
+        notifier.notify_all();
+    };
+
     let fill_fav_playlist = |direction: HeadTo| {
         let mut state = state_original.lock().unwrap();
         state.filled_source.1 = ui::PlaylistbarSource::Favourates;
@@ -350,6 +938,35 @@ pub async fn event_sender(
         notifier.notify_all();
     };
 
+    // Fill the musicbar with latest uploads of subscribed artists and artistbar with the artists
+    let fill_subscriptions = || {
+        let mut state = state_original.lock().unwrap();
+        state.filled_source.0 = ui::MusicbarSource::Subscriptions;
+        state.filled_source.2 = ui::ArtistbarSource::Subscriptions;
+        state.fetched_page[MIDDLE_MUSIC_INDEX] = Some(0);
+        state.fetched_page[MIDDLE_ARTIST_INDEX] = Some(0);
+        notifier.notify_all();
+    };
+
+    // Fill the musicbar with uploads of followed and subscribed artists since they were last seen
+    let fill_new_releases = || {
+        let mut state = state_original.lock().unwrap();
+        state.filled_source.0 = ui::MusicbarSource::NewReleases;
+        state.fetched_page[MIDDLE_MUSIC_INDEX] = Some(0);
+        notifier.notify_all();
+    };
+
+    // Fill the musicbar with all the music from local directory and playlistbar with the local
+    // albums (directory containing music)
+    let fill_local_source = || {
+        let mut state = state_original.lock().unwrap();
+        state.filled_source.0 = ui::MusicbarSource::Local;
+        state.filled_source.1 = ui::PlaylistbarSource::Local;
+        state.fetched_page[MIDDLE_MUSIC_INDEX] = Some(0);
+        state.fetched_page[MIDDLE_PLAYLIST_INDEX] = Some(0);
+        notifier.notify_all();
+    };
+
     let fill_music_from_playlist = |direction: HeadTo| {
         let mut state = state_original.lock().unwrap();
         if let ui::MusicbarSource::Playlist(playlist_id) = &state.filled_source.0 {
@@ -382,323 +999,2078 @@ pub async fn event_sender(
         }
     };
 
-    // play next/previous song from queue
-    let change_track = |direction: HeadTo| match direction {
-        HeadTo::Next => state_original.lock().unwrap().player.play_next(),
-        HeadTo::Prev => state_original.lock().unwrap().player.play_prev(),
-        HeadTo::Initial => unreachable!(),
+    // Replace the music section with the music related to currently focused music
+    let fill_related_music = || {
+        let mut state = state_original.lock().unwrap();
+        if state.active != ui::Window::Musicbar {
+            return;
+        }
+        if let Some(selected_index) = state.musicbar.1.selected() {
+            let music = &state.musicbar.0[selected_index];
+            let (music_id, name) = (music.id.clone(), music.name.clone());
+            state.source_names.insert(music_id.clone(), name);
+            state.filled_source.0 = ui::MusicbarSource::Related(music_id);
+            state.fetched_page[MIDDLE_MUSIC_INDEX] = Some(0);
+            notifier.notify_all();
+        }
     };
 
-    // navigating page is just changing to fetched_page value to next/prev value
-    // or changing the prev/next track
-    let handle_nav = |direction: HeadTo| {
+    // Replace the music section with the Mix seeded from currently focused music
+    let fill_mix = || {
         let mut state = state_original.lock().unwrap();
-        let target_index: usize;
-        match state.active {
-            ui::Window::Musicbar => target_index = MIDDLE_MUSIC_INDEX,
-            ui::Window::Playlistbar => target_index = MIDDLE_PLAYLIST_INDEX,
-            ui::Window::Artistbar => target_index = MIDDLE_ARTIST_INDEX,
-            ui::Window::BottomControl => {
-                // On reciving next/prev event when active window is bottom control
-                // It implied to change the track
-                return drop_and_call!(state, change_track, direction);
-            }
-            ui::Window::Searchbar | ui::Window::Sidebar | ui::Window::Popup(..) => {
-                // If none of above windows are active then nothing to navigate.
-                // Early return instead of initilizing `target_index`
-                return;
+        if state.active != ui::Window::Musicbar {
+            return;
+        }
+        if let Some(selected_index) = state.musicbar.1.selected() {
+            match fetcher::mix_id(&state.musicbar.0[selected_index].id) {
+                Some(mix_id) => {
+                    let name = format!("Mix of {}", state.musicbar.0[selected_index].name);
+                    state.source_names.insert(mix_id.clone(), name);
+                    state.filled_source.0 = ui::MusicbarSource::Playlist(mix_id);
+                    state.fetched_page[MIDDLE_MUSIC_INDEX] = Some(0);
+                }
+                None => state.warn("No mix.."),
             }
-            ui::Window::None => unreachable!(),
+            notifier.notify_all();
         }
-        let page = get_page(&state.fetched_page[target_index], direction);
-        state.fetched_page[target_index] = Some(page);
-        notifier.notify_all();
     };
 
-    let seek_forward = || {
-        state_original
-            .lock()
-            .unwrap()
-            .player
-            .seek_forward(CONFIG.constants.seek_forward_secs as f64)
-            .ok();
-        notifier.notify_all();
+    // Show the episodes of focused artist as podcast. Playing an episode from this list resumes
+    // it from where it was left off
+    let fill_podcast_episodes = || {
+        let mut state = state_original.lock().unwrap();
+        if state.active != ui::Window::Artistbar {
+            return;
+        }
+        if let Some(selected_index) = state.artistbar.1.selected() {
+            let artist = &state.artistbar.0[selected_index];
+            let (channel_id, name) = (artist.id.clone(), artist.name.clone());
+            state.source_names.insert(channel_id.clone(), name);
+            state.filled_source.0 = ui::MusicbarSource::Podcast(channel_id);
+            state.fetched_page[MIDDLE_MUSIC_INDEX] = Some(0);
+            notifier.notify_all();
+        }
     };
 
-    let seek_backward = || {
-        state_original
-            .lock()
-            .unwrap()
-            .player
-            .seek_backward(CONFIG.constants.seek_backward_secs as f64)
-            .ok();
+    // Ask to load every music of focused playlist. When playlist section is not active, the
+    // playlist shown in music section is loaded instead
+    let load_all_music = || {
+        let mut state = state_original.lock().unwrap();
+        let playlist_id = match (&state.active, state.playlistbar.1.selected()) {
+            (ui::Window::Playlistbar, Some(selected_index)) => {
+                state.playlistbar.0[selected_index].id.clone()
+            }
+            _ => match &state.filled_source.0 {
+                ui::MusicbarSource::Playlist(playlist_id) => playlist_id.clone(),
+                _ => {
+                    state.warn("No playlist..");
+                    notifier.notify_all();
+                    return;
+                }
+            },
+        };
+        state.full_playlist.requested = Some(playlist_id);
         notifier.notify_all();
     };
 
-    let handle_repeat = || {
+    let seek_chapter = |direction: HeadTo| {
         let mut state = state_original.lock().unwrap();
-        state.player.repeat_nothing();
-        if state.playback_behaviour.repeat {
-            state.player.repeat_one();
+        if state.playing_live() {
+            state.warn("Live, no seek..");
         } else {
-            state.player.repeat_playlist();
+            state.seek_chapter(matches!(direction, HeadTo::Next));
         }
-        state.playback_behaviour.repeat = !state.playback_behaviour.repeat;
         notifier.notify_all();
     };
 
-    let toggle_shuffle = || {
+    // Show or hide the chapter list. Chapter being played is selected initially
+    let toggle_chapters = || {
         let mut state = state_original.lock().unwrap();
-        if state.playback_behaviour.shuffle {
-            state.player.unshuffle();
+        state.moods.popup = None;
+        state.cast.popup = None;
+        state.outputs.popup = None;
+        state.queue = None;
+        if state.chapters.popup.is_some() {
+            state.chapters.popup = None;
+        } else if state.chapters.list.is_empty() {
+            state.warn("No chapter..");
         } else {
-            state.player.shuffle();
+            let mut list_state = tui::widgets::ListState::default();
+            list_state.select(Some(state.current_chapter().unwrap_or_default()));
+            state.chapters.popup = Some(list_state);
         }
-        state.playback_behaviour.shuffle = !state.playback_behaviour.shuffle;
         notifier.notify_all();
     };
 
-    let toggle_play = || {
-        state_original.lock().unwrap().toggle_pause();
-        notifier.notify_all();
+    // Move selection in chapter list. Returns false if chapter list is not shown so that caller
+    // can handle the key as usual
+    let advance_chapter_list = |direction: HeadTo| -> bool {
+        let mut state = state_original.lock().unwrap();
+        let count = state.chapters.list.len();
+        match state.chapters.popup.as_mut() {
+            Some(list_state) => {
+                let current = list_state.selected().unwrap_or_default();
+                list_state.select(Some(advance_index(current, count, direction)));
+                notifier.notify_all();
+                true
+            }
+            None => false,
+        }
     };
 
-    let handle_download = || async {
+    // Jump to selected chapter from chapter list and close the list. Returns false if chapter
+    // list is not shown
+    let select_chapter = || -> bool {
         let mut state = state_original.lock().unwrap();
+        match state.chapters.popup.take() {
+            Some(list_state) => {
+                if let Some(index) = list_state.selected() {
+                    if index < state.chapters.list.len() {
+                        state.jump_to_chapter(index);
+                    }
+                }
+                notifier.notify_all();
+                true
+            }
+            None => false,
+        }
+    };
 
-        // TODO: Ask for conformation before downloading
-        let mut command = tokio::process::Command::new("youtube-dl");
-        let download_url;
-        if let Some(focused_index) = state.musicbar.1.selected() {
-            let music_id = &state.musicbar.0[focused_index].id;
-            download_url = format!("https://www.youtube.com/watch?v={}", music_id);
-        } else if let Some(focused_index) = state.playlistbar.1.selected() {
-            let playlist_id = &state.playlistbar.0[focused_index].id;
-            download_url = format!("https://www.youtube.com/playlist?list={}", playlist_id);
+    let close_chapters = || -> bool {
+        let mut state = state_original.lock().unwrap();
+        let was_shown = state.chapters.popup.take().is_some();
+        notifier.notify_all();
+        was_shown
+    };
+
+    // Show the list of mood/genre categories. Categories are fetched first if not fetched yet
+    let show_moods = || {
+        let mut state = state_original.lock().unwrap();
+        state.chapters.popup = None;
+        state.cast.popup = None;
+        state.outputs.popup = None;
+        state.queue = None;
+        if state.moods.list.is_empty() {
+            state.moods.requested = true;
+            state.info("Fetch moods..");
         } else {
-            return;
+            let mut list_state = tui::widgets::ListState::default();
+            list_state.select(Some(0));
+            state.moods.popup = Some(list_state);
         }
-
-        state.status = "Download started..";
-        state.active = ui::Window::Popup(
-            "Downloading...",
-            format!(
+        notifier.notify_all();
+    };
+
+    // Same as advance_chapter_list but for mood list
+    let advance_mood_list = |direction: HeadTo| -> bool {
+        let mut state = state_original.lock().unwrap();
+        let count = state.moods.list.len();
+        match state.moods.popup.as_mut() {
+            Some(list_state) => {
+                let current = list_state.selected().unwrap_or_default();
+                list_state.select(Some(advance_index(current, count, direction)));
+                notifier.notify_all();
+                true
+            }
+            None => false,
+        }
+    };
+
+    // Fill the playlistbar with playlists of selected category and close the list. Returns false if
+    // mood list is not shown
+    let select_mood = || -> bool {
+        let mut state = state_original.lock().unwrap();
+        match state.moods.popup.take() {
+            Some(list_state) => {
+                let category = list_state
+                    .selected()
+                    .and_then(|index| state.moods.list.get(index))
+                    .cloned();
+                if let Some(category) = category {
+                    state.filled_source.1 =
+                        ui::PlaylistbarSource::Mood(category.params, category.title);
+                    state.fetched_page[MIDDLE_PLAYLIST_INDEX] = Some(0);
+                    state.active = ui::Window::Playlistbar;
+                }
+                notifier.notify_all();
+                true
+            }
+            None => false,
+        }
+    };
+
+    let close_moods = || -> bool {
+        let mut state = state_original.lock().unwrap();
+        let was_shown = state.moods.popup.take().is_some();
+        notifier.notify_all();
+        was_shown
+    };
+
+    // Same as advance_chapter_list but for list of cast devices
+    let advance_cast_list = |direction: HeadTo| -> bool {
+        let mut state = state_original.lock().unwrap();
+        let count = state.cast.devices.len() + 1;
+        match state.cast.popup.as_mut() {
+            Some(list_state) => {
+                let current = list_state.selected().unwrap_or_default();
+                list_state.select(Some(advance_index(current, count, direction)));
+                notifier.notify_all();
+                true
+            }
+            None => false,
+        }
+    };
+
+    // Cast to selected device (or play here when first entry is selected) and close the list.
+    // Returns false if device list is not shown
+    let select_cast_device = || -> bool {
+        let mut state = state_original.lock().unwrap();
+        match state.cast.popup.take() {
+            Some(list_state) => {
+                if let Some(index) = list_state.selected() {
+                    state.cast.target = index
+                        .checked_sub(1)
+                        .and_then(|index| state.cast.devices.get(index))
+                        .cloned();
+                }
+                notifier.notify_all();
+                true
+            }
+            None => false,
+        }
+    };
+
+    let close_cast_list = || -> bool {
+        let mut state = state_original.lock().unwrap();
+        let was_shown = state.cast.popup.take().is_some();
+        notifier.notify_all();
+        was_shown
+    };
+
+    // Same as advance_chapter_list but for list of audio devices
+    let advance_output_list = |direction: HeadTo| -> bool {
+        let mut state = state_original.lock().unwrap();
+        let count = state.outputs.devices.len();
+        match state.outputs.popup.as_mut() {
+            Some(list_state) => {
+                let current = list_state.selected().unwrap_or_default();
+                list_state.select(Some(advance_index(current, count, direction)));
+                notifier.notify_all();
+                true
+            }
+            None => false,
+        }
+    };
+
+    // Play to selected device from now on (also after restart) and close the list. Returns false if
+    // device list is not shown
+    let select_output = || -> bool {
+        let mut state = state_original.lock().unwrap();
+        match state.outputs.popup.take() {
+            Some(list_state) => {
+                let device = list_state
+                    .selected()
+                    .and_then(|index| state.outputs.devices.get(index))
+                    .cloned();
+                if let Some((name, description)) = device {
+                    // auto is what empty in config means anyway
+                    let pinned = if name == "auto" { "" } else { name.as_str() };
+                    if state
+                        .player
+                        .set_property("audio-device", name.as_str())
+                        .is_err()
+                    {
+                        state.error("Cannot use this device..");
+                    } else if let Err(err) =
+                        config::ConfigContainer::save_constant("audio_device", pinned)
+                    {
+                        state.warn(format!(
+                            "Output set but cannot save it in config. {}..",
+                            err
+                        ));
+                    } else {
+                        state.info(format!("Output {}..", description));
+                    }
+                }
+                notifier.notify_all();
+                true
+            }
+            None => false,
+        }
+    };
+
+    let close_output_list = || -> bool {
+        let mut state = state_original.lock().unwrap();
+        let was_shown = state.outputs.popup.take().is_some();
+        notifier.notify_all();
+        was_shown
+    };
+
+    // Show or hide the play queue. Music being played is selected initially
+    let toggle_queue = || {
+        let mut state = state_original.lock().unwrap();
+        state.chapters.popup = None;
+        state.moods.popup = None;
+        state.cast.popup = None;
+        state.outputs.popup = None;
+        if state.queue.take().is_none() {
+            let (entries, current) = state.player.queue();
+            if entries.is_empty() {
+                state.warn("Queue empty..");
+            } else {
+                let mut list_state = tui::widgets::ListState::default();
+                list_state.select(Some(current.unwrap_or_default()));
+                state.queue = Some(list_state);
+            }
+        }
+        notifier.notify_all();
+    };
+
+    // Move selection in queue. Returns false if queue is not shown
+    let advance_queue = |direction: HeadTo| -> bool {
+        let mut state = state_original.lock().unwrap();
+        let count = state.player.queue().0.len();
+        match state.queue.as_mut() {
+            Some(list_state) => {
+                let current = list_state.selected().unwrap_or_default();
+                list_state.select(Some(advance_index(current, count, direction)));
+                notifier.notify_all();
+                true
+            }
+            None => false,
+        }
+    };
+
+    // Move the selected music one step down (Next) or up (Prev) in queue. Selection follows the
+    // music. Returns false if queue is not shown or searchbar is being typed into
+    let move_in_queue = |direction: HeadTo| -> bool {
+        let mut state = state_original.lock().unwrap();
+        if state.active == ui::Window::Searchbar {
+            return false;
+        }
+        let count = state.player.queue().0.len();
+        let selected = match state.queue.as_ref() {
+            Some(list_state) => list_state.selected(),
+            None => return false,
+        };
+        if let Some(from) = selected.filter(|index| *index < count) {
+            let to = match direction {
+                HeadTo::Next if from + 1 < count => from + 1,
+                HeadTo::Prev if from > 0 => from - 1,
+                _ => from,
+            };
+            if to != from {
+                state.player.move_in_queue(from, to);
+                if let Some(list_state) = state.queue.as_mut() {
+                    list_state.select(Some(to));
+                }
+            }
+        }
+        notifier.notify_all();
+        true
+    };
+
+    // Remove the selected music from queue. Returns false if queue is not shown or searchbar is
+    // being typed into
+    let remove_from_queue = || -> bool {
+        let mut state = state_original.lock().unwrap();
+        if state.active == ui::Window::Searchbar {
+            return false;
+        }
+        let selected = match state.queue.as_ref() {
+            Some(list_state) => list_state.selected(),
+            None => return false,
+        };
+        if let Some(index) = selected {
+            state.player.remove_from_queue(index);
+            let count = state.player.queue().0.len();
+            if let Some(list_state) = state.queue.as_mut() {
+                list_state.select(Some(index.min(count.saturating_sub(1))));
+            }
+        }
+        notifier.notify_all();
+        true
+    };
+
+    // Play the selected music from queue and close the queue. Returns false if queue is not shown
+    let play_from_queue = || -> bool {
+        let mut state = state_original.lock().unwrap();
+        match state.queue.take() {
+            Some(list_state) => {
+                if let Some(index) = list_state.selected() {
+                    state.player.play_index(index);
+                }
+                notifier.notify_all();
+                true
+            }
+            None => false,
+        }
+    };
+
+    let close_queue = || -> bool {
+        let mut state = state_original.lock().unwrap();
+        let was_shown = state.queue.take().is_some();
+        notifier.notify_all();
+        was_shown
+    };
+
+    let toggle_help = || {
+        let mut state = state_original.lock().unwrap();
+        state.help = match state.help {
+            Some(_) => None,
+            None => Some(ui::HelpState::default()),
+        };
+        notifier.notify_all();
+    };
+
+    // Scroll the help by a line. Returns false if help is not shown
+    let scroll_help = |direction: HeadTo| -> bool {
+        let mut state = state_original.lock().unwrap();
+        match state.help.as_mut() {
+            Some(help) => {
+                help.scroll = match direction {
+                    HeadTo::Next => help.scroll.saturating_add(1),
+                    _ => help.scroll.saturating_sub(1),
+                };
+                notifier.notify_all();
+                true
+            }
+            None => false,
+        }
+    };
+
+    // Clear the filter if any. Otherwise close the help. Returns false if help is not shown
+    let close_help = || -> bool {
+        let mut state = state_original.lock().unwrap();
+        match state.help.as_mut() {
+            Some(help) if !help.query.is_empty() => {
+                help.query.clear();
+                help.scroll = 0;
+            }
+            Some(_) => state.help = None,
+            None => return false,
+        }
+        notifier.notify_all();
+        true
+    };
+
+    // Type into help filter. Returns false if help is not shown
+    let help_input = |ch: char| -> bool {
+        let mut state = state_original.lock().unwrap();
+        match state.help.as_mut() {
+            Some(help) => {
+                help.query.push(ch);
+                help.scroll = 0;
+            }
+            None => return false,
+        }
+        notifier.notify_all();
+        true
+    };
+
+    let help_backspace = || -> bool {
+        let mut state = state_original.lock().unwrap();
+        match state.help.as_mut() {
+            Some(help) => {
+                help.query.pop();
+                help.scroll = 0;
+            }
+            None => return false,
+        }
+        notifier.notify_all();
+        true
+    };
+
+    // Lyrics and comments are shown in same place so only one of them is shown at a time
+    let toggle_lyrics = || {
+        let mut state = state_original.lock().unwrap();
+        state.lyrics.shown = !state.lyrics.shown;
+        state.comments.shown = false;
+        notifier.notify_all();
+    };
+
+    // Show details of focused music or close them if already shown
+    let toggle_details = || {
+        let mut state = state_original.lock().unwrap();
+        if state.details.take().is_none() {
+            let focused = match state.active {
+                ui::Window::Musicbar => state.musicbar.1.selected(),
+                _ => None,
+            };
+            match focused.and_then(|index| state.musicbar.0.get(index)) {
+                Some(music) => {
+                    state.details = Some(ui::DetailsState {
+                        music: music.clone(),
+                        description: None,
+                        scroll: 0,
+                    });
+                    // Communicator fetches the description
+                    state.info("Fetch description..");
+                }
+                None => state.warn("Focus a music to see its details.."),
+            }
+        }
+        notifier.notify_all();
+    };
+
+    // Scroll the details by a line. Returns false if details are not shown
+    let scroll_details = |direction: HeadTo| -> bool {
+        let mut state = state_original.lock().unwrap();
+        match state.details.as_mut() {
+            Some(details) => {
+                details.scroll = match direction {
+                    HeadTo::Next => details.scroll.saturating_add(1),
+                    _ => details.scroll.saturating_sub(1),
+                };
+            }
+            None => return false,
+        }
+        notifier.notify_all();
+        true
+    };
+
+    let close_details = || -> bool {
+        let mut state = state_original.lock().unwrap();
+        let was_shown = state.details.take().is_some();
+        notifier.notify_all();
+        was_shown
+    };
+
+    let toggle_comments = || {
+        let mut state = state_original.lock().unwrap();
+        state.comments.shown = !state.comments.shown;
+        state.lyrics.shown = false;
+        notifier.notify_all();
+    };
+
+    // Scroll the comments by one comment. Next chunk of comments is requested when scrolled near
+    // the end. Returns false if comments are not shown so that caller can handle the key as usual
+    let scroll_comments = |direction: HeadTo| -> bool {
+        let mut state = state_original.lock().unwrap();
+        if !state.comments.shown {
+            return false;
+        }
+        let comments = &mut state.comments;
+        match direction {
+            HeadTo::Next => {
+                comments.scroll = (comments.scroll + 1).min(comments.list.len().saturating_sub(1))
+            }
+            HeadTo::Prev => comments.scroll = comments.scroll.saturating_sub(1),
+            HeadTo::Initial => comments.scroll = 0,
+        }
+        if comments.scroll + COMMENTS_FETCH_AHEAD >= comments.list.len()
+            && comments.continuation.is_some()
+        {
+            comments.more_requested = true;
+        }
+        notifier.notify_all();
+        true
+    };
+
+    let close_comments = || -> bool {
+        let mut state = state_original.lock().unwrap();
+        let was_shown = std::mem::replace(&mut state.comments.shown, false);
+        notifier.notify_all();
+        was_shown
+    };
+
+    // play next/previous song from queue. Music that was being tried to play is left as is as
+    // moving away from it is not the failure to play it
+    let change_track = |direction: HeadTo| {
+        let mut state = state_original.lock().unwrap();
+        state.playback_attempt = None;
+        match direction {
+            HeadTo::Next => state.player.play_next(),
+            HeadTo::Prev => state.player.play_prev(),
+            HeadTo::Initial => unreachable!(),
+        }
+    };
+
+    // navigating page is just changing to fetched_page value to next/prev value
+    // or changing the prev/next track
+    let handle_nav = |direction: HeadTo| {
+        let mut state = state_original.lock().unwrap();
+        let target_index: usize;
+        match state.active {
+            ui::Window::Musicbar => target_index = MIDDLE_MUSIC_INDEX,
+            ui::Window::Playlistbar => target_index = MIDDLE_PLAYLIST_INDEX,
+            ui::Window::Artistbar => target_index = MIDDLE_ARTIST_INDEX,
+            ui::Window::BottomControl => {
+                // On reciving next/prev event when active window is bottom control
+                // It implied to change the track
+                return drop_and_call!(state, change_track, direction);
+            }
+            ui::Window::Searchbar | ui::Window::Sidebar | ui::Window::Popup(..) => {
+                // If none of above windows are active then nothing to navigate.
+                // Early return instead of initilizing `target_index`
+                return;
+            }
+            ui::Window::None => unreachable!(),
+        }
+        let page = get_page(&state.fetched_page[target_index], direction);
+        state.fetched_page[target_index] = Some(page);
+        notifier.notify_all();
+    };
+
+    // Live stream cannot be seeked. Only status is updated for those
+    let seek_forward = || {
+        let mut state = state_original.lock().unwrap();
+        if state.playing_live() {
+            state.warn("Live, no seek..");
+        } else {
+            state
+                .player
+                .seek_forward(CONFIG.constants.seek_forward_secs as f64)
+                .ok();
+        }
+        notifier.notify_all();
+    };
+
+    let seek_backward = || {
+        let mut state = state_original.lock().unwrap();
+        if state.playing_live() {
+            state.warn("Live, no seek..");
+        } else {
+            state
+                .player
+                .seek_backward(CONFIG.constants.seek_backward_secs as f64)
+                .ok();
+        }
+        notifier.notify_all();
+    };
+
+    // Left/Right seek instead of moving between windows while bottom bar is focused
+    let is_controls_focused = || state_original.lock().unwrap().active == ui::Window::BottomControl;
+
+    let handle_repeat = || {
+        let mut state = state_original.lock().unwrap();
+        let repeat = state.playback_behaviour.repeat;
+        state.set_repeat(!repeat);
+        notifier.notify_all();
+    };
+
+    let toggle_shuffle = || {
+        let mut state = state_original.lock().unwrap();
+        if state.playback_behaviour.shuffle {
+            state.player.unshuffle();
+        } else {
+            state.player.shuffle();
+        }
+        state.playback_behaviour.shuffle = !state.playback_behaviour.shuffle;
+        notifier.notify_all();
+    };
+
+    let toggle_play = || {
+        state_original.lock().unwrap().toggle_pause();
+        notifier.notify_all();
+    };
+
+    let handle_download = || async {
+        let mut state = state_original.lock().unwrap();
+
+        // TODO: Ask for conformation before downloading
+        // Every marked music/playlist is downloaded when there are marks in focused list
+        let download_ids = if state.active == ui::Window::Musicbar && !state.marked.music.is_empty()
+        {
+            std::mem::take(&mut state.marked.music)
+                .into_iter()
+                .map(|music| (music.id, false))
+                .collect()
+        } else if state.active == ui::Window::Playlistbar && !state.marked.playlist.is_empty() {
+            std::mem::take(&mut state.marked.playlist)
+                .into_iter()
+                .map(|playlist| (playlist.id, true))
+                .collect()
+        } else if let Some(focused_index) = state.musicbar.1.selected() {
+            vec![(state.musicbar.0[focused_index].id.clone(), false)]
+        } else if let Some(focused_index) = state.playlistbar.1.selected() {
+            vec![(state.playlistbar.0[focused_index].id.clone(), true)]
+        } else {
+            return;
+        };
+
+        // Local music is already in the disk. Nothing to download
+        let download_urls = download_ids
+            .iter()
+            .filter(|(id, _)| !fetcher::Fetcher::is_local_id(id))
+            .map(|(id, is_playlist)| {
+                if *is_playlist {
+                    fetcher::playlist_url(id)
+                } else {
+                    fetcher::music_url(id)
+                }
+            })
+            .collect::<Vec<String>>();
+        if download_urls.is_empty() {
+            state.warn("Already local..");
+            notifier.notify_all();
+            return;
+        }
+
+        state.info("Download started..");
+        state.toast(match download_urls.as_slice() {
+            [download_url] => format!("Downloading: {}", download_url),
+            _ => format!("Downloading {} items", download_urls.len()),
+        });
+        let message = match download_urls.as_slice() {
+            [download_url] => format!(
                 "Download of {} have an eye on your Music folder",
                 download_url
             ),
-        );
-        command.arg(download_url);
+            _ => format!(
+                "Download of {} items have an eye on your Music folder",
+                download_urls.len()
+            ),
+        };
+        state.active = ui::Window::Popup("Downloading...", message);
+        std::mem::drop(state);
 
-        command
-            .stdin(std::process::Stdio::null())
-            .stdout(std::process::Stdio::null())
-            .stderr(std::process::Stdio::null())
-            .args(&["--extract-audio", "--audio-format", &CONFIG.download.format])
-            .current_dir(&CONFIG.download.path)
-            .kill_on_drop(false);
+        for download_url in download_urls {
+            let mut command = tokio::process::Command::new("youtube-dl");
+            command.arg(download_url);
+
+            command
+                .stdin(std::process::Stdio::null())
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .args(["--extract-audio", "--audio-format", &CONFIG.download.format])
+                .args(match CONFIG.constants.proxy.trim() {
+                    "" => vec![],
+                    proxy => vec!["--proxy", proxy],
+                })
+                .current_dir(&CONFIG.download.path)
+                .kill_on_drop(false);
+
+            *download_counter.lock().unwrap() += 1;
+            let counter_clone = Arc::clone(&download_counter);
+
+            // Wait for 5 second just to make sure that command has finished executing.
+            // It usually donot take all those 5 seconds
+            // Anyway, download won't finish before 5 seconds
+            // Then just wait for command to finish by waiting for exit status
+            // decrease the download queue count
+            tokio::task::spawn(async move {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                command.status().await.unwrap();
+                *counter_clone.lock().unwrap() -= 1;
+            });
+        }
+    };
+
+    // If play is true it means also play the playlist
+    // if is false then only expand the playlist and show url but do not play it
+    let select_playlist = |play: bool| {
+        let mut state = state_original.lock().unwrap();
+        if let Some(selected_index) = state.playlistbar.1.selected() {
+            let playlist = &state.playlistbar.0[selected_index];
+            let (playlist_id, name) = (playlist.id.clone(), playlist.name.clone());
+            state.source_names.insert(playlist_id.clone(), name);
+            if play {
+                state.activate_playlist(&playlist_id);
+            } else {
+                let message = format!("Playlist url: {}", fetcher::playlist_url(&playlist_id));
+                state.active = ui::Window::Popup("Info!", message);
+            }
+            state.filled_source.0 = ui::MusicbarSource::Playlist(playlist_id);
+            drop_and_call!(state, fill_music_from_playlist, HeadTo::Initial);
+        }
+    };
+
+    let select_music = |play: bool| {
+        let mut state = state_original.lock().unwrap();
+        if let Some(selected_index) = state.musicbar.1.selected() {
+            let music_id = &state.musicbar.0[selected_index].id;
+            if play {
+                let music_id = music_id.clone();
+                state.play_music(&music_id);
+            } else {
+                let message = format!("Music url: {}", fetcher::music_url(music_id));
+                state.active = ui::Window::Popup("Info!", message);
+                notifier.notify_all();
+            }
+        }
+    };
+
+    let toggle_mute = || {
+        let mut state = state_original.lock().unwrap();
+
+        let muted = !state.playback_behaviour.muted;
+        match state.player.set_property("mute", muted) {
+            Ok(_) => state.playback_behaviour.muted = muted,
+            Err(_) => state.error("Mute error.."),
+        }
+
+        notifier.notify_all();
+    };
+
+    let handle_view = || {
+        let state = state_original.lock().unwrap();
+        match state.active {
+            ui::Window::Playlistbar => {
+                drop_and_call!(state, select_playlist, false);
+            }
+            ui::Window::Musicbar => {
+                drop_and_call!(state, select_music, false);
+            }
+            ui::Window::Artistbar => {}
+            _ => {}
+        }
+    };
+
+    let handle_enter = || {
+        let mut state = state_original.lock().unwrap();
+        let active_window = state.active.clone();
+        match active_window {
+            ui::Window::Sidebar => {
+                let side_select =
+                    ui::SidebarOption::try_from(state.sidebar.selected().unwrap()).unwrap();
+
+                match side_select {
+                    ui::SidebarOption::Trending => {
+                        // Selecting trending again while it is already shown moves to next category
+                        if let ui::MusicbarSource::Trending(..) = state.filled_source.0 {
+                            state.trending_category = state.trending_category.next();
+                        }
+                        drop_and_call!(state, fill_trending_music, HeadTo::Initial);
+                    }
+                    ui::SidebarOption::Charts => {
+                        // Selecting charts again while it is already shown switches between
+                        // global charts and charts of region in config file
+                        if let ui::MusicbarSource::Charts(..) = state.filled_source.0 {
+                            state.charts_country =
+                                if state.charts_country == fetcher::innertube::GLOBAL_CHARTS {
+                                    CONFIG.constants.region.to_uppercase()
+                                } else {
+                                    fetcher::innertube::GLOBAL_CHARTS.to_string()
+                                };
+                        }
+                        drop_and_call!(state, fill_charts);
+                    }
+                    ui::SidebarOption::Moods => drop_and_call!(state, show_moods),
+                    ui::SidebarOption::YoutubeCommunity => {
+                        drop_and_call!(state, fill_community_source);
+                    }
+                    ui::SidebarOption::Liked => {
+                        drop_and_call!(state, fill_fav_music, HeadTo::Initial);
+                    }
+                    ui::SidebarOption::Saved => {
+                        drop_and_call!(state, fill_fav_playlist, HeadTo::Initial);
+                    }
+                    ui::SidebarOption::Following => {
+                        drop_and_call!(state, fill_fav_artist, HeadTo::Initial);
+                    }
+                    ui::SidebarOption::Subscriptions => {
+                        drop_and_call!(state, fill_subscriptions);
+                    }
+                    ui::SidebarOption::NewReleases => {
+                        drop_and_call!(state, fill_new_releases);
+                    }
+                    ui::SidebarOption::Local => {
+                        drop_and_call!(state, fill_local_source);
+                    }
+                    ui::SidebarOption::History => drop_and_call!(state, fill_history),
+                    ui::SidebarOption::Search => drop_and_call!(state, activate_search),
+                }
+            }
+            ui::Window::Searchbar => {
+                drop_and_call!(state, start_search);
+            }
+
+            // On enter play the music
+            ui::Window::Musicbar => drop_and_call!(state, select_music, true),
+
+            // On enter selection view the playlist content as well as play it
+            ui::Window::Playlistbar => drop_and_call!(state, select_playlist, true),
+
+            ui::Window::Artistbar => {
+                if let Some(selected_index) = state.artistbar.1.selected() {
+                    let artist = &state.artistbar.0[selected_index];
+                    let (artist_id, name) = (artist.id.clone(), artist.name.clone());
+                    state.source_names.insert(artist_id.clone(), name);
+                    state.filled_source.0 = ui::MusicbarSource::Artist(artist_id.clone());
+                    state.filled_source.1 = ui::PlaylistbarSource::Artist(artist_id);
+                    std::mem::drop(state);
+                    fill_music_from_artist(HeadTo::Initial);
+                    fill_playlist_from_artist(HeadTo::Initial);
+                }
+            }
+            ui::Window::None | ui::Window::BottomControl | ui::Window::Popup(..) => {}
+        }
+    };
+
+    let toggle_subscription = || {
+        let mut state = state_original.lock().unwrap();
+        if state.active != ui::Window::Artistbar {
+            return;
+        }
+        match state.artistbar.1.selected() {
+            Some(selected_index) => {
+                let artist = state.artistbar.0[selected_index].clone();
+                state.toggle_subscription(&artist);
+            }
+            None => state.warn("Nothing selected.."),
+        }
+        notifier.notify_all();
+    };
+
+    let handle_favourates = |add: bool| {
+        let mut state = state_original.lock().unwrap();
+
+        state.info("Processing..");
+
+        // Every marked row of focused list is added/removed at once
+        match state.active {
+            ui::Window::Musicbar if !state.marked.music.is_empty() => {
+                let marked = std::mem::take(&mut state.marked.music);
+                let count = marked.len();
+                for music in marked {
+                    if add {
+                        state.add_music_to_favourates(&music);
+                    } else {
+                        state.remove_music_from_favourates(&music);
+                    }
+                }
+                state.toast(favourites_toast(add, count));
+                notifier.notify_all();
+                return;
+            }
+            ui::Window::Playlistbar if !state.marked.playlist.is_empty() => {
+                let marked = std::mem::take(&mut state.marked.playlist);
+                let count = marked.len();
+                for playlist in marked {
+                    if add {
+                        state.add_playlist_to_favourates(&playlist);
+                    } else {
+                        state.remove_playlist_from_favourates(&playlist);
+                    }
+                }
+                state.toast(favourites_toast(add, count));
+                notifier.notify_all();
+                return;
+            }
+            ui::Window::Artistbar if !state.marked.artist.is_empty() => {
+                let marked = std::mem::take(&mut state.marked.artist);
+                let count = marked.len();
+                for artist in marked {
+                    if add {
+                        state.add_artist_to_favourates(&artist);
+                    } else {
+                        state.remove_artist_from_favourates(&artist);
+                    }
+                }
+                state.toast(favourites_toast(add, count));
+                notifier.notify_all();
+                return;
+            }
+            _ => {}
+        }
+
+        match state.active {
+            ui::Window::Musicbar => {
+                if let Some(selected_index) = state.musicbar.1.selected() {
+                    // Why clone? Used unsafe to bypass borrow as immutable and mutable at same time error.
+                    // yeah. I am sure this is fine (up until now).
+                    let selected_music =
+                        &state.musicbar.0[selected_index] as *const fetcher::MusicUnit;
+                    if add {
+                        state.add_music_to_favourates(unsafe { &*selected_music });
+                    } else {
+                        state.remove_music_from_favourates(unsafe { &*selected_music });
+                    }
+                } else {
+                    state.warn("Nothing selected..");
+                }
+            }
+
+            ui::Window::Playlistbar => {
+                if let Some(selected_index) = state.playlistbar.1.selected() {
+                    let selected_playlist =
+                        &state.playlistbar.0[selected_index] as *const fetcher::PlaylistUnit;
+                    if add {
+                        state.add_playlist_to_favourates(unsafe { &*selected_playlist });
+                    } else {
+                        state.remove_playlist_from_favourates(unsafe { &*selected_playlist });
+                    }
+                } else {
+                    state.warn("Nothing selected..");
+                }
+            }
+
+            ui::Window::Artistbar => {
+                if let Some(selected_index) = state.artistbar.1.selected() {
+                    let selected_artist =
+                        &state.artistbar.0[selected_index] as *const fetcher::ArtistUnit;
+                    if add {
+                        state.add_artist_to_favourates(unsafe { &*selected_artist });
+                    } else {
+                        state.remove_artist_from_favourates(unsafe { &*selected_artist });
+                    }
+                } else {
+                    state.warn("Nothing selected..");
+                }
+            }
+            _ => {}
+        }
+
+        notifier.notify_all();
+    };
+
+    // Add focused (or marked) rows to favourites or remove them if every one of them already is
+    let toggle_favourite = || {
+        let mut state = state_original.lock().unwrap();
+        let ids = state.focused_ids();
+        if ids.is_empty() {
+            state.warn("Nothing selected..");
+            notifier.notify_all();
+            return;
+        }
+        let add = !ids.iter().all(|id| state.favourites.contains(*id));
+        drop_and_call!(state, handle_favourates, add);
+    };
+
+    // Focus the window under the mouse and select the row under it if any. Returns true if the
+    // row was selected. Nothing is selected from the lists drawn over windows (like queue)
+    let select_at = |column: u16, line: u16| -> bool {
+        let mut state = state_original.lock().unwrap();
+        let position = screen_position(&state);
+        let window = match position.window_at(column, line) {
+            Some(window) => window,
+            None => return false,
+        };
+        let is_covered = match window {
+            ui::Window::Sidebar => state.help.is_some(),
+            ui::Window::Musicbar => {
+                state.help.is_some()
+                    || state.queue.is_some()
+                    || state.chapters.popup.is_some()
+                    || state.moods.popup.is_some()
+                    || state.cast.popup.is_some()
+                    || state.outputs.popup.is_some()
+            }
+            ui::Window::Playlistbar => state.lyrics.shown || state.comments.shown,
+            ui::Window::Artistbar => {
+                state.help.is_some() || state.lyrics.shown || state.comments.shown
+            }
+            _ => false,
+        };
+        if is_covered {
+            return false;
+        }
+
+        let row = match window {
+            ui::Window::Sidebar => {
+                ui::utils::row_at(&position.shortcut, line, 0, state.sidebar_offset)
+                    .filter(|&row| row < ui::utils::SIDEBAR_LIST_COUNT)
+            }
+            ui::Window::Musicbar => ui::utils::row_at(
+                &position.music,
+                line,
+                1,
+                state.list_offset[MIDDLE_MUSIC_INDEX],
+            )
+            .filter(|&row| row < state.musicbar.0.len()),
+            ui::Window::Playlistbar => ui::utils::row_at(
+                &position.playlist,
+                line,
+                1,
+                state.list_offset[MIDDLE_PLAYLIST_INDEX],
+            )
+            .filter(|&row| row < state.playlistbar.0.len()),
+            ui::Window::Artistbar => ui::utils::row_at(
+                &position.artist,
+                line,
+                1,
+                state.list_offset[MIDDLE_ARTIST_INDEX],
+            )
+            .filter(|&row| row < state.artistbar.0.len()),
+            _ => None,
+        };
+        match window {
+            // Sidebar always have something selected
+            ui::Window::Sidebar if row.is_some() => state.sidebar.select(row),
+            ui::Window::Musicbar => state.musicbar.1.select(row),
+            ui::Window::Playlistbar => state.playlistbar.1.select(row),
+            ui::Window::Artistbar => state.artistbar.1.select(row),
+            _ => {}
+        }
+        state.active = window;
+        notifier.notify_all();
+        row.is_some()
+    };
+
+    // Scroll wheel scrolls whatever is shown over the windows first. Otherwise window under the
+    // mouse is focused and selection in it's list is moved. Scrolling past the last (or first)
+    // row of music, playlist or artist list moves to next (or previous) page
+    let scroll_at = |column: u16, line: u16, direction: HeadTo| {
+        if scroll_help(direction.clone())
+            || advance_queue(direction.clone())
+            || advance_chapter_list(direction.clone())
+            || advance_mood_list(direction.clone())
+            || advance_cast_list(direction.clone())
+            || advance_output_list(direction.clone())
+        {
+            return;
+        }
+
+        let mut state = state_original.lock().unwrap();
+        let window = match screen_position(&state).window_at(column, line) {
+            Some(window) => window,
+            None => return,
+        };
+        if matches!(window, ui::Window::Playlistbar | ui::Window::Artistbar) {
+            if state.comments.shown {
+                drop_and_call!(state, scroll_comments, direction);
+                return;
+            } else if state.lyrics.shown {
+                return;
+            }
+        }
+        let (len, selected, page) = match window {
+            ui::Window::Sidebar => (
+                ui::utils::SIDEBAR_LIST_COUNT,
+                state.sidebar.selected(),
+                None,
+            ),
+            ui::Window::Musicbar => (
+                state.musicbar.0.len(),
+                state.musicbar.1.selected(),
+                state.fetched_page[MIDDLE_MUSIC_INDEX],
+            ),
+            ui::Window::Playlistbar => (
+                state.playlistbar.0.len(),
+                state.playlistbar.1.selected(),
+                state.fetched_page[MIDDLE_PLAYLIST_INDEX],
+            ),
+            ui::Window::Artistbar => (
+                state.artistbar.0.len(),
+                state.artistbar.1.selected(),
+                state.fetched_page[MIDDLE_ARTIST_INDEX],
+            ),
+            _ => return,
+        };
+        state.active = window;
+        notifier.notify_all();
+
+        let (at_edge, has_page) = match (direction.clone(), selected) {
+            (HeadTo::Next, Some(selected)) => (selected + 1 >= len, page.is_some()),
+            (HeadTo::Prev, Some(selected)) => (selected == 0, page.unwrap_or_default() > 0),
+            _ => (false, false),
+        };
+        if !at_edge {
+            drop_and_call!(state, handle_up_down, direction);
+        } else if has_page {
+            drop_and_call!(state, handle_nav, direction);
+        }
+    };
+
+    // Move the selection of list shown over music section if any, otherwise of active list
+    let jump_in_list = |jump: JumpTo| {
+        let mut state = state_original.lock().unwrap();
+        let position = screen_position(&state);
+        // 2 lines for border and 1 for header
+        let list_height = |area: Rect| area.height.saturating_sub(3) as usize;
+        let popup_height = position.music.height.saturating_sub(2) as usize;
+
+        let queue_len = state.player.queue().0.len();
+        let chapter_count = state.chapters.list.len();
+        let mood_count = state.moods.list.len();
+        let device_count = state.cast.devices.len() + 1;
+        let output_count = state.outputs.devices.len();
+        let popup = if let Some(list_state) = state.queue.as_mut() {
+            Some((list_state, queue_len))
+        } else if let Some(list_state) = state.chapters.popup.as_mut() {
+            Some((list_state, chapter_count))
+        } else if let Some(list_state) = state.cast.popup.as_mut() {
+            Some((list_state, device_count))
+        } else if let Some(list_state) = state.outputs.popup.as_mut() {
+            Some((list_state, output_count))
+        } else {
+            state
+                .moods
+                .popup
+                .as_mut()
+                .map(|list_state| (list_state, mood_count))
+        };
+        if let Some((list_state, len)) = popup {
+            let current = list_state.selected().unwrap_or_default();
+            list_state.select(Some(jump_index(current, len, popup_height, jump)));
+            notifier.notify_all();
+            return;
+        }
+
+        match state.active {
+            ui::Window::Sidebar => {
+                let current = state.sidebar.selected().unwrap_or_default();
+                let visible = position.shortcut.height.saturating_sub(2) as usize;
+                state.sidebar.select(Some(jump_index(
+                    current,
+                    ui::utils::SIDEBAR_LIST_COUNT,
+                    visible,
+                    jump,
+                )));
+            }
+            ui::Window::Musicbar if !state.musicbar.0.is_empty() => {
+                let current = state.musicbar.1.selected().unwrap_or_default();
+                let len = state.musicbar.0.len();
+                let visible = list_height(position.music);
+                state
+                    .musicbar
+                    .1
+                    .select(Some(jump_index(current, len, visible, jump)));
+            }
+            ui::Window::Playlistbar if !state.playlistbar.0.is_empty() => {
+                let current = state.playlistbar.1.selected().unwrap_or_default();
+                let len = state.playlistbar.0.len();
+                let visible = list_height(position.playlist);
+                state
+                    .playlistbar
+                    .1
+                    .select(Some(jump_index(current, len, visible, jump)));
+            }
+            ui::Window::Artistbar if !state.artistbar.0.is_empty() => {
+                let current = state.artistbar.1.selected().unwrap_or_default();
+                let len = state.artistbar.0.len();
+                let visible = list_height(position.artist);
+                state
+                    .artistbar
+                    .1
+                    .select(Some(jump_index(current, len, visible, jump)));
+            }
+            _ => return,
+        }
+        notifier.notify_all();
+    };
+
+    // Jump to next entry starting with the letter in focused list. Returns false if no list is
+    // focused so that caller can handle the key as usual
+    let jump_to_letter = |letter: char| -> bool {
+        let mut state = state_original.lock().unwrap();
+        if !letter.is_alphanumeric()
+            || state.queue.is_some()
+            || state.chapters.popup.is_some()
+            || state.moods.popup.is_some()
+            || state.cast.popup.is_some()
+            || state.outputs.popup.is_some()
+            || state.help.is_some()
+            || state.actions.is_some()
+        {
+            return false;
+        }
+        let jump = |names: Vec<&str>, current: Option<usize>| {
+            index_starting_with(names.into_iter(), current.unwrap_or_default(), letter)
+        };
+        let index = match state.active {
+            ui::Window::Sidebar => jump(
+                ui::utils::SIDEBAR_LIST_ITEMS.to_vec(),
+                state.sidebar.selected(),
+            ),
+            ui::Window::Musicbar => jump(
+                state
+                    .musicbar
+                    .0
+                    .iter()
+                    .map(|music| music.name.as_str())
+                    .collect(),
+                state.musicbar.1.selected(),
+            ),
+            ui::Window::Playlistbar => jump(
+                state
+                    .playlistbar
+                    .0
+                    .iter()
+                    .map(|playlist| playlist.name.as_str())
+                    .collect(),
+                state.playlistbar.1.selected(),
+            ),
+            ui::Window::Artistbar => jump(
+                state
+                    .artistbar
+                    .0
+                    .iter()
+                    .map(|artist| artist.name.as_str())
+                    .collect(),
+                state.artistbar.1.selected(),
+            ),
+            _ => return false,
+        };
+        match (index, &state.active) {
+            (None, _) => state.warn("No entry starts with that letter.."),
+            (Some(index), ui::Window::Sidebar) => state.sidebar.select(Some(index)),
+            (Some(index), ui::Window::Musicbar) => state.musicbar.1.select(Some(index)),
+            (Some(index), ui::Window::Playlistbar) => state.playlistbar.1.select(Some(index)),
+            (Some(index), _) => state.artistbar.1.select(Some(index)),
+        }
+        notifier.notify_all();
+        true
+    };
+
+    // Mark/unmark the focused row and move to the next one so that rows can be marked one after
+    // another. See ui::MarkState
+    let toggle_mark = || {
+        let mut state = state_original.lock().unwrap();
+        if !state.toggle_mark() {
+            state.warn("Nothing to mark..");
+            notifier.notify_all();
+            return;
+        }
+        drop_and_call!(state, handle_up_down, HeadTo::Next);
+    };
+
+    // Returns false if nothing is marked in focused list so that caller can handle the key as usual
+    let clear_marks = || -> bool {
+        let mut state = state_original.lock().unwrap();
+        if !state.clear_marks() {
+            return false;
+        }
+        state.info("Unmarked..");
+        notifier.notify_all();
+        true
+    };
+
+    // Append the marked (or focused) music to the play queue. Music is started if nothing is
+    // being played
+    let enqueue = || {
+        let mut state = state_original.lock().unwrap();
+        let music = marked_or_focused_music(&mut state);
+        if music.is_empty() {
+            state.warn("Nothing to queue..");
+        } else {
+            for music in &music {
+                let url = fetcher::music_url(&music.id);
+                state
+                    .player
+                    .command("loadfile", [url.as_str(), "append-play"].as_ref())
+                    .ok();
+            }
+            state.info("Added to queue..");
+            state.toast(match music.as_slice() {
+                [music] => format!("Added to queue: {}", music.name),
+                _ => format!("Added {} to queue", music.len()),
+            });
+        }
+        notifier.notify_all();
+    };
+
+    let add_to_local_playlist = || {
+        let mut state = state_original.lock().unwrap();
+        let music = marked_or_focused_music(&mut state);
+        if music.is_empty() {
+            state.warn("Nothing to add..");
+        } else {
+            match append_to_local_playlist(&music) {
+                Ok(()) => state.info("Added to local playlist.."),
+                Err(_) => state.error("Cannot write playlist.."),
+            }
+        }
+        notifier.notify_all();
+    };
+
+    // Make the sidebar (or music section) bigger or smaller. New size is saved in config so that
+    // it is used again on next start
+    let resize_pane = |is_sidebar: bool, grow: bool| {
+        let mut state = state_original.lock().unwrap();
+        let (current, range, key) = if is_sidebar {
+            (
+                state.panes.sidebar,
+                ui::utils::SIDEBAR_WIDTH_RANGE,
+                "sidebar_width",
+            )
+        } else {
+            (
+                state.panes.music,
+                ui::utils::MUSIC_HEIGHT_RANGE,
+                "music_height",
+            )
+        };
+        let resized = if grow {
+            (current + PANE_RESIZE_STEP).min(*range.end())
+        } else {
+            current.saturating_sub(PANE_RESIZE_STEP).max(*range.start())
+        };
+
+        if resized == current {
+            state.warn("Cannot resize this section any further..");
+        } else {
+            if is_sidebar {
+                state.panes.sidebar = resized;
+            } else {
+                state.panes.music = resized;
+            }
+            // Resized section should be seen
+            state.panes.maximized = None;
+            if let Err(err) = config::ConfigContainer::save_constant(key, resized) {
+                state.warn(format!(
+                    "Resized but cannot save the size in config. {}..",
+                    err
+                ));
+            }
+        }
+        notifier.notify_all();
+    };
+
+    // Maximize the focused section or bring back every section if some is already maximized
+    let toggle_maximize = || {
+        let mut state = state_original.lock().unwrap();
+        if state.panes.maximized.is_some() {
+            state.panes.maximized = None;
+        } else if state.active.is_pane() {
+            state.panes.maximized = Some(state.active.clone());
+        } else {
+            state.warn("Move to sidebar, music, playlist or artist section to maximize it..");
+        }
+        notifier.notify_all();
+    };
 
-        std::mem::drop(state);
+    let toggle_hints = || {
+        let mut state = state_original.lock().unwrap();
+        state.panes.hints = !state.panes.hints;
+        notifier.notify_all();
+    };
 
-        *download_counter.lock().unwrap() += 1;
-        let counter_clone = Arc::clone(&download_counter);
-
-        // Wait for 5 second just to make sure that command has finished executing.
-        // It usually donot take all those 5 seconds
-        // Anyway, download won't finish before 5 seconds
-        // Then just wait for command to finish by waiting for exit status
-        // decrease the download queue count
-        tokio::task::spawn(async move {
-            tokio::time::sleep(Duration::from_secs(5)).await;
-            command.status().await.unwrap();
-            *counter_clone.lock().unwrap() -= 1;
-        });
+    // Switch high contrast mode. Whole screen is drawn with it from next draw
+    let toggle_contrast = || {
+        let mut state = state_original.lock().unwrap();
+        let on = !ui::utils::high_contrast();
+        ui::utils::set_high_contrast(on);
+        state.info(format!("High contrast {}..", if on { "on" } else { "off" }));
+        notifier.notify_all();
     };
 
-    // If play is true it means also play the playlist
-    // if is false then only expand the playlist and show url but do not play it
-    let select_playlist = |play: bool| {
+    // Switch to next layout preset. Search layout starts with searchbar focused and mini player
+    // starts with the queue shown
+    let set_layout = |preset: ui::LayoutPreset| {
         let mut state = state_original.lock().unwrap();
-        if let Some(selected_index) = state.playlistbar.1.selected() {
-            let playlist_id = state.playlistbar.0[selected_index].id.clone();
-            if play {
-                state.activate_playlist(&playlist_id);
-            } else {
-                let message = format!(
-                    "Playlist url: https://youtu.be/playlist?list={}",
-                    playlist_id
-                );
-                state.active = ui::Window::Popup("Info!", message);
+        state.panes.preset = preset;
+        state.panes.maximized = None;
+        match preset {
+            ui::LayoutPreset::Search => state.active = ui::Window::Searchbar,
+            ui::LayoutPreset::Mini if state.queue.is_none() => {
+                let (entries, current) = state.player.queue();
+                if !entries.is_empty() {
+                    let mut list_state = tui::widgets::ListState::default();
+                    list_state.select(Some(current.unwrap_or_default()));
+                    state.queue = Some(list_state);
+                }
             }
-            state.filled_source.0 = ui::MusicbarSource::Playlist(playlist_id);
-            drop_and_call!(state, fill_music_from_playlist, HeadTo::Initial);
+            _ => {}
+        }
+        if !state.panes.is_shown(&state.active) {
+            state.active = ui::Window::Musicbar;
         }
+        state.info(format!("{} layout", preset.name()));
+        notifier.notify_all();
     };
 
-    let select_music = |play: bool| {
+    let switch_layout = || {
+        let preset = state_original.lock().unwrap().panes.preset.next();
+        set_layout(preset);
+    };
+
+    // Open the command line. See ui::command
+    let open_command = || {
         let mut state = state_original.lock().unwrap();
-        if let Some(selected_index) = state.musicbar.1.selected() {
-            let music_id = &state.musicbar.0[selected_index].id;
-            if play {
-                let music_id = music_id.clone();
-                state.play_music(&music_id);
-            } else {
-                let message = format!("Music url: https://youtu.be/{}", music_id);
-                state.active = ui::Window::Popup("Info!", message);
-                notifier.notify_all();
-            }
+        state.command = Some(String::new());
+        notifier.notify_all();
+    };
+
+    // Type into command line. Returns false if command line is not open
+    let command_input = |ch: char| -> bool {
+        let mut state = state_original.lock().unwrap();
+        match state.command.as_mut() {
+            Some(line) => line.push(ch),
+            None => return false,
         }
+        notifier.notify_all();
+        true
     };
 
-    let change_volume = |direction: HeadTo| {
+    // Backspace on empty command line closes it
+    let command_backspace = || -> bool {
         let mut state = state_original.lock().unwrap();
+        let is_empty = match state.command.as_mut() {
+            Some(line) => line.pop().is_none(),
+            None => return false,
+        };
+        if is_empty {
+            state.command = None;
+        }
+        notifier.notify_all();
+        true
+    };
 
-        let increase_by = match direction {
-            HeadTo::Next => CONFIG.constants.volume_step,
-            HeadTo::Prev => CONFIG.constants.volume_step * -1,
-            HeadTo::Initial => 0
+    // Complete the command or its argument being typed as much as possible
+    let complete_command = || -> bool {
+        let mut state = state_original.lock().unwrap();
+        let completed = match &state.command {
+            Some(line) => ui::command::complete(line),
+            None => return false,
         };
+        if completed.is_some() {
+            state.command = completed;
+            notifier.notify_all();
+        }
+        true
+    };
 
-        let res = state.player.change_volume(increase_by);
+    let close_command = || -> bool {
+        let mut state = state_original.lock().unwrap();
+        let was_open = state.command.take().is_some();
+        notifier.notify_all();
+        was_open
+    };
 
-        match res {
-            Some(vol) => {
-                state.playback_behaviour.volume = vol;
+    let is_command_open = || state_original.lock().unwrap().command.is_some();
+
+    // Close the command line and return the command that was typed in it
+    let take_command = || -> Option<ui::command::Command> {
+        let mut state = state_original.lock().unwrap();
+        let line = state.command.take().unwrap_or_default();
+        notifier.notify_all();
+        if line.trim().is_empty() {
+            return None;
+        }
+        match ui::command::parse(&line) {
+            Ok(command) => Some(command),
+            Err(message) => {
+                state.error(message);
+                None
             }
-            None => {
-                state.status = "Volume error..";
+        }
+    };
+
+    // Run the command taken from command line. Download is run by the caller as it is async
+    let run_command = |command: ui::command::Command| {
+        use ui::command::Command;
+        let mut state = state_original.lock().unwrap();
+        // Open, region and charts are the same as typing them in searchbar
+        let query = match command {
+            Command::Open(target) => Some(format!("{} {}", OPEN_COMMAND_PREFIX, target)),
+            Command::Region(region) => Some(format!("{}{}", REGION_COMMAND_PREFIX, region)),
+            Command::Charts(country) => Some(format!("{}{}", CHARTS_COMMAND_PREFIX, country)),
+            Command::Volume(volume) => {
+                match state.player.set_property("volume", volume as f64) {
+                    Ok(_) => state.playback_behaviour.volume = volume,
+                    Err(_) => state.error("Volume error.."),
+                }
+                None
+            }
+            Command::Seek(_, _) if state.playing_live() => {
+                state.warn("Live, no seek..");
+                None
+            }
+            Command::Seek(secs, is_relative) => {
+                let result = match (secs, is_relative) {
+                    (secs, false) => state.player.seek_absolute(secs as f64),
+                    (secs, true) if secs < 0 => state.player.seek_backward(-secs as f64),
+                    (secs, true) => state.player.seek_forward(secs as f64),
+                };
+                if result.is_err() {
+                    state.error("Seek error..");
+                }
+                None
+            }
+            Command::Theme(name) => {
+                let mut theme = match name.as_str() {
+                    "dark" => config::Theme::default(),
+                    "light" => config::Theme::light(),
+                    _ => CONFIG.theme.clone(),
+                };
+                // Only colours are switched. Border and row styles are kept as in config file
+                theme.border_highlight_type = CONFIG.theme.border_highlight_type.clone();
+                theme.list_hilight_bold = CONFIG.theme.list_hilight_bold;
+                theme.list_hilight_reversed = CONFIG.theme.list_hilight_reversed;
+                theme.list_hilight_background = CONFIG.theme.list_hilight_background;
+                ui::utils::set_theme(theme);
+                state.info(format!("Theme {}..", name));
+                None
             }
+            Command::Layout(preset) => {
+                drop_and_call!(state, set_layout, preset);
+                return;
+            }
+            Command::Cast(true) => {
+                state.cast.requested = true;
+                state.info("Finding devices..");
+                None
+            }
+            Command::Cast(false) => {
+                state.cast.target = None;
+                None
+            }
+            // Device being played to is selected initially
+            Command::Output => {
+                state.outputs.devices = state.player.audio_devices();
+                if state.outputs.devices.is_empty() {
+                    state.warn("No audio device..");
+                } else {
+                    let current = state
+                        .player
+                        .get_property::<String>("audio-device")
+                        .unwrap_or_default();
+                    let selected = state
+                        .outputs
+                        .devices
+                        .iter()
+                        .position(|(name, _)| *name == current)
+                        .unwrap_or_default();
+                    let mut list_state = tui::widgets::ListState::default();
+                    list_state.select(Some(selected));
+                    state.outputs.popup = Some(list_state);
+                    state.chapters.popup = None;
+                    state.moods.popup = None;
+                    state.cast.popup = None;
+                    state.queue = None;
+                }
+                None
+            }
+            Command::Notify(on) => {
+                state.track_notifications = on;
+                state.info(format!("Notify {}..", if on { "on" } else { "off" }));
+                None
+            }
+            Command::Download => None,
         };
+        match query {
+            Some(query) => {
+                state.search.0 = query;
+                state.search_cursor = 0;
+                drop_and_call!(state, start_search);
+            }
+            None => notifier.notify_all(),
+        }
+    };
 
+    // Show the menu of actions that can be taken on focused music/playlist/artist
+    let show_actions = || {
+        let mut state = state_original.lock().unwrap();
+        let is_selected = match state.active {
+            ui::Window::Musicbar => state.musicbar.1.selected().is_some(),
+            ui::Window::Playlistbar => state.playlistbar.1.selected().is_some(),
+            ui::Window::Artistbar => state.artistbar.1.selected().is_some(),
+            _ => false,
+        };
+        if is_selected {
+            state.queue = None;
+            state.chapters.popup = None;
+            state.moods.popup = None;
+            state.cast.popup = None;
+            state.outputs.popup = None;
+            let mut list = tui::widgets::ListState::default();
+            list.select(Some(0));
+            state.actions = Some(ui::ActionMenu {
+                actions: ui::ItemAction::of_window(&state.active),
+                list,
+            });
+        } else {
+            state.warn("Nothing selected..");
+        }
         notifier.notify_all();
     };
 
-    let handle_view = || {
-        let state = state_original.lock().unwrap();
-        match state.active {
-            ui::Window::Playlistbar => {
-                drop_and_call!(state, select_playlist, false);
-            }
-            ui::Window::Musicbar => {
-                drop_and_call!(state, select_music, false);
+    // Move selection in action menu. Returns false if action menu is not shown
+    let advance_action_menu = |direction: HeadTo| -> bool {
+        let mut state = state_original.lock().unwrap();
+        match state.actions.as_mut() {
+            Some(menu) => {
+                let current = menu.list.selected().unwrap_or_default();
+                let next = advance_index(current, menu.actions.len(), direction);
+                menu.list.select(Some(next));
+                notifier.notify_all();
+                true
             }
-            ui::Window::Artistbar => {}
-            _ => {}
+            None => false,
         }
     };
 
-    let handle_enter = || {
+    let is_action_menu_shown = || state_original.lock().unwrap().actions.is_some();
+
+    // Close the action menu and return the selected action
+    let take_action = || -> Option<ui::ItemAction> {
         let mut state = state_original.lock().unwrap();
-        let active_window = state.active.clone();
-        match active_window {
-            ui::Window::Sidebar => {
-                let side_select =
-                    ui::SidebarOption::try_from(state.sidebar.selected().unwrap()).unwrap();
+        let menu = state.actions.take()?;
+        notifier.notify_all();
+        menu.list
+            .selected()
+            .and_then(|index| menu.actions.get(index).copied())
+    };
 
-                match side_select {
-                    ui::SidebarOption::Trending => {
-                        drop_and_call!(state, fill_trending_music, HeadTo::Initial);
-                    }
-                    ui::SidebarOption::YoutubeCommunity => {
-                        drop_and_call!(state, fill_community_source);
-                    }
-                    ui::SidebarOption::Liked => {
-                        drop_and_call!(state, fill_fav_music, HeadTo::Initial);
-                    }
-                    ui::SidebarOption::Saved => {
-                        drop_and_call!(state, fill_fav_playlist, HeadTo::Initial);
-                    }
-                    ui::SidebarOption::Following => {
-                        drop_and_call!(state, fill_fav_artist, HeadTo::Initial);
-                    }
-                    ui::SidebarOption::Search => drop_and_call!(state, activate_search),
-                }
-            }
-            ui::Window::Searchbar => {
-                drop_and_call!(state, start_search);
-            }
+    let close_actions = || -> bool {
+        let mut state = state_original.lock().unwrap();
+        let was_shown = state.actions.take().is_some();
+        notifier.notify_all();
+        was_shown
+    };
 
-            // On enter play the music
-            ui::Window::Musicbar => drop_and_call!(state, select_music, true),
+    let show_filter = || {
+        let mut state = state_original.lock().unwrap();
+        if !state.start_filter() {
+            state.warn("Nothing to filter..");
+        }
+        notifier.notify_all();
+    };
 
-            // On enter selection view the playlist content as well as play it
-            ui::Window::Playlistbar => drop_and_call!(state, select_playlist, true),
+    // Type into filter prompt. Returns false if filter prompt is not being typed into so that
+    // caller can handle the key as usual
+    let filter_input = |ch: char| -> bool {
+        let mut state = state_original.lock().unwrap();
+        if !state.filter.typing {
+            return false;
+        }
+        state.filter.query.push(ch);
+        state.apply_filter();
+        notifier.notify_all();
+        true
+    };
 
-            ui::Window::Artistbar => {
-                if let Some(selected_index) = state.artistbar.1.selected() {
-                    let artist_id = state.artistbar.0[selected_index].id.clone();
-                    state.filled_source.0 = ui::MusicbarSource::Artist(artist_id.clone());
-                    state.filled_source.1 = ui::PlaylistbarSource::Artist(artist_id);
-                    std::mem::drop(state);
-                    fill_music_from_artist(HeadTo::Initial);
-                    fill_playlist_from_artist(HeadTo::Initial);
-                }
-            }
-            ui::Window::None | ui::Window::BottomControl | ui::Window::Popup(..) => {}
+    let filter_backspace = || -> bool {
+        let mut state = state_original.lock().unwrap();
+        if !state.filter.typing {
+            return false;
         }
+        state.filter.query.pop();
+        state.apply_filter();
+        notifier.notify_all();
+        true
     };
 
-    let handle_favourates = |add: bool| {
+    // Stop typing and keep the filtered list
+    let accept_filter = || -> bool {
         let mut state = state_original.lock().unwrap();
+        let was_typing = std::mem::take(&mut state.filter.typing);
+        notifier.notify_all();
+        was_typing
+    };
 
-        state.status = "Processing..";
+    // Bring back the whole list. Returns false if nothing is filtered
+    let cancel_filter = || -> bool {
+        let mut state = state_original.lock().unwrap();
+        let was_filtered = state.clear_filter();
+        notifier.notify_all();
+        was_filtered
+    };
 
-        match state.active {
-            ui::Window::Musicbar => {
-                if let Some(selected_index) = state.musicbar.1.selected() {
-                    // Why clone? Used unsafe to bypass borrow as immutable and mutable at same time error.
-                    // yeah. I am sure this is fine (up until now).
-                    let selected_music =
-                        &state.musicbar.0[selected_index] as *const fetcher::MusicUnit;
-                    if add {
-                        state.add_music_to_favourates(unsafe { &*selected_music });
-                    } else {
-                        state.remove_music_from_favourates(unsafe { &*selected_music });
-                    }
-                } else {
-                    state.status = "Nothing selected..";
-                }
-            }
+    // Url of focused item. Url of music being played is used when nothing is focused
+    let focused_url = |state: &ui::State| {
+        let url = match state.active {
+            ui::Window::Musicbar => state
+                .musicbar
+                .1
+                .selected()
+                .map(|index| fetcher::music_url(&state.musicbar.0[index].id)),
+            ui::Window::Playlistbar => state
+                .playlistbar
+                .1
+                .selected()
+                .map(|index| fetcher::playlist_url(&state.playlistbar.0[index].id)),
+            ui::Window::Artistbar => state
+                .artistbar
+                .1
+                .selected()
+                .map(|index| fetcher::artist_url(&state.artistbar.0[index].id)),
+            _ => None,
+        };
+        url.or_else(|| {
+            state
+                .player
+                .get_property::<String>("path")
+                .ok()
+                .filter(|path| !path.is_empty())
+        })
+    };
 
-            ui::Window::Playlistbar => {
-                if let Some(selected_index) = state.playlistbar.1.selected() {
-                    let selected_playlist =
-                        &state.playlistbar.0[selected_index] as *const fetcher::PlaylistUnit;
-                    if add {
-                        state.add_playlist_to_favourates(unsafe { &*selected_playlist });
-                    } else {
-                        state.remove_playlist_from_favourates(unsafe { &*selected_playlist });
-                    }
-                } else {
-                    state.status = "Nothing selected..";
-                }
+    // Copy url of focused item to clipboard
+    let yank_url = || {
+        let mut state = state_original.lock().unwrap();
+        match focused_url(&state) {
+            Some(url) if copy_to_clipboard(&url) => {
+                state.info(format!("Copied {}..", url));
+                state.toast(format!("Copied url: {}", url));
             }
+            Some(_) => state.error("Cannot copy.."),
+            None => state.warn("Nothing selected or playing.."),
+        }
+        notifier.notify_all();
+    };
 
-            ui::Window::Artistbar => {
-                if let Some(selected_index) = state.artistbar.1.selected() {
-                    let selected_artist =
-                        &state.artistbar.0[selected_index] as *const fetcher::ArtistUnit;
-                    if add {
-                        state.add_artist_to_favourates(unsafe { &*selected_artist });
-                    } else {
-                        state.remove_artist_from_favourates(unsafe { &*selected_artist });
-                    }
-                } else {
-                    state.status = "Nothing selected..";
-                }
-            }
-            _ => {}
+    // Sort the music already shown in musicbar in next order
+    let sort_music = || {
+        let mut state = state_original.lock().unwrap();
+        if state.active == ui::Window::Musicbar {
+            state.cycle_music_sort();
+            notifier.notify_all();
         }
+    };
 
+    // Open url of focused item in web browser
+    let browse_url = || {
+        let mut state = state_original.lock().unwrap();
+        match focused_url(&state) {
+            Some(url) if open_in_browser(&url) => state.info(format!("Opened {}..", url)),
+            Some(_) => state.error("Cannot open browser.."),
+            None => state.warn("Nothing selected or playing.."),
+        }
         notifier.notify_all();
     };
 
+    // Select the option in sidebar and open it as if it was selected by pressing enter
+    let goto_sidebar = |option: ui::SidebarOption| {
+        let mut state = state_original.lock().unwrap();
+        state.sidebar.select(Some(option as usize));
+        state.active = ui::Window::Sidebar;
+        drop_and_call!(state, handle_enter);
+    };
+
+    let run_chord = |chord: &Chord| match chord {
+        Chord::Goto(option) => goto_sidebar(option.clone()),
+        Chord::Top => jump_in_list(JumpTo::Top),
+        Chord::YankUrl => yank_url(),
+    };
+
+    let chord_keys = &CONFIG.chord_keys;
+    let mut chords = [
+        (
+            &chord_keys.goto_trending,
+            Chord::Goto(ui::SidebarOption::Trending),
+        ),
+        (
+            &chord_keys.goto_charts,
+            Chord::Goto(ui::SidebarOption::Charts),
+        ),
+        (
+            &chord_keys.goto_moods,
+            Chord::Goto(ui::SidebarOption::Moods),
+        ),
+        (
+            &chord_keys.goto_liked,
+            Chord::Goto(ui::SidebarOption::Liked),
+        ),
+        (
+            &chord_keys.goto_saved,
+            Chord::Goto(ui::SidebarOption::Saved),
+        ),
+        (
+            &chord_keys.goto_following,
+            Chord::Goto(ui::SidebarOption::Following),
+        ),
+        (
+            &chord_keys.goto_subscriptions,
+            Chord::Goto(ui::SidebarOption::Subscriptions),
+        ),
+        (
+            &chord_keys.goto_new_releases,
+            Chord::Goto(ui::SidebarOption::NewReleases),
+        ),
+        (
+            &chord_keys.goto_local,
+            Chord::Goto(ui::SidebarOption::Local),
+        ),
+        (
+            &chord_keys.goto_history,
+            Chord::Goto(ui::SidebarOption::History),
+        ),
+        (&chord_keys.yank_url, Chord::YankUrl),
+    ]
+    .into_iter()
+    .filter_map(|(keys, chord)| Some((chord_keys_of(keys)?, chord)))
+    .collect::<Vec<([char; 2], Chord)>>();
+    if CONFIG.constants.vim_keys {
+        chords.push((['g', 'g'], Chord::Top));
+    }
+    // First key of chord and when it was pressed
+    let mut pending_chord: Option<(char, std::time::Instant)> = None;
+
+    let mut last_click: Option<(std::time::Instant, u16)> = None;
+
+    // Text typed in searchbar and when it was last changed. None once it is searched
+    let live_search_delay = Duration::from_millis(CONFIG.constants.live_search_ms);
+    let mut live_query: (String, Option<std::time::Instant>) = (String::new(), None);
+
     'listener_loop: loop {
-        if event::poll(Duration::from_millis(CONFIG.constants.refresh_rate)).unwrap() {
+        if TERMINATE.load(std::sync::atomic::Ordering::Relaxed) {
+            quit(true);
+            break 'listener_loop;
+        }
+        // App can also be quit from remote or control socket
+        if state_original.lock().unwrap().active == ui::Window::None {
+            break 'listener_loop;
+        }
+
+        // Query is searched once typing stops for live_search_ms. Waiting for events is cut short
+        // so that search is not delayed until the next refresh
+        let mut poll_timeout = Duration::from_millis(CONFIG.constants.refresh_rate);
+        if !live_search_delay.is_zero() {
+            let typed = state_original.lock().unwrap().suggestions.0.clone();
+            if typed != live_query.0 {
+                live_query = (typed, Some(std::time::Instant::now()));
+            }
+            if let Some(changed_at) = live_query.1 {
+                match live_search_delay.checked_sub(changed_at.elapsed()) {
+                    Some(left) if !left.is_zero() => poll_timeout = poll_timeout.min(left),
+                    _ => {
+                        live_query.1 = None;
+                        live_search();
+                    }
+                }
+            }
+        }
+
+        if event::poll(poll_timeout).unwrap() {
             match event::read().unwrap() {
                 Event::Key(key) => {
                     let is_with_control = key.modifiers.contains(KeyModifiers::CONTROL);
-
+                    let is_typing = {
+                        let state = state_original.lock().unwrap();
+                        state.active == ui::Window::Searchbar
+                            || state.filter.typing
+                            || state.command.is_some()
+                            || state.help.is_some()
+                    };
+
+                    // Key pressed right after first key of chord completes it. Any other key
+                    // cancels the chord. Key that starts a chord waits for the next key
+                    let first_key = pending_chord
+                        .take()
+                        .filter(|(_, pressed_at)| pressed_at.elapsed() < CHORD_TIMEOUT)
+                        .map(|(first_key, _)| first_key);
                     match key.code {
+                        KeyCode::Char(ch) if !is_with_control && !is_typing => {
+                            let completed = first_key.and_then(|first_key| {
+                                chords.iter().find(|(keys, _)| *keys == [first_key, ch])
+                            });
+                            if let Some((_, chord)) = completed {
+                                run_chord(chord);
+                                continue 'listener_loop;
+                            } else if chords.iter().any(|(keys, _)| keys[0] == ch) {
+                                pending_chord = Some((ch, std::time::Instant::now()));
+                                continue 'listener_loop;
+                            }
+                        }
+                        _ => {}
+                    }
+
+                    // Vim style keys are only used outside of searchbar. j/k/h/l are same as
+                    // arrow keys. See `vim_keys` in config
+                    let is_vim = CONFIG.constants.vim_keys && !is_typing;
+                    let code = match key.code {
+                        KeyCode::Char(ch) if is_vim && !is_with_control => match ch {
+                            'j' => KeyCode::Down,
+                            'k' => KeyCode::Up,
+                            'h' => KeyCode::Left,
+                            'l' => KeyCode::Right,
+                            _ => key.code,
+                        },
+                        _ => key.code,
+                    };
+
+                    match code {
+                        KeyCode::Char(ch) if !is_with_control && command_input(ch) => {}
+                        KeyCode::Backspace if command_backspace() => {}
+                        KeyCode::Tab if complete_command() => {}
+                        KeyCode::Esc if close_command() => {}
+                        KeyCode::Enter if is_command_open() => match take_command() {
+                            Some(ui::command::Command::Download) => handle_download().await,
+                            Some(command) => run_command(command),
+                            None => {}
+                        },
+                        KeyCode::Char(ch) if !is_with_control && filter_input(ch) => {}
+                        KeyCode::Backspace if filter_backspace() => {}
+                        KeyCode::Enter if accept_filter() => {}
+                        KeyCode::Esc if cancel_filter() => {}
+                        KeyCode::Left
+                            if is_with_control && edit_search(ui::SearchEdit::WordLeft) => {}
+                        KeyCode::Right
+                            if is_with_control && edit_search(ui::SearchEdit::WordRight) => {}
+                        KeyCode::Left if edit_search(ui::SearchEdit::Left) => {}
+                        KeyCode::Right if edit_search(ui::SearchEdit::Right) => {}
+                        KeyCode::Home if edit_search(ui::SearchEdit::Home) => {}
+                        KeyCode::End if edit_search(ui::SearchEdit::End) => {}
+                        KeyCode::Delete if edit_search(ui::SearchEdit::Delete) => {}
+                        KeyCode::Char('v') if is_with_control => paste_to_search(),
+                        KeyCode::Char('a')
+                            if is_with_control && edit_search(ui::SearchEdit::Home) => {}
+                        KeyCode::Char('e')
+                            if is_with_control && edit_search(ui::SearchEdit::End) => {}
+                        KeyCode::Char('w')
+                            if is_with_control && edit_search(ui::SearchEdit::DeleteWord) => {}
+                        KeyCode::Char('u')
+                            if is_with_control && edit_search(ui::SearchEdit::KillLine) => {}
+                        KeyCode::Down if advance_action_menu(HeadTo::Next) => {}
+                        KeyCode::Up if advance_action_menu(HeadTo::Prev) => {}
+                        KeyCode::Esc if close_actions() => {}
+                        KeyCode::Enter if is_action_menu_shown() => match take_action() {
+                            Some(ui::ItemAction::Play | ui::ItemAction::Open) => handle_enter(),
+                            Some(ui::ItemAction::View) => handle_view(),
+                            Some(ui::ItemAction::Enqueue) => enqueue(),
+                            Some(ui::ItemAction::FavouriteAdd) => handle_favourates(true),
+                            Some(ui::ItemAction::FavouriteRemove) => handle_favourates(false),
+                            Some(ui::ItemAction::Download) => handle_download().await,
+                            Some(ui::ItemAction::AddToPlaylist) => add_to_local_playlist(),
+                            Some(ui::ItemAction::CopyUrl) => yank_url(),
+                            Some(ui::ItemAction::OpenInBrowser) => browse_url(),
+                            Some(ui::ItemAction::Details) => toggle_details(),
+                            Some(ui::ItemAction::Subscribe) => toggle_subscription(),
+                            Some(ui::ItemAction::Podcast) => fill_podcast_episodes(),
+                            None => {}
+                        },
+                        KeyCode::Char('G') if is_vim => jump_in_list(JumpTo::Bottom),
+                        KeyCode::Char(ch)
+                            if !is_typing
+                                && !is_with_control
+                                && key.modifiers.contains(KeyModifiers::ALT)
+                                && jump_to_letter(ch) => {}
+                        KeyCode::Char('d')
+                            if is_vim && is_with_control && !is_control_shortcut('d') =>
+                        {
+                            jump_in_list(JumpTo::HalfPageDown);
+                        }
+                        KeyCode::Char('u')
+                            if is_vim && is_with_control && !is_control_shortcut('u') =>
+                        {
+                            jump_in_list(JumpTo::HalfPageUp);
+                        }
+                        KeyCode::Down | KeyCode::PageDown if scroll_help(HeadTo::Next) => {}
+                        KeyCode::Up | KeyCode::PageUp if scroll_help(HeadTo::Prev) => {}
+                        KeyCode::Char(ch) if !is_with_control && help_input(ch) => {}
+                        KeyCode::Backspace if help_backspace() => {}
+                        KeyCode::Esc if close_help() => {}
+                        KeyCode::Down | KeyCode::PageDown if scroll_details(HeadTo::Next) => {}
+                        KeyCode::Up | KeyCode::PageUp if scroll_details(HeadTo::Prev) => {}
+                        KeyCode::Esc if close_details() => {}
+                        KeyCode::Down if advance_queue(HeadTo::Next) => {}
+                        KeyCode::Up if advance_queue(HeadTo::Prev) => {}
+                        KeyCode::Enter if play_from_queue() => {}
+                        KeyCode::Esc if close_queue() => {}
+                        KeyCode::Char('J') if move_in_queue(HeadTo::Next) => {}
+                        KeyCode::Char('K') if move_in_queue(HeadTo::Prev) => {}
+                        KeyCode::Char('x') | KeyCode::Delete if remove_from_queue() => {}
+                        KeyCode::Down if advance_suggestion(HeadTo::Next) => {}
+                        KeyCode::Up if advance_suggestion(HeadTo::Prev) => {}
+                        // Tab completes the query to first suggestion and cycles through the
+                        // rest when pressed again. Dropdown is left open to keep cycling
+                        KeyCode::Tab if advance_suggestion(HeadTo::Next) => {}
+                        KeyCode::BackTab if advance_suggestion(HeadTo::Prev) => {}
+                        KeyCode::Down if advance_chapter_list(HeadTo::Next) => {}
+                        KeyCode::Up if advance_chapter_list(HeadTo::Prev) => {}
+                        KeyCode::Enter if select_chapter() => {}
+                        KeyCode::Esc if close_chapters() => {}
+                        KeyCode::Down if advance_mood_list(HeadTo::Next) => {}
+                        KeyCode::Up if advance_mood_list(HeadTo::Prev) => {}
+                        KeyCode::Enter if select_mood() => {}
+                        KeyCode::Esc if close_moods() => {}
+                        KeyCode::Down if advance_cast_list(HeadTo::Next) => {}
+                        KeyCode::Up if advance_cast_list(HeadTo::Prev) => {}
+                        KeyCode::Enter if select_cast_device() => {}
+                        KeyCode::Esc if close_cast_list() => {}
+                        KeyCode::Down if advance_output_list(HeadTo::Next) => {}
+                        KeyCode::Up if advance_output_list(HeadTo::Prev) => {}
+                        KeyCode::Enter if select_output() => {}
+                        KeyCode::Esc if close_output_list() => {}
+                        KeyCode::PageDown if scroll_comments(HeadTo::Next) => {}
+                        KeyCode::PageUp if scroll_comments(HeadTo::Prev) => {}
+                        KeyCode::Esc if close_comments() => {}
+                        KeyCode::Esc if clear_marks() => {}
+                        KeyCode::Right if is_controls_focused() => seek_forward(),
+                        KeyCode::Left if is_controls_focused() => seek_backward(),
                         KeyCode::Down | KeyCode::PageDown => {
                             handle_up_down(HeadTo::Next);
                         }
@@ -741,10 +3113,14 @@ pub async fn event_sender(
                                 seek_backward();
                             } else if ch == CONFIG.shortcut_keys.view {
                                 handle_view();
+                            } else if ch == CONFIG.shortcut_keys.details {
+                                toggle_details();
                             } else if ch == CONFIG.shortcut_keys.favourates_add {
                                 handle_favourates(true);
                             } else if ch == CONFIG.shortcut_keys.favourates_remove {
                                 handle_favourates(false);
+                            } else if ch == CONFIG.shortcut_keys.favourite {
+                                toggle_favourite();
                             } else if ch == CONFIG.shortcut_keys.prev {
                                 if is_with_control {
                                     change_track(HeadTo::Prev);
@@ -763,6 +3139,72 @@ pub async fn event_sender(
                                 change_volume(HeadTo::Next);
                             } else if ch == CONFIG.shortcut_keys.vol_decrease {
                                 change_volume(HeadTo::Prev);
+                            } else if ch == CONFIG.shortcut_keys.mute {
+                                toggle_mute();
+                            } else if ch == CONFIG.shortcut_keys.related {
+                                fill_related_music();
+                            } else if ch == CONFIG.shortcut_keys.mix {
+                                fill_mix();
+                            } else if ch == CONFIG.shortcut_keys.lyrics {
+                                toggle_lyrics();
+                            } else if ch == CONFIG.shortcut_keys.comments {
+                                toggle_comments();
+                            } else if ch == CONFIG.shortcut_keys.subscribe {
+                                toggle_subscription();
+                            } else if ch == CONFIG.shortcut_keys.podcast {
+                                fill_podcast_episodes();
+                            } else if ch == CONFIG.shortcut_keys.load_all {
+                                load_all_music();
+                            } else if ch == CONFIG.shortcut_keys.next_chapter {
+                                seek_chapter(HeadTo::Next);
+                            } else if ch == CONFIG.shortcut_keys.prev_chapter {
+                                seek_chapter(HeadTo::Prev);
+                            } else if ch == CONFIG.shortcut_keys.chapters {
+                                toggle_chapters();
+                            } else if ch == CONFIG.shortcut_keys.help {
+                                toggle_help();
+                            } else if ch == CONFIG.shortcut_keys.queue {
+                                toggle_queue();
+                            } else if ch == CONFIG.shortcut_keys.filter {
+                                show_filter();
+                            } else if ch == CONFIG.shortcut_keys.mark {
+                                toggle_mark();
+                            } else if ch == CONFIG.shortcut_keys.enqueue {
+                                enqueue();
+                            } else if ch == CONFIG.shortcut_keys.add_to_playlist {
+                                add_to_local_playlist();
+                            } else if ch == CONFIG.shortcut_keys.actions {
+                                show_actions();
+                            } else if ch == CONFIG.shortcut_keys.shrink_sidebar {
+                                resize_pane(true, false);
+                            } else if ch == CONFIG.shortcut_keys.grow_sidebar {
+                                resize_pane(true, true);
+                            } else if ch == CONFIG.shortcut_keys.shrink_music {
+                                resize_pane(false, false);
+                            } else if ch == CONFIG.shortcut_keys.grow_music {
+                                resize_pane(false, true);
+                            } else if ch == CONFIG.shortcut_keys.maximize {
+                                toggle_maximize();
+                            } else if ch == CONFIG.shortcut_keys.layout {
+                                switch_layout();
+                            } else if ch == CONFIG.shortcut_keys.hints {
+                                toggle_hints();
+                            } else if ch == CONFIG.shortcut_keys.search_scope {
+                                toggle_search_scope();
+                            } else if ch == CONFIG.shortcut_keys.retry {
+                                retry_failed();
+                            } else if ch == CONFIG.shortcut_keys.contrast {
+                                toggle_contrast();
+                            } else if ch == CONFIG.shortcut_keys.clear_history {
+                                clear_history();
+                            } else if ch == CONFIG.shortcut_keys.yank_url {
+                                yank_url();
+                            } else if ch == CONFIG.shortcut_keys.open_in_browser {
+                                browse_url();
+                            } else if ch == CONFIG.shortcut_keys.sort_music {
+                                sort_music();
+                            } else if ch == CONFIG.shortcut_keys.command {
+                                open_command();
                             } else if ch == CONFIG.shortcut_keys.quit && is_with_control {
                                 let force_quit = key.modifiers.contains(KeyModifiers::ALT);
                                 if quit(force_quit) {
@@ -777,7 +3219,28 @@ pub async fn event_sender(
                     // just update the layout
                     notifier.notify_all();
                 }
-                Event::Mouse(..) => {}
+                Event::Mouse(mouse) => match mouse.kind {
+                    // Double click does the same as pressing enter over the clicked row
+                    MouseEventKind::Down(MouseButton::Left) => {
+                        let is_double = matches!(last_click,
+                            Some((time, line)) if line == mouse.row
+                                && time.elapsed() < DOUBLE_CLICK_INTERVAL);
+                        last_click = Some((std::time::Instant::now(), mouse.row));
+                        if select_at(mouse.column, mouse.row) && is_double {
+                            last_click = None;
+                            handle_enter();
+                        }
+                    }
+                    // So does the middle click
+                    MouseEventKind::Down(MouseButton::Middle)
+                        if select_at(mouse.column, mouse.row) =>
+                    {
+                        handle_enter();
+                    }
+                    MouseEventKind::ScrollDown => scroll_at(mouse.column, mouse.row, HeadTo::Next),
+                    MouseEventKind::ScrollUp => scroll_at(mouse.column, mouse.row, HeadTo::Prev),
+                    _ => {}
+                },
             }
         } else {
             notifier.notify_all();
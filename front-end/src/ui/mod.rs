@@ -1,5 +1,15 @@
+mod album_art;
+pub mod cast;
+pub mod command;
+pub mod ctl;
+pub mod daemon;
 pub mod event;
+pub mod mpris;
+pub mod mqtt;
+pub mod remote;
+pub mod status_file;
 mod utils;
+pub mod webhook;
 use std::sync::Condvar;
 use tui::{backend::CrosstermBackend, Terminal};
 // These are the imports also used in __utils.rs__ so make this import shareable
@@ -19,11 +29,12 @@ mod shared_import {
         text::{self, Span, Spans, Text},
         widgets::{
             self, Block, BorderType, Borders, Cell, Gauge, List, ListItem, ListState, Paragraph,
-            Row, Table, TableState, Tabs, Widget,
+            Row, Table, TableState, Tabs, Widget, Wrap,
         },
     };
 }
 use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
     execute,
     terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -105,6 +116,7 @@ pub struct MiddleLayout {
 // the playlist defined in youtube. See `Fetcher::PlaylistUnit` type
 // right half show the list of artist which is actually
 // the channel from youtube. See `Fetcher::ArtistUnit` type
+// In wide terminal these are stacked instead, playlist above artist. See `Panes::wide`
 pub struct MiddleBottom {
     layout: [Rect; 2],
 }
@@ -150,6 +162,8 @@ pub struct Position {
     pub artist: Rect,
     pub music_info: Rect,
     pub bottom_icons: Rect,
+    pub hints: Rect,
+    pub header: Rect,
     pub popup: Rect,
 }
 
@@ -167,6 +181,7 @@ pub struct Position {
 pub fn draw_ui(state: &mut Arc<Mutex<State>>, cvar: &mut Arc<Condvar>) {
     let mut stdout = std::io::stdout();
     execute!(stdout, EnterAlternateScreen).expect("Failed to enter alternate screen");
+    execute!(stdout, EnableMouseCapture).unwrap_or_else(|_| eprintln!("Failed to capture mouse"));
     terminal::enable_raw_mode().expect("Faild to enable raw mode");
 
     let backed = CrosstermBackend::new(stdout);
@@ -180,23 +195,51 @@ pub fn draw_ui(state: &mut Arc<Mutex<State>>, cvar: &mut Arc<Condvar>) {
         .unwrap_or_else(|_| eprintln!("Failed to hide cursor"));
 
     let mut previous_dimension: Rect = Rect::default();
-    let mut position = Position::caclulate(&previous_dimension);
-    let mut paint_ui = || {
+    let mut previous_panes = Panes::default();
+    let mut position = Position::caclulate(&previous_dimension, &previous_panes);
+    let art_protocol = album_art::Protocol::from_config();
+    // Area and path of music of the thumbnail last written with graphics protocol
+    let mut written_art: Option<(Rect, String)> = None;
+    let mut paint_ui = || loop {
+        let mut art = None;
         terminal
             .draw(|screen| {
                 let mut state_unlocked = state.lock().unwrap();
+                // Area of everything drawn over the main layout
+                let mut overlays = Vec::new();
 
                 // As screen size doesn't change that often (is chaged when terminal window is
                 // resized) so it is unnecessary to calcuate position for components in every draw
-                // loop. Calculate once and recalculate when window size change
+                // loop. Calculate once and recalculate when window size or size of panes change
                 let current_dimension = screen.size();
-                if previous_dimension != current_dimension {
-                    position = Position::caclulate(&current_dimension);
+                // Tiny terminal collapses the layout. Focus can't be left in hidden section
+                state_unlocked.panes.compact = utils::is_tiny_screen(&current_dimension);
+                state_unlocked.panes.wide = utils::is_wide_screen(&current_dimension);
+                if state_unlocked.active.is_pane()
+                    && !state_unlocked.panes.is_shown(&state_unlocked.active)
+                {
+                    state_unlocked.active = Window::Musicbar;
+                }
+                // Maximized section follows the focus
+                if state_unlocked.panes.maximized.is_some() && state_unlocked.active.is_pane() {
+                    state_unlocked.panes.maximized = Some(state_unlocked.active.clone());
+                }
+                // So does the header follow the music being shown
+                state_unlocked.panes.header = state_unlocked.header_artist().is_some()
+                    || state_unlocked.header_playlist().is_some();
+                if previous_dimension != current_dimension || previous_panes != state_unlocked.panes
+                {
+                    position = Position::caclulate(&current_dimension, &state_unlocked.panes);
                     previous_dimension = current_dimension;
+                    previous_panes = state_unlocked.panes.clone();
                 }
 
+                state_unlocked.notifications.expire();
                 screen.render_widget(TopLayout::get_statusbox(&state_unlocked), position.status);
-                screen.render_widget(TopLayout::get_searchbox(&state_unlocked), position.search);
+                screen.render_widget(
+                    TopLayout::get_searchbox(&state_unlocked, position.search.width),
+                    position.search,
+                );
                 screen.render_stateful_widget(
                     SideBar::get_shortcuts(&state_unlocked),
                     position.shortcut,
@@ -212,7 +255,7 @@ pub fn draw_ui(state: &mut Arc<Mutex<State>>, cvar: &mut Arc<Condvar>) {
                 // One first thought is to wrap inside some cell but as this loop keeps running in
                 // short time interval copying anything for that purpose would be consuming more
                 // cpu. And it may be good time to play with unsafe
-                let state_ptr = &mut state_unlocked as *mut std::sync::MutexGuard<'_, State<'_>>;
+                let state_ptr = &mut state_unlocked as *mut std::sync::MutexGuard<'_, State>;
                 let (mut music_state, mut playlist_state, mut artist_state);
                 unsafe {
                     music_state = &mut (*state_ptr).musicbar.1;
@@ -220,16 +263,96 @@ pub fn draw_ui(state: &mut Arc<Mutex<State>>, cvar: &mut Arc<Condvar>) {
                     artist_state = &mut (*state_ptr).artistbar.1;
                 }
 
-                let music_table = MiddleLayout::get_music_container(&mut state_unlocked);
+                let music_table =
+                    MiddleLayout::get_music_container(&mut state_unlocked, position.music.width);
                 screen.render_stateful_widget(music_table, position.music, &mut music_state);
-                let playlist_table = MiddleBottom::get_playlist_container(&mut state_unlocked);
+                let playlist_table = MiddleBottom::get_playlist_container(
+                    &mut state_unlocked,
+                    position.playlist.width,
+                );
                 screen.render_stateful_widget(
                     playlist_table,
                     position.playlist,
                     &mut playlist_state,
                 );
-                let artist_table = MiddleBottom::get_artist_container(&mut state_unlocked);
+                let artist_table =
+                    MiddleBottom::get_artist_container(&mut state_unlocked, position.artist.width);
                 screen.render_stateful_widget(artist_table, position.artist, &mut artist_state);
+                state_unlocked.follow_offsets(&position);
+
+                // 2 lines for border and 1 for header
+                let lengths = [
+                    state_unlocked.musicbar.0.len(),
+                    state_unlocked.playlistbar.0.len(),
+                    state_unlocked.artistbar.0.len(),
+                ];
+                for ((area, len), offset) in [position.music, position.playlist, position.artist]
+                    .iter()
+                    .zip(lengths)
+                    .zip(state_unlocked.list_offset)
+                {
+                    let scrollbar = utils::Scrollbar {
+                        len,
+                        offset,
+                        visible: area.height.saturating_sub(3) as usize,
+                    };
+                    screen.render_widget(scrollbar, *area);
+                }
+
+                // List being fetched is empty until it is fetched so placeholder is shown in it
+                let lists = [position.music, position.playlist, position.artist];
+                for (area, loading) in lists.iter().zip(state_unlocked.loading) {
+                    if let Some(since) = loading {
+                        // Below the border and header
+                        let area = Rect {
+                            x: area.x + 1,
+                            y: area.y + 2,
+                            width: area.width.saturating_sub(2),
+                            height: area.height.saturating_sub(3).min(1),
+                        };
+                        screen.render_widget(utils::get_loading_placeholder(since), area);
+                    }
+                }
+
+                // So is the reason when list could not be fetched
+                for ((area, error), len) in
+                    lists.iter().zip(state_unlocked.fetch_error).zip(lengths)
+                {
+                    if let (Some(reason), 0) = (error, len) {
+                        let area = Rect {
+                            x: area.x + 1,
+                            y: area.y + 2,
+                            width: area.width.saturating_sub(2),
+                            height: area.height.saturating_sub(3).min(1),
+                        };
+                        screen.render_widget(utils::get_fetch_error(reason), area);
+                    }
+                }
+
+                // Filter prompt takes the bottom border of list being filtered
+                if let Some(list) = state_unlocked.filter.list {
+                    let area = [position.music, position.playlist, position.artist][list];
+                    let prompt_area = Rect {
+                        x: area.x + 1,
+                        y: (area.y + area.height).saturating_sub(1),
+                        width: area.width.saturating_sub(2),
+                        height: area.height.min(1),
+                    };
+                    screen.render_widget(utils::get_filter_prompt(&state_unlocked), prompt_area);
+                }
+
+                // Lyrics are shown over both playlist and artist section
+                if state_unlocked.lyrics.shown {
+                    let area = position.playlist.union(position.artist);
+                    screen.render_widget(widgets::Clear, area);
+                    screen.render_widget(MiddleBottom::get_lyrics(&state_unlocked, &area), area);
+                }
+                // So are the comments
+                if state_unlocked.comments.shown {
+                    let area = position.playlist.union(position.artist);
+                    screen.render_widget(widgets::Clear, area);
+                    screen.render_widget(MiddleBottom::get_comments(&state_unlocked), area);
+                }
 
                 state_unlocked.refresh_mpv_status();
 
@@ -241,15 +364,191 @@ pub fn draw_ui(state: &mut Arc<Mutex<State>>, cvar: &mut Arc<Condvar>) {
                     BottomLayout::get_icons_set(&state_unlocked),
                     position.bottom_icons,
                 );
+                screen.render_widget(utils::get_key_hints(&state_unlocked), position.hints);
+                screen.render_widget(MiddleLayout::get_header(&state_unlocked), position.header);
+
+                // Chapter list is shown over music section
+                if let Some(mut chapter_state) = state_unlocked.chapters.popup.clone() {
+                    screen.render_widget(widgets::Clear, position.music);
+                    screen.render_stateful_widget(
+                        MiddleLayout::get_chapter_list(&state_unlocked),
+                        position.music,
+                        &mut chapter_state,
+                    );
+                }
+
+                // So is the play queue
+                if let Some(mut queue_state) = state_unlocked.queue.clone() {
+                    screen.render_widget(widgets::Clear, position.music);
+                    screen.render_stateful_widget(
+                        MiddleLayout::get_queue_list(&state_unlocked),
+                        position.music,
+                        &mut queue_state,
+                    );
+                }
+
+                // So is the list of mood/genre categories
+                if let Some(mut mood_state) = state_unlocked.moods.popup.clone() {
+                    screen.render_widget(widgets::Clear, position.music);
+                    screen.render_stateful_widget(
+                        MiddleLayout::get_mood_list(&state_unlocked),
+                        position.music,
+                        &mut mood_state,
+                    );
+                }
+
+                // So is the list of cast devices
+                if let Some(mut cast_state) = state_unlocked.cast.popup.clone() {
+                    screen.render_widget(widgets::Clear, position.music);
+                    screen.render_stateful_widget(
+                        MiddleLayout::get_cast_list(&state_unlocked),
+                        position.music,
+                        &mut cast_state,
+                    );
+                }
+
+                // So is the list of audio devices
+                if let Some(mut output_state) = state_unlocked.outputs.popup.clone() {
+                    screen.render_widget(widgets::Clear, position.music);
+                    screen.render_stateful_widget(
+                        MiddleLayout::get_output_list(&state_unlocked),
+                        position.music,
+                        &mut output_state,
+                    );
+                }
+
+                // Action menu is shown in the middle of list of which item it belongs to
+                if let Some(menu) = &state_unlocked.actions {
+                    let list_area = match state_unlocked.active {
+                        Window::Playlistbar => position.playlist,
+                        Window::Artistbar => position.artist,
+                        _ => position.music,
+                    };
+                    let area = utils::get_action_menu_area(&list_area, &menu.actions);
+                    let mut menu_state = menu.list.clone();
+                    screen.render_widget(widgets::Clear, area);
+                    screen.render_stateful_widget(
+                        utils::get_action_menu(&menu.actions),
+                        area,
+                        &mut menu_state,
+                    );
+                }
+
+                // Help covers the whole middle section as it is long
+                if state_unlocked.help.is_some() {
+                    let area = position
+                        .shortcut
+                        .union(position.music)
+                        .union(position.artist);
+                    screen.render_widget(widgets::Clear, area);
+                    if let Some(help) = &state_unlocked.help {
+                        screen.render_widget(utils::get_help(help), area);
+                    }
+                    overlays.push(area);
+                }
+
+                // Details popup is larger than other popups as description can be long
+                if let Some(details) = &state_unlocked.details {
+                    let area = utils::get_details_area(&current_dimension);
+                    screen.render_widget(widgets::Clear, area);
+                    screen.render_widget(utils::get_details(details), area);
+                    overlays.push(area);
+                }
+
+                // Suggestions dropdown is drawn over the middle section just below the searchbar
+                if state_unlocked.active == Window::Searchbar
+                    && !state_unlocked.suggestions.1.is_empty()
+                {
+                    let area = TopLayout::get_suggestion_area(
+                        &position.search,
+                        state_unlocked.suggestions.1.len(),
+                        &current_dimension,
+                    );
+                    let mut suggestion_state = state_unlocked.suggestions.2.clone();
+                    overlays.push(area);
+                    screen.render_widget(widgets::Clear, area);
+                    screen.render_stateful_widget(
+                        TopLayout::get_suggestions(&state_unlocked),
+                        area,
+                        &mut suggestion_state,
+                    );
+                }
+
+                // Toast is drawn over the bottom right corner of middle section until it expires
+                state_unlocked.expire_toast();
+                if let Some((message, _)) = &state_unlocked.toast {
+                    let area = utils::get_toast_area(&position.music_info, message);
+                    screen.render_widget(widgets::Clear, area);
+                    screen.render_widget(utils::get_toast(message, area.width), area);
+                    overlays.push(area);
+                }
 
                 // Sho this popup at last after everything else is drawn.
                 // This makes sure that background is not empty and user can
                 // see some things like progress of music player
                 if let Window::Popup(title, ref content) = state_unlocked.active {
                     utils::show_pupop_text(screen, [title, content], &position.popup);
+                    overlays.push(position.popup);
+                }
+
+                // Command line is drawn over the last row like in vim
+                if let Some(line) = &state_unlocked.command {
+                    let area = Rect {
+                        y: current_dimension.bottom().saturating_sub(1),
+                        height: current_dimension.height.min(1),
+                        ..current_dimension
+                    };
+                    screen.render_widget(widgets::Clear, area);
+                    screen.render_widget(utils::get_command_line(line), area);
+                    overlays.push(area);
+                }
+
+                // Thumbnail is only shown when nothing is drawn over it
+                if let (Some(protocol), Some(image)) =
+                    (art_protocol, &state_unlocked.album_art.image)
+                {
+                    let area =
+                        album_art::get_area(&position.shortcut, image, utils::SIDEBAR_LIST_COUNT)
+                            .filter(|area| !overlays.iter().any(|over| over.intersects(*area)));
+                    if let Some(area) = area {
+                        if protocol.is_graphics() {
+                            screen.render_widget(widgets::Clear, area);
+                            art = Some((area, state_unlocked.album_art.path.clone()));
+                        } else {
+                            screen.render_widget(album_art::HalfBlock(image), area);
+                        }
+                    }
+                }
+
+                if config::initilize::CONFIG.constants.ascii_only {
+                    screen.render_widget(utils::AsciiOnly, current_dimension);
                 }
             })
             .unwrap();
+
+        // Thumbnail written with graphics protocol is written again when it's area or music
+        // changes. Old one is removed first. Only kitty can remove the image, others are removed
+        // by clearing the screen after which ui is drawn again
+        match art_protocol {
+            Some(protocol) if protocol.is_graphics() && art != written_art => {
+                if written_art.take().is_some() {
+                    if protocol == album_art::Protocol::Kitty {
+                        album_art::remove_kitty_images().ok();
+                    } else {
+                        terminal.clear().ok();
+                        continue;
+                    }
+                }
+                if let Some((area, _)) = art {
+                    if let Some(image) = &state.lock().unwrap().album_art.image {
+                        album_art::write_image(protocol, image, area).ok();
+                    }
+                }
+                written_art = art;
+            }
+            _ => {}
+        }
+        break;
     };
     paint_ui();
 
@@ -263,28 +562,42 @@ pub fn draw_ui(state: &mut Arc<Mutex<State>>, cvar: &mut Arc<Condvar>) {
         }
     }
 
-    // Attempt to bring terminal in original state before thi appbut when any attempt is failed
-    // do not panic but simply leave the message about failure and user will be responsibe to
-    // handle their terminal on their own
+    restore_terminal();
+    terminal
+        .show_cursor()
+        .unwrap_or_else(|_| eprintln!("Failed to show cursor. Try: stty sane"));
+}
+
+// Attempt to bring terminal in original state before thi appbut when any attempt is failed
+// do not panic but simply leave the message about failure and user will be responsibe to
+// handle their terminal on their own. This is also called when app panics so that panic message
+// is readable and terminal is usable afterwards
+pub fn restore_terminal() {
     crossterm::terminal::disable_raw_mode().unwrap_or_else(|_| {
         eprintln!("Failed to leave raw mode. You may need to restart the terminal")
     });
+    execute!(std::io::stdout(), DisableMouseCapture).unwrap_or_else(|_| {
+        eprintln!("Failed to release mouse. You may need to restart the terminal")
+    });
     execute!(std::io::stdout(), LeaveAlternateScreen).unwrap_or_else(|_| {
         eprintln!("Failed to leave alternate mode. You may need to restart the terminal")
     });
-    terminal
-        .show_cursor()
-        .unwrap_or_else(|_| eprintln!("Failed to show cursor. Try: stty sane"));
 }
 
 #[derive(Clone)]
 pub enum SidebarOption {
     Trending = 0,
-    YoutubeCommunity = 1,
-    Liked = 2,
-    Saved = 3,
-    Following = 4,
-    Search = 5,
+    Charts = 1,
+    Moods = 2,
+    YoutubeCommunity = 3,
+    Liked = 4,
+    Saved = 5,
+    Following = 6,
+    Subscriptions = 7,
+    NewReleases = 8,
+    Local = 9,
+    History = 10,
+    Search = 11,
 }
 
 #[derive(PartialEq, Clone)]
@@ -307,16 +620,36 @@ pub struct BottomState {
     // false in Some means music is paused
     // None means playing nothing. eg: At the start of program
     playing: Option<(String, bool)>,
+    // true if live stream is being played. Live stream have no duration and cannot be seeked
+    live: bool,
+    // Artist of music being played along with path (as reported by mpv) of that music. Artist is
+    // empty when it is not known
+    artist: (String, String),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum MusicbarSource {
-    Search(String),
-    Trending,
+    // Query and whether only songs are searched. See State::songs_only
+    Search(String, bool),
+    // Region (country code) and category of which trending music is shown
+    Trending(String, fetcher::TrendingCategory),
+    // Top songs and trending music of charts of this country code
+    Charts(String),
     RecentlyPlayed,
     Favourates,
     Playlist(String),
     Artist(String),
+    Local,
+    // Music related to the music of this id
+    Related(String),
+    // Episodes of podcast channel of this id
+    Podcast(String),
+    // Music from uploads of channel (first) that matches the query (second)
+    ArtistSearch(String, String),
+    // Latest uploads of all subscribed artists
+    Subscriptions,
+    // Uploads of favourite and subscribed artists not seen yet
+    NewReleases,
 }
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum PlaylistbarSource {
@@ -324,12 +657,298 @@ pub enum PlaylistbarSource {
     RecentlyPlayed,
     Favourates,
     Artist(String),
+    Local,
+    // Video charts of this country code
+    Charts(String),
+    // Playlists of mood/genre category of this params (first) and title (second)
+    Mood(String, String),
 }
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum ArtistbarSource {
     Search(String),
     RecentlyPlayed,
     Favourates,
+    Subscriptions,
+    // Top artists of this country code
+    Charts(String),
+}
+
+pub struct LyricsState {
+    // true if lyrics pane is being shown
+    pub shown: bool,
+    // Title of the music of which lyrics is fetched or is being fetched
+    pub title: String,
+    // None until lyrics is fetched or if lyrics is not found
+    pub lyrics: Option<fetcher::lyrics::Lyrics>,
+}
+
+pub struct PodcastState {
+    // Id of the episode being played. Only set when episode is played from podcast episodes list
+    pub playing: Option<String>,
+    // Position where each episode was left off. This is loaded from storage at start so that
+    // musicbar can show it without querying storage on every draw
+    pub positions: std::collections::HashMap<String, Duration>,
+}
+
+// Chapters of music being played. Long form videos like mixes and podcast usually have one.
+// Chapters known by player (youtube-dl usually gets those from youtube) are used when available,
+// otherwise chapters are parsed from description of music
+pub struct ChapterState {
+    // Path of music (as reported by player) to which `list` belongs
+    pub path: String,
+    pub list: Vec<fetcher::chapters::Chapter>,
+    // true if `list` is read from player. Those are preferred over the chapters from description
+    pub from_player: bool,
+    // Some when chapter list popup is shown. This is the state of that list
+    pub popup: Option<ListState>,
+}
+
+// Thumbnail of music being played. See __album_art.rs__
+pub struct AlbumArtState {
+    // Path of music (as reported by player) to which `image` belongs
+    pub path: String,
+    pub image: Option<image::RgbImage>,
+}
+
+// Music being listened to. It is queued in `pending` once it is played long enough to be
+// scrobbled and communicator submits it to ListenBrainz from there. See State::track_listen
+#[derive(Default)]
+pub struct ListenState {
+    // Path of music (as reported by player) being listened to
+    pub path: String,
+    // Time music have actually been played for. Time spent paused or skipped by seeking is not
+    // counted
+    pub listened: Duration,
+    // When listened time was last counted
+    pub counted_at: Option<std::time::Instant>,
+    // When music started playing in seconds since unix epoch
+    pub started_at: u64,
+    // Music is only scrobbled once however long it is played
+    pub queued: bool,
+    pub pending: Vec<fetcher::listenbrainz::Listen>,
+}
+
+// Help overlay. Typing while help is shown filters the keys and commands to the ones whose key or
+// description contains the typed text
+#[derive(Default)]
+pub struct HelpState {
+    // Number of lines it is scrolled by
+    pub scroll: u16,
+    pub query: String,
+}
+
+// Popup with everything known about a music as list truncates most of it. Description is fetched
+// by communicator once the popup is shown
+pub struct DetailsState {
+    pub music: fetcher::MusicUnit,
+    // None until description is fetched. Empty when music has no description or it failed to fetch
+    pub description: Option<String>,
+    // Number of lines it is scrolled by
+    pub scroll: u16,
+}
+
+// Music, playlist or artist list narrowed to the entries fuzzy matching the query typed in filter
+// prompt. Whole list is kept as it was so that it can be brought back. See utils::fuzzy_score
+#[derive(Default)]
+pub struct FilterState {
+    // Index (as in fetched_page) of the list being filtered. None when nothing is filtered
+    pub list: Option<usize>,
+    pub query: String,
+    // true while query is being typed in prompt
+    pub typing: bool,
+    // Whole list. Only the one being filtered is filled
+    pub music: Vec<fetcher::MusicUnit>,
+    pub playlist: Vec<fetcher::PlaylistUnit>,
+    pub artist: Vec<fetcher::ArtistUnit>,
+}
+
+// Size of sidebar and music section in percentage, same as `sidebar_width` and `music_height` in
+// config. Playlist and artist section take whatever is left. When some section is maximized it is
+// given the whole space between top and bottom bar and rest of the sections are not drawn
+#[derive(Clone, PartialEq)]
+pub struct Panes {
+    pub sidebar: u16,
+    pub music: u16,
+    pub maximized: Option<Window>,
+    pub preset: LayoutPreset,
+    // true if key hints footer is shown below the bottom bar
+    pub hints: bool,
+    // true if artist or playlist header is shown above music section. This follows the music being
+    // shown. See State::header_artist and State::header_playlist
+    pub header: bool,
+    // true if terminal is too small to fit every section. Only searchbar, music section and bottom
+    // bar are shown then. This follows the terminal size. See utils::is_tiny_screen
+    pub compact: bool,
+    // true if terminal is wide enough to show music section beside playlist and artist section
+    // instead of above them. This follows the terminal size. See utils::is_wide_screen
+    pub wide: bool,
+}
+
+// Sections shown in the screen. Search layout leaves out the sidebar and mini layout leaves out
+// everything except the play queue (shown in place of music section) and the bottom status bar
+#[derive(Clone, Copy, PartialEq)]
+pub enum LayoutPreset {
+    Full,
+    Search,
+    Mini,
+}
+
+// Changes made to search query from where the cursor is. Word is anything between whitespaces
+#[derive(Clone, Copy)]
+pub enum SearchEdit {
+    Insert(char),
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    WordLeft,
+    WordRight,
+    Home,
+    End,
+    // Delete the word before cursor
+    DeleteWord,
+    // Delete everything before cursor
+    KillLine,
+}
+
+// Order in which the music already shown in musicbar is sorted. Sorting is done on the entries
+// already fetched without searching again
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MusicSort {
+    // Order in which music was fetched
+    Original,
+    Title,
+    Duration,
+    Channel,
+}
+
+// Rows marked in music, playlist and artist list to which next action is applied all at once
+// instead of only the focused row. Marked items are kept themselves rather than their index so that
+// marks stay while moving to other pages or filtering the list. Kept in order of marking
+#[derive(Default)]
+pub struct MarkState {
+    pub music: Vec<fetcher::MusicUnit>,
+    pub playlist: Vec<fetcher::PlaylistUnit>,
+    pub artist: Vec<fetcher::ArtistUnit>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Severity {
+    Info,
+    Warn,
+    Error,
+}
+
+pub struct Notification {
+    pub message: std::borrow::Cow<'static, str>,
+    pub severity: Severity,
+    // When it was first shown. Message is removed once it's timeout passes after this
+    pub shown_at: Option<std::time::Instant>,
+}
+
+// Messages shown in status box one after another. Info tells what is going on right now so it is
+// only shown when there is nothing else to show and is replaced by any newer message. Warnings
+// and errors are queued so that none of them is missed (eg: fetch failure of one list being
+// replaced by success of other list right away)
+#[derive(Default)]
+pub struct Notifications {
+    pub queue: std::collections::VecDeque<Notification>,
+}
+
+// Things that can be done with focused music, playlist or artist. These are listed in action menu
+// so that every shortcut need not be remembered
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ItemAction {
+    Play,
+    Open,
+    // Open playlist without playing it
+    View,
+    Enqueue,
+    FavouriteAdd,
+    FavouriteRemove,
+    Download,
+    AddToPlaylist,
+    CopyUrl,
+    OpenInBrowser,
+    Details,
+    Subscribe,
+    Podcast,
+}
+
+// Popup menu of actions of focused item shown over the list it belongs to
+pub struct ActionMenu {
+    pub actions: Vec<ItemAction>,
+    pub list: ListState,
+}
+
+// Categories of moods and genres. Categories are shown in a list over music section from which
+// selected category fills the playlistbar
+pub struct MoodState {
+    pub list: Vec<fetcher::innertube::MoodCategory>,
+    // Set to ask communicator to fetch the categories. List is shown once they are fetched
+    pub requested: bool,
+    // Some when category list popup is shown. This is the state of that list
+    pub popup: Option<ListState>,
+}
+
+// Devices music can be cast to. See __cast.rs__
+pub struct CastState {
+    pub devices: Vec<cast::CastDevice>,
+    // Set to ask for devices to be looked for. List is shown once they are found
+    pub requested: bool,
+    // Some when device list popup is shown. First entry of list is this computer
+    pub popup: Option<ListState>,
+    // Device being cast to. None to play on this computer
+    pub target: Option<cast::CastDevice>,
+}
+
+// Audio devices known by player. Shown in a list over music section from which selected device is
+// played to and pinned in config
+pub struct OutputState {
+    // Name (as understood by player) and description of each device. First one is auto which is
+    // the default device of system
+    pub devices: Vec<(String, String)>,
+    // Some when device list popup is shown. This is the state of that list
+    pub popup: Option<ListState>,
+}
+
+// Comments of music being played. Shown in place of playlist and artist section like lyrics.
+// Comments are fetched in chunks and next chunk is requested when scrolled near the end
+pub struct CommentsState {
+    // true if comments pane is being shown
+    pub shown: bool,
+    // Id of music to which `list` belongs. Empty if nothing is being played
+    pub music_id: String,
+    pub list: Vec<fetcher::comments::Comment>,
+    // Token to fetch next chunk. None if every comment is already fetched
+    pub continuation: Option<String>,
+    // Set when next chunk is to be fetched. See communicator
+    pub more_requested: bool,
+    // Index of comment shown at the top of pane
+    pub scroll: usize,
+}
+
+// Music that is requested to play but is not yet seen playing. See State::check_playback_attempt
+pub struct PlaybackAttempt {
+    pub music_id: String,
+    // Url of music loaded in player. This changes as other routes are tried
+    pub url: String,
+    // Index of route to try next from fetcher::fallback_music_urls
+    pub next_route: usize,
+    // Urls of music queued after this music. Queue is loaded again along with other route
+    pub queue: Vec<String>,
+    // true once player is seen loading the url
+    pub is_loading: bool,
+    pub started_at: std::time::Instant,
+}
+
+pub struct FullPlaylistState {
+    // Id of playlist that user requested to load completely. None when nothing is requested or
+    // the request is already handled
+    pub requested: Option<String>,
+    // Id of playlist of which every music is loaded in `music`
+    pub id: String,
+    pub music: Vec<fetcher::MusicUnit>,
 }
 
 #[derive(Debug)]
@@ -343,15 +962,16 @@ pub struct PlaybackBehaviour {
     repeat: bool,
     // Current volume level. This is store here instead of fecthing with get_prop everytime
     volume: u8,
+    // true while playback is muted. Volume is left untouched so unmuting restores it
+    muted: bool,
 }
 
-pub struct State<'p> {
+pub struct State {
     sidebar: ListState,
     bottom: BottomState,
 
-    // String showing the status of player. This present status with single letter.
-    // For example. `P` and `_` are used to indicate the player status of playing and paused.
-    pub status: &'p str,
+    // Messages shown in status box. Set with State::info/warn/error
+    pub notifications: Notifications,
 
     // First memeber of tuple is the data being currently rendered in musicbar area and second is
     // that state of same list which define the hilighed item index.
@@ -374,6 +994,21 @@ pub struct State<'p> {
     // second member is the string of searchbar when use pressed ENTER last time in searchbar
     pub search: (String, String),
 
+    // Number of characters of search.0 after the cursor. Counted from the end so that cursor stays
+    // at the end when whole query is replaced (eg: by suggestion or on clear)
+    pub search_cursor: usize,
+
+    // Suggestions shown in dropdown below searchbar while typing.
+    // First member is the text that user have typed for which suggestions are fetched. This is
+    // kept seperate from search.0 because selecting the suggestion also change search.0 but
+    // suggestions should not be fetched again for selected one.
+    // Second member is the list of suggestions and third is the state of dropdown list
+    pub suggestions: (String, Vec<String>, ListState),
+
+    // true while lists are being filled with results of live search. Focus is then left in
+    // searchbar when results arrive so that typing can continue. See live_search_ms in config
+    pub live_search: bool,
+
     // Currently active window. In UI, this windows title is hilighted and keypress are evaluated
     // depending on active window
     pub active: Window,
@@ -388,4 +1023,133 @@ pub struct State<'p> {
 
     // See documentation for respective struct
     pub playback_behaviour: PlaybackBehaviour,
+
+    // true if music search only finds songs rather than every video. Initially this is
+    // constants.songs_only from config file and is toggled with search_scope shortcut key
+    pub songs_only: bool,
+
+    // Country code of region to show trending music from. Initially this is constants.region
+    // from config file and can be changed by searching `region:<code>`
+    pub trending_region: String,
+
+    // Category of trending music to show. This changes every time Trending is selected from
+    // sidebar while trending is already being shown
+    pub trending_category: fetcher::TrendingCategory,
+
+    // Country code of which charts is shown. Initially this is global charts and can be changed by
+    // searching `charts:<code>`. Selecting Charts again switches between global and constants.region
+    pub charts_country: String,
+
+    // Name of playlist/artist/music (by id) that lists were filled from. Only used to show where
+    // the content of list came from in its title. Anything opened by url has no name here
+    pub source_names: std::collections::HashMap<String, String>,
+
+    // Album being shown in musicbar if any. This is only used to show the album info and track
+    // number. Tracks are shown from musicbar as usual
+    pub album: Option<fetcher::Album>,
+
+    // Info of playlist being shown in musicbar as told by server. Drawn as header above music
+    // section along with album. Playlist listed in playlist section is used when this is not known
+    pub playlist_header: Option<fetcher::PlaylistUnit>,
+
+    // See documentation for respective struct
+    pub lyrics: LyricsState,
+
+    // See documentation for respective struct
+    pub podcast: PodcastState,
+
+    // See documentation for respective struct
+    pub full_playlist: FullPlaylistState,
+
+    // See documentation for respective struct
+    pub chapters: ChapterState,
+
+    // See documentation for respective struct
+    pub album_art: AlbumArtState,
+
+    // See documentation for respective struct
+    pub listen: ListenState,
+
+    // Whether desktop notification is sent when new music starts. Starts as in config and is
+    // switched with `notify` command
+    pub track_notifications: bool,
+
+    // See documentation for respective struct
+    pub filter: FilterState,
+
+    // See documentation for respective struct
+    pub marked: MarkState,
+
+    // Width of columns of music section. Only kept here as table borrows the widths while drawn
+    pub music_widths: Vec<tui::layout::Constraint>,
+
+    // Current sort of musicbar and id of music in the order they were fetched so that original
+    // order can be brought back. Sort is reset whenever musicbar is filled again
+    pub music_sort: (MusicSort, Vec<String>),
+
+    // Some when action menu of focused item is shown
+    pub actions: Option<ActionMenu>,
+
+    // Some while command line is open. This is the text typed after `:`. See ui::command
+    pub command: Option<String>,
+
+    // See documentation for respective struct
+    pub panes: Panes,
+
+    // See documentation for respective struct
+    pub moods: MoodState,
+
+    // See documentation for respective struct
+    pub cast: CastState,
+
+    // See documentation for respective struct
+    pub outputs: OutputState,
+
+    // See documentation for respective struct
+    pub comments: CommentsState,
+
+    // Some when play queue is shown. This is the state of that list. See ExtendMpv::queue
+    pub queue: Option<ListState>,
+
+    // Some when help overlay is shown. See documentation for respective struct
+    pub help: Option<HelpState>,
+
+    // Some when details popup of a music is shown. See DetailsState
+    pub details: Option<DetailsState>,
+
+    // Message drawn above the bottom bar for a moment after an action that has nothing to show in
+    // focused section (like queueing or copying url) and when it was set. See State::toast
+    pub toast: Option<(String, std::time::Instant)>,
+
+    // Info of the artist whose music is being shown, drawn as header above music section. This is
+    // fetched by communicator when music of other artist is shown. Fields other than id are left
+    // empty when info could not be fetched
+    pub artist_header: Option<fetcher::ChannelInfo>,
+
+    // Index of first shown row of sidebar and of music, playlist and artist list. Used to find the
+    // row under mouse. See utils::follow_offset
+    pub sidebar_offset: usize,
+    pub list_offset: [usize; 3],
+
+    // When fetching of music, playlist and artist list started. None when that list is not being
+    // fetched. Placeholder is shown in the list meanwhile
+    pub loading: [Option<std::time::Instant>; 3],
+
+    // true when there was nothing in the page of music, playlist and artist list last fetched
+    pub end_of_results: [bool; 3],
+
+    // Why music, playlist and artist list failed to fetch last time. This is shown inside the
+    // list until it is fetched again. Setting retry_requested fetches the same page again
+    pub fetch_error: [Option<&'static str>; 3],
+    pub retry_requested: [bool; 3],
+
+    // See documentation for respective struct
+    pub playback_attempt: Option<PlaybackAttempt>,
+
+    // Id of music that cannot be played from any route. These are shown crossed out in musicbar
+    pub unavailable: std::collections::HashSet<String>,
+
+    // Id of every favourite music, playlist and artist. Read from storage at start and kept in sync
+    // as they are added and removed so that favourites can be marked in lists and toggled
+    pub favourites: std::collections::HashSet<String>,
 }
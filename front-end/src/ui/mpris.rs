@@ -0,0 +1,555 @@
+use super::shared_import::*;
+use super::utils::ExtendMpv;
+use config::initilize::CONFIG;
+use futures_util::StreamExt;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Condvar;
+use zbus::{
+    fdo, interface,
+    zvariant::{ObjectPath, Value},
+    SignalContext,
+};
+
+// Player is exposed on session bus as MPRIS2 service so that desktop media controls, widgets and
+// playerctl can see what is being played and control it.
+// See https://specifications.freedesktop.org/mpris-spec/latest/
+// Nothing here is pushed by the rest of app. Instead state is looked at every POLL_INTERVAL and
+// properties that changed since last look are signaled to the bus
+const BUS_NAME: &str = "org.mpris.MediaPlayer2.ytui_music";
+const OBJECT_PATH: &str = "/org/mpris/MediaPlayer2";
+const NO_TRACK: &str = "/org/mpris/MediaPlayer2/TrackList/NoTrack";
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+// Desktops that do not hand media keys to MPRIS players by themselves (older GNOME, MATE,
+// Cinnamon) only send them to the application that grabbed them from their settings daemon.
+// Service, object path and interface of those daemons
+const MEDIA_KEYS_DAEMONS: [(&str, &str, &str); 2] = [
+    (
+        "org.gnome.SettingsDaemon.MediaKeys",
+        "/org/gnome/SettingsDaemon/MediaKeys",
+        "org.gnome.SettingsDaemon.MediaKeys",
+    ),
+    (
+        "org.mate.SettingsDaemon",
+        "/org/mate/SettingsDaemon/MediaKeys",
+        "org.mate.SettingsDaemon.MediaKeys",
+    ),
+];
+const MEDIA_KEYS_APP: &str = "ytui_music";
+// Elapsed time is only updated by ui on every refresh so it is a bit behind. Position that is this
+// far from where it was expected to be means music was seeked
+const SEEK_THRESHOLD: Duration = Duration::from_secs(3);
+
+// What was last told to the bus. Compared with the current one to know what have changed
+#[derive(Default, PartialEq)]
+struct Snapshot {
+    status: &'static str,
+    // Path of music being played as reported by player. Metadata changes along with it
+    path: String,
+    title: String,
+    duration: Duration,
+    position: Duration,
+    volume: u8,
+    shuffle: bool,
+    repeat: bool,
+    live: bool,
+}
+
+impl Snapshot {
+    fn of(state: &super::State) -> Self {
+        Snapshot {
+            status: playback_status(state),
+            path: state.bottom.artist.0.clone(),
+            title: state
+                .bottom
+                .playing
+                .as_ref()
+                .map(|(title, _)| title.clone())
+                .unwrap_or_default(),
+            duration: state.bottom.music_duration,
+            position: state.bottom.music_elapse,
+            volume: state.playback_behaviour.volume,
+            shuffle: state.playback_behaviour.shuffle,
+            repeat: state.playback_behaviour.repeat,
+            live: state.playing_live(),
+        }
+    }
+}
+
+fn playback_status(state: &super::State) -> &'static str {
+    match state.bottom.playing {
+        Some((_, true)) => "Playing",
+        Some((_, false)) => "Paused",
+        None => "Stopped",
+    }
+}
+
+// Object path only allows ascii letters, digits and underscore so everything else in path of music
+// is replaced with underscore
+fn track_id(path: &str) -> ObjectPath<'static> {
+    if path.is_empty() {
+        return ObjectPath::from_static_str_unchecked(NO_TRACK);
+    }
+    let id = path
+        .chars()
+        .map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '_' })
+        .collect::<String>();
+    ObjectPath::try_from(format!("/org/ytui_music/track/{}", id))
+        .unwrap_or_else(|_| ObjectPath::from_static_str_unchecked(NO_TRACK))
+}
+
+fn micros(duration: Duration) -> i64 {
+    duration.as_micros().try_into().unwrap_or(i64::MAX)
+}
+
+// org.mpris.MediaPlayer2. Nothing much to do here as terminal cannot be raised and quitting is
+// left to user
+struct Root;
+
+#[interface(name = "org.mpris.MediaPlayer2")]
+impl Root {
+    fn raise(&self) {}
+
+    fn quit(&self) {}
+
+    #[zbus(property)]
+    fn can_quit(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn can_raise(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn has_track_list(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn identity(&self) -> &str {
+        "ytui-music"
+    }
+
+    #[zbus(property)]
+    fn supported_uri_schemes(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    #[zbus(property)]
+    fn supported_mime_types(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+// org.mpris.MediaPlayer2.Player. Every method does what the respective shortcut key does
+struct Player {
+    state: Arc<Mutex<super::State>>,
+    notifier: Arc<Condvar>,
+    // Set when Seeked is already signaled for seek requested over the bus so that the jump is not
+    // signaled again once it is seen by serve
+    seeked: AtomicBool,
+}
+
+impl Player {
+    fn new(state: &Arc<Mutex<super::State>>, notifier: &Arc<Condvar>) -> Self {
+        Player {
+            state: Arc::clone(state),
+            notifier: Arc::clone(notifier),
+            seeked: AtomicBool::new(false),
+        }
+    }
+
+    // Ui is redrawn after every change so that it is seen in app too
+    fn with_state<T>(&self, change: impl FnOnce(&mut super::State) -> T) -> T {
+        let result = change(&mut self.state.lock().unwrap());
+        self.notifier.notify_all();
+        result
+    }
+
+    // Elapsed time is set to where it was seeked to right away so that Seeked tells the position
+    // controller will see from now on
+    async fn signal_seeked(&self, ctxt: &SignalContext<'_>, position: Option<Duration>) {
+        if let Some(position) = position {
+            self.state.lock().unwrap().bottom.music_elapse = position;
+            self.seeked.store(true, Ordering::Relaxed);
+            Player::seeked(ctxt, micros(position)).await.ok();
+        }
+    }
+}
+
+#[interface(name = "org.mpris.MediaPlayer2.Player")]
+impl Player {
+    fn next(&self) {
+        self.with_state(|state| {
+            state.playback_attempt = None;
+            state.player.play_next();
+        });
+    }
+
+    fn previous(&self) {
+        self.with_state(|state| {
+            state.playback_attempt = None;
+            state.player.play_prev();
+        });
+    }
+
+    fn pause(&self) {
+        self.with_state(|state| {
+            if let Some((_, true)) = state.bottom.playing {
+                state.toggle_pause();
+            }
+        });
+    }
+
+    fn play(&self) {
+        self.with_state(|state| {
+            if let Some((_, false)) = state.bottom.playing {
+                state.toggle_pause();
+            }
+        });
+    }
+
+    fn play_pause(&self) {
+        self.with_state(|state| state.toggle_pause());
+    }
+
+    // Nothing is unloaded from player so stopping is same as pausing
+    fn stop(&self) {
+        self.pause();
+    }
+
+    // Offset is in microseconds and is negative to seek backward. Seeking past the end plays the
+    // next music in which case there is nothing to signal
+    async fn seek(&self, offset: i64, #[zbus(signal_context)] ctxt: SignalContext<'_>) {
+        let position = self.with_state(|state| {
+            if state.playing_live() || state.bottom.playing.is_none() {
+                return None;
+            }
+            let secs = offset as f64 / 1_000_000.0;
+            if secs >= 0.0 {
+                state.player.seek_forward(secs).ok();
+            } else {
+                state.player.seek_backward(-secs).ok();
+            }
+            let target = state.bottom.music_elapse.as_secs_f64() + secs;
+            (target < state.bottom.music_duration.as_secs_f64())
+                .then(|| Duration::from_secs_f64(target.max(0.0)))
+        });
+        self.signal_seeked(&ctxt, position).await;
+    }
+
+    // Position is ignored if it is not for the music being played anymore
+    async fn set_position(
+        &self,
+        track: ObjectPath<'_>,
+        position: i64,
+        #[zbus(signal_context)] ctxt: SignalContext<'_>,
+    ) {
+        let position = self.with_state(|state| {
+            if state.playing_live() || track != track_id(&state.bottom.artist.0) {
+                return None;
+            }
+            let secs = position as f64 / 1_000_000.0;
+            let is_valid = (0.0..=state.bottom.music_duration.as_secs_f64()).contains(&secs);
+            (is_valid && state.player.set_property("time-pos", secs).is_ok())
+                .then(|| Duration::from_secs_f64(secs))
+        });
+        self.signal_seeked(&ctxt, position).await;
+    }
+
+    fn open_uri(&self, _uri: &str) -> fdo::Result<()> {
+        Err(fdo::Error::NotSupported(String::from(
+            "Opening uri is not supported",
+        )))
+    }
+
+    #[zbus(signal)]
+    async fn seeked(ctxt: &SignalContext<'_>, position: i64) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn playback_status(&self) -> &str {
+        playback_status(&self.state.lock().unwrap())
+    }
+
+    // Repeat is toggled between all and one in app so None is never reported
+    #[zbus(property)]
+    fn loop_status(&self) -> &str {
+        if self.state.lock().unwrap().playback_behaviour.repeat {
+            "Playlist"
+        } else {
+            "Track"
+        }
+    }
+
+    // Same as toggling repeat in app. There is no way to not repeat at all there
+    #[zbus(property)]
+    fn set_loop_status(&self, loop_status: &str) -> zbus::Result<()> {
+        let all = match loop_status {
+            "Playlist" => true,
+            "Track" => false,
+            _ => {
+                return Err(fdo::Error::NotSupported(format!(
+                    "Loop status {} is not supported",
+                    loop_status
+                ))
+                .into())
+            }
+        };
+        self.with_state(|state| state.set_repeat(all));
+        Ok(())
+    }
+
+    #[zbus(property)]
+    fn rate(&self) -> f64 {
+        1.0
+    }
+
+    // Music is always played at normal speed. Only rate in between minimum and maximum is allowed
+    #[zbus(property)]
+    fn set_rate(&self, rate: f64) -> zbus::Result<()> {
+        if rate == 1.0 {
+            Ok(())
+        } else {
+            Err(fdo::Error::NotSupported(String::from("Changing rate is not supported")).into())
+        }
+    }
+
+    #[zbus(property)]
+    fn minimum_rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn maximum_rate(&self) -> f64 {
+        1.0
+    }
+
+    #[zbus(property)]
+    fn shuffle(&self) -> bool {
+        self.state.lock().unwrap().playback_behaviour.shuffle
+    }
+
+    #[zbus(property)]
+    fn set_shuffle(&self, shuffle: bool) {
+        self.with_state(|state| {
+            if shuffle {
+                state.player.shuffle();
+            } else {
+                state.player.unshuffle();
+            }
+            state.playback_behaviour.shuffle = shuffle;
+        });
+    }
+
+    // Art is the youtube thumbnail. Music from other places have none
+    #[zbus(property)]
+    fn metadata(&self) -> HashMap<&str, Value<'_>> {
+        let state = self.state.lock().unwrap();
+        let mut metadata = HashMap::new();
+        let (path, artist) = &state.bottom.artist;
+        metadata.insert("mpris:trackid", Value::from(track_id(path)));
+        if let Some((title, _)) = &state.bottom.playing {
+            metadata.insert("xesam:title", Value::from(title.clone()));
+            metadata.insert("xesam:url", Value::from(path.clone()));
+            if !artist.is_empty() {
+                metadata.insert("xesam:artist", Value::from(vec![artist.clone()]));
+            }
+            if !state.bottom.music_duration.is_zero() {
+                metadata.insert(
+                    "mpris:length",
+                    Value::from(micros(state.bottom.music_duration)),
+                );
+            }
+            if let Some(music_id) = fetcher::music_id_from_url(path) {
+                metadata.insert(
+                    "mpris:artUrl",
+                    Value::from(fetcher::thumbnail::thumbnail_url(&music_id)),
+                );
+            }
+        }
+        metadata
+    }
+
+    #[zbus(property)]
+    fn volume(&self) -> f64 {
+        self.state.lock().unwrap().playback_behaviour.volume as f64 / 100.0
+    }
+
+    #[zbus(property)]
+    fn set_volume(&self, volume: f64) {
+        let volume = (volume * 100.0).round().clamp(0.0, 100.0);
+        self.with_state(|state| {
+            if state.player.set_property("volume", volume).is_ok() {
+                state.playback_behaviour.volume = volume as u8;
+            }
+        });
+    }
+
+    #[zbus(property(emits_changed_signal = "false"))]
+    fn position(&self) -> i64 {
+        micros(self.state.lock().unwrap().bottom.music_elapse)
+    }
+
+    #[zbus(property)]
+    fn can_go_next(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_go_previous(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_play(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_pause(&self) -> bool {
+        true
+    }
+
+    #[zbus(property)]
+    fn can_seek(&self) -> bool {
+        !self.state.lock().unwrap().playing_live()
+    }
+
+    #[zbus(property(emits_changed_signal = "const"))]
+    fn can_control(&self) -> bool {
+        true
+    }
+}
+
+// Grab the media keys from first settings daemon that is running and control the player as they
+// are pressed. Keys are received even when terminal is not focused. Returns when no daemon is found
+async fn listen_media_keys(connection: zbus::Connection, player: Player) {
+    for (service, path, interface) in MEDIA_KEYS_DAEMONS {
+        let daemon = match zbus::Proxy::new(&connection, service, path, interface).await {
+            Ok(daemon) => daemon,
+            Err(_) => continue,
+        };
+        // Listen before grabbing so that no key pressed in between is missed
+        let mut pressed = match daemon.receive_signal("MediaPlayerKeyPressed").await {
+            Ok(pressed) => pressed,
+            Err(_) => continue,
+        };
+        if daemon
+            .call_method("GrabMediaPlayerKeys", &(MEDIA_KEYS_APP, 0u32))
+            .await
+            .is_err()
+        {
+            continue;
+        }
+
+        while let Some(message) = pressed.next().await {
+            // Signal is sent to every application that have ever grabbed the keys
+            match message.body().deserialize::<(String, String)>() {
+                Ok((application, key)) if application == MEDIA_KEYS_APP => match key.as_str() {
+                    "Play" => player.play_pause(),
+                    "Pause" | "Stop" => player.pause(),
+                    "Next" => player.next(),
+                    "Previous" => player.previous(),
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+        return;
+    }
+}
+
+// Serve the player on session bus until app is quit. Nothing is served when mpris is turned off in
+// config or there is no session bus (eg: over ssh or outside of linux/bsd desktop)
+pub async fn serve(state_original: &mut Arc<Mutex<super::State>>, notifier: &mut Arc<Condvar>) {
+    if !CONFIG.constants.mpris {
+        return;
+    }
+
+    let player = Player::new(state_original, notifier);
+    let connection = match zbus::connection::Builder::session()
+        .and_then(|builder| builder.name(BUS_NAME))
+        .and_then(|builder| builder.serve_at(OBJECT_PATH, Root))
+        .and_then(|builder| builder.serve_at(OBJECT_PATH, player))
+    {
+        Ok(builder) => builder.build().await,
+        Err(err) => Err(err),
+    };
+    // Connection is kept alive (and so is the service) as long as it is not dropped
+    let connection = match connection {
+        Ok(connection) => connection,
+        Err(_) => return,
+    };
+    let player_ref = match connection
+        .object_server()
+        .interface::<_, Player>(OBJECT_PATH)
+        .await
+    {
+        Ok(player_ref) => player_ref,
+        Err(_) => return,
+    };
+    tokio::spawn(listen_media_keys(
+        connection.clone(),
+        Player::new(state_original, notifier),
+    ));
+
+    let mut last = Snapshot::default();
+    let mut last_at = std::time::Instant::now();
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+
+        let current = {
+            let state = state_original.lock().unwrap();
+            if state.active == super::Window::None {
+                break;
+            }
+            Snapshot::of(&state)
+        };
+        let seeked_here = player_ref.get().await.seeked.swap(false, Ordering::Relaxed);
+        if current == last {
+            last_at = std::time::Instant::now();
+            continue;
+        }
+
+        let player = player_ref.get().await;
+        let ctxt = player_ref.signal_context();
+        if current.status != last.status {
+            player.playback_status_changed(ctxt).await.ok();
+        }
+        if (&current.path, &current.title, current.duration)
+            != (&last.path, &last.title, last.duration)
+        {
+            player.metadata_changed(ctxt).await.ok();
+        }
+        if current.volume != last.volume {
+            player.volume_changed(ctxt).await.ok();
+        }
+        if current.shuffle != last.shuffle {
+            player.shuffle_changed(ctxt).await.ok();
+        }
+        if current.repeat != last.repeat {
+            player.loop_status_changed(ctxt).await.ok();
+        }
+        if current.live != last.live {
+            player.can_seek_changed(ctxt).await.ok();
+        }
+
+        // Position is not signaled as it changes. Only the jump is told (whether it was seeked from
+        // here or from inside the app) so that others can keep counting from there. Seek requested
+        // over the bus is already signaled
+        let expected = if last.status == "Playing" {
+            last.position + last_at.elapsed()
+        } else {
+            last.position
+        };
+        let drift = current.position.abs_diff(expected);
+        if current.path == last.path && drift > SEEK_THRESHOLD && !seeked_here {
+            Player::seeked(ctxt, micros(current.position)).await.ok();
+        }
+
+        last = current;
+        last_at = std::time::Instant::now();
+    }
+}
@@ -0,0 +1,141 @@
+use super::shared_import::*;
+use config::initilize::CONFIG;
+use rumqttc::{AsyncClient, Event, LastWill, MqttOptions, Outgoing, Packet, QoS};
+use serde_json::Value;
+use std::sync::Condvar;
+
+// Playback state is published to MQTT broker set in config for home automation (eg: Home Assistant
+// with mosquitto). Every message is retained so that whoever subscribes later gets the latest one.
+// Topics under mqtt_topic_prefix are:
+//
+// <prefix>/availability: online while app is running. offline (as last will) once it is not
+// <prefix>/state: playing, paused or stopped
+// <prefix>/status: json same as status of remote control (title, artist, url, duration, volume..)
+//
+// Status is published whenever anything in it other than position changes. Position in it is the
+// one at that time
+const CHECK_INTERVAL: Duration = Duration::from_millis(500);
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+// Last messages are given this long to reach broker when app quits
+const QUIT_TIMEOUT: Duration = Duration::from_secs(2);
+const KEEP_ALIVE: Duration = Duration::from_secs(30);
+const DEFAULT_PORT: u16 = 1883;
+
+// Host and port of mqtt_broker like localhost or 192.168.1.10:1883
+fn broker_address(broker: &str) -> (String, u16) {
+    match broker.rsplit_once(':') {
+        Some((host, port)) => match port.parse() {
+            Ok(port) => (host.to_string(), port),
+            Err(_) => (broker.to_string(), DEFAULT_PORT),
+        },
+        None => (broker.to_string(), DEFAULT_PORT),
+    }
+}
+
+// Everything but position which changes all the time
+fn without_position(status: &Value) -> Value {
+    let mut status = status.clone();
+    if let Some(status) = status.as_object_mut() {
+        status.remove("position");
+    }
+    status
+}
+
+// Publish until app is quit. Nothing is done when mqtt_broker is empty. Connection is made again
+// when broker goes away and everything is then published again
+pub async fn serve(state_original: &mut Arc<Mutex<super::State>>, notifier: &mut Arc<Condvar>) {
+    let constants = &CONFIG.constants;
+    let broker = constants.mqtt_broker.trim();
+    if broker.is_empty() {
+        return;
+    }
+    let prefix = constants.mqtt_topic_prefix.trim_end_matches('/');
+    let topic = |name: &str| format!("{}/{}", prefix, name);
+
+    let (host, port) = broker_address(broker);
+    let mut options = MqttOptions::new(format!("ytui_music-{}", std::process::id()), host, port);
+    options.set_keep_alive(KEEP_ALIVE);
+    options.set_last_will(LastWill::new(
+        topic("availability"),
+        "offline",
+        QoS::AtLeastOnce,
+        true,
+    ));
+    if !constants.mqtt_username.is_empty() {
+        options.set_credentials(&constants.mqtt_username, &constants.mqtt_password);
+    }
+    let (client, mut event_loop) = AsyncClient::new(options, 16);
+
+    // Messages are only sent while event loop is polled. Poller tells when it have (re)connected so
+    // that everything is published again
+    let (connected_sender, mut connected) = tokio::sync::mpsc::unbounded_channel();
+    let poller = {
+        let state_original = Arc::clone(state_original);
+        let notifier = Arc::clone(notifier);
+        tokio::spawn(async move {
+            let mut is_failing = false;
+            loop {
+                match event_loop.poll().await {
+                    Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                        is_failing = false;
+                        if connected_sender.send(()).is_err() {
+                            break;
+                        }
+                    }
+                    Ok(Event::Outgoing(Outgoing::Disconnect)) => break,
+                    Ok(_) => {}
+                    Err(_) => {
+                        // Error is shown once until connection is made again
+                        if !is_failing {
+                            is_failing = true;
+                            state_original
+                                .lock()
+                                .unwrap()
+                                .error("Cannot connect to MQTT..");
+                            notifier.notify_all();
+                        }
+                        tokio::time::sleep(RECONNECT_DELAY).await;
+                    }
+                }
+            }
+        })
+    };
+
+    // Messages are dropped instead of waiting while broker is away. They are published again on
+    // reconnect anyway
+    let publish = |name: &str, payload: String| {
+        client
+            .try_publish(topic(name), QoS::AtLeastOnce, true, payload)
+            .ok();
+    };
+    let mut published: Option<Value> = None;
+    loop {
+        let status = {
+            let state = state_original.lock().unwrap();
+            if state.active == super::Window::None {
+                break;
+            }
+            super::remote::status(&state)
+        };
+        if connected.try_recv().is_ok() {
+            publish("availability", "online".to_string());
+            published = None;
+        }
+        let compared = without_position(&status);
+        if published.as_ref() != Some(&compared) {
+            publish(
+                "state",
+                status["state"].as_str().unwrap_or_default().to_string(),
+            );
+            publish("status", status.to_string());
+            published = Some(compared);
+        }
+        tokio::time::sleep(CHECK_INTERVAL).await;
+    }
+
+    // Nothing is played once app is gone
+    publish("state", "stopped".to_string());
+    publish("availability", "offline".to_string());
+    client.try_disconnect().ok();
+    tokio::time::timeout(QUIT_TIMEOUT, poller).await.ok();
+}
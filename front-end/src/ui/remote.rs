@@ -0,0 +1,461 @@
+use super::shared_import::*;
+use super::utils::ExtendMpv;
+use config::initilize::CONFIG;
+use serde_json::{json, Value};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Condvar;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+// Small http server to control the player from phone, scripts or Home Assistant. It listens on
+// remote_host (localhost unless set otherwise) and every request have to carry the token from
+// config either as `Authorization: Bearer <token>` header or `token` query parameter. Parameters
+// are always passed in query (even for POST) so that it can be used with plain curl. Every
+// response is json except the page below.
+//
+// GET  /                     Remote page for phone (see remote.html). Page itself have nothing to
+//                            protect so it is served without token. It takes the token from it's
+//                            own url (/?token=...) and uses it for everything it requests
+// GET  /status               What is being played, position, volume, shuffle, repeat, queue
+// GET  /music                Music shown in music section
+// GET  /queue                Play queue (with names) and index of music being played
+// POST /play                 Resume. With `index` plays that music of music section (queueing the
+//                            rest as pressing Enter does) and with `id` (or url) plays only that
+// POST /pause, /toggle, /next, /previous
+// POST /seek?secs=-10        Seek by this many seconds. Negative to seek backward
+// POST /volume?level=50      Set the volume (0 to 100). +10 or -10 changes it by that much
+// POST /queue?id=...         Add music (id or url) to the end of play queue
+// POST /search?q=...         Search as if typed in searchbar. Results are then in /music
+// POST /quit                 Quit the app. Mostly to stop the one running with --daemon
+//
+// Nothing is pushed from rest of app. State is read and changed under the same lock as everywhere
+const REMOTE_PAGE: &str = include_str!("remote.html");
+// Requests are small. Anything larger than this is not from someone we want to talk to
+const MAX_REQUEST_SIZE: usize = 8 * 1024;
+// Listener stops waiting for connection this often to check if app have been quit
+const QUIT_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+struct Request {
+    method: String,
+    path: String,
+    params: Vec<(String, String)>,
+    authorization: Option<String>,
+}
+
+impl Request {
+    // Only the request line and headers are read. Body is never needed
+    fn parse(head: &str) -> Option<Self> {
+        let mut lines = head.lines();
+        let mut request_line = lines.next()?.split_whitespace();
+        let method = request_line.next()?.to_string();
+        let target = request_line.next()?;
+        let url = reqwest::Url::parse(&format!("http://localhost{}", target)).ok()?;
+        let authorization = lines
+            .filter_map(|line| line.split_once(':'))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case("authorization"))
+            .map(|(_, value)| value.trim().to_string());
+
+        Some(Request {
+            method,
+            path: url.path().trim_end_matches('/').to_string(),
+            params: url.query_pairs().into_owned().collect(),
+            authorization,
+        })
+    }
+
+    fn is_authorized(&self) -> bool {
+        let token = CONFIG.constants.remote_token.as_str();
+        let from_header = self
+            .authorization
+            .as_deref()
+            .and_then(|value| value.strip_prefix("Bearer "));
+        from_header
+            .or_else(|| param(&self.params, "token"))
+            .is_some_and(|given| tokens_match(given, token))
+    }
+
+    fn is_page(&self) -> bool {
+        self.method == "GET" && self.path.is_empty()
+    }
+}
+
+// Compare every byte even after a mismatch so that time taken does not tell how much of the token
+// was guessed right. Only the length can be learnt this way
+fn tokens_match(given: &str, token: &str) -> bool {
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn seconds(duration: Duration) -> f64 {
+    duration.as_secs_f64()
+}
+
+pub(super) fn status(state: &super::State) -> Value {
+    let (path, artist) = &state.bottom.artist;
+    let (title, playback) = match &state.bottom.playing {
+        Some((title, true)) => (title.as_str(), "playing"),
+        Some((title, false)) => (title.as_str(), "paused"),
+        None => ("", "stopped"),
+    };
+    // Position in queue is counted from 1 like it is shown in queue popup. 0 when nothing is queued
+    let queue_length = state
+        .player
+        .get_property::<i64>("playlist-count")
+        .unwrap_or_default();
+    let queue_position = state
+        .player
+        .get_property::<i64>("playlist-pos")
+        .map_or(0, |index| index + 1);
+    let thumbnail = fetcher::music_id_from_url(path)
+        .filter(|_| state.bottom.playing.is_some())
+        .map(|music_id| fetcher::thumbnail::thumbnail_url(&music_id));
+    json!({
+        "state": playback,
+        "title": title,
+        "artist": artist,
+        "url": path,
+        "thumbnail": thumbnail,
+        "live": state.playing_live(),
+        "position": seconds(state.bottom.music_elapse),
+        "duration": seconds(state.bottom.music_duration),
+        "volume": state.playback_behaviour.volume,
+        "muted": state.playback_behaviour.muted,
+        "shuffle": state.playback_behaviour.shuffle,
+        "repeat": if state.playback_behaviour.repeat { "all" } else { "one" },
+        "queue_length": queue_length,
+        "queue_position": queue_position,
+    })
+}
+
+fn music_list(state: &super::State) -> Value {
+    let music = state
+        .musicbar
+        .0
+        .iter()
+        .enumerate()
+        .map(|(index, music)| {
+            json!({
+                "index": index,
+                "id": music.id,
+                "title": music.name,
+                "artist": music.artist,
+                "duration": music.duration,
+                "url": fetcher::music_url(&music.id),
+            })
+        })
+        .collect::<Vec<_>>();
+    json!({ "music": music })
+}
+
+fn param<'p>(params: &'p [(String, String)], name: &str) -> Option<&'p str> {
+    params
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+// Actions that only tell what is going on. These are the GET requests of http server. Queue is
+// only listed when no music is given to add in it
+pub(super) fn is_query(action: &str, params: &[(String, String)]) -> bool {
+    matches!(action, "status" | "music") || (action == "queue" && param(params, "id").is_none())
+}
+
+// Do the action (named same as http endpoint without `/`) and tell the result. This is shared by
+// http server, control socket (see __ctl.rs__) and request given on command line when app is
+// started (see STARTUP_REQUEST in __cli.rs__). None if there is no such action
+pub fn control(
+    state: &mut super::State,
+    action: &str,
+    params: &[(String, String)],
+) -> Option<Result<Value, &'static str>> {
+    let ok = Ok(json!({ "ok": true }));
+    let result = match action {
+        "status" => Ok(status(state)),
+        "music" => Ok(music_list(state)),
+        "queue" => match param(params, "id") {
+            Some(music_id) => {
+                let url = fetcher::music_url(music_id);
+                state
+                    .player
+                    .command("loadfile", [url.as_str(), "append-play"].as_ref())
+                    .ok();
+                state.info("Added to queue..");
+                ok
+            }
+            None => {
+                let (entries, current) = state.player.queue();
+                let names = state.queue_names();
+                Ok(json!({ "queue": entries, "names": names, "current": current }))
+            }
+        },
+
+        "play" => {
+            if let Some(index) = param(params, "index") {
+                match index
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| state.musicbar.0.get(index).map(|music| music.id.clone()))
+                {
+                    Some(music_id) => {
+                        state.play_music(&music_id);
+                        ok
+                    }
+                    None => Err("No music at that index"),
+                }
+            } else if let Some(music_id) = param(params, "id") {
+                state.play_single(music_id);
+                ok
+            } else {
+                if let Some((_, false)) = state.bottom.playing {
+                    state.toggle_pause();
+                }
+                ok
+            }
+        }
+        "pause" => {
+            if let Some((_, true)) = state.bottom.playing {
+                state.toggle_pause();
+            }
+            ok
+        }
+        "toggle" => {
+            state.toggle_pause();
+            ok
+        }
+        "next" => {
+            state.playback_attempt = None;
+            state.player.play_next();
+            ok
+        }
+        "previous" => {
+            state.playback_attempt = None;
+            state.player.play_prev();
+            ok
+        }
+        "seek" => match param(params, "secs").map(str::parse::<f64>) {
+            Some(Ok(_)) if state.playing_live() => Err("Live stream cannot be seeked"),
+            Some(Ok(secs)) if secs >= 0.0 => {
+                state.player.seek_forward(secs).ok();
+                ok
+            }
+            Some(Ok(secs)) => {
+                state.player.seek_backward(-secs).ok();
+                ok
+            }
+            _ => Err("Expected secs"),
+        },
+        // Level with sign (eg: +10 or -10) changes the volume by that much
+        "volume" => {
+            let current = state.playback_behaviour.volume as i64;
+            let level = param(params, "level").and_then(|level| {
+                if level.starts_with(['+', '-']) {
+                    level
+                        .parse::<i64>()
+                        .ok()
+                        .map(|change| current.saturating_add(change))
+                } else {
+                    level.parse::<i64>().ok()
+                }
+            });
+            match level {
+                Some(level) => {
+                    let level = level.clamp(0, 100);
+                    if state.player.set_property("volume", level).is_ok() {
+                        state.playback_behaviour.volume = level as u8;
+                    }
+                    ok
+                }
+                None => Err("Expected level from 0 to 100"),
+            }
+        }
+        "search" => match param(params, "q").map(str::trim) {
+            Some(query) if !query.is_empty() => {
+                let songs_only = state.songs_only;
+                state.fetched_page = [Some(0); 3];
+                state.filled_source.0 =
+                    super::MusicbarSource::Search(query.to_string(), songs_only);
+                state.filled_source.1 = super::PlaylistbarSource::Search(query.to_string());
+                state.filled_source.2 = super::ArtistbarSource::Search(query.to_string());
+                ok
+            }
+            _ => Err("Expected q"),
+        },
+        "quit" => {
+            state.quit();
+            ok
+        }
+        _ => return None,
+    };
+    Some(result)
+}
+
+// Status code and body of response to the request. Ui is redrawn after anything is changed
+fn respond(
+    request: &Request,
+    state_original: &Mutex<super::State>,
+    notifier: &Condvar,
+) -> (u16, Value) {
+    if !request.is_authorized() {
+        return (401, json!({ "error": "Invalid token" }));
+    }
+
+    let action = request.path.trim_start_matches('/');
+    let method = if is_query(action, &request.params) {
+        "GET"
+    } else {
+        "POST"
+    };
+    if request.method != method {
+        return (404, json!({ "error": "Not found" }));
+    }
+
+    let result = control(&mut state_original.lock().unwrap(), action, &request.params);
+    notifier.notify_all();
+    match result {
+        Some(Ok(body)) => (200, body),
+        Some(Err(reason)) => (400, json!({ "error": reason })),
+        None => (404, json!({ "error": "Not found" })),
+    }
+}
+
+fn reason_phrase(code: u16) -> &'static str {
+    match code {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        _ => "",
+    }
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    state_original: Arc<Mutex<super::State>>,
+    notifier: Arc<Condvar>,
+) {
+    // Read until end of headers. Client that is too slow or sends too much is just dropped
+    let mut head = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+        match tokio::time::timeout(READ_TIMEOUT, stream.read(&mut buffer)).await {
+            Ok(Ok(read)) if read > 0 && head.len() + read <= MAX_REQUEST_SIZE => {
+                head.extend_from_slice(&buffer[..read]);
+            }
+            _ => return,
+        }
+    }
+
+    let json = "application/json";
+    let (code, content_type, body) = match Request::parse(&String::from_utf8_lossy(&head)) {
+        Some(request) if request.is_page() => {
+            (200, "text/html; charset=utf-8", REMOTE_PAGE.to_string())
+        }
+        Some(request) => {
+            let (code, body) = respond(&request, &state_original, &notifier);
+            (code, json, body.to_string())
+        }
+        None => (400, json, json!({ "error": "Invalid request" }).to_string()),
+    };
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        code,
+        reason_phrase(code),
+        content_type,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await.ok();
+    stream.shutdown().await.ok();
+}
+
+// Address of this computer in local network. Socket is only connected (nothing is sent) so that
+// system picks the interface that would be used to reach outside
+fn lan_ip() -> Option<IpAddr> {
+    let socket = std::net::UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:80").ok()?;
+    Some(socket.local_addr().ok()?.ip())
+}
+
+// Url to open remote page at. When listening on every interface (0.0.0.0) address in local network
+// is shown as that is what phone can reach. Token is left out as url is shown on screen
+fn page_url(address: SocketAddr) -> String {
+    let ip = match address.ip() {
+        ip if ip.is_unspecified() => lan_ip().unwrap_or(ip),
+        ip => ip,
+    };
+    format!("http://{}/", SocketAddr::new(ip, address.port()))
+}
+
+// Serve until app is quit. Nothing is served when remote_port is 0. Token is required so server is
+// not started without it
+pub async fn serve(state_original: &mut Arc<Mutex<super::State>>, notifier: &mut Arc<Condvar>) {
+    if CONFIG.constants.remote_port == 0 {
+        return;
+    }
+    if CONFIG.constants.remote_token.is_empty() {
+        state_original.lock().unwrap().error("Remote needs token..");
+        return;
+    }
+    let host = CONFIG.constants.remote_host.trim();
+    let listener = match TcpListener::bind((host, CONFIG.constants.remote_port)).await {
+        Ok(listener) => listener,
+        Err(_) => {
+            state_original
+                .lock()
+                .unwrap()
+                .error("Cannot start remote server..");
+            return;
+        }
+    };
+    if let Ok(address) = listener.local_addr() {
+        state_original
+            .lock()
+            .unwrap()
+            .info(format!("Remote page at {}..", page_url(address)));
+    }
+
+    loop {
+        if state_original.lock().unwrap().active == super::Window::None {
+            break;
+        }
+        if let Ok(Ok((stream, _))) =
+            tokio::time::timeout(QUIT_CHECK_INTERVAL, listener.accept()).await
+        {
+            tokio::spawn(handle_connection(
+                stream,
+                Arc::clone(state_original),
+                Arc::clone(notifier),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn url_of_page() {
+        let address = SocketAddr::from(([192, 168, 1, 10], 8686));
+        assert_eq!(page_url(address), "http://192.168.1.10:8686/");
+        let address = SocketAddr::from(([127, 0, 0, 1], 8686));
+        assert_eq!(page_url(address), "http://127.0.0.1:8686/");
+        let address = SocketAddr::from(([0, 0, 0, 0, 0, 0, 0, 1], 8686));
+        assert_eq!(page_url(address), "http://[::1]:8686/");
+    }
+
+    #[test]
+    fn token_comparison() {
+        assert!(tokens_match("s3cret", "s3cret"));
+        assert!(!tokens_match("s3cres", "s3cret"));
+        assert!(!tokens_match("s3cre", "s3cret"));
+        assert!(!tokens_match("s3crett", "s3cret"));
+        assert!(!tokens_match("", "s3cret"));
+        assert!(tokens_match("", ""));
+    }
+}
@@ -0,0 +1,238 @@
+use super::shared_import::*;
+use config::initilize::CONFIG;
+use fetcher::ExtendDuration;
+use std::sync::Condvar;
+
+// Music being played is written as one line of text to status_file in config so that polybar,
+// waybar, i3status or tmux can show it. Line is formatted from status_format by replacing the
+// placeholders below and is empty when nothing is being played.
+//
+// {title} {artist} {elapsed} {duration} {state} {volume}
+//
+// Regular file is replaced whenever the line changes, so it can be read at any time (eg: with
+// `cat` from tmux). Named pipe instead gets a line every WRITE_INTERVAL while something reads from
+// it (eg: `tail -F` script of polybar or waybar)
+//
+// now_playing_file is written the same way from now_playing_format for text source of OBS (which
+// reloads file when it changes). Text there is written as it is, so it can span many lines
+const WRITE_INTERVAL: Duration = Duration::from_secs(1);
+
+struct Output {
+    path: std::path::PathBuf,
+    format: &'static str,
+    // Status bars read lines so line is ended with newline. Overlay shows the text as it is
+    newline: bool,
+    // Text last written to regular file
+    written: Option<String>,
+}
+
+impl Output {
+    fn from_config(path: &str, format: &'static str, newline: bool) -> Option<Self> {
+        match path.trim() {
+            "" => None,
+            path => Some(Output {
+                path: std::path::PathBuf::from(path),
+                format,
+                newline,
+                written: None,
+            }),
+        }
+    }
+
+    fn write(&mut self, text: &str) -> std::io::Result<()> {
+        let mut content = text.to_string();
+        if self.newline {
+            content.push('\n');
+        }
+        if is_fifo(&self.path) {
+            write_fifo(&self.path, &content)
+        } else if self.written.as_deref() != Some(text) {
+            // Not tried again until text changes so that error is not shown every second
+            self.written = Some(text.to_string());
+            write_file(&self.path, &content)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+// Replace every {name} in format with it's value. Done in single pass so that value containing
+// placeholder itself (eg: title "{artist}") is written as is. Unknown placeholder is kept
+fn fill_placeholders(format: &str, values: &[(&str, &str)]) -> String {
+    let mut line = String::with_capacity(format.len());
+    let mut rest = format;
+    while let Some(start) = rest.find('{') {
+        line.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let value = rest.find('}').and_then(|end| {
+            let (_, value) = values.iter().find(|(name, _)| *name == &rest[1..end])?;
+            Some((value, end))
+        });
+        match value {
+            Some((value, end)) => {
+                line.push_str(value);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                line.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    line.push_str(rest);
+    line
+}
+
+fn status_line(state: &super::State, format: &str) -> String {
+    let (title, playback) = match &state.bottom.playing {
+        Some((title, true)) => (title.as_str(), "playing"),
+        Some((title, false)) => (title.as_str(), "paused"),
+        None => return String::new(),
+    };
+    let duration = if state.playing_live() {
+        "live".to_string()
+    } else {
+        state.bottom.music_duration.to_string()
+    };
+    fill_placeholders(
+        format,
+        &[
+            ("title", title),
+            ("artist", &state.bottom.artist.1),
+            ("elapsed", &state.bottom.music_elapse.to_string()),
+            ("duration", &duration),
+            ("state", playback),
+            ("volume", &state.playback_behaviour.volume.to_string()),
+        ],
+    )
+}
+
+#[cfg(unix)]
+fn is_fifo(path: &std::path::Path) -> bool {
+    use std::os::unix::fs::FileTypeExt;
+    std::fs::metadata(path).is_ok_and(|metadata| metadata.file_type().is_fifo())
+}
+
+#[cfg(not(unix))]
+fn is_fifo(_path: &std::path::Path) -> bool {
+    false
+}
+
+// Opening pipe for writing blocks until someone opens it for reading. It is opened without blocking
+// instead which fails when no one is reading and line is then just skipped
+#[cfg(unix)]
+fn write_fifo(path: &std::path::Path, content: &str) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+    std::fs::OpenOptions::new()
+        .write(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(path)?
+        .write_all(content.as_bytes())
+}
+
+#[cfg(not(unix))]
+fn write_fifo(_path: &std::path::Path, _content: &str) -> std::io::Result<()> {
+    Ok(())
+}
+
+// File is written next to it and renamed over so that reader never sees half written text
+fn write_file(path: &std::path::Path, content: &str) -> std::io::Result<()> {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    std::fs::write(&temp_path, content)?;
+    std::fs::rename(&temp_path, path)
+}
+
+// Write until app is quit. Nothing is written to file that is not set in config. Files are emptied
+// on quit so that status bar or overlay do not keep showing music that is no longer being played
+pub async fn serve(state_original: &mut Arc<Mutex<super::State>>, _notifier: &mut Arc<Condvar>) {
+    let constants = &CONFIG.constants;
+    let mut outputs: Vec<Output> = [
+        Output::from_config(&constants.status_file, &constants.status_format, true),
+        Output::from_config(
+            &constants.now_playing_file,
+            &constants.now_playing_format,
+            false,
+        ),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if outputs.is_empty() {
+        return;
+    }
+
+    loop {
+        let texts = {
+            let state = state_original.lock().unwrap();
+            if state.active == super::Window::None {
+                break;
+            }
+            outputs
+                .iter()
+                .map(|output| status_line(&state, output.format))
+                .collect::<Vec<String>>()
+        };
+        let mut failed = false;
+        for (output, text) in outputs.iter_mut().zip(texts) {
+            failed |= output.write(&text).is_err() && !is_fifo(&output.path);
+        }
+        if failed {
+            state_original
+                .lock()
+                .unwrap()
+                .error("Cannot write status..");
+        }
+        tokio::time::sleep(WRITE_INTERVAL).await;
+    }
+
+    for output in outputs.iter_mut() {
+        output.written = None;
+        output.write("").ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders() {
+        let values = [
+            ("artist", "Someone"),
+            ("title", "Song"),
+            ("state", "playing"),
+        ];
+        assert_eq!(
+            fill_placeholders("{artist} - {title} [{state}]", &values),
+            "Someone - Song [playing]"
+        );
+        assert_eq!(fill_placeholders("", &values), "");
+        assert_eq!(
+            fill_placeholders("no placeholder", &values),
+            "no placeholder"
+        );
+        assert_eq!(fill_placeholders("{title}{title}", &values), "SongSong");
+    }
+
+    #[test]
+    fn value_is_not_substituted_again() {
+        let values = [("title", "{artist} live"), ("artist", "Someone")];
+        assert_eq!(
+            fill_placeholders("{title} by {artist}", &values),
+            "{artist} live by Someone"
+        );
+    }
+
+    #[test]
+    fn unknown_and_broken_placeholders() {
+        let values = [("title", "Song")];
+        assert_eq!(fill_placeholders("{nope} {title}", &values), "{nope} Song");
+        assert_eq!(fill_placeholders("{{title}}", &values), "{Song}");
+        assert_eq!(fill_placeholders("{title", &values), "{title");
+        assert_eq!(fill_placeholders("} {", &values), "} {");
+        assert_eq!(fill_placeholders("{}", &values), "{}");
+        assert_eq!(fill_placeholders("♪ {title} ♪", &values), "♪ Song ♪");
+    }
+}
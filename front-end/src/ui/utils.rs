@@ -1,21 +1,60 @@
-use crate::ui;
+use crate::ui::{
+    self,
+    event::{MIDDLE_ARTIST_INDEX, MIDDLE_MUSIC_INDEX, MIDDLE_PLAYLIST_INDEX},
+};
 use fetcher::ExtendDuration;
 use std::borrow::Cow;
 use tui;
 use ui::shared_import::*;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-pub const SIDEBAR_LIST_COUNT: usize = 6;
+// Widths of number, name and creator column of playlist section and number and name column of
+// artist section
+const PLAYLIST_WIDTHS: [Constraint; 3] = [
+    Constraint::Percentage(10),
+    Constraint::Percentage(60),
+    Constraint::Percentage(30),
+];
+const ARTIST_WIDTHS: [Constraint; 2] = [Constraint::Percentage(10), Constraint::Percentage(90)];
+// Shown as title of playing music when mpv have not yet loaded the title
+const UNKNOWN_TITLE: &str = ">> Play some music <<";
+// Shown before the music being played in music list and play queue
+const PLAYING_ICON: &str = "▶ ";
+const PLAYING_ICON_ASCII: &str = "> ";
+pub const SIDEBAR_LIST_COUNT: usize = 12;
 pub const SIDEBAR_LIST_ITEMS: [&str; SIDEBAR_LIST_COUNT] = [
     "Trending",
+    "Charts",
+    "Moods & genres",
     "Youtube Community",
     "Liked songs",
     "My playlist",
     "Following",
+    "Subscriptions",
+    "New releases",
+    "Local music",
+    "History",
     "Search",
 ];
 use config::initilize::{
     CONFIG, STORAGE, TB_FAVOURATES_ARTIST, TB_FAVOURATES_MUSIC, TB_FAVOURATES_PLAYLIST,
+    TB_PODCAST_POSITION, TB_SUBSCRIPTIONS,
 };
+// Going to previous chapter after this much time since current chapter started goes to the start
+// of current chapter instead
+const CHAPTER_RESTART_AFTER: Duration = Duration::from_secs(3);
+// Music that is not seen loading by player within this time after requested is considered to be
+// failed to play
+const PLAYBACK_LOAD_TIMEOUT: Duration = Duration::from_secs(10);
+// Episode left with less than this much time remaining is considered to be finished and will
+// start from beginning next time
+const EPISODE_FINISH_MARGIN: Duration = Duration::from_secs(30);
+// Messages in status box are shown for this long. See ui::Notifications
+const INFO_TIMEOUT: Duration = Duration::from_secs(4);
+const WARN_TIMEOUT: Duration = Duration::from_secs(6);
+const ERROR_TIMEOUT: Duration = Duration::from_secs(10);
+// Toast is shown for this long. See ui::State::toast
+const TOAST_TIMEOUT: Duration = Duration::from_secs(2);
 
 pub fn show_pupop_text<'a, B>(frame: &mut tui::terminal::Frame<B>, text: [&'a str; 2], area: &Rect)
 where
@@ -38,10 +77,1042 @@ where
 //  Second((i32, i32, i32)) => accepts single tuple with 3 memebers
 // }
 // Rgb in tui is defined in fasion of First in above example
+// Colour is taken out once so that theme is not locked again for every component
 macro_rules! rgb {
-    ($tuple: expr) => {
-        Color::Rgb($tuple.0, $tuple.1, $tuple.2)
+    ($tuple: expr) => {{
+        let (red, green, blue) = $tuple;
+        Color::Rgb(red, green, blue)
+    }};
+}
+
+lazy_static::lazy_static! {
+    // Colours being used. This is the theme from config file until changed by `:theme` command
+    static ref THEME: std::sync::RwLock<config::Theme> =
+        std::sync::RwLock::new(CONFIG.theme.clone());
+    // Colours of high contrast mode. Follows background of theme being used
+    static ref HIGH_CONTRAST_THEME: std::sync::RwLock<config::Theme> =
+        std::sync::RwLock::new(CONFIG.theme.high_contrast());
+    static ref HIGH_CONTRAST: std::sync::atomic::AtomicBool =
+        std::sync::atomic::AtomicBool::new(CONFIG.constants.high_contrast);
+}
+
+fn theme() -> std::sync::RwLockReadGuard<'static, config::Theme> {
+    if high_contrast() {
+        HIGH_CONTRAST_THEME.read().unwrap()
+    } else {
+        THEME.read().unwrap()
+    }
+}
+
+// Change the colours being used. Config file is left as it is
+pub fn set_theme(theme: config::Theme) {
+    *HIGH_CONTRAST_THEME.write().unwrap() = theme.high_contrast();
+    *THEME.write().unwrap() = theme;
+}
+
+pub fn high_contrast() -> bool {
+    HIGH_CONTRAST.load(std::sync::atomic::Ordering::Relaxed)
+}
+
+// Switch high contrast mode on/off. Config file is left as it is
+pub fn set_high_contrast(on: bool) {
+    HIGH_CONTRAST.store(on, std::sync::atomic::Ordering::Relaxed);
+}
+
+// How the key is shown in help. Keys that are used along with CTRL are prefixed by it
+fn key_name(key: char, with_control: bool) -> String {
+    let name = match key {
+        ' ' => "<SPACE>".to_string(),
+        _ => key.to_string(),
+    };
+    if with_control {
+        format!("<CTRL>+{}", name)
+    } else {
+        name
+    }
+}
+
+// Every shortcut grouped by where it applies. Keys are read from config so remapped keys are shown
+// as they are
+fn help_sections() -> Vec<(&'static str, Vec<(String, &'static str)>)> {
+    let keys = &CONFIG.shortcut_keys;
+    let chords = &CONFIG.chord_keys;
+    let mut sections = vec![
+        (
+            "General",
+            vec![
+                (key_name(keys.start_search, false), "Move to searchbar"),
+                (
+                    key_name(keys.command, false),
+                    "Open command line (Tab completes)",
+                ),
+                ("Tab / Right".to_string(), "Move to next section"),
+                ("BackTab / Left".to_string(), "Move to previous section"),
+                (
+                    "Esc".to_string(),
+                    "Move to status bar or close whatever is open",
+                ),
+                (key_name(keys.help, false), "Show/hide this help"),
+                (
+                    format!(
+                        "{} / {}",
+                        key_name(keys.shrink_sidebar, false),
+                        key_name(keys.grow_sidebar, false)
+                    ),
+                    "Make sidebar narrower/wider",
+                ),
+                (
+                    format!(
+                        "{} / {}",
+                        key_name(keys.shrink_music, false),
+                        key_name(keys.grow_music, false)
+                    ),
+                    "Make music section shorter/taller",
+                ),
+                (
+                    key_name(keys.maximize, false),
+                    "Maximize focused section or restore",
+                ),
+                (
+                    key_name(keys.layout, false),
+                    "Switch layout (full, search, mini)",
+                ),
+                (key_name(keys.hints, false), "Show/hide key hints footer"),
+                (key_name(keys.contrast, false), "High contrast mode on/off"),
+                (
+                    key_name(keys.quit, true),
+                    "Quit (with ALT to quit even when downloading)",
+                ),
+            ],
+        ),
+        (
+            "Searchbar",
+            vec![
+                ("Left / Right".to_string(), "Move cursor"),
+                ("Up / Down".to_string(), "Select suggestion"),
+                (
+                    "Tab / BackTab".to_string(),
+                    "Complete to suggestion (again to cycle)",
+                ),
+                ("<CTRL>+Left / Right".to_string(), "Move cursor by a word"),
+                ("Home / <CTRL>+a".to_string(), "Move cursor to start"),
+                ("End / <CTRL>+e".to_string(), "Move cursor to end"),
+                ("<CTRL>+w".to_string(), "Delete word before cursor"),
+                ("<CTRL>+u".to_string(), "Delete everything before cursor"),
+                (
+                    "<CTRL>+v".to_string(),
+                    "Paste from clipboard (from anywhere)",
+                ),
+            ],
+        ),
+        (
+            "Music, playlist and artist section",
+            vec![
+                ("Up / Down".to_string(), "Move selection"),
+                ("Enter".to_string(), "Play music / open playlist or artist"),
+                (key_name(keys.next, false), "Next page"),
+                (key_name(keys.prev, false), "Previous page"),
+                (key_name(keys.view, false), "Show url of focused item"),
+                (
+                    key_name(keys.yank_url, false),
+                    "Copy url of focused (or playing) music",
+                ),
+                (
+                    key_name(keys.open_in_browser, false),
+                    "Open focused (or playing) music in browser",
+                ),
+                (
+                    key_name(keys.details, false),
+                    "Details and description of focused music",
+                ),
+                (key_name(keys.filter, false), "Filter the list by typing"),
+                (
+                    key_name(keys.retry, false),
+                    "Retry the list that failed to fetch",
+                ),
+                (
+                    key_name(keys.search_scope, false),
+                    "Search songs only or all videos",
+                ),
+                (
+                    key_name(keys.sort_music, false),
+                    "Sort music by title/duration/channel",
+                ),
+                (
+                    "<ALT>+letter".to_string(),
+                    "Jump to next entry starting with letter",
+                ),
+                (
+                    key_name(keys.mark, false),
+                    "Mark/unmark for action on all marked",
+                ),
+                (key_name(keys.enqueue, false), "Add music to play queue"),
+                (
+                    key_name(keys.clear_history, false),
+                    "Clear history (when shown)",
+                ),
+                (
+                    key_name(keys.actions, false),
+                    "Menu of every action on focused item",
+                ),
+                (
+                    key_name(keys.add_to_playlist, false),
+                    "Add music to local playlist file",
+                ),
+                (key_name(keys.favourates_add, false), "Add to favourites"),
+                (
+                    key_name(keys.favourates_remove, false),
+                    "Remove from favourites",
+                ),
+                (
+                    key_name(keys.favourite, false),
+                    "Add to or remove from favourites",
+                ),
+                (
+                    key_name(keys.download, true),
+                    "Download focused music/playlist",
+                ),
+                (
+                    key_name(keys.related, false),
+                    "Music related to focused music",
+                ),
+                (key_name(keys.mix, false), "Mix of focused music"),
+                (
+                    key_name(keys.load_all, false),
+                    "Load every page of playlist",
+                ),
+                (
+                    key_name(keys.podcast, false),
+                    "Episodes of focused artist as podcast",
+                ),
+                (
+                    key_name(keys.subscribe, false),
+                    "Subscribe/unsubscribe focused artist",
+                ),
+            ],
+        ),
+        (
+            "Playback",
+            vec![
+                (key_name(keys.toggle_play, false), "Pause/resume"),
+                (key_name(keys.next, true), "Play next music"),
+                (key_name(keys.prev, true), "Play previous music"),
+                (key_name(keys.forward, false), "Seek forward"),
+                (key_name(keys.backward, false), "Seek backward"),
+                (key_name(keys.vol_increase, false), "Increase volume"),
+                (key_name(keys.vol_decrease, false), "Decrease volume"),
+                (key_name(keys.mute, false), "Mute/unmute"),
+                (
+                    "Left / Right".to_string(),
+                    "Seek while bottom bar is focused (Esc)",
+                ),
+                (
+                    "Up / Down".to_string(),
+                    "Volume while bottom bar is focused (Esc)",
+                ),
+                (key_name(keys.suffle, false), "Toggle shuffle"),
+                (key_name(keys.repeat, false), "Toggle repeat"),
+                (key_name(keys.next_chapter, false), "Next chapter"),
+                (key_name(keys.prev_chapter, false), "Previous chapter"),
+                (key_name(keys.chapters, false), "List of chapters"),
+                (
+                    key_name(keys.queue, false),
+                    "Play queue (J/K move, x remove, Enter play)",
+                ),
+            ],
+        ),
+        (
+            "Panes",
+            vec![
+                (key_name(keys.lyrics, false), "Show/hide lyrics"),
+                (key_name(keys.comments, false), "Show/hide comments"),
+                ("PageUp / PageDown".to_string(), "Scroll comments"),
+            ],
+        ),
+        (
+            "Chords (second key shortly after first)",
+            vec![
+                (chords.goto_trending.clone(), "Go to Trending"),
+                (chords.goto_charts.clone(), "Go to Charts"),
+                (chords.goto_moods.clone(), "Go to Moods & genres"),
+                (chords.goto_liked.clone(), "Go to Liked"),
+                (chords.goto_saved.clone(), "Go to Saved"),
+                (chords.goto_following.clone(), "Go to Following"),
+                (chords.goto_subscriptions.clone(), "Go to Subscriptions"),
+                (chords.goto_new_releases.clone(), "Go to New releases"),
+                (chords.goto_local.clone(), "Go to Local"),
+                (chords.goto_history.clone(), "Go to History"),
+                (chords.yank_url.clone(), "Copy url of focused item"),
+            ]
+            .into_iter()
+            // Disabled chords
+            .filter(|(keys, _)| !keys.is_empty())
+            .collect(),
+        ),
+    ];
+    sections.push((
+        "Commands (after pressing command line key)",
+        ui::command::COMMANDS
+            .iter()
+            .map(|(_, _, usage, description)| (usage.to_string(), *description))
+            .collect(),
+    ));
+    if CONFIG.constants.vim_keys {
+        let mut keys = vec![
+            ("j / k".to_string(), "Same as Down / Up"),
+            ("h / l".to_string(), "Same as Left / Right"),
+            ("gg / G".to_string(), "Move to first / last row"),
+        ];
+        // Not shown when taken by shortcut key
+        if !ui::event::is_control_shortcut('d') {
+            keys.push(("<CTRL>+d".to_string(), "Move down by half page"));
+        }
+        if !ui::event::is_control_shortcut('u') {
+            keys.push(("<CTRL>+u".to_string(), "Move up by half page"));
+        }
+        sections.push(("Vim style keys", keys));
+    }
+    sections
+}
+
+impl ui::ItemAction {
+    // Actions that can be taken on item of given list
+    pub fn of_window(window: &ui::Window) -> Vec<Self> {
+        use ui::ItemAction::*;
+        match window {
+            ui::Window::Musicbar => vec![
+                Play,
+                Enqueue,
+                FavouriteAdd,
+                FavouriteRemove,
+                Download,
+                AddToPlaylist,
+                CopyUrl,
+                OpenInBrowser,
+                Details,
+            ],
+            ui::Window::Playlistbar => vec![
+                Play,
+                View,
+                FavouriteAdd,
+                FavouriteRemove,
+                Download,
+                CopyUrl,
+                OpenInBrowser,
+            ],
+            ui::Window::Artistbar => vec![
+                Open,
+                Podcast,
+                Subscribe,
+                FavouriteAdd,
+                FavouriteRemove,
+                CopyUrl,
+                OpenInBrowser,
+            ],
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ui::ItemAction::Play => "Play",
+            ui::ItemAction::Open => "Open",
+            ui::ItemAction::View => "Open without playing",
+            ui::ItemAction::Enqueue => "Add to queue",
+            ui::ItemAction::FavouriteAdd => "Add to favourites",
+            ui::ItemAction::FavouriteRemove => "Remove from favourites",
+            ui::ItemAction::Download => "Download",
+            ui::ItemAction::AddToPlaylist => "Add to local playlist",
+            ui::ItemAction::CopyUrl => "Copy url",
+            ui::ItemAction::OpenInBrowser => "Open in browser",
+            ui::ItemAction::Details => "Show details",
+            ui::ItemAction::Subscribe => "Subscribe/unsubscribe",
+            ui::ItemAction::Podcast => "Open as podcast",
+        }
+    }
+
+    // Key that does the same thing. Shown along with the name so that it can be learned
+    fn key(self) -> String {
+        let keys = &CONFIG.shortcut_keys;
+        match self {
+            ui::ItemAction::Play | ui::ItemAction::Open => "Enter".to_string(),
+            ui::ItemAction::View => key_name(keys.view, false),
+            ui::ItemAction::Enqueue => key_name(keys.enqueue, false),
+            ui::ItemAction::FavouriteAdd => key_name(keys.favourates_add, false),
+            ui::ItemAction::FavouriteRemove => key_name(keys.favourates_remove, false),
+            ui::ItemAction::Download => key_name(keys.download, true),
+            ui::ItemAction::AddToPlaylist => key_name(keys.add_to_playlist, false),
+            ui::ItemAction::CopyUrl => key_name(keys.yank_url, false),
+            ui::ItemAction::OpenInBrowser => key_name(keys.open_in_browser, false),
+            ui::ItemAction::Details => key_name(keys.details, false),
+            ui::ItemAction::Subscribe => key_name(keys.subscribe, false),
+            ui::ItemAction::Podcast => key_name(keys.podcast, false),
+        }
+    }
+}
+
+// Lines of action menu. Keys are aligned to the right of names
+fn action_menu_lines(actions: &[ui::ItemAction]) -> Vec<String> {
+    let name_width = actions
+        .iter()
+        .map(|action| action.name().len())
+        .max()
+        .unwrap_or_default();
+    actions
+        .iter()
+        .map(|action| {
+            format!(
+                "{:<width$}  {}",
+                action.name(),
+                action.key(),
+                width = name_width
+            )
+            .trim_end()
+            .to_string()
+        })
+        .collect()
+}
+
+// Action menu is just large enough to fit every action and is placed in middle of the list
+pub fn get_action_menu_area(list: &Rect, actions: &[ui::ItemAction]) -> Rect {
+    let longest = action_menu_lines(actions)
+        .iter()
+        .map(|line| display_width(line))
+        .max()
+        .unwrap_or_default();
+    let width = (longest as u16 + 4).min(list.width);
+    let height = (actions.len() as u16 + 2).min(list.height);
+    Rect {
+        x: list.x + (list.width - width) / 2,
+        y: list.y + (list.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+pub fn get_action_menu(actions: &[ui::ItemAction]) -> List<'static> {
+    List::new(
+        action_menu_lines(actions)
+            .into_iter()
+            .map(|line| ListItem::new(Span::styled(line, Style::list_idle())))
+            .collect::<Vec<ListItem>>(),
+    )
+    .highlight_style(Style::list_selected())
+    .block(Block::active("Actions ".to_owned()))
+}
+
+// Title of list with the page being shown and number of items in it. Page shorter than a full page
+// is the last one so going to next page is pointless. Nothing is added while list is being fetched
+fn title_with_page(title: String, state: &ui::State, list: usize, len: usize) -> String {
+    if state.loading[list].is_some() {
+        title
+    } else if state.end_of_results[list] {
+        format!("{}[End of results] ", title)
+    } else if let (Some(page), true) = (state.fetched_page[list], len > 0) {
+        let is_last = len < CONFIG.constants.item_per_list;
+        format!(
+            "{}[Page {}, {} results{}] ",
+            title,
+            page + 1,
+            len,
+            if is_last { ", end" } else { "" }
+        )
+    } else {
+        title
+    }
+}
+
+// Columns that can be shown in music section. See config::MusicColumn
+#[derive(Clone, Copy, PartialEq)]
+enum MusicColumn {
+    Title,
+    Artist,
+    Duration,
+    Views,
+    Uploaded,
+}
+
+// Column narrower than this many characters is not worth showing
+const MIN_COLUMN_WIDTH: u16 = 12;
+
+impl MusicColumn {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "title" => Some(MusicColumn::Title),
+            "artist" | "channel" => Some(MusicColumn::Artist),
+            "duration" | "length" => Some(MusicColumn::Duration),
+            "views" => Some(MusicColumn::Views),
+            "uploaded" | "upload_date" => Some(MusicColumn::Uploaded),
+            _ => None,
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            MusicColumn::Title => "Title",
+            MusicColumn::Artist => "Artist",
+            MusicColumn::Duration => "Length",
+            MusicColumn::Views => "Views",
+            MusicColumn::Uploaded => "Uploaded",
+        }
+    }
+
+    // Sort that orders the music by this column if any
+    fn sort(self) -> Option<ui::MusicSort> {
+        match self {
+            MusicColumn::Title => Some(ui::MusicSort::Title),
+            MusicColumn::Artist => Some(ui::MusicSort::Channel),
+            MusicColumn::Duration => Some(ui::MusicSort::Duration),
+            MusicColumn::Views | MusicColumn::Uploaded => None,
+        }
+    }
+}
+
+// Columns of music section as set in config along with their relative width. When every column
+// do not fit in given width, columns with lowest priority are left out (rightmost first on tie)
+fn music_columns(width: u16) -> Vec<(MusicColumn, u16)> {
+    let mut columns: Vec<(MusicColumn, u16, u8)> = CONFIG
+        .constants
+        .music_columns
+        .iter()
+        .filter_map(|column| {
+            let kind = MusicColumn::from_name(&column.name)?;
+            Some((kind, column.width.max(1), column.priority))
+        })
+        .collect();
+    if columns.is_empty() {
+        columns = vec![
+            (MusicColumn::Title, 55, 2),
+            (MusicColumn::Artist, 30, 0),
+            (MusicColumn::Duration, 15, 1),
+        ];
+    }
+    while columns.len() > 1 && columns.len() as u16 * MIN_COLUMN_WIDTH > width {
+        let lowest = columns
+            .iter()
+            .enumerate()
+            .rev()
+            .min_by_key(|(_, (_, _, priority))| *priority)
+            .map(|(index, _)| index)
+            .unwrap_or_default();
+        columns.remove(lowest);
+    }
+    columns
+        .into_iter()
+        .map(|(kind, width, _)| (kind, width))
+        .collect()
+}
+
+fn playing_icon() -> &'static str {
+    if CONFIG.constants.ascii_only {
+        PLAYING_ICON_ASCII
+    } else {
+        PLAYING_ICON
+    }
+}
+
+// Shown before favourite music, playlist and artist in lists. Empty when turned off in config
+fn favourite_mark() -> String {
+    let icon = CONFIG.constants.favourite_icon.as_str();
+    match icon {
+        "" => String::new(),
+        _ if CONFIG.constants.ascii_only && !icon.is_ascii() => "* ".to_string(),
+        _ => format!("{} ", icon),
+    }
+}
+
+// Name prefixed with icons (like playing and favourite) and cut to fit in the rest of the width
+fn name_with_icons<'a>(mut icons: Vec<Span<'a>>, name: &'a str, width: usize) -> Spans<'a> {
+    let used: usize = icons.iter().map(|icon| icon.width()).sum();
+    icons.push(Span::raw(truncate_to_width(
+        name,
+        width.saturating_sub(used),
+    )));
+    Spans::from(icons)
+}
+
+// Compact form of what icons set shows, like: `|| Vol 80 S R1`
+// Pause state, volume (or muted), shuffle if it is on and repeat all/one
+fn playback_indicators(state: &ui::State) -> String {
+    let ascii = CONFIG.constants.ascii_only;
+    let behaviour = &state.playback_behaviour;
+    let mut indicators = Vec::new();
+    match state.bottom.playing {
+        Some((_, true)) => indicators.push(if ascii { ">" } else { "▶" }.to_string()),
+        Some((_, false)) => indicators.push(if ascii { "||" } else { "⏸" }.to_string()),
+        None => {}
+    }
+    if behaviour.muted {
+        indicators.push("Muted".to_string());
+    } else {
+        indicators.push(format!("Vol {}", behaviour.volume));
+    }
+    if behaviour.shuffle {
+        indicators.push(if ascii { "S" } else { "⤮" }.to_string());
+    }
+    let repeat = match (behaviour.repeat, ascii) {
+        (true, true) => "R",
+        (false, true) => "R1",
+        (true, false) => "↻",
+        (false, false) => "↻1",
+    };
+    indicators.push(repeat.to_string());
+    indicators.join(" ")
+}
+
+// Where the charts being shown are from. Used in title of lists
+fn charts_name(country: &str) -> String {
+    if country == fetcher::innertube::GLOBAL_CHARTS {
+        "Charts: Global".to_owned()
+    } else {
+        format!("Charts: {}", country)
+    }
+}
+
+// Number of cells text takes in terminal. CJK and other wide characters take two cells each
+pub fn display_width(text: &str) -> usize {
+    text.width()
+}
+
+// Cut text to fit in given number of cells ending with an ellipsis. Text that already fits is
+// left as it is and wide character is never cut in half
+pub fn truncate_to_width(text: &str, width: usize) -> Cow<'_, str> {
+    if display_width(text) <= width {
+        return Cow::Borrowed(text);
+    }
+    let ellipsis = match CONFIG.constants.ascii_only {
+        _ if width < 3 => "",
+        true => "...",
+        false => "…",
+    };
+    let room = width - display_width(ellipsis);
+    let mut used = 0;
+    let mut end = 0;
+    for (index, ch) in text.char_indices() {
+        used += ch.width().unwrap_or_default();
+        if used > room {
+            break;
+        }
+        end = index + ch.len_utf8();
+    }
+    Cow::Owned(format!("{}{}", &text[..end], ellipsis))
+}
+
+// Pad text with spaces on the right to take given number of cells. `format!("{:<width$}")` counts
+// characters instead so wide characters would misalign following column
+fn pad_to_width(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(display_width(text));
+    format!("{}{}", text, " ".repeat(padding))
+}
+
+// Width of each column of table as tui lays them out so that cells can be cut with an ellipsis
+// before tui clips them. `width` is of the table without border
+fn table_column_widths(widths: &[Constraint], spacing: u16, width: u16) -> Vec<usize> {
+    let mut constraints = Vec::with_capacity(widths.len() * 2 + 1);
+    for constraint in widths {
+        constraints.push(*constraint);
+        constraints.push(Constraint::Length(spacing));
+    }
+    constraints.pop();
+    // tui does not stretch last column to fill the rest, which is left to this instead
+    constraints.push(Constraint::Min(0));
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(constraints)
+        .split(Rect::new(0, 0, width, 1))
+        .iter()
+        .step_by(2)
+        .take(widths.len())
+        .map(|chunk| chunk.width as usize)
+        .collect()
+}
+
+// Title of list with number of marked rows in it if any. See ui::MarkState
+fn title_with_marks(title: String, marked: usize) -> String {
+    if marked == 0 {
+        title
+    } else {
+        format!("{}[{} marked] ", title, marked)
+    }
+}
+
+// Spinner moves by a frame on every redraw (which happens at least every refresh_rate)
+pub fn get_loading_placeholder(since: std::time::Instant) -> Paragraph<'static> {
+    const FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+    const ASCII_FRAMES: [char; 4] = ['|', '/', '-', '\\'];
+    let frames: &[char] = if CONFIG.constants.ascii_only {
+        &ASCII_FRAMES
+    } else {
+        &FRAMES
+    };
+    let refresh_rate = CONFIG.constants.refresh_rate.max(1) as u128;
+    let frame = frames[(since.elapsed().as_millis() / refresh_rate) as usize % frames.len()];
+    Paragraph::new(Span::styled(
+        format!("{} Loading..", frame),
+        Style::list_title().add_modifier(Modifier::ITALIC),
+    ))
+    .alignment(Alignment::Center)
+}
+
+pub fn get_fetch_error(reason: &str) -> Paragraph<'static> {
+    let icon = if CONFIG.constants.ascii_only {
+        "x"
+    } else {
+        "✗"
+    };
+    Paragraph::new(Span::styled(
+        format!(
+            "{} {} Press {} to retry",
+            icon,
+            reason,
+            key_name(CONFIG.shortcut_keys.retry, false)
+        ),
+        Style::default().fg(rgb!(theme().status_error)),
+    ))
+    .alignment(Alignment::Center)
+}
+
+// Filter prompt drawn over the bottom border of list being filtered
+pub fn get_filter_prompt(state: &ui::State) -> Paragraph<'static> {
+    let cursor = if state.filter.typing { "_" } else { "" };
+    Paragraph::new(Spans::from(vec![
+        Span::styled(" Filter: ", Style::list_title()),
+        Span::styled(
+            format!("{}{} ", state.filter.query, cursor),
+            Style::list_idle().fg(rgb!(theme().color_primary)),
+        ),
+    ]))
+}
+
+pub fn get_command_line(line: &str) -> Paragraph<'static> {
+    Paragraph::new(Spans::from(vec![
+        Span::styled(":", Style::list_title()),
+        Span::styled(
+            format!("{}_", line),
+            Style::list_idle().fg(rgb!(theme().color_primary)),
+        ),
+    ]))
+}
+
+// Most used keys of focused section like the function key bar of htop. Command line, help and filter
+// take whatever is typed so their own keys are shown while they are open, and so do the lists drawn
+// over sections. Hints follow the focused row too, like unfavourite for favourite one
+fn key_hints(state: &ui::State) -> Vec<(String, &'static str)> {
+    let keys = &CONFIG.shortcut_keys;
+    let key = |ch: char| key_name(ch, false);
+    let mut hints = if state.command.is_some() {
+        vec![
+            ("Enter".to_string(), "Run"),
+            ("Tab".to_string(), "Complete"),
+            ("Esc".to_string(), "Close"),
+        ]
+    } else if state.help.is_some() {
+        vec![
+            ("Type".to_string(), "Filter"),
+            ("PgUp/PgDn".to_string(), "Scroll"),
+            ("Esc".to_string(), "Close"),
+        ]
+    } else if state.details.is_some() {
+        vec![
+            ("Up/Down".to_string(), "Scroll"),
+            ("Esc".to_string(), "Close"),
+        ]
+    } else if state.filter.typing {
+        vec![("Enter".to_string(), "Done"), ("Esc".to_string(), "Clear")]
+    } else if state.actions.is_some() {
+        vec![
+            ("Enter".to_string(), "Select"),
+            ("Esc".to_string(), "Close"),
+        ]
+    } else if state.queue.is_some() {
+        vec![
+            ("Enter".to_string(), "Play"),
+            ("J/K".to_string(), "Move"),
+            ("x".to_string(), "Remove"),
+            ("Esc".to_string(), "Close"),
+        ]
+    } else if state.chapters.popup.is_some() {
+        vec![("Enter".to_string(), "Jump"), ("Esc".to_string(), "Close")]
+    } else if state.moods.popup.is_some() {
+        vec![("Enter".to_string(), "Open"), ("Esc".to_string(), "Close")]
+    } else if state.cast.popup.is_some() {
+        vec![
+            ("Enter".to_string(), "Play on"),
+            ("Esc".to_string(), "Close"),
+        ]
+    } else if state.outputs.popup.is_some() {
+        vec![("Enter".to_string(), "Use"), ("Esc".to_string(), "Close")]
+    } else {
+        window_hints(state)
+    };
+    if state.command.is_none() && state.help.is_none() && !state.filter.typing {
+        hints.push((key(keys.help), "Help"));
+        hints.push((key(keys.command), "Command"));
+        hints.push((key(keys.hints), "Hide"));
+    }
+    hints
+}
+
+// Hints of focused section. Empty list only has keys to fill it
+fn window_hints(state: &ui::State) -> Vec<(String, &'static str)> {
+    let keys = &CONFIG.shortcut_keys;
+    let key = |ch: char| key_name(ch, false);
+    let list = match state.active {
+        ui::Window::Musicbar => Some(MIDDLE_MUSIC_INDEX),
+        ui::Window::Playlistbar => Some(MIDDLE_PLAYLIST_INDEX),
+        ui::Window::Artistbar => Some(MIDDLE_ARTIST_INDEX),
+        _ => None,
+    };
+    if let Some(list) = list {
+        let is_empty = [
+            state.musicbar.0.is_empty(),
+            state.playlistbar.0.is_empty(),
+            state.artistbar.0.is_empty(),
+        ][list];
+        if is_empty && state.fetch_error[list].is_some() {
+            return vec![
+                (key(keys.retry), "Retry"),
+                (key(keys.start_search), "Search"),
+            ];
+        } else if is_empty {
+            return vec![(key(keys.start_search), "Search")];
+        }
+    }
+
+    let favourite = if state
+        .focused_ids()
+        .iter()
+        .all(|id| state.favourites.contains(*id))
+    {
+        "Unfavourite"
+    } else {
+        "Favourite"
+    };
+    let mut hints = match state.active {
+        ui::Window::Searchbar if !state.suggestions.1.is_empty() => vec![
+            ("Enter".to_string(), "Search"),
+            ("Tab".to_string(), "Complete"),
+            ("Up/Down".to_string(), "Suggestion"),
+            ("Esc".to_string(), "Leave"),
+        ],
+        ui::Window::Searchbar => vec![
+            ("Enter".to_string(), "Search"),
+            ("Tab".to_string(), "Next"),
+            ("Esc".to_string(), "Leave"),
+        ],
+        ui::Window::Sidebar => vec![
+            ("Enter".to_string(), "Open"),
+            ("Tab".to_string(), "Next"),
+            (key(keys.start_search), "Search"),
+        ],
+        ui::Window::Musicbar => vec![
+            ("Enter".to_string(), "Play"),
+            (key(keys.enqueue), "Enqueue"),
+            (key(keys.details), "Details"),
+            (key(keys.actions), "Actions"),
+            (key(keys.favourite), favourite),
+            (key(keys.related), "Related"),
+            (key(keys.filter), "Filter"),
+            (key(keys.sort_music), "Sort"),
+        ],
+        ui::Window::Playlistbar => vec![
+            ("Enter".to_string(), "Open"),
+            (key(keys.load_all), "Load all"),
+            (key(keys.actions), "Actions"),
+            (key(keys.favourite), favourite),
+            (key(keys.filter), "Filter"),
+        ],
+        ui::Window::Artistbar => vec![
+            ("Enter".to_string(), "Open"),
+            (key(keys.subscribe), "Subscribe"),
+            (key(keys.podcast), "Podcast"),
+            (key(keys.actions), "Actions"),
+            (key(keys.favourite), favourite),
+            (key(keys.filter), "Filter"),
+        ],
+        ui::Window::BottomControl => {
+            let paused = matches!(state.bottom.playing, Some((_, false)));
+            let muted = state.playback_behaviour.muted;
+            vec![
+                (
+                    key(keys.toggle_play),
+                    if paused { "Resume" } else { "Pause" },
+                ),
+                ("Left/Right".to_string(), "Seek"),
+                ("Up/Down".to_string(), "Volume"),
+                (key(keys.mute), if muted { "Unmute" } else { "Mute" }),
+                (key(keys.suffle), "Shuffle"),
+                (key(keys.repeat), "Repeat"),
+                (key(keys.queue), "Queue"),
+            ]
+        }
+        ui::Window::Popup(..) => vec![("Esc".to_string(), "Close")],
+        ui::Window::None => vec![],
+    };
+    if let Some(list) = list {
+        if !state.end_of_results[list] {
+            hints.push((key(keys.next), "More"));
+        }
+        let marked = [
+            state.marked.music.len(),
+            state.marked.playlist.len(),
+            state.marked.artist.len(),
+        ][list];
+        if marked > 0 {
+            hints.push(("Esc".to_string(), "Unmark"));
+        }
+    }
+    hints
+}
+
+pub fn get_key_hints(state: &ui::State) -> Paragraph<'static> {
+    let key_style = Style::list_highlight().add_modifier(Modifier::REVERSED);
+    let spans: Vec<Span> = key_hints(state)
+        .into_iter()
+        .flat_map(|(key, description)| {
+            [
+                Span::styled(key, key_style),
+                Span::styled(format!(" {} ", description), Style::list_idle()),
+            ]
+        })
+        .collect();
+    Paragraph::new(Spans::from(spans))
+}
+
+// Just large enough for the message (upto half of the bottom bar) and placed right above the right
+// end of bottom bar
+pub fn get_toast_area(bottom: &Rect, message: &str) -> Rect {
+    let width = (display_width(message) as u16 + 4).min(bottom.width / 2);
+    let height = 3.min(bottom.y);
+    Rect {
+        x: bottom.right() - width,
+        y: bottom.y - height,
+        width,
+        height,
+    }
+}
+
+// Message that does not fit is cut. Width is of the area toast is drawn in
+pub fn get_toast(message: &str, width: u16) -> Paragraph<'_> {
+    Paragraph::new(Span::styled(
+        truncate_to_width(message, width.saturating_sub(4) as usize),
+        Style::list_highlight().add_modifier(Modifier::BOLD),
+    ))
+    .alignment(Alignment::Center)
+    .block(Block::active(String::new()))
+}
+
+// Two third of the screen in the middle
+pub fn get_details_area(screen: &Rect) -> Rect {
+    let width = screen.width * 2 / 3;
+    let height = screen.height * 2 / 3;
+    Rect {
+        x: screen.x + (screen.width - width) / 2,
+        y: screen.y + (screen.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+pub fn get_details(details: &ui::DetailsState) -> Paragraph<'static> {
+    let music = &details.music;
+    let duration = if music.live {
+        "LIVE".to_string()
+    } else {
+        music.duration.clone()
     };
+    // Views and upload date are not known for every backend
+    let fields = [
+        ("Title", music.name.clone()),
+        ("Channel", music.artist.clone()),
+        ("Duration", duration),
+        ("Views", music.views.clone()),
+        ("Uploaded", music.published.clone()),
+        ("Url", fetcher::music_url(&music.id)),
+    ];
+    let mut lines: Vec<Spans> = fields
+        .into_iter()
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(name, value)| {
+            Spans::from(vec![
+                Span::styled(format!("{:<10}", name), Style::list_title()),
+                Span::styled(value, Style::list_idle()),
+            ])
+        })
+        .collect();
+    lines.push(Spans::default());
+    match details.description.as_deref() {
+        None => lines.push(Spans::from(Span::styled(
+            "Fetching description..",
+            Style::list_idle(),
+        ))),
+        Some("") => lines.push(Spans::from(Span::styled(
+            "No description",
+            Style::list_idle(),
+        ))),
+        Some(description) => lines.extend(
+            description
+                .lines()
+                .map(|line| Spans::from(Span::styled(line.to_string(), Style::list_idle()))),
+        ),
+    }
+
+    Paragraph::new(Text::from(lines))
+        .wrap(widgets::Wrap { trim: false })
+        .scroll((details.scroll, 0))
+        .block(Block::active(
+            "Details [Up/Down: scroll, Esc: close] ".to_string(),
+        ))
+}
+
+pub fn get_help(help: &ui::HelpState) -> Paragraph<'static> {
+    let key_width = help_sections()
+        .iter()
+        .flat_map(|(_, keys)| keys.iter().map(|(key, _)| key.chars().count()))
+        .max()
+        .unwrap_or_default();
+
+    // Only the keys matching the query are shown along with the title of their section
+    let query = help.query.to_lowercase();
+    let sections = help_sections().into_iter().filter_map(|(title, keys)| {
+        let keys: Vec<_> = keys
+            .into_iter()
+            .filter(|(key, description)| {
+                key.to_lowercase().contains(&query) || description.to_lowercase().contains(&query)
+            })
+            .collect();
+        if keys.is_empty() {
+            None
+        } else {
+            Some((title, keys))
+        }
+    });
+
+    let mut lines = Vec::new();
+    for (title, keys) in sections {
+        lines.push(Spans::from(Span::styled(title, Style::list_title())));
+        for (key, description) in keys {
+            lines.push(Spans::from(vec![
+                Span::styled(
+                    format!("  {:<width$}  ", key, width = key_width),
+                    Style::list_idle().fg(rgb!(theme().color_primary)),
+                ),
+                Span::styled(description, Style::list_idle()),
+            ]));
+        }
+        lines.push(Spans::from(""));
+    }
+
+    let title = if help.query.is_empty() {
+        "Help (Type to filter, Esc to close) ".to_owned()
+    } else if lines.is_empty() {
+        format!("Help [Nothing matches: {}] ", help.query)
+    } else {
+        format!("Help [Filter: {}] (Esc to clear) ", help.query)
+    };
+    Paragraph::new(lines)
+        .scroll((help.scroll, 0))
+        .block(Block::active(title))
 }
 
 impl<'parent> ui::TopLayout {
@@ -56,113 +1127,653 @@ impl<'parent> ui::TopLayout {
         }
     }
 
+    // Number of messages waiting to be shown after the current one is shown along with it
     pub fn get_statusbox(state: &'parent ui::State) -> Paragraph<'parent> {
-        Paragraph::new(Span::styled(
-            state.status,
-            Style::default()
-                .add_modifier(Modifier::BOLD | Modifier::ITALIC)
-                .fg(rgb!(CONFIG.theme.color_secondary)),
-        ))
-        .block(Block::new("status".to_owned()))
-        .block(Block::new("status".to_owned()))
+        let notifications = &state.notifications.queue;
+        let text = match notifications.front() {
+            Some(notification) => {
+                let color = match notification.severity {
+                    ui::Severity::Info => theme().status_text,
+                    ui::Severity::Warn => theme().status_warning,
+                    ui::Severity::Error => theme().status_error,
+                };
+                let style = Style::default()
+                    .add_modifier(Modifier::BOLD | Modifier::ITALIC)
+                    .fg(rgb!(color));
+                let mut text = vec![Span::styled(notification.message.as_ref(), style)];
+                if notifications.len() > 1 {
+                    text.push(Span::styled(
+                        format!(" +{}", notifications.len() - 1),
+                        Style::list_title(),
+                    ));
+                }
+                Spans::from(text)
+            }
+            None => Spans::default(),
+        };
+        Paragraph::new(text).block(Block::new("status".to_owned()))
     }
 
-    pub fn get_searchbox(state: &'parent ui::State) -> Paragraph<'parent> {
-        let mut cursor_style = Style::default().fg(rgb!(CONFIG.theme.color_secondary));
+    pub fn get_searchbox(state: &'parent ui::State, width: u16) -> Paragraph<'parent> {
+        let mut cursor_style = Style::default().fg(rgb!(theme().color_secondary));
 
+        // Let user know that query will only be searched within the artist being shown
+        let title = if state.search_channel().is_some() {
+            "Search in artist (all: for everywhere) ".to_owned()
+        } else if state.songs_only {
+            "Search songs ".to_owned()
+        } else {
+            "Search ".to_owned()
+        };
         let block = match state.active {
             ui::Window::Searchbar => {
                 cursor_style = cursor_style.add_modifier(Modifier::BOLD | Modifier::RAPID_BLINK);
-                Block::active("Search ".to_owned())
+                Block::active(title)
             }
             _ => {
                 cursor_style = cursor_style.add_modifier(Modifier::HIDDEN);
-                Block::new("Search ".to_owned())
+                Block::new(title)
             }
         };
-        let text = text::Spans::from(vec![
-            Span::styled(
-                state.search.0.as_str(),
-                Style::default()
-                    .fg(rgb!(CONFIG.theme.color_primary))
-                    .add_modifier(Modifier::ITALIC),
-            ),
-            Span::styled("/", cursor_style),
-        ]);
-        Paragraph::new(text).block(block)
+        let text_style = Style::default()
+            .fg(rgb!(theme().color_primary))
+            .add_modifier(Modifier::ITALIC);
+
+        // Cursor in between the query is shown by reversing the character under it
+        let (mut before, after) = state.search.0.split_at(state.search_cursor_index());
+        // Query longer than searchbar is scrolled so that cursor stays in view. It is measured in
+        // cells as wide characters take two of them
+        let room = width.saturating_sub(2) as usize;
+        let cursor_width = after
+            .chars()
+            .next()
+            .and_then(|ch| ch.width())
+            .unwrap_or(1)
+            .max(1);
+        while !before.is_empty() && display_width(before) + cursor_width > room {
+            let first = before.chars().next().map_or(0, char::len_utf8);
+            before = &before[first..];
+        }
+        let mut spans = vec![Span::styled(before, text_style)];
+        match after.chars().next() {
+            None => spans.push(Span::styled("/", cursor_style)),
+            Some(ch) => {
+                let (under, rest) = after.split_at(ch.len_utf8());
+                let under_style = if state.active == ui::Window::Searchbar {
+                    text_style.add_modifier(Modifier::REVERSED)
+                } else {
+                    text_style
+                };
+                spans.push(Span::styled(under, under_style));
+                spans.push(Span::styled(rest, text_style));
+            }
+        }
+        Paragraph::new(text::Spans::from(spans)).block(block)
+    }
+
+    // Dropdown is of same width as searchbar and is placed right below it. Height is enough to show
+    // all suggestions (upto MAX_SUGGESTION_ROWS) but never goes beyond the screen
+    pub fn get_suggestion_area(search: &Rect, count: usize, screen: &Rect) -> Rect {
+        const MAX_SUGGESTION_ROWS: usize = 10;
+        let y = search.y + search.height;
+        let wanted = std::cmp::min(count, MAX_SUGGESTION_ROWS) as u16 + 2;
+        Rect {
+            x: search.x,
+            y,
+            width: search.width,
+            height: std::cmp::min(wanted, screen.height.saturating_sub(y)),
+        }
+    }
+
+    pub fn get_suggestions(state: &'parent ui::State) -> List<'parent> {
+        List::new(
+            state
+                .suggestions
+                .1
+                .iter()
+                .map(|suggestion| {
+                    ListItem::new(Span::styled(
+                        suggestion.as_str(),
+                        Style::list_idle().fg(rgb!(theme().color_primary)),
+                    ))
+                })
+                .collect::<Vec<ListItem>>(),
+        )
+        .highlight_style(Style::list_selected())
+        .block(Block::new("Suggestions ".to_owned()))
     }
 }
 
 impl<'parent> ui::MainLayout {
-    pub fn new(parent: Rect) -> Self {
+    pub fn new(parent: Rect, panes: &ui::Panes) -> Self {
         let layout = Layout::default()
             .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(20), Constraint::Percentage(80)])
+            .constraints([
+                Constraint::Percentage(panes.sidebar),
+                Constraint::Percentage(100 - panes.sidebar),
+            ])
             .split(parent);
 
         ui::MainLayout {
             sidebar: ui::SideBar::new(layout[0]),
-            middle_section: ui::MiddleLayout::new(layout[1]),
+            middle_section: ui::MiddleLayout::new(layout[1], panes),
         }
     }
 }
 
 impl<'parent> ui::MiddleLayout {
-    pub fn new(parent: Rect) -> Self {
+    // Wide terminal puts music section on the left and playlist and artist section on the right
+    // one above another. Size of music section is then it's width
+    pub fn new(parent: Rect, panes: &ui::Panes) -> Self {
+        let direction = if panes.wide {
+            Direction::Horizontal
+        } else {
+            Direction::Vertical
+        };
         let layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .direction(direction)
+            .constraints([
+                Constraint::Percentage(panes.music),
+                Constraint::Percentage(100 - panes.music),
+            ])
             .split(parent);
 
         ui::MiddleLayout {
             layout: layout[0],
-            bottom: ui::MiddleBottom::new(layout[1]),
+            bottom: ui::MiddleBottom::new(layout[1], panes.wide),
+        }
+    }
+
+    // Header of artist or playlist whose music is being shown
+    pub fn get_header(state: &'parent ui::State) -> Paragraph<'parent> {
+        if state.header_artist().is_some() {
+            Self::get_artist_header(state)
+        } else {
+            Self::get_playlist_header(state)
+        }
+    }
+
+    // Title of playlist followed by author and number of music, and which of those are listed so
+    // that it is known how many more are left in next pages
+    fn get_playlist_header(state: &'parent ui::State) -> Paragraph<'parent> {
+        let playlist_id = state.header_playlist().unwrap_or_default();
+        let listed = state
+            .playlistbar
+            .0
+            .iter()
+            .find(|playlist| playlist.id == playlist_id);
+        let info = state
+            .playlist_header
+            .as_ref()
+            .filter(|info| info.id == playlist_id)
+            .or(listed);
+        let (name, author, count) = match (&state.album, info) {
+            (Some(album), _) if album.id == playlist_id => (
+                album.title.clone(),
+                album.artist.clone(),
+                album.tracks.len().to_string(),
+            ),
+            (_, Some(info)) => (
+                info.name.clone(),
+                info.author.clone(),
+                info.video_count.clone(),
+            ),
+            _ => (String::new(), String::new(), String::new()),
+        };
+        let name = if name.is_empty() {
+            state
+                .source_name(playlist_id)
+                .unwrap_or(playlist_id)
+                .to_string()
+        } else {
+            name
+        };
+
+        let mut line = vec![Span::styled(
+            name,
+            Style::list_title().add_modifier(Modifier::BOLD),
+        )];
+        if !author.is_empty() {
+            line.push(Span::styled(format!("  by {}", author), Style::list_idle()));
+        }
+        if !count.is_empty() {
+            line.push(Span::styled(
+                format!("  {} music", count),
+                Style::list_idle(),
+            ));
+        }
+
+        let shown = state.musicbar.0.len();
+        let progress = if playlist_id == state.full_playlist.id {
+            format!("All {} loaded", state.full_playlist.music.len())
+        } else if shown == 0 {
+            String::new()
+        } else {
+            let first = state.fetched_page[MIDDLE_MUSIC_INDEX].unwrap_or_default()
+                * CONFIG.constants.item_per_list
+                + 1;
+            let last = first + shown - 1;
+            if count.is_empty() {
+                format!("Showing {}-{}", first, last)
+            } else {
+                format!("Showing {}-{} of {}", first, last, count)
+            }
+        };
+
+        Paragraph::new(Text::from(vec![
+            Spans::from(line),
+            Spans::from(Span::styled(progress, Style::list_idle())),
+        ]))
+        .block(Block::new("Playlist".to_string()))
+    }
+
+    // Name of artist followed by subscriber and video count, and first line of description
+    fn get_artist_header(state: &'parent ui::State) -> Paragraph<'parent> {
+        let channel_id = state.header_artist().unwrap_or_default();
+        let info = state
+            .artist_header
+            .as_ref()
+            .filter(|info| info.id == channel_id);
+        let name = match info {
+            Some(info) if !info.name.is_empty() => info.name.as_str(),
+            _ => state.source_name(channel_id).unwrap_or(channel_id),
+        };
+
+        let mut line = vec![Span::styled(
+            name,
+            Style::list_title().add_modifier(Modifier::BOLD),
+        )];
+        let subscribers = info
+            .filter(|info| !info.subscribers.is_empty())
+            .map(|info| format!("{} subscribers", info.subscribers));
+        // Video count is only known when artist is listed in artist section
+        let videos = state
+            .artistbar
+            .0
+            .iter()
+            .find(|artist| artist.id == channel_id && !artist.video_count.is_empty())
+            .map(|artist| format!("{} videos", artist.video_count));
+        for fact in subscribers.into_iter().chain(videos) {
+            line.push(Span::styled(format!("  {}", fact), Style::list_idle()));
         }
+
+        let description = match info {
+            None => "Fetching channel info..",
+            Some(info) => info
+                .description
+                .lines()
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+                .unwrap_or_default(),
+        };
+
+        Paragraph::new(Text::from(vec![
+            Spans::from(line),
+            Spans::from(Span::styled(description, Style::list_idle())),
+        ]))
+        .block(Block::new("Artist".to_string()))
     }
 
-    pub fn get_music_container(state: &'parent mut ui::State) -> Table<'parent> {
+    // Width is of the area the music section is drawn in. Columns that do not fit are left out
+    pub fn get_music_container(state: &'parent mut ui::State, width: u16) -> Table<'parent> {
+        // Album being shown in musicbar. Album info is kept until another music source is
+        // filled so it is checked if it really is the album being shown
+        let album = match (&state.filled_source.0, &state.album) {
+            (ui::MusicbarSource::Playlist(playlist_id), Some(album))
+                if *playlist_id == album.id =>
+            {
+                Some(album)
+            }
+            _ => None,
+        };
+
+        // When trending is shown also show which category and region it is from
+        // and when album is shown show the info of album
+        let title = match (&state.filled_source.0, album) {
+            (ui::MusicbarSource::Trending(region, category), _) => {
+                format!("Music [Trending: {} in {}] ", category.name(), region)
+            }
+            (ui::MusicbarSource::Charts(country), _) => {
+                format!("Music [{}] ", charts_name(country))
+            }
+            (ui::MusicbarSource::Podcast(channel_id), _) => match state.source_name(channel_id) {
+                Some(name) => format!("Episodes [{}, newest first] ", name),
+                None => "Episodes [Newest first] ".to_owned(),
+            },
+            (ui::MusicbarSource::Search(query, false), _) => format!("Music [Search: {}] ", query),
+            (ui::MusicbarSource::Search(query, true), _) => {
+                format!("Music [Search songs: {}] ", query)
+            }
+            (ui::MusicbarSource::Favourates, _) => "Music [Favourites] ".to_owned(),
+            (ui::MusicbarSource::Local, _) => "Music [Local] ".to_owned(),
+            (ui::MusicbarSource::Related(music_id), _) => {
+                format!("Music [{}] ", state.source_label("Related to", music_id))
+            }
+            (ui::MusicbarSource::Artist(channel_id), _) => {
+                format!(
+                    "Music [{} uploads] ",
+                    state.source_label("Artist", channel_id)
+                )
+            }
+            (ui::MusicbarSource::Subscriptions, _) => "Music [Subscriptions feed] ".to_owned(),
+            (ui::MusicbarSource::NewReleases, _) => "Music [New releases] ".to_owned(),
+            // Nothing is shown from history until it is opened
+            (ui::MusicbarSource::RecentlyPlayed, _)
+                if state.fetched_page[MIDDLE_MUSIC_INDEX].is_some() =>
+            {
+                "Music [History] ".to_owned()
+            }
+            (ui::MusicbarSource::ArtistSearch(channel_id, query), _) => {
+                match state.source_name(channel_id) {
+                    Some(name) => format!("Music [Search in {}: {}] ", name, query),
+                    None => format!("Music [Search in artist: {}] ", query),
+                }
+            }
+            (ui::MusicbarSource::Playlist(playlist_id), None)
+                if *playlist_id == state.full_playlist.id =>
+            {
+                format!(
+                    "Music [{} - all {} loaded] ",
+                    state.source_label("Playlist", playlist_id),
+                    state.full_playlist.music.len()
+                )
+            }
+            (_, Some(album)) => match &album.year {
+                Some(year) => format!(
+                    "Album [{} by {} ({}) - {} tracks] ",
+                    album.title,
+                    album.artist,
+                    year,
+                    album.tracks.len()
+                ),
+                None => format!(
+                    "Album [{} by {} - {} tracks] ",
+                    album.title,
+                    album.artist,
+                    album.tracks.len()
+                ),
+            },
+            (ui::MusicbarSource::Playlist(playlist_id), None) => {
+                format!("Music [{}] ", state.source_label("Playlist", playlist_id))
+            }
+            _ => "Music ".to_owned(),
+        };
+        let title = title_with_page(title, state, MIDDLE_MUSIC_INDEX, state.musicbar.0.len());
+        let title = title_with_marks(title, state.marked.music.len());
         let block = match state.active {
-            ui::Window::Musicbar => Block::active("Music ".to_owned()),
+            ui::Window::Musicbar => Block::active(title),
             _ => {
                 state.musicbar.1.select(None);
-                Block::new("Music ".to_owned())
+                Block::new(title)
             }
         };
 
-        let data_list = &state.musicbar.0;
-        let items: Vec<Row> = data_list
+        let data_list = &state.musicbar.0;
+        let marked = &state.marked.music;
+        let is_marked = |music: &fetcher::MusicUnit| marked.iter().any(|m| m.id == music.id);
+
+        // Column by which music is sorted is marked in the header
+        let sort = state.music_sort.0;
+        let arrow = if CONFIG.constants.ascii_only {
+            " v"
+        } else {
+            " ▼"
+        };
+        let columns = music_columns(width.saturating_sub(2));
+        let mut header: Vec<String> = columns
+            .iter()
+            .map(|(column, _)| {
+                if column.sort() == Some(sort) {
+                    format!("{}{}", column.title(), arrow)
+                } else {
+                    column.title().to_owned()
+                }
+            })
+            .collect();
+        let total_width: u32 = columns.iter().map(|(_, width)| *width as u32).sum();
+        let mut widths: Vec<Constraint> = columns
+            .iter()
+            .map(|(_, width)| Constraint::Ratio(*width as u32, total_width))
+            .collect();
+
+        // Tracks of album are shown with track number as first column. Track number is of the
+        // original order even when tracks are sorted
+        let first_track =
+            state.fetched_page[0].unwrap_or_default() * CONFIG.constants.item_per_list + 1;
+        let order = &state.music_sort.1;
+        let track_number = |index: usize, music: &fetcher::MusicUnit| {
+            let index = match sort {
+                ui::MusicSort::Original => index,
+                _ => order.iter().position(|id| *id == music.id).unwrap_or(index),
+            };
+            Cell::from((first_track + index).to_string())
+        };
+        if album.is_some() {
+            header.insert(0, "#".to_owned());
+            widths.insert(0, Constraint::Length(4));
+        }
+        state.music_widths = widths;
+        // Title and artist are cut to width of their column. Track number column is not counted
+        let cell_widths = table_column_widths(&state.music_widths, 2, width.saturating_sub(2));
+        let cell_widths = &cell_widths[album.map_or(0, |_| 1)..];
+
+        // Episode that was left in middle shows where it will be resumed from
+        let is_podcast = matches!(state.filled_source.0, ui::MusicbarSource::Podcast(_));
+        let positions = &state.podcast.positions;
+        let playing_url = state.playing_url();
+        let favourites = &state.favourites;
+        let favourite = favourite_mark();
+        let items: Vec<Row> = data_list
+            .iter()
+            .enumerate()
+            .map(|(index, music)| {
+                let is_playing = playing_url == Some(fetcher::music_url(&music.id).as_str());
+                let mut cells: Vec<Cell> = columns
+                    .iter()
+                    .zip(cell_widths)
+                    .map(|((column, _), &cell_width)| match column {
+                        MusicColumn::Title => {
+                            let mut icons = Vec::new();
+                            if is_playing {
+                                icons.push(Span::styled(playing_icon(), Style::list_title()));
+                            }
+                            if favourites.contains(&music.id) {
+                                icons.push(Span::styled(favourite.clone(), Style::list_title()));
+                            }
+                            Cell::from(name_with_icons(icons, &music.name, cell_width))
+                        }
+                        MusicColumn::Artist => {
+                            Cell::from(Span::raw(truncate_to_width(&music.artist, cell_width)))
+                        }
+                        MusicColumn::Duration => match positions.get(&music.id) {
+                            _ if music.live => {
+                                Cell::from(Span::styled("LIVE", Style::list_title()))
+                            }
+                            Some(position) if is_podcast => {
+                                Cell::from(format!("{} / {}", position.to_string(), music.duration))
+                            }
+                            _ => Cell::from(music.duration.as_str()),
+                        },
+                        MusicColumn::Views => Cell::from(music.views.as_str()),
+                        MusicColumn::Uploaded => Cell::from(music.published.as_str()),
+                    })
+                    .collect();
+                if album.is_some() {
+                    cells.insert(0, track_number(index, music));
+                }
+                let row = Row::new(cells);
+                // Music that could not be played from any route
+                if state.unavailable.contains(&music.id) {
+                    row.style(Style::list_idle().add_modifier(Modifier::CROSSED_OUT))
+                } else if is_marked(music) {
+                    row.style(Style::list_marked())
+                } else if is_playing {
+                    row.style(Style::list_idle().fg(rgb!(theme().color_primary)))
+                } else {
+                    row
+                }
+            })
+            .collect();
+        Table::new(items)
+            .header(Row::new(header).style(Style::list_title()))
+            .widths(&state.music_widths)
+            .column_spacing(2)
+            .style(Style::list_idle())
+            .highlight_style(Style::list_selected())
+            .block(block)
+    }
+
+    pub fn get_chapter_list(state: &'parent ui::State) -> List<'parent> {
+        let current = state.current_chapter();
+        List::new(
+            state
+                .chapters
+                .list
+                .iter()
+                .enumerate()
+                .map(|(index, chapter)| {
+                    let mut style = Style::list_idle();
+                    if Some(index) == current {
+                        style = style.fg(rgb!(theme().color_primary));
+                    }
+                    ListItem::new(Spans::from(vec![
+                        Span::styled(format!("{:>8}  ", chapter.start.to_string()), style),
+                        Span::styled(chapter.title.as_str(), style),
+                    ]))
+                })
+                .collect::<Vec<ListItem>>(),
+        )
+        .highlight_style(Style::list_selected())
+        .block(Block::active("Chapters ".to_owned()))
+    }
+
+    // Music in queue is shown by it's name when it is in any list being shown. Otherwise player
+    // only knows the url (or path) of it
+    pub fn get_queue_list(state: &'parent ui::State) -> List<'parent> {
+        let (_, current) = state.player.queue();
+        List::new(
+            state
+                .queue_names()
+                .into_iter()
+                .enumerate()
+                .map(|(index, name)| {
+                    let mut style = Style::list_idle();
+                    let mut icon = "  ";
+                    if Some(index) == current {
+                        style = style.fg(rgb!(theme().color_primary));
+                        icon = playing_icon();
+                    }
+                    ListItem::new(Span::styled(
+                        format!("{:>4} {}{}", index + 1, icon, name),
+                        style,
+                    ))
+                })
+                .collect::<Vec<ListItem>>(),
+        )
+        .highlight_style(Style::list_selected())
+        .block(Block::active(
+            "Queue [J/K: move, x: remove, Enter: play] ".to_owned(),
+        ))
+    }
+
+    // Categories are shown along with the group they belong to. eg: `Genres  Pop`
+    pub fn get_mood_list(state: &'parent ui::State) -> List<'parent> {
+        let group_width = state
+            .moods
+            .list
             .iter()
-            .map(|music| {
-                Row::new(vec![
-                    music.name.as_str(),
-                    music.artist.as_str(),
-                    music.duration.as_str(),
-                ])
-            })
-            .collect();
-        let table = Table::new(items)
-            .header(Row::new(vec!["Title", "Artist", "Length"]).style(Style::list_title()))
-            .widths(
-                [
-                    Constraint::Percentage(55),
-                    Constraint::Percentage(30),
-                    Constraint::Percentage(15),
-                ]
-                .as_ref(),
-            )
-            .column_spacing(2)
-            .style(Style::list_idle())
-            .highlight_style(Style::list_highlight())
-            .block(block);
+            .map(|category| display_width(&category.group))
+            .max()
+            .unwrap_or_default();
+        List::new(
+            state
+                .moods
+                .list
+                .iter()
+                .map(|category| {
+                    ListItem::new(Spans::from(vec![
+                        Span::styled(
+                            format!("{}  ", pad_to_width(&category.group, group_width)),
+                            Style::list_idle().fg(rgb!(theme().color_primary)),
+                        ),
+                        Span::styled(category.title.as_str(), Style::list_idle()),
+                    ]))
+                })
+                .collect::<Vec<ListItem>>(),
+        )
+        .highlight_style(Style::list_selected())
+        .block(Block::active("Moods & genres ".to_owned()))
+    }
 
-        table
+    // Device being cast to (or this computer when not casting) is marked as being played
+    pub fn get_cast_list(state: &'parent ui::State) -> List<'parent> {
+        let current = state.cast.target.as_ref();
+        let entries = std::iter::once((None, "This computer".to_string())).chain(
+            state.cast.devices.iter().map(|device| {
+                (
+                    Some(device),
+                    format!(
+                        "{}  ({} {})",
+                        device.name,
+                        device.kind.name(),
+                        device.address
+                    ),
+                )
+            }),
+        );
+        List::new(
+            entries
+                .map(|(device, name)| {
+                    let mut style = Style::list_idle();
+                    let mut icon = "  ";
+                    if device == current {
+                        style = style.fg(rgb!(theme().color_primary));
+                        icon = playing_icon();
+                    }
+                    ListItem::new(Span::styled(format!("{}{}", icon, name), style))
+                })
+                .collect::<Vec<ListItem>>(),
+        )
+        .highlight_style(Style::list_selected())
+        .block(Block::active("Play on ".to_owned()))
+    }
+
+    // Device being played to is marked as being played
+    pub fn get_output_list(state: &'parent ui::State) -> List<'parent> {
+        let current = state
+            .player
+            .get_property::<String>("audio-device")
+            .unwrap_or_default();
+        List::new(
+            state
+                .outputs
+                .devices
+                .iter()
+                .map(|(name, description)| {
+                    let mut style = Style::list_idle();
+                    let mut icon = "  ";
+                    if *name == current {
+                        style = style.fg(rgb!(theme().color_primary));
+                        icon = playing_icon();
+                    }
+                    ListItem::new(Span::styled(format!("{}{}", icon, description), style))
+                })
+                .collect::<Vec<ListItem>>(),
+        )
+        .highlight_style(Style::list_selected())
+        .block(Block::active("Audio output ".to_owned()))
     }
 }
 
 impl<'parent> ui::MiddleBottom {
-    pub fn new(parent: Rect) -> Self {
+    pub fn new(parent: Rect, stacked: bool) -> Self {
+        let direction = if stacked {
+            Direction::Vertical
+        } else {
+            Direction::Horizontal
+        };
         let layout = Layout::default()
-            .direction(Direction::Horizontal)
+            .direction(direction)
             .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
             .split(parent);
 
@@ -171,63 +1782,213 @@ impl<'parent> ui::MiddleBottom {
         }
     }
 
-    pub fn get_playlist_container(state: &'parent mut ui::State) -> Table<'parent> {
+    pub fn get_playlist_container(state: &'parent mut ui::State, width: u16) -> Table<'parent> {
+        // Show which category is shown when playlists of mood/genre is listed
+        let title = match &state.filled_source.1 {
+            ui::PlaylistbarSource::Mood(_, category) => format!("Playlist [{}] ", category),
+            ui::PlaylistbarSource::Search(query) => format!("Playlist [Search: {}] ", query),
+            ui::PlaylistbarSource::Favourates => "Playlist [Favourites] ".to_owned(),
+            ui::PlaylistbarSource::Local => "Playlist [Local] ".to_owned(),
+            ui::PlaylistbarSource::Charts(country) => {
+                format!("Playlist [{}] ", charts_name(country))
+            }
+            ui::PlaylistbarSource::Artist(channel_id) => {
+                format!("Playlist [{}] ", state.source_label("Artist", channel_id))
+            }
+            ui::PlaylistbarSource::RecentlyPlayed => "Playlist ".to_owned(),
+        };
+        let title = title_with_page(
+            title,
+            state,
+            MIDDLE_PLAYLIST_INDEX,
+            state.playlistbar.0.len(),
+        );
+        let title = title_with_marks(title, state.marked.playlist.len());
         let block = match state.active {
-            ui::Window::Playlistbar => Block::active("Playlist ".to_owned()),
+            ui::Window::Playlistbar => Block::active(title),
             _ => {
                 state.playlistbar.1.select(None);
-                Block::new("Playlist ".to_owned())
+                Block::new(title)
             }
         };
         let data_list = &state.playlistbar.0;
+        let marked = &state.marked.playlist;
+        let cell_widths = table_column_widths(&PLAYLIST_WIDTHS, 1, width.saturating_sub(2));
+        let favourites = &state.favourites;
+        let favourite = favourite_mark();
         let items: Vec<Row> = data_list
             .iter()
             .map(|playlist| {
-                Row::new(vec![
-                    playlist.video_count.as_str(),
-                    playlist.name.as_str(),
-                    playlist.author.as_str(),
-                ])
+                let mut icons = Vec::new();
+                if favourites.contains(&playlist.id) {
+                    icons.push(Span::styled(favourite.clone(), Style::list_title()));
+                }
+                // Albums are labeled so that they can be distinguished from regular playlist
+                if fetcher::is_album_id(&playlist.id) {
+                    icons.push(Span::styled("[Album] ", Style::list_title()));
+                }
+                let row = Row::new(vec![
+                    Cell::from(playlist.video_count.as_str()),
+                    Cell::from(name_with_icons(icons, &playlist.name, cell_widths[1])),
+                    Cell::from(Span::raw(truncate_to_width(
+                        &playlist.author,
+                        cell_widths[2],
+                    ))),
+                ]);
+                if marked.iter().any(|m| m.id == playlist.id) {
+                    row.style(Style::list_marked())
+                } else {
+                    row
+                }
             })
             .collect();
         let table = Table::new(items)
             .header(Row::new(vec!["#", "Name", "Creator"]).style(Style::list_title()))
-            .widths(
-                [
-                    Constraint::Percentage(10),
-                    Constraint::Percentage(60),
-                    Constraint::Percentage(30),
-                ]
-                .as_ref(),
-            )
+            .widths(&PLAYLIST_WIDTHS)
             .column_spacing(1)
             .style(Style::list_idle())
-            .highlight_style(Style::list_highlight())
+            .highlight_style(Style::list_selected())
             .block(block);
 
         table
     }
 
-    pub fn get_artist_container(state: &'parent mut ui::State) -> Table<'parent> {
+    // Lyrics are scrolled so that the line being sung is always in the middle of area
+    pub fn get_lyrics(state: &'parent ui::State, area: &Rect) -> Paragraph<'parent> {
+        let block = Block::new("Lyrics ".to_owned());
+        let lyrics = match &state.lyrics.lyrics {
+            Some(lyrics) if !lyrics.lines.is_empty() => lyrics,
+            _ => {
+                let message = if state.lyrics.title.is_empty() {
+                    "Play some music to see lyrics"
+                } else {
+                    "Lyrics not available"
+                };
+                return Paragraph::new(Span::styled(message, Style::list_idle()))
+                    .alignment(Alignment::Center)
+                    .block(block);
+            }
+        };
+
+        let current = lyrics.current_line(state.bottom.music_elapse);
+        let lines = lyrics
+            .lines
+            .iter()
+            .enumerate()
+            .map(|(index, (_, line))| {
+                let style = if Some(index) == current {
+                    Style::list_highlight().add_modifier(Modifier::BOLD)
+                } else {
+                    Style::list_idle()
+                };
+                Spans::from(Span::styled(line.as_str(), style))
+            })
+            .collect::<Vec<Spans>>();
+        let half_height = area.height.saturating_sub(2) / 2;
+        let scroll = (current.unwrap_or_default() as u16).saturating_sub(half_height);
+
+        Paragraph::new(lines)
+            .alignment(Alignment::Center)
+            .scroll((scroll, 0))
+            .block(block)
+    }
+
+    // Every comment is shown as author and it's info followed by the comment itself. Comments
+    // before `scroll` are skipped rather than scrolling by line so that comment is never cut
+    pub fn get_comments(state: &'parent ui::State) -> Paragraph<'parent> {
+        let comments = &state.comments;
+        let title = if comments.continuation.is_some() {
+            format!("Comments [{} loaded, more..] ", comments.list.len())
+        } else {
+            format!("Comments [{}] ", comments.list.len())
+        };
+        let block = Block::new(title);
+        if comments.list.is_empty() {
+            let message = if comments.music_id.is_empty() {
+                "Play some music to see comments"
+            } else {
+                "Comments not available"
+            };
+            return Paragraph::new(Span::styled(message, Style::list_idle()))
+                .alignment(Alignment::Center)
+                .block(block);
+        }
+
+        let mut lines = Vec::new();
+        for comment in comments.list.iter().skip(comments.scroll) {
+            lines.push(Spans::from(vec![
+                Span::styled(
+                    comment.author.as_str(),
+                    Style::list_idle()
+                        .fg(rgb!(theme().color_primary))
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(
+                    format!("  {} | {} likes", comment.published, comment.likes),
+                    Style::list_idle(),
+                ),
+            ]));
+            lines.extend(
+                comment
+                    .content
+                    .lines()
+                    .map(|line| Spans::from(Span::styled(line, Style::list_idle()))),
+            );
+            lines.push(Spans::default());
+        }
+
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: false })
+            .block(block)
+    }
+
+    pub fn get_artist_container(state: &'parent mut ui::State, width: u16) -> Table<'parent> {
         let block;
+        let title = match &state.filled_source.2 {
+            ui::ArtistbarSource::Search(query) => format!("Artist [Search: {}] ", query),
+            ui::ArtistbarSource::Favourates => "Artist [Favourites] ".to_owned(),
+            ui::ArtistbarSource::Subscriptions => "Artist [Subscriptions] ".to_owned(),
+            ui::ArtistbarSource::Charts(country) => format!("Artist [{}] ", charts_name(country)),
+            ui::ArtistbarSource::RecentlyPlayed => "Artist ".to_owned(),
+        };
+        let title = title_with_page(title, state, MIDDLE_ARTIST_INDEX, state.artistbar.0.len());
+        let title = title_with_marks(title, state.marked.artist.len());
         if state.active == ui::Window::Artistbar {
-            block = Block::active("Artist ".to_string());
+            block = Block::active(title);
         } else {
-            block = Block::new("Artist ".to_string());
+            block = Block::new(title);
             state.artistbar.1.select(None);
         }
         let data_list = &state.artistbar;
+        let marked = &state.marked.artist;
+        let cell_widths = table_column_widths(&ARTIST_WIDTHS, 1, width.saturating_sub(2));
+        let favourites = &state.favourites;
+        let favourite = favourite_mark();
         let items: Vec<Row> = data_list
             .0
             .iter()
-            .map(|artist| Row::new(vec![artist.video_count.as_str(), artist.name.as_str()]))
+            .map(|artist| {
+                let mut icons = Vec::new();
+                if favourites.contains(&artist.id) {
+                    icons.push(Span::styled(favourite.clone(), Style::list_title()));
+                }
+                let row = Row::new(vec![
+                    Cell::from(artist.video_count.as_str()),
+                    Cell::from(name_with_icons(icons, &artist.name, cell_widths[1])),
+                ]);
+                if marked.iter().any(|m| m.id == artist.id) {
+                    row.style(Style::list_marked())
+                } else {
+                    row
+                }
+            })
             .collect();
         let table = Table::new(items)
             .header(Row::new(vec!["#", "Name"]).style(Style::list_title()))
-            .widths([Constraint::Percentage(10), Constraint::Percentage(90)].as_ref())
+            .widths(&ARTIST_WIDTHS)
             .column_spacing(1)
             .style(Style::list_idle())
-            .highlight_style(Style::list_highlight())
+            .highlight_style(Style::list_selected())
             .block(block);
 
         table
@@ -270,12 +2031,12 @@ impl<'parent> ui::SideBar {
                 .map(|v| {
                     ListItem::new(Span::styled(
                         *v,
-                        Style::list_idle().fg(rgb!(CONFIG.theme.color_primary)),
+                        Style::list_idle().fg(rgb!(theme().color_primary)),
                     ))
                 })
                 .collect::<Vec<ListItem>>(),
         )
-        .highlight_style(Style::list_highlight())
+        .highlight_style(Style::list_selected())
         .block(block)
     }
 }
@@ -291,18 +2052,35 @@ impl<'parent> ui::BottomLayout {
     }
 
     pub fn get_status_bar(state: &'parent ui::State) -> Gauge<'parent> {
-        let content;
-        if let Some((name, _)) = &state.bottom.playing {
-            content = name.as_str()
-        } else {
-            content = ">> Play some Music <<"
+        let content = match &state.bottom.playing {
+            Some((name, _)) if !state.bottom.artist.1.is_empty() => {
+                format!("{} - {}", name, state.bottom.artist.1)
+            }
+            Some((name, _)) => name.clone(),
+            None => ">> Play some Music <<".to_string(),
         };
 
-        let heading = format!(
-            "{} / {}",
-            state.bottom.music_elapse.to_string(),
-            state.bottom.music_duration.to_string()
-        );
+        // Live stream have no duration to show progress of
+        let mut heading = if state.bottom.live {
+            "LIVE".to_string()
+        } else {
+            format!(
+                "{} / {}",
+                state.bottom.music_elapse.to_string(),
+                state.bottom.music_duration.to_string()
+            )
+        };
+        if let Some(chapter) = state.current_chapter() {
+            let title = &state.chapters.list[chapter].title;
+            if !title.is_empty() {
+                heading.push_str(&format!(" | {}", title));
+            }
+        }
+        if let Some(device) = &state.cast.target {
+            heading.push_str(&format!(" | Cast: {}", device.name));
+        }
+        // Icons set in sidebar is not shown in every layout so status is also shown here
+        heading = format!("{} | {}", playback_indicators(state), heading);
 
         let mut block;
         if state.active == ui::Window::BottomControl {
@@ -314,7 +2092,7 @@ impl<'parent> ui::BottomLayout {
 
         let mut ratio =
             state.bottom.music_elapse.as_secs_f64() / state.bottom.music_duration.as_secs_f64();
-        if ratio > 1.0 {
+        if ratio > 1.0 || state.bottom.live {
             ratio = 1.0
         } else if ratio.is_nan() || ratio < 0.0 {
             ratio = 0.0
@@ -322,10 +2100,10 @@ impl<'parent> ui::BottomLayout {
 
         Gauge::default()
             .ratio(ratio)
-            .gauge_style(Style::default().fg(rgb!(CONFIG.theme.gauge_fill)))
+            .gauge_style(Style::default().fg(rgb!(theme().gauge_fill)))
             .label(Span::styled(
                 content,
-                Style::default().fg(rgb!(CONFIG.theme.color_primary)),
+                Style::default().fg(rgb!(theme().color_primary)),
             ))
             .block(block)
     }
@@ -335,16 +2113,21 @@ impl<'parent> ui::BottomLayout {
     // | suffle | <strikethrough>suffle<strikethrough>
     // | (no-)repeat
     // | playing | paused (blinked)
+    // High contrast mode tells shuffle and mute state with text as strikethrough and blinking
+    // is not shown by every terminal
     pub fn get_icons_set(state: &'parent ui::State) -> Paragraph<'parent> {
         let block = Block::active(String::new());
+        let textual = high_contrast();
 
         let mut paused_status = Span::styled("playing", Style::list_highlight());
         if let Some((_, false)) = state.bottom.playing {
             // is paused
-            paused_status = Span::styled(
-                "paused",
-                Style::list_idle().add_modifier(Modifier::SLOW_BLINK),
-            );
+            let attention = if textual {
+                Modifier::REVERSED
+            } else {
+                Modifier::SLOW_BLINK
+            };
+            paused_status = Span::styled("paused", Style::list_idle().add_modifier(attention));
         }
 
         let mut repeat = Span::styled("repeat-all", Style::list_highlight());
@@ -353,14 +2136,21 @@ impl<'parent> ui::BottomLayout {
         }
 
         let mut suffle = Span::styled("suffle", Style::list_highlight());
-        if !state.playback_behaviour.shuffle {
+        if !state.playback_behaviour.shuffle && textual {
+            suffle.content = Cow::Borrowed("no-suffle");
+        } else if !state.playback_behaviour.shuffle {
             suffle.style = suffle.style.add_modifier(Modifier::CROSSED_OUT);
         }
 
-        let volume = Span::styled(
+        let mut volume = Span::styled(
             format!("Vol: {}", state.playback_behaviour.volume),
             Style::list_highlight(),
         );
+        if state.playback_behaviour.muted && textual {
+            volume.content = Cow::Borrowed("Vol: muted");
+        } else if state.playback_behaviour.muted {
+            volume.style = volume.style.add_modifier(Modifier::CROSSED_OUT);
+        }
 
         let content = Text {
             lines: [
@@ -384,26 +2174,54 @@ pub trait ExtendBlock<'a> {
 }
 pub trait ExtendStyle {
     fn list_highlight() -> Style;
+    fn list_selected() -> Style;
     fn block_title() -> Style;
     fn list_idle() -> Style;
     fn list_title() -> Style;
+    fn list_marked() -> Style;
 }
 
 impl ExtendStyle for Style {
     #[inline(always)]
     fn list_highlight() -> Style {
-        Style::default().fg(rgb!(CONFIG.theme.list_hilight))
+        Style::default().fg(rgb!(theme().list_hilight))
+    }
+
+    // Row under cursor. High contrast mode tells it apart without relying on colour
+    #[inline(always)]
+    fn list_selected() -> Style {
+        if high_contrast() {
+            return Style::list_highlight().add_modifier(Modifier::BOLD | Modifier::REVERSED);
+        }
+        let theme = theme();
+        let mut style = Style::default().fg(rgb!(theme.list_hilight));
+        if theme.list_hilight_bold {
+            style = style.add_modifier(Modifier::BOLD);
+        }
+        if theme.list_hilight_reversed {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        if let Some(background) = theme.list_hilight_background {
+            style = style.bg(rgb!(background));
+        }
+        style
     }
 
     #[inline(always)]
     fn list_title() -> Style {
-        Style::default().fg(rgb!(CONFIG.theme.color_secondary))
+        Style::default().fg(rgb!(theme().color_secondary))
+    }
+
+    // Row marked for batch action. See ui::MarkState
+    #[inline(always)]
+    fn list_marked() -> Style {
+        Style::list_title().add_modifier(Modifier::BOLD | Modifier::UNDERLINED)
     }
 
     #[inline(always)]
     fn block_title() -> Style {
         Style {
-            fg: Some(rgb!(CONFIG.theme.block_title)),
+            fg: Some(rgb!(theme().block_title)),
             bg: None,
             add_modifier: Modifier::BOLD | Modifier::ITALIC,
             sub_modifier: Modifier::empty(),
@@ -413,7 +2231,7 @@ impl ExtendStyle for Style {
     #[inline(always)]
     fn list_idle() -> Style {
         Style {
-            fg: Some(rgb!(CONFIG.theme.list_idle)),
+            fg: Some(rgb!(theme().list_idle)),
             bg: None,
             add_modifier: Modifier::BOLD,
             sub_modifier: Modifier::empty(),
@@ -421,50 +2239,289 @@ impl ExtendStyle for Style {
     }
 }
 
+// Border of active section as set in theme. Unknown names are left rounded like other borders
+fn highlight_border_type() -> BorderType {
+    match theme().border_highlight_type.as_str() {
+        "plain" => BorderType::Plain,
+        "double" => BorderType::Double,
+        "thick" => BorderType::Thick,
+        _ => BorderType::Rounded,
+    }
+}
+
 impl<'a> ExtendBlock<'a> for Block<'_> {
     fn new(title: String) -> Self {
         Block::default()
             .title(Span::styled(title, Style::block_title()))
             .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(rgb!(CONFIG.theme.border_idle)))
+            .border_style(Style::default().fg(rgb!(theme().border_idle)))
             .borders(Borders::ALL)
     }
+    // High contrast mode draws focused block with thick border and marks it's title so that focus
+    // is not told only by colour
     fn active(title: String) -> Self {
+        let (title, border_type) = if high_contrast() && !title.is_empty() {
+            (format!("[*] {}", title), BorderType::Thick)
+        } else if high_contrast() {
+            (title, BorderType::Thick)
+        } else {
+            (title, highlight_border_type())
+        };
         Block::default()
             .title(Span::styled(
                 title,
-                Style::block_title().fg(rgb!(CONFIG.theme.border_highlight)),
+                Style::block_title().fg(rgb!(theme().border_highlight)),
             ))
-            .border_type(BorderType::Rounded)
-            .border_style(Style::default().fg(rgb!(CONFIG.theme.border_highlight)))
+            .border_type(border_type)
+            .border_style(Style::default().fg(rgb!(theme().border_highlight)))
             .borders(Borders::ALL)
     }
 }
 
+// Thumb drawn over the right border of list telling which part of the list is shown. Nothing is
+// drawn when every row fits. Rendered in area of whole list (including border and header)
+pub struct Scrollbar {
+    pub len: usize,
+    pub offset: usize,
+    // Number of rows that fits in the list
+    pub visible: usize,
+}
+
+impl Widget for Scrollbar {
+    fn render(self, area: Rect, buf: &mut tui::buffer::Buffer) {
+        if self.len <= self.visible || area.width == 0 || area.height < 3 {
+            return;
+        }
+        // Thumb moves between the top and bottom border
+        let track = area.height as usize - 2;
+        let size = (track * self.visible / self.len).clamp(1, track);
+        let start = if self.offset + self.visible >= self.len {
+            track - size
+        } else {
+            (track * self.offset / self.len).min(track - size)
+        };
+        let x = area.x + area.width - 1;
+        for y in start..start + size {
+            buf.get_mut(x, area.y + 1 + y as u16)
+                .set_symbol(if CONFIG.constants.ascii_only {
+                    "#"
+                } else {
+                    "┃"
+                })
+                .set_fg(rgb!(theme().border_highlight));
+        }
+    }
+}
+
+// Drawn over the whole screen after everything else to replace box drawing characters (of borders
+// drawn by tui) and block elements with ascii. tui can only draw borders with box drawing characters
+// so they are replaced afterwards instead
+pub struct AsciiOnly;
+
+impl Widget for AsciiOnly {
+    fn render(self, area: Rect, buf: &mut tui::buffer::Buffer) {
+        const HORIZONTAL: &str = "─━┄┅┈┉═╌╍╴╶╸╺╼╾";
+        const VERTICAL: &str = "│┃┆┇┊┋║╎╏╵╷╹╻╽╿";
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                let cell = buf.get_mut(x, y);
+                let ch = match cell.symbol.chars().next() {
+                    Some(ch) => ch,
+                    None => continue,
+                };
+                let replacement = match ch {
+                    _ if HORIZONTAL.contains(ch) => "-",
+                    _ if VERTICAL.contains(ch) => "|",
+                    '\u{2500}'..='\u{257f}' => "+",
+                    '\u{2580}'..='\u{259f}' => "#",
+                    _ => continue,
+                };
+                cell.set_symbol(replacement);
+            }
+        }
+    }
+}
+
+// Index of first row of list that is shown. ListState and TableState do not tell this so it is
+// followed here the same way as tui moves it while rendering list of single line rows. `visible` is
+// the number of rows that fits in the list
+pub fn follow_offset(offset: usize, selected: Option<usize>, len: usize, visible: usize) -> usize {
+    if len == 0 {
+        return 0;
+    }
+    let offset = offset.min(len - 1);
+    let selected = selected.unwrap_or_default().min(len - 1);
+    if visible > 0 && selected >= offset + visible {
+        selected + 1 - visible
+    } else if selected < offset {
+        selected
+    } else {
+        offset
+    }
+}
+
+// How well the query matches the text. Every character of query (except spaces) should appear in
+// text in same order, ignoring the case. Characters matched one after another and at the start of
+// word score more while skipping characters in between scores less. None if query does not match
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let text = text.to_lowercase().chars().collect::<Vec<char>>();
+    let mut score = 0;
+    let mut next = 0;
+    for ch in query
+        .to_lowercase()
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+    {
+        let found = (next..text.len()).find(|&index| text[index] == ch)?;
+        score += 1;
+        if found > 0 && found == next {
+            score += 4;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 2;
+        }
+        score -= ((found - next) as i64).min(3);
+        next = found + 1;
+    }
+    Some(score)
+}
+
+// Entries of list matching the query, best match first. Entries scoring same are kept in order
+fn fuzzy_filter<T: Clone>(list: &[T], query: &str, text: impl Fn(&T) -> String) -> Vec<T> {
+    let mut matched = list
+        .iter()
+        .filter_map(|item| Some((fuzzy_score(query, &text(item))?, item)))
+        .collect::<Vec<(i64, &T)>>();
+    matched.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    matched.into_iter().map(|(_, item)| item.clone()).collect()
+}
+
+// Index of row of list at given line of screen. List is drawn in bordered `area` and first
+// `header` lines are not rows. None if line is not of any row
+pub fn row_at(area: &Rect, line: u16, header: u16, offset: usize) -> Option<usize> {
+    let first_row = area.y + 1 + header;
+    if line >= first_row && line + 1 < area.y + area.height {
+        Some(offset + (line - first_row) as usize)
+    } else {
+        None
+    }
+}
+
 impl ui::Position {
-    pub fn caclulate(screen_rect: &Rect) -> Self {
+    // Window drawn at given cell of screen. Only the windows that can be focused are considered
+    pub fn window_at(&self, column: u16, line: u16) -> Option<ui::Window> {
+        let cell = Rect::new(column, line, 1, 1);
+        [
+            (self.search, ui::Window::Searchbar),
+            (self.shortcut, ui::Window::Sidebar),
+            (self.music, ui::Window::Musicbar),
+            (self.playlist, ui::Window::Playlistbar),
+            (self.artist, ui::Window::Artistbar),
+            (self.music_info, ui::Window::BottomControl),
+        ]
+        .into_iter()
+        .find(|(area, _)| area.intersects(cell))
+        .map(|(_, window)| window)
+    }
+
+    pub fn caclulate(screen_rect: &Rect, panes: &ui::Panes) -> Self {
         // 3 line for each bottom and top bar (1 for content and 2 for border)
-        // remaining height for middlebar
-        let for_middle = screen_rect.height.checked_sub(3 + 3).unwrap_or_default();
+        // remaining height for middlebar. Mini layout has no top bar
+        let top_height = if panes.preset == ui::LayoutPreset::Mini {
+            0
+        } else {
+            3
+        };
+        // Key hints footer takes one line below the bottom bar. Not in tiny terminal
+        let hints_height = if panes.hints && !panes.compact { 1 } else { 0 };
+        let for_middle = screen_rect
+            .height
+            .saturating_sub(top_height + 3 + hints_height);
 
         let main_layout = Layout::default()
             .direction(Direction::Vertical)
             .constraints(
                 [
-                    Constraint::Length(3),
+                    Constraint::Length(top_height),
                     Constraint::Length(for_middle),
                     Constraint::Length(3),
+                    Constraint::Length(hints_height),
                 ]
                 .as_ref(),
             )
             .split(*screen_rect);
 
         let top_section = ui::TopLayout::new(main_layout[0]);
-        let main_section = ui::MainLayout::new(main_layout[1]);
+        let main_section = ui::MainLayout::new(main_layout[1], panes);
         let bottom_section = ui::BottomLayout::new(main_layout[2]);
         let sidebar = main_section.sidebar;
         let middle_section = main_section.middle_section;
         let middle_bottom = middle_section.bottom;
+        // Sections that are not shown are left with no space at all so that nothing is drawn there
+        // nor they are found under mouse
+        let whole = main_layout[1];
+        let hidden = Rect {
+            width: 0,
+            height: 0,
+            ..whole
+        };
+        let mut panes_area = match panes.preset {
+            // Tiny terminal only has space for music section in the middle
+            ui::LayoutPreset::Full | ui::LayoutPreset::Search if panes.compact => {
+                [hidden, hidden, whole, hidden, hidden]
+            }
+            ui::LayoutPreset::Full => [
+                sidebar.layout[0],
+                sidebar.layout[1],
+                middle_section.layout,
+                middle_bottom.layout[0],
+                middle_bottom.layout[1],
+            ],
+            ui::LayoutPreset::Search => {
+                let middle = ui::MiddleLayout::new(whole, panes);
+                [
+                    hidden,
+                    hidden,
+                    middle.layout,
+                    middle.bottom.layout[0],
+                    middle.bottom.layout[1],
+                ]
+            }
+            ui::LayoutPreset::Mini => [hidden, hidden, whole, hidden, hidden],
+        };
+
+        // Maximized section takes the space of every section
+        if let Some(window) = &panes.maximized {
+            panes_area = [hidden; 5];
+            match window {
+                ui::Window::Sidebar => {
+                    let sidebar = ui::SideBar::new(whole);
+                    panes_area[0] = sidebar.layout[0];
+                    panes_area[1] = sidebar.layout[1];
+                }
+                ui::Window::Musicbar => panes_area[2] = whole,
+                ui::Window::Playlistbar => panes_area[3] = whole,
+                ui::Window::Artistbar => panes_area[4] = whole,
+                _ => {}
+            }
+        }
+        let [shortcut, bottom_icons, mut music, playlist, artist] = panes_area;
+
+        // Artist or playlist header takes the top of music section when there is enough space left for music
+        let mut header = hidden;
+        if panes.header
+            && panes.maximized.is_none()
+            && panes.preset != ui::LayoutPreset::Mini
+            && !panes.compact
+            && music.height >= HEADER_HEIGHT * 3
+        {
+            header = Rect {
+                height: HEADER_HEIGHT,
+                ..music
+            };
+            music.y += HEADER_HEIGHT;
+            music.height -= HEADER_HEIGHT;
+        }
 
         let center_x = screen_rect.width / 2;
         let center_y = screen_rect.height / 2;
@@ -480,18 +2537,152 @@ impl ui::Position {
         ui::Position {
             search: top_section.layout[0],
             status: top_section.layout[1],
-            shortcut: sidebar.layout[0],
-            music: middle_section.layout,
-            playlist: middle_bottom.layout[0],
-            artist: middle_bottom.layout[1],
+            shortcut,
+            music,
+            playlist,
+            artist,
             music_info: bottom_section.layout,
-            bottom_icons: sidebar.layout[1],
+            bottom_icons,
+            hints: main_layout[3],
+            header,
             popup: popup_pos,
         }
     }
 }
 
-impl Default for ui::State<'_> {
+// Terminal smaller than this (in either width or height) gets the compact layout
+const TINY_SCREEN_WIDTH: u16 = 60;
+const TINY_SCREEN_HEIGHT: u16 = 16;
+
+pub fn is_tiny_screen(screen_rect: &Rect) -> bool {
+    screen_rect.width < TINY_SCREEN_WIDTH || screen_rect.height < TINY_SCREEN_HEIGHT
+}
+
+// Terminal at least `wide_columns` (from config) wide shows music section side by side with
+// playlist and artist section
+pub fn is_wide_screen(screen_rect: &Rect) -> bool {
+    let wide_columns = CONFIG.constants.wide_columns;
+    wide_columns != 0 && screen_rect.width >= wide_columns
+}
+
+// 2 lines for border, 1 for name and numbers and 1 for description (or page) of artist or playlist
+const HEADER_HEIGHT: u16 = 4;
+pub const SIDEBAR_WIDTH_RANGE: std::ops::RangeInclusive<u16> = 10..=50;
+pub const MUSIC_HEIGHT_RANGE: std::ops::RangeInclusive<u16> = 20..=80;
+
+impl Default for ui::Panes {
+    fn default() -> Self {
+        let clamp = |size: u16, range: std::ops::RangeInclusive<u16>| {
+            size.clamp(*range.start(), *range.end())
+        };
+        let preset = match CONFIG.constants.layout.as_str() {
+            "search" => ui::LayoutPreset::Search,
+            "mini" => ui::LayoutPreset::Mini,
+            _ => ui::LayoutPreset::Full,
+        };
+        ui::Panes {
+            sidebar: clamp(CONFIG.constants.sidebar_width, SIDEBAR_WIDTH_RANGE),
+            music: clamp(CONFIG.constants.music_height, MUSIC_HEIGHT_RANGE),
+            maximized: None,
+            preset,
+            hints: CONFIG.constants.key_hints,
+            header: false,
+            compact: false,
+            wide: false,
+        }
+    }
+}
+
+impl ui::Panes {
+    // Whether the window is drawn in current layout. Windows that are not drawn should not be
+    // focused
+    pub fn is_shown(&self, window: &ui::Window) -> bool {
+        let collapsed = matches!(
+            window,
+            ui::Window::Sidebar | ui::Window::Playlistbar | ui::Window::Artistbar
+        );
+        if self.compact && collapsed {
+            return false;
+        }
+        match self.preset {
+            ui::LayoutPreset::Full => true,
+            ui::LayoutPreset::Search => *window != ui::Window::Sidebar,
+            ui::LayoutPreset::Mini => !matches!(
+                window,
+                ui::Window::Searchbar
+                    | ui::Window::Sidebar
+                    | ui::Window::Playlistbar
+                    | ui::Window::Artistbar
+            ),
+        }
+    }
+}
+
+impl ui::LayoutPreset {
+    pub fn next(self) -> Self {
+        match self {
+            ui::LayoutPreset::Full => ui::LayoutPreset::Search,
+            ui::LayoutPreset::Search => ui::LayoutPreset::Mini,
+            ui::LayoutPreset::Mini => ui::LayoutPreset::Full,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ui::LayoutPreset::Full => "Full",
+            ui::LayoutPreset::Search => "Search",
+            ui::LayoutPreset::Mini => "Mini player",
+        }
+    }
+}
+
+impl ui::MusicSort {
+    pub fn next(self) -> Self {
+        match self {
+            ui::MusicSort::Original => ui::MusicSort::Title,
+            ui::MusicSort::Title => ui::MusicSort::Duration,
+            ui::MusicSort::Duration => ui::MusicSort::Channel,
+            ui::MusicSort::Channel => ui::MusicSort::Original,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ui::MusicSort::Original => "original order",
+            ui::MusicSort::Title => "title",
+            ui::MusicSort::Duration => "duration",
+            ui::MusicSort::Channel => "channel",
+        }
+    }
+
+    // Sort the list in place. Order is list of ids in original order. Sort is stable so music with
+    // same key stay in original order
+    fn apply(self, list: &mut [fetcher::MusicUnit], order: &[String]) {
+        match self {
+            ui::MusicSort::Original => list.sort_by_key(|music| {
+                order
+                    .iter()
+                    .position(|id| *id == music.id)
+                    .unwrap_or(usize::MAX)
+            }),
+            ui::MusicSort::Title => list.sort_by_cached_key(|music| music.name.to_lowercase()),
+            // Live streams have no duration and are kept at last
+            ui::MusicSort::Duration => {
+                list.sort_by_key(|music| (music.live, duration_secs(&music.duration)))
+            }
+            ui::MusicSort::Channel => list.sort_by_cached_key(|music| music.artist.to_lowercase()),
+        }
+    }
+}
+
+// Seconds in duration like 3:45 or 1:02:03. Anything that cannot be understood is taken as 0
+fn duration_secs(duration: &str) -> u64 {
+    duration.split(':').fold(0, |secs, part| {
+        secs * 60 + part.trim().parse::<u64>().unwrap_or_default()
+    })
+}
+
+impl Default for ui::State {
     fn default() -> Self {
         let mpv = libmpv::Mpv::new().unwrap();
         mpv.configure_defult();
@@ -504,14 +2695,23 @@ impl Default for ui::State<'_> {
 
         let mut sidebar_list_state = ListState::default();
         sidebar_list_state.select(Some(0));
+        let panes = ui::Panes::default();
+        let active = if panes.is_shown(&ui::Window::Sidebar) {
+            ui::Window::Sidebar
+        } else {
+            ui::Window::Musicbar
+        };
         ui::State {
-            status: "@sudipghimire533",
+            notifications: ui::Notifications::default(),
             sidebar: sidebar_list_state,
             musicbar: (Vec::new(), TableState::default()),
             playlistbar: (Vec::new(), TableState::default()),
             artistbar: (Vec::new(), TableState::default()),
             search: (String::new(), String::new()),
-            active: ui::Window::Sidebar,
+            search_cursor: 0,
+            suggestions: (String::new(), Vec::new(), ListState::default()),
+            live_search: false,
+            active,
             fetched_page: [None; 3],
             filled_source: (
                 ui::MusicbarSource::RecentlyPlayed,
@@ -522,15 +2722,156 @@ impl Default for ui::State<'_> {
                 playing: None,
                 music_duration: Duration::new(0, 0),
                 music_elapse: Duration::new(0, 0),
+                live: false,
+                artist: (String::new(), String::new()),
             },
             player: mpv,
+            source_names: std::collections::HashMap::new(),
             playback_behaviour: ui::PlaybackBehaviour {
                 shuffle: false,
                 repeat: true,
                 volume: 100,
+                muted: false,
+            },
+            songs_only: CONFIG.constants.songs_only,
+            trending_region: CONFIG.constants.region.to_uppercase(),
+            trending_category: fetcher::TrendingCategory::Music,
+            charts_country: fetcher::innertube::GLOBAL_CHARTS.to_string(),
+            album: None,
+            lyrics: ui::LyricsState {
+                shown: false,
+                title: String::new(),
+                lyrics: None,
+            },
+            podcast: ui::PodcastState {
+                playing: None,
+                positions: load_podcast_positions(),
+            },
+            full_playlist: ui::FullPlaylistState {
+                requested: None,
+                id: String::new(),
+                music: Vec::new(),
             },
+            playback_attempt: None,
+            unavailable: std::collections::HashSet::new(),
+            favourites: load_favourite_ids(),
+            filter: ui::FilterState::default(),
+            marked: ui::MarkState::default(),
+            music_sort: (ui::MusicSort::Original, Vec::new()),
+            music_widths: Vec::new(),
+            loading: [None; 3],
+            fetch_error: [None; 3],
+            retry_requested: [false; 3],
+            end_of_results: [false; 3],
+            actions: None,
+            command: None,
+            panes,
+            album_art: ui::AlbumArtState {
+                path: String::new(),
+                image: None,
+            },
+            listen: ui::ListenState::default(),
+            track_notifications: CONFIG.constants.track_notifications,
+            chapters: ui::ChapterState {
+                path: String::new(),
+                list: Vec::new(),
+                from_player: false,
+                popup: None,
+            },
+            help: None,
+            details: None,
+            toast: None,
+            artist_header: None,
+            playlist_header: None,
+            queue: None,
+            sidebar_offset: 0,
+            list_offset: [0; 3],
+            moods: ui::MoodState {
+                list: Vec::new(),
+                requested: false,
+                popup: None,
+            },
+            cast: ui::CastState {
+                devices: Vec::new(),
+                requested: false,
+                popup: None,
+                target: None,
+            },
+            outputs: ui::OutputState {
+                devices: Vec::new(),
+                popup: None,
+            },
+            comments: ui::CommentsState {
+                shown: false,
+                music_id: String::new(),
+                list: Vec::new(),
+                continuation: None,
+                more_requested: false,
+                scroll: 0,
+            },
+        }
+    }
+}
+
+// Read all the remembered episode positions from storage. Failing to read simply means that
+// every episode will start from beginning
+fn load_podcast_positions() -> std::collections::HashMap<String, Duration> {
+    let mut positions = std::collections::HashMap::new();
+    let conn = STORAGE.lock().unwrap();
+    let query = format!(
+        "SELECT id, position FROM {tb_name}",
+        tb_name = TB_PODCAST_POSITION
+    );
+    let mut stmt = match conn.prepare(&query) {
+        Ok(stmt) => stmt,
+        Err(err) => {
+            eprintln!(
+                "Error preparing select statement for podcast position. Error: {err}",
+                err = err
+            );
+            return positions;
+        }
+    };
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+    });
+    if let Ok(rows) = rows {
+        for (id, position) in rows.flatten() {
+            if let Ok(secs) = position.parse::<u64>() {
+                positions.insert(id, Duration::from_secs(secs));
+            }
+        }
+    }
+    positions
+}
+
+// Read id of every favourite music, playlist and artist from storage. Failing to read only means
+// that favourites are not marked in lists
+fn load_favourite_ids() -> std::collections::HashSet<String> {
+    let mut ids = std::collections::HashSet::new();
+    let conn = STORAGE.lock().unwrap();
+    for tb_name in [
+        TB_FAVOURATES_MUSIC,
+        TB_FAVOURATES_PLAYLIST,
+        TB_FAVOURATES_ARTIST,
+    ] {
+        let query = format!("SELECT id FROM {tb_name}", tb_name = tb_name);
+        let mut stmt = match conn.prepare(&query) {
+            Ok(stmt) => stmt,
+            Err(err) => {
+                eprintln!(
+                    "Error preparing select statement for favourites. Error: {err}",
+                    err = err
+                );
+                continue;
+            }
+        };
+        let rows = stmt.query_map([], |row| row.get::<_, String>(0));
+        if let Ok(rows) = rows {
+            ids.extend(rows.flatten());
         }
     }
+    ids
 }
 
 pub trait ExtendMpv {
@@ -545,6 +2886,11 @@ pub trait ExtendMpv {
     fn play_prev(&self);
     fn change_volume(&self, step: i8) -> Option<u8>;
     fn get_volume(&self) -> Option<f64>;
+    fn queue(&self) -> (Vec<String>, Option<usize>);
+    fn move_in_queue(&self, from: usize, to: usize);
+    fn remove_from_queue(&self, index: usize);
+    fn play_index(&self, index: usize);
+    fn audio_devices(&self) -> Vec<(String, String)>;
 }
 
 impl ExtendMpv for libmpv::Mpv {
@@ -559,16 +2905,98 @@ impl ExtendMpv for libmpv::Mpv {
 
         // Video is always hidden. Override config file
         self.set_property("video", "no").unwrap();
+
+        // Stream urls are resolved by youtube-dl which understands every kind of proxy. Stream
+        // itself is then fetched by player which only understands http proxy
+        let proxy = CONFIG.constants.proxy.trim();
+        if !proxy.is_empty() {
+            self.set_property("ytdl-raw-options", format!("proxy={}", proxy))
+                .ok();
+            if proxy.starts_with("http://") || proxy.starts_with("https://") {
+                self.set_property("http-proxy", proxy).ok();
+            }
+        }
+
+        // Device pinned in config is only used while it is connected. Otherwise player would have
+        // no sound at all
+        let audio_device = CONFIG.constants.audio_device.trim();
+        if !audio_device.is_empty()
+            && self
+                .audio_devices()
+                .iter()
+                .any(|(name, _)| name == audio_device)
+        {
+            self.set_property("audio-device", audio_device).ok();
+        }
+    }
+
+    // Name and description of every audio device player can play to
+    fn audio_devices(&self) -> Vec<(String, String)> {
+        let count = self
+            .get_property::<i64>("audio-device-list/count")
+            .unwrap_or_default();
+        (0..count)
+            .map(|index| {
+                let property = |key: &str| {
+                    self.get_property::<String>(&format!("audio-device-list/{}/{}", index, key))
+                        .unwrap_or_default()
+                };
+                let name = property("name");
+                let description = property("description");
+                if description.is_empty() {
+                    (name.clone(), name)
+                } else {
+                    (name, description)
+                }
+            })
+            .collect()
+    }
+
+    #[inline(always)]
+    fn shuffle(&self) {
+        self.command("playlist-shuffle", &[]).ok();
+    }
+
+    #[inline(always)]
+    fn unshuffle(&self) {
+        self.command("playlist-unshuffle", &[]).ok();
+    }
+
+    // Filename (url for online music) of every entry in player's playlist along with index of the
+    // one being played
+    fn queue(&self) -> (Vec<String>, Option<usize>) {
+        let count = self
+            .get_property::<i64>("playlist-count")
+            .unwrap_or_default();
+        let entries = (0..count)
+            .map(|index| {
+                self.get_property::<String>(&format!("playlist/{}/filename", index))
+                    .unwrap_or_default()
+            })
+            .collect();
+        let current = self
+            .get_property::<i64>("playlist-pos")
+            .ok()
+            .and_then(|pos| usize::try_from(pos).ok());
+        (entries, current)
+    }
+
+    // Entry at `from` is moved to be at index `to`
+    fn move_in_queue(&self, from: usize, to: usize) {
+        // mpv places the entry before the given index so moving down needs one more
+        let before = if to > from { to + 1 } else { to };
+        self.command("playlist-move", &[&from.to_string(), &before.to_string()])
+            .ok();
     }
 
     #[inline(always)]
-    fn shuffle(&self) {
-        self.command("playlist-shuffle", &[]).ok();
+    fn remove_from_queue(&self, index: usize) {
+        self.command("playlist-remove", &[&index.to_string()]).ok();
     }
 
     #[inline(always)]
-    fn unshuffle(&self) {
-        self.command("playlist-unshuffle", &[]).ok();
+    fn play_index(&self, index: usize) {
+        self.set_property("playlist-pos", index as i64).ok();
     }
 
     #[inline(always)]
@@ -630,51 +3058,446 @@ impl ExtendMpv for libmpv::Mpv {
     }
 }
 
-impl ui::State<'_> {
+impl ui::Notifications {
+    fn push(&mut self, severity: ui::Severity, message: std::borrow::Cow<'static, str>) {
+        // Info is replaced by any newer message. Same message is not queued twice
+        self.queue.retain(|notification| {
+            notification.severity != ui::Severity::Info
+                && (notification.severity, notification.message.as_ref())
+                    != (severity, message.as_ref())
+        });
+        self.queue.push_back(ui::Notification {
+            message,
+            severity,
+            shown_at: None,
+        });
+    }
+
+    // Remove the messages that have been shown for their timeout and start the timeout of the
+    // one that is now to be shown. Called before every draw
+    pub fn expire(&mut self) {
+        while let Some(notification) = self.queue.front_mut() {
+            let timeout = match notification.severity {
+                ui::Severity::Info => INFO_TIMEOUT,
+                ui::Severity::Warn => WARN_TIMEOUT,
+                ui::Severity::Error => ERROR_TIMEOUT,
+            };
+            match notification.shown_at {
+                Some(shown_at) if shown_at.elapsed() >= timeout => {
+                    self.queue.pop_front();
+                }
+                Some(_) => break,
+                None => {
+                    notification.shown_at = Some(std::time::Instant::now());
+                    break;
+                }
+            }
+        }
+    }
+}
+
+impl ui::State {
+    pub fn info(&mut self, message: impl Into<std::borrow::Cow<'static, str>>) {
+        self.notifications.push(ui::Severity::Info, message.into());
+    }
+
+    pub fn warn(&mut self, message: impl Into<std::borrow::Cow<'static, str>>) {
+        self.notifications.push(ui::Severity::Warn, message.into());
+    }
+
+    pub fn error(&mut self, message: impl Into<std::borrow::Cow<'static, str>>) {
+        self.notifications.push(ui::Severity::Error, message.into());
+    }
+
+    // Confirm an action with a short message drawn above the bottom bar. Newer toast replaces the
+    // one being shown
+    pub fn toast(&mut self, message: impl Into<String>) {
+        self.toast = Some((message.into(), std::time::Instant::now()));
+    }
+
+    pub fn expire_toast(&mut self) {
+        if let Some((_, shown_at)) = self.toast {
+            if shown_at.elapsed() >= TOAST_TIMEOUT {
+                self.toast = None;
+            }
+        }
+    }
+
+    // Start filtering the focused list or continue typing the query if it is already filtered.
+    // Returns false if focused window is not a list that can be filtered
+    pub fn start_filter(&mut self) -> bool {
+        let list = match self.active {
+            ui::Window::Musicbar => MIDDLE_MUSIC_INDEX,
+            ui::Window::Playlistbar => MIDDLE_PLAYLIST_INDEX,
+            ui::Window::Artistbar => MIDDLE_ARTIST_INDEX,
+            _ => return false,
+        };
+        if self.filter.list != Some(list) {
+            self.clear_filter();
+            self.filter.list = Some(list);
+            match list {
+                MIDDLE_MUSIC_INDEX => self.filter.music = self.musicbar.0.clone(),
+                MIDDLE_PLAYLIST_INDEX => self.filter.playlist = self.playlistbar.0.clone(),
+                _ => self.filter.artist = self.artistbar.0.clone(),
+            }
+        }
+        self.filter.typing = true;
+        true
+    }
+
+    // Fill the filtered list again with entries matching the query
+    pub fn apply_filter(&mut self) {
+        let query = &self.filter.query;
+        let is_empty = match self.filter.list {
+            Some(MIDDLE_MUSIC_INDEX) => {
+                self.musicbar.0 = fuzzy_filter(&self.filter.music, query, |music| {
+                    format!("{} {}", music.name, music.artist)
+                });
+                self.musicbar.0.is_empty()
+            }
+            Some(MIDDLE_PLAYLIST_INDEX) => {
+                self.playlistbar.0 = fuzzy_filter(&self.filter.playlist, query, |playlist| {
+                    format!("{} {}", playlist.name, playlist.author)
+                });
+                self.playlistbar.0.is_empty()
+            }
+            Some(_) => {
+                self.artistbar.0 =
+                    fuzzy_filter(&self.filter.artist, query, |artist| artist.name.clone());
+                self.artistbar.0.is_empty()
+            }
+            None => return,
+        };
+        let selected = if is_empty { None } else { Some(0) };
+        match self.filter.list {
+            Some(MIDDLE_MUSIC_INDEX) => self.musicbar.1.select(selected),
+            Some(MIDDLE_PLAYLIST_INDEX) => self.playlistbar.1.select(selected),
+            _ => self.artistbar.1.select(selected),
+        }
+    }
+
+    // Sort musicbar in next order. Filtered out music is also sorted so that sort stays when the
+    // filter is cleared. Focus stays on the same music
+    pub fn cycle_music_sort(&mut self) {
+        let is_filtered = self.filter.list == Some(MIDDLE_MUSIC_INDEX);
+        if self.music_sort.0 == ui::MusicSort::Original {
+            let list = if is_filtered {
+                &self.filter.music
+            } else {
+                &self.musicbar.0
+            };
+            self.music_sort.1 = list.iter().map(|music| music.id.clone()).collect();
+        }
+        let sort = self.music_sort.0.next();
+        self.music_sort.0 = sort;
+
+        let selected = self
+            .musicbar
+            .1
+            .selected()
+            .and_then(|index| self.musicbar.0.get(index))
+            .map(|music| music.id.clone());
+        sort.apply(&mut self.musicbar.0, &self.music_sort.1);
+        if is_filtered {
+            sort.apply(&mut self.filter.music, &self.music_sort.1);
+        }
+        if let Some(selected) = selected {
+            let index = self
+                .musicbar
+                .0
+                .iter()
+                .position(|music| music.id == selected);
+            self.musicbar.1.select(index);
+        }
+        self.info(format!("Sorted by {}..", sort.name()));
+    }
+
+    // Bring back the whole list that was filtered. Returns false if nothing was filtered
+    pub fn clear_filter(&mut self) -> bool {
+        let filter = std::mem::take(&mut self.filter);
+        match filter.list {
+            Some(MIDDLE_MUSIC_INDEX) => self.musicbar.0 = filter.music,
+            Some(MIDDLE_PLAYLIST_INDEX) => self.playlistbar.0 = filter.playlist,
+            Some(_) => self.artistbar.0 = filter.artist,
+            None => return false,
+        }
+        true
+    }
+
+    // Forget the filter when filtered list is being filled again with something else
+    pub fn drop_filter(&mut self, list: usize) {
+        if self.filter.list == Some(list) {
+            self.filter = ui::FilterState::default();
+        }
+    }
+
+    // Mark the focused row or unmark it if already marked. Returns false if focused window is not
+    // a list or nothing is focused
+    pub fn toggle_mark(&mut self) -> bool {
+        fn toggle<T: Clone>(marked: &mut Vec<T>, item: &T, is_same: impl Fn(&T) -> bool) {
+            match marked.iter().position(is_same) {
+                Some(index) => {
+                    marked.remove(index);
+                }
+                None => marked.push(item.clone()),
+            }
+        }
+        match self.active {
+            ui::Window::Musicbar => match self.musicbar.1.selected() {
+                Some(index) => {
+                    let music = &self.musicbar.0[index];
+                    toggle(&mut self.marked.music, music, |marked| {
+                        marked.id == music.id
+                    });
+                }
+                None => return false,
+            },
+            ui::Window::Playlistbar => match self.playlistbar.1.selected() {
+                Some(index) => {
+                    let playlist = &self.playlistbar.0[index];
+                    toggle(&mut self.marked.playlist, playlist, |marked| {
+                        marked.id == playlist.id
+                    });
+                }
+                None => return false,
+            },
+            ui::Window::Artistbar => match self.artistbar.1.selected() {
+                Some(index) => {
+                    let artist = &self.artistbar.0[index];
+                    toggle(&mut self.marked.artist, artist, |marked| {
+                        marked.id == artist.id
+                    });
+                }
+                None => return false,
+            },
+            _ => return false,
+        }
+        true
+    }
+
+    // Unmark every row of focused list. Returns false if nothing was marked there
+    pub fn clear_marks(&mut self) -> bool {
+        match self.active {
+            ui::Window::Musicbar => !std::mem::take(&mut self.marked.music).is_empty(),
+            ui::Window::Playlistbar => !std::mem::take(&mut self.marked.playlist).is_empty(),
+            ui::Window::Artistbar => !std::mem::take(&mut self.marked.artist).is_empty(),
+            _ => false,
+        }
+    }
+
+    // Follow the offset of every list after it is drawn. See `follow_offset`
+    pub fn follow_offsets(&mut self, position: &ui::Position) {
+        // 2 lines for border and 1 for header
+        let visible = |area: &Rect| area.height.saturating_sub(3) as usize;
+        self.sidebar_offset = follow_offset(
+            self.sidebar_offset,
+            self.sidebar.selected(),
+            SIDEBAR_LIST_COUNT,
+            position.shortcut.height.saturating_sub(2) as usize,
+        );
+        self.list_offset[MIDDLE_MUSIC_INDEX] = follow_offset(
+            self.list_offset[MIDDLE_MUSIC_INDEX],
+            self.musicbar.1.selected(),
+            self.musicbar.0.len(),
+            visible(&position.music),
+        );
+        self.list_offset[MIDDLE_PLAYLIST_INDEX] = follow_offset(
+            self.list_offset[MIDDLE_PLAYLIST_INDEX],
+            self.playlistbar.1.selected(),
+            self.playlistbar.0.len(),
+            visible(&position.playlist),
+        );
+        self.list_offset[MIDDLE_ARTIST_INDEX] = follow_offset(
+            self.list_offset[MIDDLE_ARTIST_INDEX],
+            self.artistbar.1.selected(),
+            self.artistbar.0.len(),
+            visible(&position.artist),
+        );
+    }
+
     pub fn play_music(&mut self, music_id: &str) {
+        // Remember where previous episode was left before replacing it
+        self.save_episode_position();
+
+        // Episode from podcast is started from where it was left off. `start` option applies to
+        // every file loaded after this so it is reset for any other music
+        let is_episode = matches!(self.filled_source.0, ui::MusicbarSource::Podcast(_));
+        let start_at = match self.podcast.positions.get(music_id) {
+            Some(position) if is_episode => format!("+{}", position.as_secs()),
+            _ => "none".to_string(),
+        };
+        self.player.set_property("start", start_at).ok();
+        let is_live = self
+            .musicbar
+            .0
+            .iter()
+            .any(|music| music.id == music_id && music.live);
+        self.podcast.playing = if is_episode {
+            Some(music_id.to_string())
+        } else {
+            None
+        };
+
+        // Now as the selection is being played. Add remaining item from musicbar to the play
+        // queue. When whole playlist is loaded, every music from it is queued instead of only the
+        // ones from current page
+        // Episodes are long enough on their own and other episodes should not start from saved
+        // position of this one. So only the selected episode is played
+        // Same goes for live stream which never ends so nothing after it would ever be played
+        let mut queue = Vec::new();
+        if !is_episode && !is_live {
+            let source = match &self.filled_source.0 {
+                ui::MusicbarSource::Playlist(playlist_id)
+                    if *playlist_id == self.full_playlist.id =>
+                {
+                    &self.full_playlist.music
+                }
+                _ => &self.musicbar.0,
+            };
+            // If this is the currently payed song donot add it to prevent having
+            // currently played song two time in queue
+            // Live stream in between would also block the rest of the queue forever so it is
+            // also skipped
+            queue = source
+                .iter()
+                .filter(|music| music.id != *music_id && !music.live)
+                .map(|music| fetcher::music_url(&music.id))
+                .collect();
+        }
+
+        let url = fetcher::music_url(music_id);
+        self.bottom.live = is_live;
+        self.load_with_queue(&url, &queue);
+        self.playback_attempt = Some(ui::PlaybackAttempt {
+            music_id: music_id.to_string(),
+            url,
+            next_route: 0,
+            queue,
+            is_loading: false,
+            started_at: std::time::Instant::now(),
+        });
+    }
+
+    // Play the music without queueing anything after it. eg: music opened directly by url which is
+    // not part of any list
+    pub fn play_single(&mut self, music_id: &str) {
+        self.save_episode_position();
+        self.player.set_property("start", "none".to_string()).ok();
+        self.podcast.playing = None;
+
+        let url = fetcher::music_url(music_id);
+        self.bottom.live = false;
+        self.load_with_queue(&url, &[]);
+        self.playback_attempt = Some(ui::PlaybackAttempt {
+            music_id: music_id.to_string(),
+            url,
+            next_route: 0,
+            queue: Vec::new(),
+            is_loading: false,
+            started_at: std::time::Instant::now(),
+        });
+    }
+
+    // Replace whatever is being played with music from `url` followed by the `queue`
+    fn load_with_queue(&mut self, url: &str, queue: &[String]) {
         self.player.unpause().ok();
-        match self.player.command(
-            "loadfile",
-            [format!("https://www.youtube.com/watch?v={}", music_id).as_str()].as_ref(),
-        ) {
+        match self.player.command("loadfile", [url].as_ref()) {
             Ok(_) => {
                 // clear any previous thing from bottombar
                 self.bottom.music_duration = Duration::from_secs(0);
                 self.bottom.music_elapse = Duration::from_secs(0);
 
-                self.status = "Playing...";
+                self.info("Playing...");
                 // set currently playing (unpaused) to ture. no need to set real title as it will
                 // be done by refresh_mpv_status() later on
                 self.bottom.playing = Some((String::new(), true))
             }
-            Err(_) => self.status = "Playback error..",
+            Err(_) => self.error("Playback error.."),
         }
-        // Now as the selection is being played. Add remaining item from musicbar to the play
-        // queue.
-        for music in self.musicbar.0.iter() {
-            // If this is the currently payed song donot add it to prevent having
-            // currently played song two time in queue
-            if music.id == *music_id {
-                continue;
-            }
+        for queued_url in queue {
             self.player
-                .command(
-                    "loadfile",
-                    [
-                        format!("https://www.youtube.com/watch?v={}", music.id).as_str(),
-                        "append",
-                    ]
-                    .as_ref(),
-                )
+                .command("loadfile", [queued_url.as_str(), "append"].as_ref())
                 .ok();
         }
     }
 
+    // Check if the music requested to play have really started playing. Player cannot play the
+    // music when it's unavailable in user's region or is removed in which case it either becomes
+    // idle or moves to next music in queue. When that happens, music is played via other route
+    // i.e proxied through invidious instances one by one. If every route fails music is marked
+    // as unavailable
+    fn check_playback_attempt(&mut self) {
+        let attempt = match self.playback_attempt.as_mut() {
+            Some(attempt) => attempt,
+            None => return,
+        };
+
+        if self.player.get_property::<i64>("audio-pts").is_ok() {
+            self.unavailable.remove(&attempt.music_id);
+            self.playback_attempt = None;
+            return;
+        }
+
+        let path = self
+            .player
+            .get_property::<String>("path")
+            .unwrap_or_default();
+        let is_idle = self
+            .player
+            .get_property::<bool>("idle-active")
+            .unwrap_or_default();
+        // Player takes a moment before it starts loading requested music. Until then it may still
+        // report previous state so failure is only decided once it is seen loading or it never
+        // started to load at all
+        if !attempt.is_loading {
+            if path == attempt.url && !is_idle {
+                attempt.is_loading = true;
+            } else if attempt.started_at.elapsed() < PLAYBACK_LOAD_TIMEOUT {
+                return;
+            }
+        } else if path == attempt.url && !is_idle {
+            return;
+        }
+
+        let routes = fetcher::fallback_music_urls(&attempt.music_id);
+        match routes.get(attempt.next_route) {
+            Some(route_url) => {
+                attempt.next_route += 1;
+                attempt.url = route_url.clone();
+                attempt.is_loading = false;
+                attempt.started_at = std::time::Instant::now();
+                let queue = std::mem::take(&mut attempt.queue);
+                let url = attempt.url.clone();
+                self.load_with_queue(&url, &queue);
+                if let Some(attempt) = self.playback_attempt.as_mut() {
+                    attempt.queue = queue;
+                }
+                self.info("Other route..");
+            }
+            None => {
+                let music_id = attempt.music_id.clone();
+                self.unavailable.insert(music_id);
+                self.playback_attempt = None;
+                self.error("Unavailable..");
+                // Player moves to next music in queue by itself. If there is nothing to play then
+                // there is no need to keep showing as playing
+                if is_idle {
+                    self.bottom.playing = None;
+                }
+            }
+        }
+    }
+
     // This function is called when user press enter in non-empty list of playlistbar
     pub fn activate_playlist(&mut self, playlist_id: &str) {
+        self.save_episode_position();
+        self.playback_attempt = None;
+        self.podcast.playing = None;
+        self.player.set_property("start", "none").ok();
+
         match self.player.command(
             "loadfile",
-            [format!("https://www.youtube.com/playlist?list={}", playlist_id).as_str()].as_ref(),
+            [fetcher::playlist_url(playlist_id).as_str()].as_ref(),
         ) {
             Ok(_) => {
                 // send unpause signal
@@ -684,12 +3507,13 @@ impl ui::State<'_> {
                 self.bottom.music_duration = Duration::from_secs(0);
                 self.bottom.music_elapse = Duration::from_secs(0);
 
-                self.status = "Playing..";
+                self.info("Playing..");
+                self.bottom.live = false;
                 // set currently playing (unpaused) to ture. no need to set real title as it will
                 // be done by refresh_mpv_status() later on
                 self.bottom.playing = Some((String::new(), true));
             }
-            Err(_) => self.status = "Playback error..",
+            Err(_) => self.error("Playback error.."),
         }
     }
 
@@ -700,10 +3524,18 @@ impl ui::State<'_> {
         // but for that we need tp spawn seperate thread/task
         // and also we are updating the ui anway so it may also be affordable to just query mpv in
         // ui updating loop
+        // Volume and mute can also be changed from mpv config file or the command line so keep them
+        // in sync with player instead of trusting what was set last
+        if let Some(volume) = self.player.get_volume() {
+            self.playback_behaviour.volume = volume.round().clamp(0.0, 100.0) as u8;
+        }
+        if let Ok(muted) = self.player.get_property::<bool>("mute") {
+            self.playback_behaviour.muted = muted;
+        }
         if let Some((_, true)) = self.bottom.playing {
-            match self.player.get_property::<i64>("audio-pts") {
+            match self.player.get_property::<f64>("time-pos") {
                 Ok(time) => {
-                    self.bottom.music_elapse = Duration::from_secs(time as u64);
+                    self.bottom.music_elapse = Duration::from_secs_f64(time.max(0.0));
                 }
                 Err(_e) => {
                     // This error is generally expected to be -10 (property exist but not available
@@ -722,33 +3554,476 @@ impl ui::State<'_> {
             let title = self
                 .player
                 .get_property::<String>("media-title")
-                .unwrap_or(">> Play some music <<".to_string());
+                .unwrap_or(UNKNOWN_TITLE.to_string());
             let estimated_duration_reply = self
                 .player
                 .get_property::<i64>("duration")
                 .unwrap_or_default();
 
+            if !self.chapters.from_player {
+                self.read_player_chapters();
+            }
+            self.check_playback_attempt();
+
+            let path = self
+                .player
+                .get_property::<String>("path")
+                .unwrap_or_default();
+            let is_new = self.bottom.artist.0 != path;
+            if is_new && !path.is_empty() {
+                self.record_history(&path);
+            }
+            if is_new || self.bottom.artist.1.is_empty() {
+                let artist = self.playing_artist(&path);
+                self.bottom.artist = (path, artist);
+            }
+
             self.bottom.playing = Some((title, true)); // at this scope of match playing status is always true
             self.bottom.music_duration =
                 Duration::from_secs(estimated_duration_reply.try_into().unwrap_or_default());
+            self.track_listen();
+        }
+    }
+
+    // Byte index of cursor in search query
+    pub fn search_cursor_index(&self) -> usize {
+        let query = &self.search.0;
+        query
+            .char_indices()
+            .map(|(index, _)| index)
+            .chain(std::iter::once(query.len()))
+            .rev()
+            .nth(self.search_cursor)
+            .unwrap_or_default()
+    }
+
+    pub fn edit_search(&mut self, edit: ui::SearchEdit) {
+        let mut chars = self.search.0.chars().collect::<Vec<char>>();
+        let mut cursor = chars.len() - self.search_cursor.min(chars.len());
+        let word_start = |chars: &[char], mut index: usize| {
+            while index > 0 && chars[index - 1].is_whitespace() {
+                index -= 1;
+            }
+            while index > 0 && !chars[index - 1].is_whitespace() {
+                index -= 1;
+            }
+            index
+        };
+        let word_end = |chars: &[char], mut index: usize| {
+            while index < chars.len() && chars[index].is_whitespace() {
+                index += 1;
+            }
+            while index < chars.len() && !chars[index].is_whitespace() {
+                index += 1;
+            }
+            index
+        };
+
+        match edit {
+            ui::SearchEdit::Insert(ch) => {
+                chars.insert(cursor, ch);
+                cursor += 1;
+            }
+            ui::SearchEdit::Backspace if cursor > 0 => {
+                cursor -= 1;
+                chars.remove(cursor);
+            }
+            ui::SearchEdit::Delete if cursor < chars.len() => {
+                chars.remove(cursor);
+            }
+            ui::SearchEdit::Backspace | ui::SearchEdit::Delete => {}
+            ui::SearchEdit::Left => cursor = cursor.saturating_sub(1),
+            ui::SearchEdit::Right => cursor = (cursor + 1).min(chars.len()),
+            ui::SearchEdit::WordLeft => cursor = word_start(&chars, cursor),
+            ui::SearchEdit::WordRight => cursor = word_end(&chars, cursor),
+            ui::SearchEdit::Home => cursor = 0,
+            ui::SearchEdit::End => cursor = chars.len(),
+            ui::SearchEdit::DeleteWord => {
+                let start = word_start(&chars, cursor);
+                chars.drain(start..cursor);
+                cursor = start;
+            }
+            ui::SearchEdit::KillLine => {
+                chars.drain(..cursor);
+                cursor = 0;
+            }
+        }
+
+        self.search_cursor = chars.len() - cursor;
+        let query = chars.into_iter().collect::<String>();
+        // Suggestions are only fetched again when the query itself is changed
+        if query != self.search.0 {
+            self.suggestions.0 = query.clone();
+            self.search.0 = query;
+        }
+    }
+
+    // Music that started playing is added to history. Like the artist below, it is looked up in
+    // the lists being shown as mpv only knows the url. Music not found there is left out
+    fn record_history(&self, path: &str) {
+        if let Some(music) = self
+            .musicbar
+            .0
+            .iter()
+            .chain(self.full_playlist.music.iter())
+            .find(|music| fetcher::music_url(&music.id) == path)
+        {
+            fetcher::history::record(music);
+        }
+    }
+
+    // Count the time music being played is listened to and queue it for submission to ListenBrainz
+    // once it crosses the scrobble threshold. Called on every refresh while music is playing. Music
+    // whose artist is not known is left out as ListenBrainz do not accept it
+    fn track_listen(&mut self) {
+        if CONFIG.constants.listenbrainz_token.is_empty() {
+            return;
+        }
+
+        let now = std::time::Instant::now();
+        let (path, artist) = &self.bottom.artist;
+        if self.listen.path != *path {
+            let pending = std::mem::take(&mut self.listen.pending);
+            self.listen = ui::ListenState {
+                path: path.clone(),
+                started_at: std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs(),
+                pending,
+                ..Default::default()
+            };
+        } else if let Some(counted_at) = self.listen.counted_at {
+            self.listen.listened += now - counted_at;
+        }
+        self.listen.counted_at = Some(now);
+
+        let duration = if self.bottom.live {
+            Duration::ZERO
+        } else {
+            self.bottom.music_duration
+        };
+        let reached = fetcher::listenbrainz::scrobble_threshold(duration)
+            .is_some_and(|threshold| self.listen.listened >= threshold);
+        if reached && !self.listen.queued && !path.is_empty() && !artist.is_empty() {
+            if let Some((title, _)) = &self.bottom.playing {
+                let listen = fetcher::listenbrainz::Listen {
+                    listened_at: self.listen.started_at,
+                    title: title.clone(),
+                    artist: artist.clone(),
+                    url: path.clone(),
+                    duration,
+                };
+                self.listen.pending.push(listen);
+                self.listen.queued = true;
+            }
+        }
+    }
+
+    // Url (or path) of the music being played as reported by player. See music_url
+    pub fn playing_url(&self) -> Option<&str> {
+        match (&self.bottom.playing, self.bottom.artist.0.as_str()) {
+            (Some(_), path) if !path.is_empty() => Some(path),
+            _ => None,
+        }
+    }
+
+    // Music being played is looked up in the lists being shown. When it is not there (eg: list have
+    // changed since then) artist is read from tags of file which is only the case for local music
+    fn playing_artist(&self, path: &str) -> String {
+        self.musicbar
+            .0
+            .iter()
+            .chain(self.full_playlist.music.iter())
+            .find(|music| fetcher::music_url(&music.id) == path)
+            .map(|music| music.artist.clone())
+            .or_else(|| {
+                self.player
+                    .get_property::<String>("metadata/by-key/artist")
+                    .ok()
+            })
+            .unwrap_or_default()
+    }
+
+    // Title and duration of the music being played if any. None is also returned when title is
+    // not yet known
+    pub fn playing_info(&self) -> Option<(String, Duration)> {
+        self.bottom
+            .playing
+            .as_ref()
+            .filter(|(title, _)| !title.is_empty() && title != UNKNOWN_TITLE)
+            .map(|(title, _)| (title.clone(), self.bottom.music_duration))
+    }
+
+    // Artist of music being played. Empty when it is not known yet or music at path is no longer
+    // the one being played
+    pub fn playing_artist_of(&self, path: &str) -> String {
+        match &self.bottom.artist {
+            (playing_path, artist) if playing_path == path => artist.clone(),
+            _ => String::new(),
+        }
+    }
+
+    // Store the position of episode being played so that it can be resumed later. Finished episode
+    // is forgotten so that it starts from beginning when played again
+    pub fn save_episode_position(&mut self) {
+        let episode_id = match &self.podcast.playing {
+            Some(id) => id.clone(),
+            None => return,
+        };
+        let position = self.bottom.music_elapse;
+        let is_finished = !self.bottom.music_duration.is_zero()
+            && position + EPISODE_FINISH_MARGIN >= self.bottom.music_duration;
+
+        let res = if is_finished || position.is_zero() {
+            self.podcast.positions.remove(&episode_id);
+            let query = format!(
+                "DELETE FROM {tb_name} WHERE id = :id",
+                tb_name = TB_PODCAST_POSITION
+            );
+            STORAGE
+                .lock()
+                .unwrap()
+                .execute(&query, &[(":id", &episode_id)])
+        } else {
+            self.podcast.positions.insert(episode_id.clone(), position);
+            let query = format!(
+                "INSERT OR REPLACE INTO {tb_name} (id, position) VALUES (:id, :position)",
+                tb_name = TB_PODCAST_POSITION
+            );
+            STORAGE.lock().unwrap().execute(
+                &query,
+                &[
+                    (":id", &episode_id),
+                    (":position", &position.as_secs().to_string()),
+                ],
+            )
+        };
+        if res.is_err() {
+            self.error("Err saving position..");
+        }
+    }
+
+    // Leave everything in a state that is safe to exit with and tell every loop to stop. Episode
+    // position is remembered and player is stopped so that it do not keep the audio device while
+    // app is being closed
+    pub fn quit(&mut self) {
+        self.save_episode_position();
+        self.player.command("stop", &[]).ok();
+
+        // setting active window to None is to quit
+        self.active = ui::Window::None;
+        // Also make sure databse is flushed.
+        if let Err(err) = STORAGE.lock().unwrap().cache_flush() {
+            eprintln!("Cannot flush the storage db. Error: {err}", err = err);
+        }
+    }
+
+    // Read the chapters known by player if any. Player only knows it once file is loaded so this
+    // is checked until some chapters are found
+    fn read_player_chapters(&mut self) {
+        let count = self
+            .player
+            .get_property::<i64>("chapter-list/count")
+            .unwrap_or_default();
+        if count <= 0 {
+            return;
+        }
+        self.chapters.list = (0..count)
+            .map(|index| fetcher::chapters::Chapter {
+                start: Duration::from_secs_f64(
+                    self.player
+                        .get_property::<f64>(&format!("chapter-list/{}/time", index))
+                        .unwrap_or_default()
+                        .max(0.0),
+                ),
+                title: self
+                    .player
+                    .get_property::<String>(&format!("chapter-list/{}/title", index))
+                    .unwrap_or_default(),
+            })
+            .collect();
+        self.chapters.from_player = true;
+    }
+
+    // Index of the chapter being played
+    pub fn current_chapter(&self) -> Option<usize> {
+        self.bottom.playing.as_ref()?;
+        self.chapters
+            .list
+            .iter()
+            .rposition(|chapter| chapter.start <= self.bottom.music_elapse)
+    }
+
+    // Move to start of next chapter or previous chapter. When going backward and it's been a
+    // while since current chapter started, move to start of current chapter instead just like
+    // previous track button of most players
+    pub fn seek_chapter(&mut self, forward: bool) {
+        let current = self.current_chapter();
+        let target = match (current, forward) {
+            (None, true) => Some(0),
+            (Some(current), true) => Some(current + 1),
+            (None, false) => None,
+            (Some(current), false) => {
+                let since_start = self.bottom.music_elapse - self.chapters.list[current].start;
+                if since_start > CHAPTER_RESTART_AFTER || current == 0 {
+                    Some(current)
+                } else {
+                    Some(current - 1)
+                }
+            }
+        };
+        match target {
+            Some(index) if index < self.chapters.list.len() => self.jump_to_chapter(index),
+            _ => self.warn("No chapter.."),
+        }
+    }
+
+    pub fn jump_to_chapter(&mut self, index: usize) {
+        let start = self.chapters.list[index].start;
+        match self.player.seek_absolute(start.as_secs_f64()) {
+            Ok(_) => {
+                self.bottom.music_elapse = start;
+                self.info("Chapter..");
+            }
+            Err(_) => self.error("Seek error.."),
+        }
+    }
+
+    // Name of playlist/artist/music the list was filled from. See source_names
+    pub fn source_name(&self, id: &str) -> Option<&str> {
+        self.source_names.get(id).map(String::as_str)
+    }
+
+    // Kind of source followed by its name if known. eg: "Playlist: Chillhop Essentials"
+    pub fn source_label(&self, kind: &str, id: &str) -> String {
+        match self.source_name(id) {
+            Some(name) => format!("{}: {}", kind, name),
+            None => kind.to_owned(),
+        }
+    }
+
+    // Id of the artist whose music (or podcast) is being shown. Header is shown for this artist
+    pub fn header_artist(&self) -> Option<&str> {
+        match &self.filled_source.0 {
+            ui::MusicbarSource::Artist(channel_id)
+            | ui::MusicbarSource::Podcast(channel_id)
+            | ui::MusicbarSource::ArtistSearch(channel_id, _) => Some(channel_id.as_str()),
+            _ => None,
+        }
+    }
+
+    // Id of the playlist whose music is being shown. Header is shown for this playlist
+    pub fn header_playlist(&self) -> Option<&str> {
+        match &self.filled_source.0 {
+            ui::MusicbarSource::Playlist(playlist_id) => Some(playlist_id.as_str()),
+            _ => None,
+        }
+    }
+
+    // Id of the channel within which search query is searched. When musicbar is showing music of
+    // an artist, searching finds music within that artist only
+    pub fn search_channel(&self) -> Option<&str> {
+        match &self.filled_source.0 {
+            ui::MusicbarSource::Artist(channel_id)
+            | ui::MusicbarSource::ArtistSearch(channel_id, _) => Some(channel_id.as_str()),
+            _ => None,
+        }
+    }
+
+    pub fn playing_live(&self) -> bool {
+        self.bottom.playing.is_some() && self.bottom.live
+    }
+
+    // Name of every entry of play queue. Player only knows the url so name is looked up from music
+    // that is shown (or of playlist being played). Url itself is the name of anything else
+    pub fn queue_names(&self) -> Vec<String> {
+        let names = self
+            .musicbar
+            .0
+            .iter()
+            .chain(self.full_playlist.music.iter())
+            .map(|music| (fetcher::music_url(&music.id), music.name.as_str()))
+            .collect::<std::collections::HashMap<String, &str>>();
+        self.player
+            .queue()
+            .0
+            .into_iter()
+            .map(|filename| {
+                names
+                    .get(&filename)
+                    .map(|name| name.to_string())
+                    .unwrap_or(filename)
+            })
+            .collect()
+    }
+
+    // Repeat the whole queue when all is true, otherwise only the music being played
+    pub fn set_repeat(&mut self, all: bool) {
+        self.player.repeat_nothing();
+        if all {
+            self.player.repeat_playlist();
+        } else {
+            self.player.repeat_one();
         }
+        self.playback_behaviour.repeat = all;
     }
 
     pub fn toggle_pause(&mut self) {
+        // Pausing is good time to remember the position as user may quit after that
+        if let Some((_, true)) = self.bottom.playing {
+            self.save_episode_position();
+        }
         if let Some((_, ref mut is_playing)) = self.bottom.playing {
             if *is_playing {
-                self.status = "Paused..";
                 self.player.pause().unwrap();
+                // Time spent paused is not listened
+                self.listen.counted_at = None;
             } else {
-                self.status = "Playing..";
                 self.player.unpause().unwrap();
             }
             *is_playing = !*is_playing;
+            let message = if *is_playing { "Playing.." } else { "Paused.." };
+            self.info(message);
         }
     }
 }
 
-impl ui::State<'_> {
+impl ui::State {
+    // Id of marked rows of focused list or of the focused row if nothing is marked
+    pub fn focused_ids(&self) -> Vec<&String> {
+        fn focused<T>(list: &(Vec<T>, TableState)) -> Option<&T> {
+            list.1.selected().and_then(|index| list.0.get(index))
+        }
+        match self.active {
+            ui::Window::Musicbar if !self.marked.music.is_empty() => {
+                self.marked.music.iter().map(|music| &music.id).collect()
+            }
+            ui::Window::Musicbar => focused(&self.musicbar)
+                .map(|music| &music.id)
+                .into_iter()
+                .collect(),
+            ui::Window::Playlistbar if !self.marked.playlist.is_empty() => self
+                .marked
+                .playlist
+                .iter()
+                .map(|playlist| &playlist.id)
+                .collect(),
+            ui::Window::Playlistbar => focused(&self.playlistbar)
+                .map(|playlist| &playlist.id)
+                .into_iter()
+                .collect(),
+            ui::Window::Artistbar if !self.marked.artist.is_empty() => {
+                self.marked.artist.iter().map(|artist| &artist.id).collect()
+            }
+            ui::Window::Artistbar => focused(&self.artistbar)
+                .map(|artist| &artist.id)
+                .into_iter()
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
     pub fn remove_music_from_favourates(&mut self, music: &fetcher::MusicUnit) {
         let query = format!(
             "
@@ -763,9 +4038,11 @@ impl ui::State<'_> {
 
         let res = STORAGE.lock().unwrap().execute(&query, &args);
         if res.is_ok() {
-            self.status = "Removed..";
+            self.favourites.remove(&music.id);
+            self.toast(format!("Removed from favourites: {}", music.name));
+            self.info("Removed..");
         } else {
-            self.status = "Err removing..";
+            self.error("Err removing..");
         }
     }
 
@@ -782,9 +4059,11 @@ impl ui::State<'_> {
 
         let res = STORAGE.lock().unwrap().execute(&query, &args);
         if res.is_ok() {
-            self.status = "Removed..";
+            self.favourites.remove(&playlist.id);
+            self.toast(format!("Removed from favourites: {}", playlist.name));
+            self.info("Removed..");
         } else {
-            self.status = "Err removing..";
+            self.error("Err removing..");
         }
     }
 
@@ -802,9 +4081,11 @@ impl ui::State<'_> {
 
         let res = STORAGE.lock().unwrap().execute(&query, &args);
         if res.is_ok() {
-            self.status = "Removed..."
+            self.favourites.remove(&artist.id);
+            self.toast(format!("Removed from favourites: {}", artist.name));
+            self.info("Removed...")
         } else {
-            self.status = "Err removing..";
+            self.error("Err removing..");
         }
     }
 
@@ -828,9 +4109,39 @@ impl ui::State<'_> {
 
         let res = STORAGE.lock().unwrap().execute(&query, &args);
         if res.is_ok() {
-            self.status = "Added..";
+            self.favourites.insert(artist.id.clone());
+            self.toast(format!("Added to favourites: {}", artist.name));
+            self.info("Added..");
         } else {
-            self.status = "Err adding..";
+            self.error("Err adding..");
+        }
+    }
+
+    // Subscribe to the artist. If artist is already subscribed, nothing is inserted in which case
+    // the artist is unsubscribed instead
+    pub fn toggle_subscription(&mut self, artist: &fetcher::ArtistUnit) {
+        let insert_query = format!(
+            "INSERT OR IGNORE INTO {tb_name} (id, name, count) VALUES (:id, :name, :count)",
+            tb_name = TB_SUBSCRIPTIONS
+        );
+        let delete_query = format!(
+            "DELETE FROM {tb_name} WHERE id = :id",
+            tb_name = TB_SUBSCRIPTIONS
+        );
+
+        let conn = STORAGE.lock().unwrap();
+        let args = [
+            (":id", &artist.id),
+            (":name", &artist.name),
+            (":count", &artist.video_count),
+        ];
+        match conn.execute(&insert_query, &args) {
+            Ok(0) => match conn.execute(&delete_query, &[(":id", &artist.id)]) {
+                Ok(_) => self.info("Unsubscribed.."),
+                Err(_) => self.error("Err unsubscribing.."),
+            },
+            Ok(_) => self.info("Subscribed.."),
+            Err(_) => self.error("Err subscribing.."),
         }
     }
 
@@ -855,9 +4166,11 @@ impl ui::State<'_> {
 
         let res = STORAGE.lock().unwrap().execute(&query, &args);
         if res.is_ok() {
-            self.status = "Added...";
+            self.favourites.insert(music.id.clone());
+            self.toast(format!("Added to favourites: {}", music.name));
+            self.info("Added...");
         } else {
-            self.status = "Err adding..";
+            self.error("Err adding..");
         }
     }
 
@@ -880,14 +4193,27 @@ impl ui::State<'_> {
 
         let res = STORAGE.lock().unwrap().execute(&query, &args);
         if res.is_ok() {
-            self.status = "Added...";
+            self.favourites.insert(playlist.id.clone());
+            self.toast(format!("Added to favourites: {}", playlist.name));
+            self.info("Added...");
         } else {
-            self.status = "Err adding..";
+            self.error("Err adding..");
         }
     }
 }
 
 impl ui::Window {
+    // Sections that can be resized and maximized
+    pub fn is_pane(&self) -> bool {
+        matches!(
+            self,
+            ui::Window::Sidebar
+                | ui::Window::Musicbar
+                | ui::Window::Playlistbar
+                | ui::Window::Artistbar
+        )
+    }
+
     /* Any components of top bar and bottombar are not focusable instead directly controlled by the shortcut keys */
     pub fn next(&self) -> ui::Window {
         match self {
@@ -921,11 +4247,17 @@ impl std::convert::TryFrom<usize> for ui::SidebarOption {
     fn try_from(value: usize) -> Result<Self, Self::Error> {
         match value {
             0 => Ok(ui::SidebarOption::Trending),
-            1 => Ok(ui::SidebarOption::YoutubeCommunity),
-            2 => Ok(ui::SidebarOption::Liked),
-            3 => Ok(ui::SidebarOption::Saved),
-            4 => Ok(ui::SidebarOption::Following),
-            5 => Ok(ui::SidebarOption::Search),
+            1 => Ok(ui::SidebarOption::Charts),
+            2 => Ok(ui::SidebarOption::Moods),
+            3 => Ok(ui::SidebarOption::YoutubeCommunity),
+            4 => Ok(ui::SidebarOption::Liked),
+            5 => Ok(ui::SidebarOption::Saved),
+            6 => Ok(ui::SidebarOption::Following),
+            7 => Ok(ui::SidebarOption::Subscriptions),
+            8 => Ok(ui::SidebarOption::NewReleases),
+            9 => Ok(ui::SidebarOption::Local),
+            10 => Ok(ui::SidebarOption::History),
+            11 => Ok(ui::SidebarOption::Search),
             _ => Err("No sidebar option found corresponding to this usize"),
         }
     }
@@ -0,0 +1,156 @@
+use super::shared_import::*;
+use config::initilize::CONFIG;
+use serde_json::Value;
+use std::sync::Condvar;
+
+// What happens to playback is POSTed as json to every url in webhooks of config so that Home
+// Assistant automation (or any logger) can act on it. Body is same as status of remote control
+// with name of event added. eg:
+//
+// {"event": "track-start", "state": "playing", "title": "..", "artist": "..", "url": "..", ...}
+//
+// Events are:
+// track-start: New music started. Also sent for music already being played when app starts
+// track-end: Music stopped being played (next one started, stopped or app quit). Body is the status
+//            just before it ended
+// pause, resume: Music being played was paused or resumed
+// queue-empty: Nothing is being played anymore (end of queue is reached or playback is stopped)
+//
+// Player is looked at every CHECK_INTERVAL, so change that is undone sooner than that is missed
+const CHECK_INTERVAL: Duration = Duration::from_millis(500);
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Events between previous and current status
+fn events(previous: &Value, current: &Value) -> Vec<(&'static str, Value)> {
+    let mut events = Vec::new();
+    let was_playing = previous["state"] != "stopped";
+    let is_playing = current["state"] != "stopped";
+
+    if previous["url"] != current["url"] || was_playing != is_playing {
+        if was_playing {
+            events.push(("track-end", previous.clone()));
+        }
+        if is_playing {
+            events.push(("track-start", current.clone()));
+        } else if was_playing {
+            events.push(("queue-empty", current.clone()));
+        }
+    } else if previous["state"] == "playing" && current["state"] == "paused" {
+        events.push(("pause", current.clone()));
+    } else if previous["state"] == "paused" && current["state"] == "playing" {
+        events.push(("resume", current.clone()));
+    }
+    events
+}
+
+async fn send(client: &reqwest::Client, event: &str, mut body: Value) -> bool {
+    body["event"] = event.into();
+    let mut sent = true;
+    for url in &CONFIG.constants.webhooks {
+        let response = client.post(url).json(&body).send().await;
+        sent &= response.is_ok_and(|response| response.status().is_success());
+    }
+    sent
+}
+
+// Watch until app is quit. Nothing is done when no webhook is set. track-end of music being played
+// is sent before returning
+pub async fn serve(state_original: &mut Arc<Mutex<super::State>>, notifier: &mut Arc<Condvar>) {
+    if CONFIG.constants.webhooks.is_empty() {
+        return;
+    }
+    let client = reqwest::Client::builder()
+        .timeout(SEND_TIMEOUT)
+        .build()
+        .unwrap_or_default();
+
+    // Events are sent one after another by a single task so that they arrive in the order they
+    // happened (eg: track-end before track-start) while slow (or down) receiver still do not
+    // delay the watching
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel::<(&str, Value)>();
+    let sending = {
+        let state_original = Arc::clone(state_original);
+        let notifier = Arc::clone(notifier);
+        tokio::spawn(async move {
+            while let Some((event, body)) = receiver.recv().await {
+                if !send(&client, event, body).await {
+                    state_original
+                        .lock()
+                        .unwrap()
+                        .error("Cannot send webhook..");
+                    notifier.notify_all();
+                }
+            }
+        })
+    };
+
+    let mut previous = serde_json::json!({ "state": "stopped", "url": "" });
+    loop {
+        let current = {
+            let state = state_original.lock().unwrap();
+            if state.active == super::Window::None {
+                break;
+            }
+            super::remote::status(&state)
+        };
+        for event in events(&previous, &current) {
+            sender.send(event).ok();
+        }
+        previous = current;
+        tokio::time::sleep(CHECK_INTERVAL).await;
+    }
+
+    if previous["state"] != "stopped" {
+        sender.send(("track-end", previous)).ok();
+    }
+    // Whatever is still queued is sent before app quits
+    drop(sender);
+    sending.await.ok();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn names(events: Vec<(&'static str, Value)>) -> Vec<&'static str> {
+        events.into_iter().map(|(event, _)| event).collect()
+    }
+
+    #[test]
+    fn first_status() {
+        let stopped = json!({ "state": "stopped", "url": "" });
+        let playing = json!({ "state": "playing", "url": "a" });
+        assert_eq!(names(events(&stopped, &stopped)), Vec::<&str>::new());
+        assert_eq!(names(events(&stopped, &playing)), ["track-start"]);
+    }
+
+    #[test]
+    fn track_change() {
+        let first = json!({ "state": "playing", "url": "a" });
+        let second = json!({ "state": "playing", "url": "b" });
+        let changed = events(&first, &second);
+        assert_eq!(changed[0], ("track-end", first));
+        assert_eq!(changed[1], ("track-start", second));
+        assert_eq!(changed.len(), 2);
+    }
+
+    #[test]
+    fn stop_empties_queue() {
+        let playing = json!({ "state": "paused", "url": "a" });
+        let stopped = json!({ "state": "stopped", "url": "" });
+        assert_eq!(
+            names(events(&playing, &stopped)),
+            ["track-end", "queue-empty"]
+        );
+    }
+
+    #[test]
+    fn pause_and_resume() {
+        let playing = json!({ "state": "playing", "url": "a" });
+        let paused = json!({ "state": "paused", "url": "a" });
+        assert_eq!(names(events(&playing, &paused)), ["pause"]);
+        assert_eq!(names(events(&paused, &playing)), ["resume"]);
+        assert_eq!(names(events(&paused, &paused)), Vec::<&str>::new());
+    }
+}